@@ -1,6 +1,6 @@
 use criterion::{criterion_group, criterion_main, Bencher, Criterion};
 use quadratic_core::controller::GridController;
-use quadratic_core::grid::Grid;
+use quadratic_core::grid::{CodeCellLanguage, Grid};
 use quadratic_core::{Pos, Rect, SheetPos, SheetRect};
 use std::time::Duration;
 
@@ -315,6 +315,68 @@ fn criterion_benchmark(c: &mut Criterion) {
             criterion::BatchSize::SmallInput,
         )
     });
+
+    let formulas = vec![
+        ("formulas_1000", formula_grid(1000)),
+        ("formulas_5000", formula_grid(5000)),
+    ];
+
+    benchmark_grids(c, &formulas, "build_dependency_index", |b, grid| {
+        b.iter(|| GridController::from_grid(grid.clone(), 0));
+    });
+
+    benchmark_grids(c, &formulas, "get_dependent_code_cells", |b, grid| {
+        let gc = GridController::from_grid(grid.clone(), 0);
+        let sheet_id = gc.sheet_ids()[0];
+        b.iter(|| {
+            for y in 0..100 {
+                let sheet_rect = SheetRect::single_pos(Pos { x: 0, y }, sheet_id);
+                gc.get_dependent_code_cells(&sheet_rect);
+            }
+        });
+    });
+
+    benchmark_grids(c, &formulas, "set_value_with_dependents", |b, grid| {
+        b.iter_batched(
+            || {
+                // Setup
+                let gc = GridController::from_grid(grid.clone(), 0);
+                let sheet_id = gc.sheet_ids()[0];
+                (
+                    gc,
+                    SheetPos {
+                        x: 0,
+                        y: 10,
+                        sheet_id,
+                    },
+                )
+            },
+            |(mut gc, sheet_pos)| {
+                // Test
+                gc.set_cell_value(sheet_pos, "2".to_string(), None);
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+}
+
+/// Returns a grid with a column of `count` numbers and a column of formulas
+/// that each sum a window of those numbers.
+fn formula_grid(count: i64) -> Grid {
+    let mut gc = GridController::from_grid(Grid::new(), 0);
+    let sheet_id = gc.sheet_ids()[0];
+    for y in 0..count {
+        gc.set_cell_value(SheetPos { x: 0, y, sheet_id }, y.to_string(), None);
+    }
+    for y in 0..count {
+        gc.set_code_cell(
+            SheetPos { x: 1, y, sheet_id },
+            CodeCellLanguage::Formula,
+            format!("SUM(A{}:A{})", y, y + 5),
+            None,
+        );
+    }
+    gc.grid().clone()
 }
 
 fn benchmark_grids(
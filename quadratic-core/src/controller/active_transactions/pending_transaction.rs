@@ -4,7 +4,7 @@
 //! * tracking the state of a pending transaction
 //! * converting pending transaction to a completed transaction
 
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use uuid::Uuid;
 
@@ -45,6 +45,17 @@ pub struct PendingTransaction {
     // used by Code Cell execution to track dependencies
     pub cells_accessed: HashSet<SheetRect>,
 
+    // code cells that have been ordered for computing in the current
+    // recalculation, so that each is computed at most once
+    pub code_cells_scheduled: HashSet<SheetPos>,
+
+    // code cells in a circular reference, with the cells that they access
+    pub circular_code_cells: HashMap<SheetPos, HashSet<SheetRect>>,
+
+    // formulas in a circular reference that are calculated iteratively, keyed
+    // by the formula whose ComputeCode operation calculates them
+    pub iterative_cycles: HashMap<SheetPos, Vec<SheetPos>>,
+
     // save code_cell info for async calls
     pub current_sheet_pos: Option<SheetPos>,
 
@@ -68,6 +79,9 @@ impl Default for PendingTransaction {
             has_async: false,
             summary: TransactionSummary::default(),
             cells_accessed: HashSet::new(),
+            code_cells_scheduled: HashSet::new(),
+            circular_code_cells: HashMap::new(),
+            iterative_cycles: HashMap::new(),
             current_sheet_pos: None,
             waiting_for_async: None,
             complete: false,
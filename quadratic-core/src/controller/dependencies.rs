@@ -2,22 +2,45 @@ use std::{self};
 
 use std::collections::HashSet;
 
-use crate::{SheetPos, SheetRect};
+use crate::{formulas::find_named_ranges, grid::CodeCellLanguage, CellValue, SheetPos, SheetRect};
 
-use super::GridController;
+use super::{dependency_index::DependencyIndex, GridController};
 
 impl GridController {
-    /// Searches all code_runs in all sheets for cells that are dependent on the given sheet_rect.
+    /// Returns the code cells that access any cell in the given sheet_rect.
     pub fn get_dependent_code_cells(&self, sheet_rect: &SheetRect) -> Option<HashSet<SheetPos>> {
+        let dependent_cells = self.dependency_index.dependents(sheet_rect);
+
+        if dependent_cells.is_empty() {
+            None
+        } else {
+            Some(dependent_cells)
+        }
+    }
+
+    /// Rebuilds the index of cells accessed by code runs. This is only needed
+    /// after code runs are changed directly on a sheet rather than through an
+    /// operation.
+    pub fn rebuild_dependency_index(&mut self) {
+        self.dependency_index = DependencyIndex::from_grid(&self.grid);
+    }
+
+    /// Searches all formulas in all sheets for cells that refer to the named
+    /// range `name`, whether or not it currently exists.
+    pub fn get_named_range_dependent_code_cells(&self, name: &str) -> Option<HashSet<SheetPos>> {
         let mut dependent_cells = HashSet::new();
 
         self.grid.sheets().iter().for_each(|sheet| {
-            sheet.code_runs.iter().for_each(|(pos, code_run)| {
-                code_run.cells_accessed.iter().for_each(|cell_accessed| {
-                    if sheet_rect.intersects(*cell_accessed) {
+            sheet.code_runs.keys().for_each(|pos| {
+                if let Some(CellValue::Code(code_cell)) = sheet.cell_value(*pos) {
+                    if code_cell.language == CodeCellLanguage::Formula
+                        && find_named_ranges(&code_cell.code)
+                            .iter()
+                            .any(|named_range| named_range.inner.eq_ignore_ascii_case(name))
+                    {
                         dependent_cells.insert(pos.to_sheet_pos(sheet.id));
                     }
-                });
+                }
             });
         });
 
@@ -37,7 +60,7 @@ mod test {
 
     use crate::{
         controller::GridController,
-        grid::{CodeRun, CodeRunResult},
+        grid::{CodeCellLanguage, CodeRun, CodeRunResult},
         CellValue, Pos, SheetPos, SheetRect, Value,
     };
 
@@ -77,6 +100,7 @@ mod test {
                 cells_accessed: cells_accessed.clone(),
             }),
         );
+        gc.rebuild_dependency_index();
         let sheet_pos_02 = SheetPos {
             x: 0,
             y: 2,
@@ -105,4 +129,65 @@ mod test {
         );
         assert_eq!(gc.get_dependent_code_cells(&sheet_pos_02.into()), None);
     }
+
+    #[test]
+    fn test_dependency_index_follows_operations() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let a1 = SheetPos::from((0, 0, sheet_id));
+        let b1 = SheetPos::from((1, 0, sheet_id));
+        let b2 = SheetPos::from((1, 1, sheet_id));
+
+        gc.set_code_cell(b2, CodeCellLanguage::Formula, "B0 + 1".into(), None);
+        assert_eq!(
+            gc.get_dependent_code_cells(&b1.into()),
+            Some(HashSet::from([b2]))
+        );
+        assert_eq!(gc.get_dependent_code_cells(&a1.into()), None);
+
+        // rewriting the formula replaces its dependencies
+        gc.set_code_cell(b2, CodeCellLanguage::Formula, "A0 + 1".into(), None);
+        assert_eq!(gc.get_dependent_code_cells(&b1.into()), None);
+        assert_eq!(
+            gc.get_dependent_code_cells(&a1.into()),
+            Some(HashSet::from([b2]))
+        );
+
+        // the index is rebuilt when a file is loaded
+        let loaded = GridController::from_grid(gc.grid().clone(), 0);
+        assert_eq!(
+            loaded.get_dependent_code_cells(&a1.into()),
+            Some(HashSet::from([b2]))
+        );
+
+        // inserting a column moves both the formula and its dependencies
+        gc.insert_column(sheet_id, 0, None);
+        let a1 = SheetPos::from((1, 0, sheet_id));
+        let b2 = SheetPos::from((2, 1, sheet_id));
+        assert_eq!(
+            gc.get_dependent_code_cells(&a1.into()),
+            Some(HashSet::from([b2]))
+        );
+
+        gc.undo(None);
+        let a1 = SheetPos::from((0, 0, sheet_id));
+        let b2 = SheetPos::from((1, 1, sheet_id));
+        assert_eq!(
+            gc.get_dependent_code_cells(&a1.into()),
+            Some(HashSet::from([b2]))
+        );
+
+        gc.set_cell_value(b2, "1".into(), None);
+        assert_eq!(gc.get_dependent_code_cells(&a1.into()), None);
+
+        gc.undo(None);
+        assert_eq!(
+            gc.get_dependent_code_cells(&a1.into()),
+            Some(HashSet::from([b2]))
+        );
+
+        // formulas on a deleted sheet no longer depend on anything
+        gc.delete_sheet(sheet_id, None);
+        assert_eq!(gc.get_dependent_code_cells(&a1.into()), None);
+    }
 }
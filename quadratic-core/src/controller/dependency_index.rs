@@ -0,0 +1,299 @@
+//! Spatial index from the regions that code cells access to the code cells
+//! that access them.
+//!
+//! Each accessed rectangle is stored in a grid of square buckets. There is one
+//! grid per level, and each level's buckets are 16 times wider than the
+//! previous level's. A rectangle is stored at the finest level where it
+//! overlaps at most 2x2 buckets, so small references stay cheap to find and
+//! whole-column or whole-sheet references end up in the coarse levels.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    formulas::Ctx,
+    grid::{CodeCellLanguage, CodeRun, Grid, SheetId},
+    CellValue, Rect, SheetPos, SheetRect,
+};
+
+/// Base-2 logarithm of the bucket size at each level. The last level has only
+/// four buckets, which together cover every position.
+const LEVEL_SHIFTS: [u32; 16] = [4, 8, 12, 16, 20, 24, 28, 32, 36, 40, 44, 48, 52, 56, 60, 63];
+
+type Bucket = (i64, i64);
+
+/// Index of the bucket containing each corner of `rect`.
+fn bucket_bounds(rect: Rect, shift: u32) -> (Bucket, Bucket) {
+    (
+        (rect.min.x >> shift, rect.min.y >> shift),
+        (rect.max.x >> shift, rect.max.y >> shift),
+    )
+}
+
+/// Number of buckets that `rect` overlaps at a level.
+fn bucket_count(rect: Rect, shift: u32) -> i128 {
+    let (min, max) = bucket_bounds(rect, shift);
+    (max.0 as i128 - min.0 as i128 + 1) * (max.1 as i128 - min.1 as i128 + 1)
+}
+
+/// Returns the level at which `rect` is stored.
+fn level_of(rect: Rect) -> usize {
+    LEVEL_SHIFTS
+        .iter()
+        .position(|&shift| bucket_count(rect, shift) <= 4)
+        .unwrap_or(LEVEL_SHIFTS.len() - 1)
+}
+
+fn buckets_of(rect: Rect, shift: u32) -> impl Iterator<Item = Bucket> {
+    let (min, max) = bucket_bounds(rect, shift);
+    (min.0..=max.0).flat_map(move |x| (min.1..=max.1).map(move |y| (x, y)))
+}
+
+/// Returns the regions that a code run depends on: the cells it accessed, and
+/// for formulas, every whole row and column that it references, which may
+/// extend beyond the cells it accessed.
+pub fn regions_accessed(grid: &Grid, sheet_pos: SheetPos, code_run: &CodeRun) -> Vec<SheetRect> {
+    let mut regions = code_run.cells_accessed.iter().copied().collect::<Vec<_>>();
+    let formula = grid
+        .try_sheet(sheet_pos.sheet_id)
+        .and_then(|sheet| sheet.cell_value(sheet_pos.into()));
+    if let Some(CellValue::Code(code_cell)) = formula {
+        if code_cell.language == CodeCellLanguage::Formula {
+            regions.extend(Ctx::new(grid, sheet_pos).row_col_references(&code_cell.code));
+        }
+    }
+    regions
+}
+
+/// Accessed regions within a single sheet.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct SheetDependencyIndex {
+    /// Buckets for each level in `LEVEL_SHIFTS`.
+    levels: [HashMap<Bucket, HashSet<(Rect, SheetPos)>>; LEVEL_SHIFTS.len()],
+    /// Number of entries stored at each level.
+    level_sizes: [usize; LEVEL_SHIFTS.len()],
+}
+
+impl SheetDependencyIndex {
+    fn insert(&mut self, rect: Rect, dependent: SheetPos) {
+        let level = level_of(rect);
+        let mut inserted = false;
+        for bucket in buckets_of(rect, LEVEL_SHIFTS[level]) {
+            inserted |= self.levels[level]
+                .entry(bucket)
+                .or_default()
+                .insert((rect, dependent));
+        }
+        // the same region may be accessed more than once
+        if inserted {
+            self.level_sizes[level] += 1;
+        }
+    }
+
+    fn remove(&mut self, rect: Rect, dependent: SheetPos) {
+        let level = level_of(rect);
+        let mut removed = false;
+        for bucket in buckets_of(rect, LEVEL_SHIFTS[level]) {
+            if let Some(entries) = self.levels[level].get_mut(&bucket) {
+                removed |= entries.remove(&(rect, dependent));
+                if entries.is_empty() {
+                    self.levels[level].remove(&bucket);
+                }
+            }
+        }
+        if removed {
+            self.level_sizes[level] -= 1;
+        }
+    }
+
+    fn query(&self, rect: Rect, dependents: &mut HashSet<SheetPos>) {
+        for (level, &shift) in LEVEL_SHIFTS.iter().enumerate() {
+            if self.level_sizes[level] == 0 {
+                continue;
+            }
+            let add_intersecting = |entries: &HashSet<(Rect, SheetPos)>| {
+                for (accessed, dependent) in entries {
+                    if accessed.intersects(rect) {
+                        dependents.insert(*dependent);
+                    }
+                }
+            };
+            // Large queries (such as a deleted column) overlap more buckets
+            // than there are entries, so it is faster to check every entry.
+            if bucket_count(rect, shift) > self.levels[level].len() as i128 {
+                self.levels[level].values().for_each(add_intersecting);
+            } else {
+                buckets_of(rect, shift)
+                    .filter_map(|bucket| self.levels[level].get(&bucket))
+                    .for_each(add_intersecting);
+            }
+        }
+    }
+}
+
+/// Index of the cells accessed by every code run in a grid, used to find the
+/// code cells that must rerun when cells change.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DependencyIndex {
+    /// Accessed regions, keyed by the sheet that they are on.
+    sheets: HashMap<SheetId, SheetDependencyIndex>,
+    /// Regions accessed by each code cell, used to update the index when the
+    /// code cell reruns.
+    accessed: HashMap<SheetPos, Vec<SheetRect>>,
+}
+
+impl DependencyIndex {
+    /// Constructs an index of every code run in a grid.
+    pub fn from_grid(grid: &Grid) -> Self {
+        let mut index = Self::default();
+        for sheet in grid.sheets() {
+            index.add_sheet(grid, sheet.id);
+        }
+        index
+    }
+
+    /// Adds every code run in a sheet to the index.
+    pub fn add_sheet(&mut self, grid: &Grid, sheet_id: SheetId) {
+        let Some(sheet) = grid.try_sheet(sheet_id) else {
+            return;
+        };
+        for (pos, code_run) in &sheet.code_runs {
+            let sheet_pos = pos.to_sheet_pos(sheet_id);
+            self.set(sheet_pos, regions_accessed(grid, sheet_pos, code_run));
+        }
+    }
+
+    /// Removes every code run in a sheet from the index. Code runs on other
+    /// sheets that access the sheet are kept.
+    pub fn remove_sheet(&mut self, sheet_id: SheetId) {
+        let dependents = self
+            .accessed
+            .keys()
+            .filter(|dependent| dependent.sheet_id == sheet_id)
+            .copied()
+            .collect::<Vec<_>>();
+        for dependent in dependents {
+            self.remove(dependent);
+        }
+    }
+
+    /// Sets the regions accessed by the code cell at `dependent`, replacing
+    /// any regions it previously accessed.
+    pub fn set(
+        &mut self,
+        dependent: SheetPos,
+        cells_accessed: impl IntoIterator<Item = SheetRect>,
+    ) {
+        self.remove(dependent);
+        let cells_accessed = cells_accessed.into_iter().collect::<Vec<_>>();
+        if cells_accessed.is_empty() {
+            return;
+        }
+        for sheet_rect in &cells_accessed {
+            self.sheets
+                .entry(sheet_rect.sheet_id)
+                .or_default()
+                .insert((*sheet_rect).into(), dependent);
+        }
+        self.accessed.insert(dependent, cells_accessed);
+    }
+
+    /// Removes the code cell at `dependent` from the index.
+    pub fn remove(&mut self, dependent: SheetPos) {
+        let Some(cells_accessed) = self.accessed.remove(&dependent) else {
+            return;
+        };
+        for sheet_rect in cells_accessed {
+            if let Some(sheet_index) = self.sheets.get_mut(&sheet_rect.sheet_id) {
+                sheet_index.remove(sheet_rect.into(), dependent);
+                if sheet_index.level_sizes.iter().all(|&size| size == 0) {
+                    self.sheets.remove(&sheet_rect.sheet_id);
+                }
+            }
+        }
+    }
+
+    /// Returns every code cell that accesses a cell in `sheet_rect`.
+    pub fn dependents(&self, sheet_rect: &SheetRect) -> HashSet<SheetPos> {
+        let mut dependents = HashSet::new();
+        if let Some(sheet_index) = self.sheets.get(&sheet_rect.sheet_id) {
+            sheet_index.query((*sheet_rect).into(), &mut dependents);
+        }
+        dependents
+    }
+
+    /// Returns the number of code cells in the index.
+    pub fn len(&self) -> usize {
+        self.accessed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accessed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pos;
+
+    fn rect(x1: i64, y1: i64, x2: i64, y2: i64, sheet_id: SheetId) -> SheetRect {
+        SheetRect::new_pos_span(Pos { x: x1, y: y1 }, Pos { x: x2, y: y2 }, sheet_id)
+    }
+
+    #[test]
+    fn test_dependency_index() {
+        let sheet_id = SheetId::new();
+        let other_sheet_id = SheetId::new();
+        let mut index = DependencyIndex::default();
+
+        let a = SheetPos::from((0, 0, sheet_id));
+        let b = SheetPos::from((5, 5, sheet_id));
+        let c = SheetPos::from((0, 0, other_sheet_id));
+        index.set(a, [rect(1, 1, 2, 2, sheet_id)]);
+        index.set(b, [rect(1, i64::MIN, 1, i64::MAX, sheet_id)]);
+        index.set(c, [rect(-100, -100, 1000, 1000, sheet_id)]);
+        assert_eq!(index.len(), 3);
+
+        let query = |index: &DependencyIndex, x, y| {
+            index.dependents(&SheetRect::single_pos(Pos { x, y }, sheet_id))
+        };
+        assert_eq!(query(&index, 1, 1), HashSet::from([a, b, c]));
+        assert_eq!(query(&index, 2, 2), HashSet::from([a, c]));
+        assert_eq!(query(&index, 1, -5000), HashSet::from([b]));
+        assert_eq!(query(&index, 3, 3), HashSet::from([c]));
+        assert_eq!(query(&index, 2000, 3), HashSet::new());
+        assert_eq!(
+            index.dependents(&rect(i64::MIN, 3, i64::MAX, 3, sheet_id)),
+            HashSet::from([b, c]),
+        );
+        assert_eq!(
+            index.dependents(&SheetRect::single_pos(Pos { x: 1, y: 1 }, other_sheet_id)),
+            HashSet::new(),
+        );
+
+        // replacing the accessed cells removes the old ones
+        index.set(a, [rect(10, 10, 10, 10, sheet_id)]);
+        assert_eq!(query(&index, 1, 1), HashSet::from([b, c]));
+        assert_eq!(query(&index, 10, 10), HashSet::from([a, c]));
+
+        index.remove(b);
+        assert_eq!(query(&index, 1, 1), HashSet::from([c]));
+
+        index.remove_sheet(other_sheet_id);
+        assert_eq!(query(&index, 10, 10), HashSet::from([a]));
+
+        index.set(a, []);
+        assert!(index.is_empty());
+        assert_eq!(index, DependencyIndex::default());
+
+        // a region that is accessed twice is only counted once
+        let region = rect(1, 1, 2, 2, sheet_id);
+        index.set(a, [region, region]);
+        assert_eq!(index.sheets[&sheet_id].level_sizes[0], 1);
+        index.set(a, [region]);
+        assert_eq!(index.sheets[&sheet_id].level_sizes[0], 1);
+        assert_eq!(query(&index, 1, 1), HashSet::from([a]));
+        index.remove(a);
+        assert_eq!(index, DependencyIndex::default());
+    }
+}
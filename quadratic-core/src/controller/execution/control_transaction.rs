@@ -17,9 +17,19 @@ impl GridController {
     // loop compute cycle until complete or an async call is made
     pub(super) fn start_transaction(&mut self, transaction: &mut PendingTransaction) {
         loop {
-            if transaction.operations.is_empty() {
-                transaction.complete = true;
-                break;
+            match transaction.operations.front() {
+                None => {
+                    transaction.complete = true;
+                    break;
+                }
+                Some(Operation::ComputeCode { sheet_pos }) => {
+                    if !transaction.code_cells_scheduled.contains(sheet_pos) {
+                        self.order_compute_operations(transaction);
+                    }
+                }
+                // code cells may be changed by other operations, so they are
+                // computed again in the next recalculation
+                Some(_) => transaction.code_cells_scheduled.clear(),
             }
 
             self.execute_operation(transaction);
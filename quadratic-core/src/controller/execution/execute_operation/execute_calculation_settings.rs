@@ -0,0 +1,80 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub(crate) fn execute_set_calculation_settings(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        if let Operation::SetCalculationSettings { settings } = op {
+            let old_settings = self.grid.set_calculation_settings(settings.clone());
+
+            // circular references are calculated differently, so recalculate
+            // the formulas in them; their dependents are recalculated too
+            if transaction.is_user() && old_settings != settings {
+                for sheet_pos in self.circular_formulas() {
+                    if !transaction.code_cells_scheduled.contains(&sheet_pos) {
+                        transaction
+                            .operations
+                            .push_back(Operation::ComputeCode { sheet_pos });
+                    }
+                }
+            }
+
+            transaction
+                .forward_operations
+                .push(Operation::SetCalculationSettings { settings });
+            transaction.reverse_operations.insert(
+                0,
+                Operation::SetCalculationSettings {
+                    settings: old_settings,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{controller::GridController, grid::CalculationSettings};
+
+    #[test]
+    fn test_set_calculation_settings() {
+        let mut gc = GridController::test();
+        let settings = CalculationSettings {
+            iterative: true,
+            ..Default::default()
+        };
+        let summary = gc.set_calculation_settings(settings.clone(), None);
+        assert!(summary.save);
+        assert_eq!(gc.grid().calculation_settings(), &settings);
+
+        gc.undo(None);
+        assert_eq!(
+            gc.grid().calculation_settings(),
+            &CalculationSettings::default()
+        );
+        gc.redo(None);
+        assert_eq!(gc.grid().calculation_settings(), &settings);
+    }
+
+    #[test]
+    fn test_set_invalid_calculation_settings() {
+        let mut gc = GridController::test();
+        let summary = gc.set_calculation_settings(
+            CalculationSettings {
+                max_iterations: 0,
+                ..Default::default()
+            },
+            None,
+        );
+        assert!(summary.error.is_some());
+        assert_eq!(
+            gc.grid().calculation_settings(),
+            &CalculationSettings::default()
+        );
+    }
+}
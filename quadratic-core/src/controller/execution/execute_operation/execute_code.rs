@@ -22,22 +22,48 @@ impl GridController {
                     if !skip_compute
                         .is_some_and(|skip_compute| skip_compute == *code_cell_sheet_pos)
                     {
-                        // only add a compute operation if there isn't already one pending
-                        if !transaction.operations.iter().any(|op| match op {
-                            Operation::ComputeCode { sheet_pos } => {
-                                code_cell_sheet_pos == sheet_pos
-                            }
-                            _ => false,
-                        }) {
-                            transaction.operations.push_back(Operation::ComputeCode {
-                                sheet_pos: *code_cell_sheet_pos,
-                            });
-                        }
+                        Self::add_compute_operation(transaction, *code_cell_sheet_pos);
                     }
                 });
             });
     }
 
+    /// Adds operations to compute formulas that refer to the named range
+    /// `name`.
+    pub fn add_named_range_compute_operations(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        name: &str,
+    ) {
+        self.get_named_range_dependent_code_cells(name)
+            .iter()
+            .for_each(|sheet_positions| {
+                sheet_positions.iter().for_each(|code_cell_sheet_pos| {
+                    Self::add_compute_operation(transaction, *code_cell_sheet_pos);
+                });
+            });
+    }
+
+    pub(super) fn add_compute_operation(
+        transaction: &mut PendingTransaction,
+        code_cell_sheet_pos: SheetPos,
+    ) {
+        // only add a compute operation if the code cell hasn't already been
+        // computed in this recalculation and there isn't already one pending
+        if !transaction
+            .code_cells_scheduled
+            .contains(&code_cell_sheet_pos)
+            && !transaction.operations.iter().any(|op| match op {
+                Operation::ComputeCode { sheet_pos } => code_cell_sheet_pos == *sheet_pos,
+                _ => false,
+            })
+        {
+            transaction.operations.push_back(Operation::ComputeCode {
+                sheet_pos: code_cell_sheet_pos,
+            });
+        }
+    }
+
     // delete any code runs within the sheet_rect.
     pub(super) fn check_deleted_code_runs(
         &mut self,
@@ -115,6 +141,15 @@ impl GridController {
                 _ => return,
             };
 
+            if let Some(cells_accessed) = transaction.circular_code_cells.remove(&sheet_pos) {
+                self.set_circular_reference_error(transaction, sheet_pos, cells_accessed);
+                return;
+            }
+            if let Some(cycle) = transaction.iterative_cycles.remove(&sheet_pos) {
+                self.calculate_iteratively(transaction, cycle);
+                return;
+            }
+
             match language {
                 CodeCellLanguage::Python => {
                     self.run_python(transaction, sheet_pos, code);
@@ -0,0 +1,192 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub(crate) fn execute_add_named_range(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        if let Operation::AddNamedRange { named_range } = op {
+            let name = named_range.name.clone();
+            let old_named_range = self.grid.set_named_range(named_range.clone());
+            transaction.summary.named_ranges_modified = true;
+
+            // formulas that refer to the name may now refer to different cells
+            if transaction.is_user() {
+                self.add_named_range_compute_operations(transaction, &name);
+            }
+
+            transaction
+                .forward_operations
+                .push(Operation::AddNamedRange { named_range });
+            let reverse_operation = match old_named_range {
+                Some(named_range) => Operation::AddNamedRange { named_range },
+                None => Operation::DeleteNamedRange { name },
+            };
+            transaction.reverse_operations.insert(0, reverse_operation);
+        }
+    }
+
+    pub(crate) fn execute_rename_named_range(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        if let Operation::RenameNamedRange { old_name, new_name } = op {
+            if !self.grid.rename_named_range(&old_name, &new_name) {
+                // named range may have been deleted or the name may be taken
+                return;
+            }
+            transaction.summary.named_ranges_modified = true;
+
+            if transaction.is_user() {
+                self.add_named_range_compute_operations(transaction, &old_name);
+                self.add_named_range_compute_operations(transaction, &new_name);
+            }
+
+            transaction
+                .forward_operations
+                .push(Operation::RenameNamedRange {
+                    old_name: old_name.clone(),
+                    new_name: new_name.clone(),
+                });
+            transaction.reverse_operations.insert(
+                0,
+                Operation::RenameNamedRange {
+                    old_name: new_name,
+                    new_name: old_name,
+                },
+            );
+        }
+    }
+
+    pub(crate) fn execute_delete_named_range(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        if let Operation::DeleteNamedRange { name } = op {
+            let Some(deleted_named_range) = self.grid.remove_named_range(&name) else {
+                // named range was already deleted
+                return;
+            };
+            transaction.summary.named_ranges_modified = true;
+
+            if transaction.is_user() {
+                self.add_named_range_compute_operations(transaction, &name);
+            }
+
+            transaction
+                .forward_operations
+                .push(Operation::DeleteNamedRange { name });
+            transaction.reverse_operations.insert(
+                0,
+                Operation::AddNamedRange {
+                    named_range: deleted_named_range,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        controller::GridController,
+        grid::{CodeCellLanguage, NamedRange},
+        CellValue, Pos, SheetPos, SheetRect,
+    };
+
+    #[test]
+    fn test_add_named_range() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_rect = SheetRect::single_pos(Pos { x: 0, y: 1 }, sheet_id);
+        let summary = gc.add_named_range("TaxRate".to_string(), sheet_rect, None);
+        assert!(summary.save);
+        assert!(summary.named_ranges_modified);
+        assert_eq!(
+            gc.grid().named_ranges(),
+            [NamedRange {
+                name: "TaxRate".to_string(),
+                sheet_rect: Some(sheet_rect),
+            }]
+        );
+
+        gc.undo(None);
+        assert!(gc.grid().named_ranges().is_empty());
+        gc.redo(None);
+        assert_eq!(gc.grid().named_ranges().len(), 1);
+    }
+
+    #[test]
+    fn test_add_named_range_invalid_name() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_rect = SheetRect::single_pos(Pos { x: 0, y: 1 }, sheet_id);
+        let summary = gc.add_named_range("A1".to_string(), sheet_rect, None);
+        assert!(summary.error.is_some());
+        assert!(gc.grid().named_ranges().is_empty());
+    }
+
+    #[test]
+    fn test_rename_and_delete_named_range() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_rect = SheetRect::single_pos(Pos { x: 0, y: 1 }, sheet_id);
+        gc.add_named_range("TaxRate".to_string(), sheet_rect, None);
+
+        gc.rename_named_range("TaxRate".to_string(), "Tax_Rate".to_string(), None);
+        assert!(gc.grid().named_range("TaxRate").is_none());
+        assert!(gc.grid().named_range("Tax_Rate").is_some());
+        gc.undo(None);
+        assert!(gc.grid().named_range("TaxRate").is_some());
+
+        gc.delete_named_range("TaxRate".to_string(), None);
+        assert!(gc.grid().named_ranges().is_empty());
+        gc.undo(None);
+        assert_eq!(gc.grid().named_ranges()[0].sheet_rect, Some(sheet_rect));
+    }
+
+    #[test]
+    fn test_named_range_dependents() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 1), "1".to_string(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 2), "2".to_string(), None);
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 1, 1),
+            CodeCellLanguage::Formula,
+            "TaxRate * 10".to_string(),
+            None,
+        );
+        let value = |gc: &GridController| gc.sheet(sheet_id).display_value(Pos { x: 1, y: 1 });
+        assert_eq!(value(&gc), None);
+
+        // creating the name computes formulas that already use it
+        gc.add_named_range(
+            "TaxRate".to_string(),
+            SheetRect::single_pos(Pos { x: 0, y: 1 }, sheet_id),
+            None,
+        );
+        assert_eq!(value(&gc), Some(CellValue::Number(10.into())));
+
+        // changing a cell in the named range computes dependents
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 1), "3".to_string(), None);
+        assert_eq!(value(&gc), Some(CellValue::Number(30.into())));
+
+        // redefining the name computes dependents
+        gc.add_named_range(
+            "taxrate".to_string(),
+            SheetRect::single_pos(Pos { x: 0, y: 2 }, sheet_id),
+            None,
+        );
+        assert_eq!(value(&gc), Some(CellValue::Number(20.into())));
+
+        gc.delete_named_range("TaxRate".to_string(), None);
+        assert_eq!(value(&gc), None);
+    }
+}
@@ -0,0 +1,562 @@
+use std::collections::HashSet;
+
+use crate::{
+    cell_values::CellValues,
+    controller::{
+        active_transactions::pending_transaction::PendingTransaction,
+        operations::operation::Operation, GridController,
+    },
+    formulas::replace_cell_references,
+    grid::{sheet::rows_columns::GridShift, CodeCellLanguage, GridBounds, SheetId},
+    Axis, CellValue, CodeCellValue, Pos, Rect, SheetPos,
+};
+
+/// A formula whose text changes because of a shift.
+struct ShiftedFormula {
+    old_pos: SheetPos,
+    new_pos: SheetPos,
+    old_value: CellValue,
+    new_code: String,
+}
+
+fn shift_operation(sheet_id: SheetId, shift: GridShift) -> Operation {
+    match shift {
+        GridShift::InsertColumn(column) => Operation::InsertColumn { sheet_id, column },
+        GridShift::DeleteColumn(column) => Operation::DeleteColumn { sheet_id, column },
+        GridShift::InsertRow(row) => Operation::InsertRow { sheet_id, row },
+        GridShift::DeleteRow(row) => Operation::DeleteRow { sheet_id, row },
+    }
+}
+
+impl GridController {
+    pub fn execute_insert_column(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::InsertColumn { sheet_id, column } = op {
+            self.execute_shift(transaction, sheet_id, GridShift::InsertColumn(column));
+        }
+    }
+
+    pub fn execute_delete_column(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::DeleteColumn { sheet_id, column } = op {
+            self.execute_shift(transaction, sheet_id, GridShift::DeleteColumn(column));
+        }
+    }
+
+    pub fn execute_insert_row(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::InsertRow { sheet_id, row } = op {
+            self.execute_shift(transaction, sheet_id, GridShift::InsertRow(row));
+        }
+    }
+
+    pub fn execute_delete_row(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        if let Operation::DeleteRow { sheet_id, row } = op {
+            self.execute_shift(transaction, sheet_id, GridShift::DeleteRow(row));
+        }
+    }
+
+    fn execute_shift(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        sheet_id: SheetId,
+        shift: GridShift,
+    ) {
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            // sheet may have been deleted
+            return;
+        };
+        let old_bounds = sheet.bounds(false);
+
+        // The reverse operations re-insert (or delete) the row/column, then
+        // restore its contents and the text of any rewritten formulas.
+        let mut reverse_operations = vec![shift_operation(sheet_id, shift.inverse())];
+        let mut dependents = HashSet::new();
+        if !shift.is_insert() {
+            let (slice_operations, slice_rect) = self.deleted_slice_operations(sheet_id, shift);
+            reverse_operations.extend(slice_operations);
+            if let Some(slice_rect) = slice_rect {
+                dependents = self
+                    .get_dependent_code_cells(&slice_rect.to_sheet_rect(sheet_id))
+                    .unwrap_or_default();
+            }
+        }
+        let formulas = self.shifted_formulas(sheet_id, shift);
+
+        let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+            return;
+        };
+        sheet.shift(shift);
+
+        let named_ranges = self.grid.shift_named_ranges(sheet_id, shift);
+        reverse_operations.extend(named_ranges.iter().map(|named_range| {
+            Operation::AddNamedRange {
+                named_range: named_range.clone(),
+            }
+        }));
+
+        for formula in &formulas {
+            if let Some(sheet) = self.try_sheet_mut(formula.new_pos.sheet_id) {
+                sheet.set_cell_value(
+                    formula.new_pos.into(),
+                    CellValue::Code(CodeCellValue {
+                        language: CodeCellLanguage::Formula,
+                        code: formula.new_code.clone(),
+                    }),
+                );
+            }
+            reverse_operations.push(Operation::SetCellValues {
+                sheet_pos: formula.old_pos,
+                values: CellValues::from(formula.old_value.clone()),
+            });
+        }
+
+        for sheet in self.grid.sheets_mut() {
+            for code_run in sheet.code_runs.values_mut() {
+                code_run.cells_accessed = std::mem::take(&mut code_run.cells_accessed)
+                    .into_iter()
+                    .filter_map(|sheet_rect| {
+                        if sheet_rect.sheet_id != sheet_id {
+                            return Some(sheet_rect);
+                        }
+                        Some(shift.shift_rect(sheet_rect.into())?.to_sheet_rect(sheet_id))
+                    })
+                    .collect();
+            }
+        }
+        self.rebuild_dependency_index();
+
+        transaction
+            .forward_operations
+            .push(shift_operation(sheet_id, shift));
+
+        // prepare summary
+        transaction.sheets_with_dirty_bounds.insert(sheet_id);
+        transaction.summary.fill_sheets_modified.insert(sheet_id);
+        transaction.summary.border_sheets_modified.insert(sheet_id);
+        transaction.summary.code_cells_modified.insert(sheet_id);
+        transaction.summary.offsets_modified.insert(sheet_id);
+        transaction.summary.html.insert(sheet_id);
+        transaction.summary.named_ranges_modified |= !named_ranges.is_empty();
+        if let Some(sheet) = self.try_sheet(sheet_id) {
+            let bounds = GridBounds::merge(old_bounds, sheet.bounds(false));
+            if let GridBounds::NonEmpty(mut rect) = bounds {
+                let index = shift.index();
+                match shift.axis() {
+                    Axis::X => rect.min.x = rect.min.x.max(index),
+                    Axis::Y => rect.min.y = rect.min.y.max(index),
+                }
+                if rect.min.x <= rect.max.x && rect.min.y <= rect.max.y {
+                    let sheet_rect = rect.to_sheet_rect(sheet_id);
+                    transaction
+                        .summary
+                        .add_cell_sheets_modified_rect(&sheet_rect);
+                    transaction.summary.generate_thumbnail |=
+                        self.thumbnail_dirty_sheet_rect(&sheet_rect);
+                }
+            }
+        }
+        for formula in &formulas {
+            transaction
+                .summary
+                .add_cell_sheets_modified_rect(&formula.new_pos.into());
+        }
+
+        if transaction.is_user() {
+            let to_compute = formulas.iter().map(|formula| formula.new_pos).chain(
+                dependents.into_iter().filter_map(|sheet_pos| {
+                    if sheet_pos.sheet_id != sheet_id {
+                        return Some(sheet_pos);
+                    }
+                    let pos = shift.shift_pos(sheet_pos.into())?;
+                    Some(pos.to_sheet_pos(sheet_id))
+                }),
+            );
+            for sheet_pos in to_compute {
+                Self::add_compute_operation(transaction, sheet_pos);
+            }
+            for named_range in &named_ranges {
+                self.add_named_range_compute_operations(transaction, &named_range.name);
+            }
+            self.check_all_spills(transaction, sheet_id);
+        }
+
+        transaction
+            .reverse_operations
+            .splice(0..0, reverse_operations);
+    }
+
+    /// Returns operations that restore the contents of the row or column
+    /// deleted by `shift` after it has been re-inserted, along with the region
+    /// of that row or column that has any contents.
+    fn deleted_slice_operations(
+        &self,
+        sheet_id: SheetId,
+        shift: GridShift,
+    ) -> (Vec<Operation>, Option<Rect>) {
+        let mut ops = vec![];
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return (ops, None);
+        };
+        let index = shift.index();
+
+        let bounds = match shift.axis() {
+            Axis::X => {
+                let size = sheet.offsets.column_width(index);
+                if size != crate::DEFAULT_COLUMN_WIDTH {
+                    ops.push(Operation::ResizeColumn {
+                        sheet_id,
+                        column: index,
+                        new_size: size,
+                    });
+                }
+                sheet.column_bounds(index, false)
+            }
+            Axis::Y => {
+                let size = sheet.offsets.row_height(index);
+                if size != crate::DEFAULT_ROW_HEIGHT {
+                    ops.push(Operation::ResizeRow {
+                        sheet_id,
+                        row: index,
+                        new_size: size,
+                    });
+                }
+                sheet.row_bounds(index, false)
+            }
+        };
+        let range = crate::util::union_ranges([
+            bounds.map(|(start, end)| start..end + 1),
+            sheet.borders().range_in_shift(shift),
+        ]);
+        let Some(range) = range else {
+            return (ops, None);
+        };
+        let rect = match shift.axis() {
+            Axis::X => Rect::new_span(
+                Pos {
+                    x: index,
+                    y: range.start,
+                },
+                Pos {
+                    x: index,
+                    y: range.end - 1,
+                },
+            ),
+            Axis::Y => Rect::new_span(
+                Pos {
+                    x: range.start,
+                    y: index,
+                },
+                Pos {
+                    x: range.end - 1,
+                    y: index,
+                },
+            ),
+        };
+        let sheet_rect = rect.to_sheet_rect(sheet_id);
+
+        let mut values = CellValues::new(rect.width(), rect.height());
+        for pos in rect.iter() {
+            if let Some(value) = sheet.cell_value(pos) {
+                values.set(
+                    (pos.x - rect.min.x) as u32,
+                    (pos.y - rect.min.y) as u32,
+                    value,
+                );
+            }
+        }
+        if values.into_iter().next().is_some() {
+            ops.push(Operation::SetCellValues {
+                sheet_pos: rect.min.to_sheet_pos(sheet_id),
+                values,
+            });
+        }
+
+        for attr in self.get_all_cell_formats(sheet_rect) {
+            ops.push(Operation::SetCellFormats { sheet_rect, attr });
+        }
+        ops.push(Operation::SetBorders {
+            sheet_rect,
+            borders: sheet.borders().clone_rect(&rect),
+        });
+
+        for (index, (pos, code_run)) in sheet.code_runs.iter().enumerate() {
+            if shift.shift_pos(*pos).is_none() {
+                ops.push(Operation::SetCodeRun {
+                    sheet_pos: pos.to_sheet_pos(sheet_id),
+                    code_run: Some(code_run.clone()),
+                    index,
+                });
+            }
+        }
+
+        (ops, Some(rect))
+    }
+
+    /// Finds all formulas, in all sheets, whose text changes because of
+    /// `shift`. Formulas in a deleted row or column are skipped.
+    fn shifted_formulas(&self, sheet_id: SheetId, shift: GridShift) -> Vec<ShiftedFormula> {
+        let Some(shifted_sheet) = self.try_sheet(sheet_id) else {
+            return vec![];
+        };
+        let mut formulas = vec![];
+        for sheet in self.grid.sheets() {
+            let is_shifted = |sheet_ref: &Option<String>| match sheet_ref {
+                Some(sheet_ref) => self
+                    .grid
+                    .try_sheet_from_formula_ref(sheet_ref)
+                    .is_some_and(|sheet| sheet.id == shifted_sheet.id),
+                None => sheet.id == sheet_id,
+            };
+            for (&x, column) in sheet.iter_columns() {
+                for (&y, value) in &column.values {
+                    let CellValue::Code(code_cell) = value else {
+                        continue;
+                    };
+                    if code_cell.language != CodeCellLanguage::Formula {
+                        continue;
+                    }
+                    let old_pos = Pos { x, y };
+                    let new_pos = if sheet.id == sheet_id {
+                        match shift.shift_pos(old_pos) {
+                            Some(pos) => pos,
+                            None => continue,
+                        }
+                    } else {
+                        old_pos
+                    };
+                    let new_code =
+                        replace_cell_references(&code_cell.code, old_pos, new_pos, |range_ref| {
+                            shift.shift_range_ref(range_ref, old_pos, new_pos, is_shifted)
+                        });
+                    if new_code != code_cell.code {
+                        formulas.push(ShiftedFormula {
+                            old_pos: old_pos.to_sheet_pos(sheet.id),
+                            new_pos: new_pos.to_sheet_pos(sheet.id),
+                            old_value: value.clone(),
+                            new_code,
+                        });
+                    }
+                }
+            }
+        }
+        formulas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+
+    use crate::{
+        controller::GridController,
+        grid::{Bold, CodeCellLanguage},
+        CellValue, Pos, SheetPos, SheetRect,
+    };
+
+    fn formula_at(gc: &GridController, sheet_pos: SheetPos) -> Option<String> {
+        let sheet = gc.try_sheet(sheet_pos.sheet_id).unwrap();
+        match sheet.cell_value(sheet_pos.into()) {
+            Some(CellValue::Code(code)) => Some(gc.grid().formula_sheet_ids_to_names(&code.code)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_insert_row() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_pos = |x, y| SheetPos { x, y, sheet_id };
+        gc.set_cell_value(sheet_pos(0, 0), "1".into(), None);
+        gc.set_cell_value(sheet_pos(0, 1), "2".into(), None);
+        gc.set_code_cell(
+            sheet_pos(1, 2),
+            CodeCellLanguage::Formula,
+            "A0 + A1".into(),
+            None,
+        );
+        gc.set_cell_bold(sheet_pos(0, 1).into(), Some(true), None);
+
+        let summary = gc.insert_row(sheet_id, 1, None);
+        assert!(summary.save);
+
+        let sheet = gc.try_sheet(sheet_id).unwrap();
+        assert_eq!(sheet.cell_value(Pos { x: 0, y: 1 }), None);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 2 }),
+            Some(CellValue::Number(BigDecimal::from(2)))
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 0, y: 2 }),
+            Some(true)
+        );
+        assert_eq!(formula_at(&gc, sheet_pos(1, 3)), Some("A0 + A2".into()));
+        assert_eq!(
+            sheet.display_value(Pos { x: 1, y: 3 }),
+            Some(CellValue::Number(BigDecimal::from(3)))
+        );
+
+        gc.undo(None);
+        let sheet = gc.try_sheet(sheet_id).unwrap();
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 1 }),
+            Some(CellValue::Number(BigDecimal::from(2)))
+        );
+        assert_eq!(formula_at(&gc, sheet_pos(1, 2)), Some("A0 + A1".into()));
+        assert_eq!(
+            sheet.display_value(Pos { x: 1, y: 2 }),
+            Some(CellValue::Number(BigDecimal::from(3)))
+        );
+
+        gc.redo(None);
+        assert_eq!(formula_at(&gc, sheet_pos(1, 3)), Some("A0 + A2".into()));
+    }
+
+    #[test]
+    fn test_delete_column() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_pos = |x, y| SheetPos { x, y, sheet_id };
+        gc.set_cell_value(sheet_pos(0, 0), "1".into(), None);
+        gc.set_cell_value(sheet_pos(1, 0), "2".into(), None);
+        gc.set_cell_value(sheet_pos(2, 0), "3".into(), None);
+        gc.set_cell_bold(sheet_pos(1, 0).into(), Some(true), None);
+        gc.commit_single_resize(sheet_id, Some(1), None, 200.0, None);
+        gc.set_code_cell(
+            sheet_pos(3, 1),
+            CodeCellLanguage::Formula,
+            "SUM(A0:C0)".into(),
+            None,
+        );
+        gc.set_code_cell(
+            sheet_pos(4, 1),
+            CodeCellLanguage::Formula,
+            "B0".into(),
+            None,
+        );
+
+        gc.delete_column(sheet_id, 1, None);
+
+        let sheet = gc.try_sheet(sheet_id).unwrap();
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(BigDecimal::from(3)))
+        );
+        assert_eq!(sheet.get_formatting_value::<Bold>(Pos { x: 1, y: 0 }), None);
+        assert_eq!(sheet.offsets.column_width(1), crate::DEFAULT_COLUMN_WIDTH);
+        assert_eq!(formula_at(&gc, sheet_pos(2, 1)), Some("SUM(A0:B0)".into()));
+        assert_eq!(
+            sheet.display_value(Pos { x: 2, y: 1 }),
+            Some(CellValue::Number(BigDecimal::from(4)))
+        );
+        assert_eq!(formula_at(&gc, sheet_pos(3, 1)), Some("#REF!".into()));
+        assert!(sheet.code_runs[&Pos { x: 3, y: 1 }].get_error().is_some());
+
+        gc.undo(None);
+        let sheet = gc.try_sheet(sheet_id).unwrap();
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(BigDecimal::from(2)))
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 1, y: 0 }),
+            Some(true)
+        );
+        assert_eq!(sheet.offsets.column_width(1), 200.0);
+        assert_eq!(formula_at(&gc, sheet_pos(3, 1)), Some("SUM(A0:C0)".into()));
+        assert_eq!(formula_at(&gc, sheet_pos(4, 1)), Some("B0".into()));
+        assert_eq!(
+            sheet.display_value(Pos { x: 4, y: 1 }),
+            Some(CellValue::Number(BigDecimal::from(2)))
+        );
+    }
+
+    #[test]
+    fn test_shift_updates_other_sheets() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.add_sheet(None);
+        let other_id = gc.sheet_ids()[1];
+        let name = gc.try_sheet(sheet_id).unwrap().name.clone();
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 5,
+                sheet_id,
+            },
+            "7".into(),
+            None,
+        );
+        let other_pos = SheetPos {
+            x: 0,
+            y: 0,
+            sheet_id: other_id,
+        };
+        gc.set_code_cell(
+            other_pos,
+            CodeCellLanguage::Formula,
+            format!("'{name}'!A5 + A5"),
+            None,
+        );
+
+        gc.insert_row(sheet_id, 0, None);
+        assert_eq!(
+            formula_at(&gc, other_pos),
+            Some(format!("'{name}'!A6 + A5"))
+        );
+        assert_eq!(
+            gc.try_sheet(other_id)
+                .unwrap()
+                .display_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(BigDecimal::from(7)))
+        );
+    }
+
+    #[test]
+    fn test_shift_named_ranges() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        for y in 0..3 {
+            gc.set_cell_value(SheetPos::new(sheet_id, 0, y), (y + 1).to_string(), None);
+        }
+        let revenue = SheetRect::new_pos_span(Pos { x: 0, y: 0 }, Pos { x: 0, y: 2 }, sheet_id);
+        gc.add_named_range("Revenue".to_string(), revenue, None);
+        gc.add_named_range(
+            "TaxRate".to_string(),
+            SheetRect::single_pos(Pos { x: 0, y: 1 }, sheet_id),
+            None,
+        );
+        gc.add_sheet(None);
+        let total_pos = SheetPos::new(gc.sheet_ids()[1], 0, 0);
+        gc.set_code_cell(
+            total_pos,
+            CodeCellLanguage::Formula,
+            "SUM(Revenue)".to_string(),
+            None,
+        );
+        let sheet_rect =
+            |gc: &GridController, name| gc.grid().named_range(name).unwrap().sheet_rect;
+        let total =
+            |gc: &GridController| gc.sheet(total_pos.sheet_id).display_value(total_pos.into());
+
+        // inserting a row moves the cells in the named range
+        gc.insert_row(sheet_id, 0, None);
+        let shifted = SheetRect::new_pos_span(Pos { x: 0, y: 1 }, Pos { x: 0, y: 3 }, sheet_id);
+        assert_eq!(sheet_rect(&gc, "Revenue"), Some(shifted));
+        assert_eq!(total(&gc), Some(CellValue::Number(6.into())));
+
+        // deleting a row clips the named range and removes single cells
+        gc.delete_row(sheet_id, 2, None);
+        let clipped = SheetRect::new_pos_span(Pos { x: 0, y: 1 }, Pos { x: 0, y: 2 }, sheet_id);
+        assert_eq!(sheet_rect(&gc, "Revenue"), Some(clipped));
+        assert_eq!(sheet_rect(&gc, "TaxRate"), None);
+        assert_eq!(total(&gc), Some(CellValue::Number(4.into())));
+
+        gc.undo(None);
+        assert_eq!(sheet_rect(&gc, "Revenue"), Some(shifted));
+        assert!(sheet_rect(&gc, "TaxRate").is_some());
+        gc.undo(None);
+        assert_eq!(sheet_rect(&gc, "Revenue"), Some(revenue));
+        gc.redo(None);
+        gc.redo(None);
+        assert_eq!(sheet_rect(&gc, "Revenue"), Some(clipped));
+        assert_eq!(sheet_rect(&gc, "TaxRate"), None);
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
         operations::operation::Operation, GridController,
     },
     grid::{Sheet, SheetId},
+    Pos, SheetRect,
 };
 
 impl GridController {
@@ -19,6 +20,7 @@ impl GridController {
                 return;
             }
             self.grid.add_sheet(Some(sheet.clone()));
+            self.dependency_index.add_sheet(&self.grid, sheet_id);
             transaction.summary.sheet_list_modified = true;
             transaction.summary.html.insert(sheet_id);
             transaction
@@ -40,6 +42,42 @@ impl GridController {
                 // sheet was already deleted
                 return;
             };
+            self.dependency_index.remove_sheet(sheet_id);
+
+            // named ranges in the deleted sheet become bad cell references;
+            // they are restored after the sheet is re-added
+            let named_ranges = self.grid.remove_sheet_from_named_ranges(sheet_id);
+            if !named_ranges.is_empty() {
+                transaction.summary.named_ranges_modified = true;
+            }
+            transaction.reverse_operations.splice(
+                0..0,
+                named_ranges
+                    .iter()
+                    .map(|named_range| Operation::AddNamedRange {
+                        named_range: named_range.clone(),
+                    }),
+            );
+
+            // formulas that reference the deleted sheet now evaluate to a bad
+            // cell reference
+            if transaction.is_user() {
+                for named_range in &named_ranges {
+                    self.add_named_range_compute_operations(transaction, &named_range.name);
+                }
+                let sheet_rect = SheetRect::new_pos_span(
+                    Pos {
+                        x: i64::MIN,
+                        y: i64::MIN,
+                    },
+                    Pos {
+                        x: i64::MAX,
+                        y: i64::MAX,
+                    },
+                    sheet_id,
+                );
+                self.add_compute_operations(transaction, &sheet_rect, None);
+            }
 
             // create a sheet if we deleted the last one (only for user actions)
             if transaction.is_user() && self.sheet_ids().is_empty() {
@@ -168,7 +206,11 @@ impl GridController {
 
 #[cfg(test)]
 mod tests {
-    use crate::controller::GridController;
+    use crate::{
+        controller::GridController,
+        grid::{CodeCellLanguage, CodeRunResult},
+        CellValue, Pos, RunErrorMsg, SheetPos, SheetRect,
+    };
 
     #[test]
     fn test_add_sheet() {
@@ -198,6 +240,47 @@ mod tests {
         assert!(summary.sheet_list_modified);
     }
 
+    #[test]
+    fn test_delete_sheet_named_range() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.add_sheet(None);
+        let other_id = gc.sheet_ids()[1];
+        gc.set_cell_value(SheetPos::new(other_id, 0, 0), "5".to_string(), None);
+        let sheet_rect = SheetRect::single_pos(Pos { x: 0, y: 0 }, other_id);
+        gc.add_named_range("TaxRate".to_string(), sheet_rect, None);
+        let pos = Pos { x: 0, y: 0 };
+        gc.set_code_cell(
+            pos.to_sheet_pos(sheet_id),
+            CodeCellLanguage::Formula,
+            "TaxRate * 2".to_string(),
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(pos),
+            Some(CellValue::Number(10.into()))
+        );
+
+        let summary = gc.delete_sheet(other_id, None);
+        assert!(summary.named_ranges_modified);
+        assert_eq!(gc.grid.named_range("TaxRate").unwrap().sheet_rect, None);
+        let code_run = gc.sheet(sheet_id).code_run(pos).unwrap();
+        match &code_run.result {
+            CodeRunResult::Err(error) => assert_eq!(error.msg, RunErrorMsg::BadCellReference),
+            CodeRunResult::Ok(_) => panic!("expected an error"),
+        }
+
+        gc.undo(None);
+        assert_eq!(
+            gc.grid.named_range("TaxRate").unwrap().sheet_rect,
+            Some(sheet_rect)
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(pos),
+            Some(CellValue::Number(10.into()))
+        );
+    }
+
     #[test]
     fn test_execute_operation_set_sheet_name() {
         let mut gc = GridController::test();
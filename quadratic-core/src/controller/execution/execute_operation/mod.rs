@@ -3,10 +3,13 @@ use crate::controller::operations::operation::Operation;
 use crate::controller::GridController;
 
 pub mod execute_borders;
+pub mod execute_calculation_settings;
 pub mod execute_code;
 pub mod execute_cursor;
 pub mod execute_formats;
+pub mod execute_named_ranges;
 pub mod execute_offsets;
+pub mod execute_rows_columns;
 pub mod execute_sheets;
 pub mod execute_values;
 
@@ -31,9 +34,26 @@ impl GridController {
                 Operation::SetSheetName { .. } => self.execute_set_sheet_name(transaction, op),
                 Operation::SetSheetColor { .. } => self.execute_set_sheet_color(transaction, op),
 
+                Operation::AddNamedRange { .. } => self.execute_add_named_range(transaction, op),
+                Operation::RenameNamedRange { .. } => {
+                    self.execute_rename_named_range(transaction, op);
+                }
+                Operation::DeleteNamedRange { .. } => {
+                    self.execute_delete_named_range(transaction, op);
+                }
+
+                Operation::SetCalculationSettings { .. } => {
+                    self.execute_set_calculation_settings(transaction, op);
+                }
+
                 Operation::ResizeColumn { .. } => self.execute_resize_column(transaction, op),
                 Operation::ResizeRow { .. } => self.execute_resize_row(transaction, op),
 
+                Operation::InsertColumn { .. } => self.execute_insert_column(transaction, op),
+                Operation::DeleteColumn { .. } => self.execute_delete_column(transaction, op),
+                Operation::InsertRow { .. } => self.execute_insert_row(transaction, op),
+                Operation::DeleteRow { .. } => self.execute_delete_row(transaction, op),
+
                 Operation::SetCursor { .. } => self.execute_set_cursor(transaction, op),
             }
         }
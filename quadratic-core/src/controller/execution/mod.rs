@@ -1,5 +1,6 @@
 pub mod control_transaction;
 pub mod execute_operation;
+pub mod recalculation;
 pub mod receive_multiplayer;
 pub mod run_code;
 pub mod spills;
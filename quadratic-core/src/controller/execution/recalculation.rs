@@ -0,0 +1,717 @@
+//! Ordering of code cells for recalculation.
+//!
+//! Before the `ComputeCode` operations in a transaction run, the code cells
+//! that they compute and every code cell that depends on those are sorted so
+//! that each code cell runs after the code cells it depends on. Each code cell
+//! is computed at most once per recalculation, and the code cells in a cycle
+//! are set to a circular reference error instead of running.
+
+use std::collections::{HashMap, HashSet};
+
+use bigdecimal::ToPrimitive;
+use chrono::Utc;
+use itertools::Itertools;
+
+use crate::{
+    controller::{
+        active_transactions::pending_transaction::PendingTransaction,
+        dependency_index::DependencyIndex, operations::operation::Operation, GridController,
+    },
+    formulas::{parse_formula, Ctx},
+    grid::{CodeCellLanguage, CodeRun, CodeRunResult},
+    CellValue, RunError, RunErrorMsg, SheetPos, SheetRect,
+};
+
+/// Number of significant digits kept in the numbers output by each iteration
+/// of an iterative calculation, like an `f64`, so that their digits don't grow
+/// with each iteration.
+const ITERATION_PRECISION: u64 = 15;
+
+impl GridController {
+    /// Replaces the `ComputeCode` operations at the front of the queue with
+    /// operations that compute those code cells and every code cell that
+    /// depends on them, in dependency order.
+    pub(super) fn order_compute_operations(&mut self, transaction: &mut PendingTransaction) {
+        // Whole-row and whole-column references read the sheet's bounds. They
+        // are recalculated once here for the cells changed before this
+        // recalculation, and code runs add their output to the bounds as they
+        // are computed.
+        self.recalculate_sheet_bounds(transaction);
+
+        let mut roots = vec![];
+        let mut seen = HashSet::new();
+        while let Some(Operation::ComputeCode { sheet_pos }) = transaction.operations.front() {
+            if !transaction.code_cells_scheduled.contains(sheet_pos) && seen.insert(*sheet_pos) {
+                roots.push(*sheet_pos);
+            }
+            transaction.operations.pop_front();
+        }
+
+        // The formulas being computed may have just been written, so their
+        // references are read from their source rather than from the cells
+        // they accessed the last time they ran.
+        let root_references: HashMap<SheetPos, HashSet<SheetRect>> = roots
+            .iter()
+            .filter_map(|&sheet_pos| Some((sheet_pos, self.formula_references(sheet_pos)?)))
+            .collect();
+
+        // Find every code cell that depends on the roots, with an edge from
+        // each code cell to the code cells that depend on it.
+        let mut nodes = roots.clone();
+        let mut node_indices: HashMap<SheetPos, usize> =
+            roots.iter().enumerate().map(|(i, &p)| (p, i)).collect();
+        let mut edges: Vec<Vec<usize>> = vec![vec![]; nodes.len()];
+        let mut i = 0;
+        while i < nodes.len() {
+            let output = self.code_cell_output(nodes[i]);
+            let dependents = self
+                .dependency_index
+                .dependents(&output)
+                .into_iter()
+                .filter(|dependent| {
+                    !transaction.code_cells_scheduled.contains(dependent)
+                        && !root_references.contains_key(dependent)
+                })
+                .sorted_by_key(|dependent| (dependent.y, dependent.x));
+            for dependent in dependents {
+                let j = *node_indices.entry(dependent).or_insert_with(|| {
+                    nodes.push(dependent);
+                    edges.push(vec![]);
+                    nodes.len() - 1
+                });
+                edges[i].push(j);
+            }
+            i += 1;
+        }
+
+        // Add the edges to the formulas being computed.
+        let mut outputs = DependencyIndex::default();
+        for &node in &nodes {
+            outputs.set(node, [self.code_cell_output(node)]);
+        }
+        for &root in &roots {
+            let Some(references) = root_references.get(&root) else {
+                continue;
+            };
+            let j = node_indices[&root];
+            let dependencies = references
+                .iter()
+                .flat_map(|sheet_rect| outputs.dependents(sheet_rect))
+                .collect::<HashSet<_>>();
+            for dependency in dependencies {
+                edges[node_indices[&dependency]].push(j);
+            }
+        }
+
+        let iterative = self.grid.calculation_settings().iterative;
+        let mut operations = vec![];
+        for component in strongly_connected_components(&edges) {
+            let is_cycle = is_cycle(&component, &edges);
+            let cycle = component.iter().map(|&i| nodes[i]).collect_vec();
+            if is_cycle && iterative && self.can_calculate_iteratively(&cycle) {
+                // the whole cycle is calculated by one operation
+                operations.push(Operation::ComputeCode {
+                    sheet_pos: cycle[0],
+                });
+                transaction.iterative_cycles.insert(cycle[0], cycle);
+                continue;
+            }
+            if cycle.len() > 1 {
+                for &sheet_pos in &cycle {
+                    let cells_accessed = match root_references.get(&sheet_pos) {
+                        Some(references) => references.clone(),
+                        None => self
+                            .try_sheet(sheet_pos.sheet_id)
+                            .and_then(|sheet| sheet.code_run(sheet_pos.into()))
+                            .map(|code_run| code_run.cells_accessed.clone())
+                            .unwrap_or_default(),
+                    };
+                    transaction
+                        .circular_code_cells
+                        .insert(sheet_pos, cells_accessed);
+                }
+            }
+            operations.extend(
+                cycle
+                    .into_iter()
+                    .map(|sheet_pos| Operation::ComputeCode { sheet_pos }),
+            );
+        }
+        transaction.code_cells_scheduled.extend(nodes);
+        for operation in operations.into_iter().rev() {
+            transaction.operations.push_front(operation);
+        }
+    }
+
+    /// Returns the cells referred to by the formula at `sheet_pos`, or `None`
+    /// if there is no formula there.
+    fn formula_references(&self, sheet_pos: SheetPos) -> Option<HashSet<SheetRect>> {
+        let sheet = self.try_sheet(sheet_pos.sheet_id)?;
+        match sheet.cell_value(sheet_pos.into()) {
+            Some(CellValue::Code(code_cell)) if code_cell.language == CodeCellLanguage::Formula => {
+                let mut ctx = Ctx::new(self.grid(), sheet_pos);
+                ctx.record_references(&code_cell.code);
+                Some(ctx.cells_accessed)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the source of the formula at `sheet_pos`, or `None` if there is
+    /// no formula there.
+    fn formula_code(&self, sheet_pos: SheetPos) -> Option<String> {
+        let sheet = self.try_sheet(sheet_pos.sheet_id)?;
+        match sheet.cell_value(sheet_pos.into()) {
+            Some(CellValue::Code(code_cell)) if code_cell.language == CodeCellLanguage::Formula => {
+                Some(code_cell.code)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether every code cell in a cycle is a formula that parses.
+    /// Other code cells, such as Python, cannot be run repeatedly within a
+    /// transaction.
+    fn can_calculate_iteratively(&self, cycle: &[SheetPos]) -> bool {
+        cycle.iter().all(|&sheet_pos| {
+            self.formula_code(sheet_pos)
+                .is_some_and(|code| parse_formula(&code, sheet_pos.into()).is_ok())
+        })
+    }
+
+    /// Evaluates the formulas in a cycle repeatedly until no value changes by
+    /// more than the workbook's maximum change, or until the maximum number of
+    /// iterations is reached. If the values do not converge, the last values
+    /// are kept and the failure is reported in each formula's `std_err`.
+    pub(super) fn calculate_iteratively(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        cycle: Vec<SheetPos>,
+    ) {
+        let settings = self.grid.calculation_settings().clone();
+        let code = cycle
+            .iter()
+            .map(|&sheet_pos| self.formula_code(sheet_pos).unwrap_or_default())
+            .collect_vec();
+        let original_code_runs = cycle
+            .iter()
+            .map(|&sheet_pos| self.code_run_at(sheet_pos).cloned())
+            .collect_vec();
+
+        // Each formula sees the values from earlier in the same iteration, so
+        // the code runs are set directly on the sheet while iterating.
+        let mut converged = false;
+        let mut iterations = 0;
+        while !converged && iterations < settings.max_iterations {
+            iterations += 1;
+            converged = true;
+            for (&sheet_pos, code) in cycle.iter().zip(&code) {
+                let Ok(mut code_run) = self.evaluate_formula(sheet_pos, code, true) else {
+                    continue;
+                };
+                round_numbers(&mut code_run, ITERATION_PRECISION);
+                let Some(sheet) = self.try_sheet_mut(sheet_pos.sheet_id) else {
+                    continue;
+                };
+                let old_code_run = sheet.set_code_run(sheet_pos.into(), Some(code_run.clone()));
+                converged &= max_change(old_code_run.as_ref(), &code_run)
+                    .is_some_and(|change| change <= settings.max_change);
+            }
+        }
+
+        // Restore the original code runs so that the changes are recorded as
+        // operations for undo and multiplayer.
+        for (&sheet_pos, original_code_run) in cycle.iter().zip(original_code_runs) {
+            let Some(sheet) = self.try_sheet_mut(sheet_pos.sheet_id) else {
+                continue;
+            };
+            let Some(mut code_run) = sheet.set_code_run(sheet_pos.into(), original_code_run) else {
+                continue;
+            };
+            if !converged {
+                code_run.std_err = Some(format!(
+                    "Circular reference did not converge after {iterations} iterations"
+                ));
+            }
+            self.finalize_code_run(transaction, sheet_pos, Some(code_run), None);
+        }
+    }
+
+    /// Returns every formula that is in a cycle of code cells that depend on
+    /// each other or that has a circular reference error. These are the only
+    /// formulas whose results depend on the calculation settings.
+    pub(crate) fn circular_formulas(&self) -> Vec<SheetPos> {
+        let nodes = self
+            .grid
+            .sheets()
+            .iter()
+            .flat_map(|sheet| sheet.code_runs.keys().map(|pos| pos.to_sheet_pos(sheet.id)))
+            .collect_vec();
+        let node_indices: HashMap<SheetPos, usize> =
+            nodes.iter().enumerate().map(|(i, &p)| (p, i)).collect();
+        let edges = nodes
+            .iter()
+            .map(|&node| {
+                self.dependency_index
+                    .dependents(&self.code_cell_output(node))
+                    .into_iter()
+                    .filter_map(|dependent| node_indices.get(&dependent).copied())
+                    .collect()
+            })
+            .collect_vec();
+
+        let in_cycle = strongly_connected_components(&edges)
+            .into_iter()
+            .filter(|component| is_cycle(component, &edges))
+            .flatten()
+            .collect::<HashSet<_>>();
+        nodes
+            .iter()
+            .enumerate()
+            .filter(|&(i, &sheet_pos)| {
+                in_cycle.contains(&i)
+                    || self.code_run_at(sheet_pos).is_some_and(|code_run| {
+                        matches!(
+                            &code_run.result,
+                            CodeRunResult::Err(e) if e.msg == RunErrorMsg::CircularReference
+                        )
+                    })
+            })
+            .map(|(_, &sheet_pos)| sheet_pos)
+            .filter(|&sheet_pos| self.formula_code(sheet_pos).is_some())
+            .collect()
+    }
+
+    fn code_run_at(&self, sheet_pos: SheetPos) -> Option<&CodeRun> {
+        self.try_sheet(sheet_pos.sheet_id)?
+            .code_run(sheet_pos.into())
+    }
+
+    /// Returns the cells output by the code cell at `sheet_pos`.
+    fn code_cell_output(&self, sheet_pos: SheetPos) -> SheetRect {
+        self.try_sheet(sheet_pos.sheet_id)
+            .and_then(|sheet| sheet.code_run(sheet_pos.into()))
+            .map_or(sheet_pos.into(), |code_run| {
+                code_run.output_sheet_rect(sheet_pos, false)
+            })
+    }
+
+    /// Sets the code cell at `sheet_pos` to a circular reference error. The
+    /// cells that it accesses are kept so that it is computed again when the
+    /// cycle is broken.
+    pub(super) fn set_circular_reference_error(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        sheet_pos: SheetPos,
+        cells_accessed: HashSet<SheetRect>,
+    ) {
+        let error = RunError {
+            span: None,
+            msg: RunErrorMsg::CircularReference,
+        };
+        let formatted_code_string = self
+            .try_sheet(sheet_pos.sheet_id)
+            .and_then(|sheet| sheet.code_run(sheet_pos.into()))
+            .and_then(|code_run| code_run.formatted_code_string.clone());
+        let code_run = CodeRun {
+            formatted_code_string,
+            std_out: None,
+            std_err: Some(error.msg.to_string()),
+            spill_error: false,
+            last_modified: Utc::now(),
+            cells_accessed,
+            result: CodeRunResult::Err(error),
+        };
+        self.finalize_code_run(transaction, sheet_pos, Some(code_run), None);
+    }
+}
+
+/// Returns whether a strongly connected component is a cycle, either of
+/// several code cells or of one code cell that depends on itself.
+fn is_cycle(component: &[usize], edges: &[Vec<usize>]) -> bool {
+    component.len() > 1 || edges[component[0]].contains(&component[0])
+}
+
+/// Rounds the numbers output by a code run to `precision` significant digits.
+fn round_numbers(code_run: &mut CodeRun, precision: u64) {
+    if let CodeRunResult::Ok(value) = &mut code_run.result {
+        for cell_value in value.cell_values_slice_mut() {
+            if let CellValue::Number(n) = cell_value {
+                *n = n.with_prec(precision).normalized();
+            }
+        }
+    }
+}
+
+/// Returns the largest change between the values of two code runs, or `None`
+/// if they cannot be compared because a value other than a number changed.
+fn max_change(old: Option<&CodeRun>, new: &CodeRun) -> Option<f64> {
+    let old = old?;
+    let (CodeRunResult::Ok(old_value), CodeRunResult::Ok(new_value)) = (&old.result, &new.result)
+    else {
+        return (old.result == new.result).then_some(0.0);
+    };
+    if old.output_size() != new.output_size() {
+        return None;
+    }
+    let mut max_change = 0.0_f64;
+    for (old, new) in old_value
+        .cell_values_slice()
+        .iter()
+        .zip(new_value.cell_values_slice())
+    {
+        match (old, new) {
+            (CellValue::Number(old), CellValue::Number(new)) => {
+                max_change = max_change.max((new - old).abs().to_f64()?);
+            }
+            _ if old == new => (),
+            _ => return None,
+        }
+    }
+    Some(max_change)
+}
+
+/// Returns the strongly connected components of a directed graph in
+/// topological order, using an iterative version of Tarjan's algorithm.
+/// `edges[i]` lists the nodes that node `i` has an edge to.
+fn strongly_connected_components(edges: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+
+    let mut index = vec![UNVISITED; edges.len()];
+    let mut lowlink = vec![0; edges.len()];
+    let mut on_stack = vec![false; edges.len()];
+    let mut stack = vec![];
+    let mut next_index = 0;
+    let mut components = vec![];
+
+    for root in 0..edges.len() {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        // Each entry is a node and the index of the next edge to follow.
+        let mut call_stack = vec![(root, 0)];
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((node, edge)) = call_stack.pop() {
+            if let Some(&next) = edges[node].get(edge) {
+                call_stack.push((node, edge + 1));
+                if index[next] == UNVISITED {
+                    index[next] = next_index;
+                    lowlink[next] = next_index;
+                    next_index += 1;
+                    stack.push(next);
+                    on_stack[next] = true;
+                    call_stack.push((next, 0));
+                } else if on_stack[next] {
+                    lowlink[node] = lowlink[node].min(index[next]);
+                }
+                continue;
+            }
+
+            if lowlink[node] == index[node] {
+                let mut component = vec![];
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.reverse();
+                components.push(component);
+            }
+            if let Some(&(parent, _)) = call_stack.last() {
+                lowlink[parent] = lowlink[parent].min(lowlink[node]);
+            }
+        }
+    }
+
+    // Tarjan's algorithm finds each component after every component that it
+    // has an edge to.
+    components.reverse();
+    components
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        controller::transaction_types::{JsCodeResult, JsComputeGetCells},
+        grid::{CalculationSettings, SheetId},
+        Pos, Rect,
+    };
+
+    #[test]
+    fn test_strongly_connected_components() {
+        // 0 -> 1 -> 2 -> 1, 2 -> 3
+        let edges = vec![vec![1], vec![2], vec![1, 3], vec![]];
+        assert_eq!(
+            strongly_connected_components(&edges),
+            vec![vec![0], vec![1, 2], vec![3]],
+        );
+
+        let edges = vec![vec![], vec![0], vec![1]];
+        assert_eq!(
+            strongly_connected_components(&edges),
+            vec![vec![2], vec![1], vec![0]],
+        );
+    }
+
+    fn set_formula(gc: &mut GridController, sheet_pos: SheetPos, code: &str) {
+        gc.set_code_cell(sheet_pos, CodeCellLanguage::Formula, code.into(), None);
+    }
+
+    fn result(gc: &GridController, sheet_pos: SheetPos) -> CodeRunResult {
+        gc.sheet(sheet_pos.sheet_id)
+            .code_run(sheet_pos.into())
+            .unwrap()
+            .result
+            .clone()
+    }
+
+    fn is_circular(gc: &GridController, sheet_pos: SheetPos) -> bool {
+        matches!(
+            result(gc, sheet_pos),
+            CodeRunResult::Err(RunError {
+                msg: RunErrorMsg::CircularReference,
+                ..
+            })
+        )
+    }
+
+    fn number(gc: &GridController, sheet_pos: SheetPos) -> Option<CellValue> {
+        gc.sheet(sheet_pos.sheet_id).display_value(sheet_pos.into())
+    }
+
+    fn pos(x: i64, y: i64, sheet_id: SheetId) -> SheetPos {
+        SheetPos { x, y, sheet_id }
+    }
+
+    #[test]
+    fn test_recalculation_order() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+
+        // a diamond: B0 and C0 read A0, and D0 reads both
+        set_formula(&mut gc, pos(0, 0, sheet_id), "1");
+        set_formula(&mut gc, pos(1, 0, sheet_id), "A0 * 2");
+        set_formula(&mut gc, pos(2, 0, sheet_id), "A0 + B0");
+        set_formula(&mut gc, pos(3, 0, sheet_id), "B0 + C0");
+        assert_eq!(number(&gc, pos(3, 0, sheet_id)), Some(5.into()));
+
+        let code_cell_count = gc.sheet(sheet_id).code_runs.len();
+        let summary = gc.set_code_cell(
+            pos(0, 0, sheet_id),
+            CodeCellLanguage::Formula,
+            "10".into(),
+            None,
+        );
+        assert!(summary.save);
+        assert_eq!(number(&gc, pos(1, 0, sheet_id)), Some(20.into()));
+        assert_eq!(number(&gc, pos(2, 0, sheet_id)), Some(30.into()));
+        assert_eq!(number(&gc, pos(3, 0, sheet_id)), Some(50.into()));
+
+        // each code cell is computed once, so the undo stack has one change
+        // per code cell
+        let undo = gc.undo_stack.last().unwrap();
+        let set_code_runs = undo
+            .operations
+            .iter()
+            .filter(|op| matches!(op, Operation::SetCodeRun { .. }))
+            .count();
+        assert_eq!(set_code_runs, code_cell_count);
+    }
+
+    #[test]
+    fn test_circular_reference() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let (a0, b0, c0, d0) = (
+            pos(0, 0, sheet_id),
+            pos(1, 0, sheet_id),
+            pos(2, 0, sheet_id),
+            pos(3, 0, sheet_id),
+        );
+
+        set_formula(&mut gc, a0, "B0 + 1");
+        set_formula(&mut gc, c0, "A0 + 1");
+        assert_eq!(number(&gc, c0), Some(2.into()));
+
+        // closing the cycle marks both cells, but not the cells that read
+        // the cycle
+        set_formula(&mut gc, b0, "A0 + 1");
+        assert!(is_circular(&gc, a0));
+        assert!(is_circular(&gc, b0));
+        assert!(!is_circular(&gc, c0));
+
+        set_formula(&mut gc, d0, "SUM(A0:C0)");
+        assert!(!is_circular(&gc, d0));
+        assert!(is_circular(&gc, a0));
+        let mut circular = gc.circular_formulas();
+        circular.sort_by_key(|sheet_pos| sheet_pos.x);
+        assert_eq!(circular, vec![a0, b0]);
+
+        // breaking the cycle recomputes every cell in it
+        set_formula(&mut gc, b0, "5");
+        assert_eq!(number(&gc, b0), Some(5.into()));
+        assert_eq!(number(&gc, a0), Some(6.into()));
+        assert_eq!(number(&gc, c0), Some(7.into()));
+        assert_eq!(number(&gc, d0), Some(18.into()));
+
+        // undo restores the cycle
+        gc.undo(None);
+        assert!(is_circular(&gc, a0));
+        assert!(is_circular(&gc, b0));
+    }
+
+    #[test]
+    fn test_circular_reference_through_python() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let (a0, b0) = (pos(0, 0, sheet_id), pos(1, 0, sheet_id));
+
+        // B0 is a Python cell that reads A0
+        gc.set_code_cell(b0, CodeCellLanguage::Python, "c(0, 0)".into(), None);
+        let transaction_id = gc.async_transactions()[0].id;
+        gc.calculation_get_cells(JsComputeGetCells::new(
+            transaction_id.to_string(),
+            Rect::single_pos(Pos { x: 0, y: 0 }),
+            None,
+            None,
+        ))
+        .unwrap();
+        gc.calculation_complete(JsCodeResult::new_from_rust(
+            transaction_id.to_string(),
+            true,
+            None,
+            None,
+            None,
+            Some("1".into()),
+            None,
+            None,
+            None,
+        ))
+        .unwrap();
+        assert_eq!(number(&gc, b0), Some(1.into()));
+
+        // A0 reads B0, which closes the cycle without running Python again
+        set_formula(&mut gc, a0, "B0 + 1");
+        assert!(gc.async_transactions().is_empty());
+        assert!(is_circular(&gc, a0));
+        assert!(is_circular(&gc, b0));
+    }
+
+    fn set_iterative(gc: &mut GridController, iterative: bool, max_iterations: u32) {
+        let settings = CalculationSettings {
+            iterative,
+            max_iterations,
+            ..Default::default()
+        };
+        let summary = gc.set_calculation_settings(settings, None);
+        assert!(summary.save);
+    }
+
+    fn float(gc: &GridController, sheet_pos: SheetPos) -> f64 {
+        match number(gc, sheet_pos) {
+            Some(CellValue::Number(n)) => n.to_f64().unwrap(),
+            other => panic!("expected a number, got {other:?}"),
+        }
+    }
+
+    fn std_err(gc: &GridController, sheet_pos: SheetPos) -> Option<String> {
+        gc.sheet(sheet_pos.sheet_id)
+            .code_run(sheet_pos.into())
+            .unwrap()
+            .std_err
+            .clone()
+    }
+
+    #[test]
+    fn test_iterative_calculation() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let (a0, b0, c0) = (
+            pos(0, 0, sheet_id),
+            pos(1, 0, sheet_id),
+            pos(2, 0, sheet_id),
+        );
+
+        set_iterative(&mut gc, true, 100);
+        set_formula(&mut gc, a0, "B0 / 2 + 1");
+        set_formula(&mut gc, c0, "A0 * 10");
+        set_formula(&mut gc, b0, "A0");
+        assert!(!is_circular(&gc, a0));
+        assert!(!is_circular(&gc, b0));
+        assert!((float(&gc, a0) - 2.0).abs() < 0.001);
+        assert!((float(&gc, b0) - 2.0).abs() < 0.001);
+        assert!((float(&gc, c0) - 20.0).abs() < 0.01);
+        assert_eq!(std_err(&gc, a0), None);
+
+        // each iteration is rounded, so the digits don't grow
+        for sheet_pos in [a0, b0, c0] {
+            let Some(CellValue::Number(n)) = number(&gc, sheet_pos) else {
+                panic!("expected a number");
+            };
+            assert!(n.digits() <= ITERATION_PRECISION);
+        }
+        let mut circular = gc.circular_formulas();
+        circular.sort_by_key(|sheet_pos| sheet_pos.x);
+        assert_eq!(circular, vec![a0, b0]);
+
+        // turning off iterative calculation recomputes the cycle
+        set_iterative(&mut gc, false, 100);
+        assert!(is_circular(&gc, a0));
+        assert!(is_circular(&gc, b0));
+
+        gc.undo(None);
+        assert!(gc.grid().calculation_settings().iterative);
+        assert!((float(&gc, a0) - 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_iterative_self_reference() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let (a0, b0) = (pos(0, 0, sheet_id), pos(1, 0, sheet_id));
+
+        set_formula(&mut gc, a0, "A0 / 2 + 1");
+        assert!(is_circular(&gc, a0));
+        assert_eq!(gc.circular_formulas(), vec![a0]);
+
+        // turning on iterative calculation recomputes the cell
+        set_iterative(&mut gc, true, 100);
+        assert!(!is_circular(&gc, a0));
+        assert!((float(&gc, a0) - 2.0).abs() < 0.001);
+        assert_eq!(gc.circular_formulas(), vec![a0]);
+
+        // a cell that reads the self-referencing cell is not part of the cycle
+        set_formula(&mut gc, b0, "A0 * 10");
+        assert!((float(&gc, b0) - 20.0).abs() < 0.01);
+        assert_eq!(gc.circular_formulas(), vec![a0]);
+
+        set_iterative(&mut gc, false, 100);
+        assert!(is_circular(&gc, a0));
+    }
+
+    #[test]
+    fn test_iterative_calculation_does_not_converge() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let (a0, b0) = (pos(0, 0, sheet_id), pos(1, 0, sheet_id));
+
+        set_iterative(&mut gc, true, 10);
+        set_formula(&mut gc, a0, "B0 + 1");
+        set_formula(&mut gc, b0, "A0");
+        assert!(!is_circular(&gc, a0));
+        // the cycle starts at B0, which was edited
+        assert_eq!(number(&gc, b0), Some(10.into()));
+        assert_eq!(number(&gc, a0), Some(11.into()));
+        let message = "Circular reference did not converge after 10 iterations";
+        assert_eq!(std_err(&gc, a0).as_deref(), Some(message));
+        assert_eq!(std_err(&gc, b0).as_deref(), Some(message));
+    }
+}
@@ -1,6 +1,7 @@
 use chrono::Utc;
 
 use crate::controller::active_transactions::pending_transaction::PendingTransaction;
+use crate::controller::dependency_index::regions_accessed;
 use crate::controller::operations::operation::Operation;
 use crate::error_core::{CoreError, Result};
 use crate::grid::CodeRunResult;
@@ -62,6 +63,9 @@ impl GridController {
                 transaction.summary.html.insert(sheet_id);
             }
         }
+        if let Some(new_code_run) = &new_code_run {
+            sheet.add_code_run_to_bounds(pos, new_code_run);
+        }
 
         let sheet_rect = match (&old_code_run, &new_code_run) {
             (None, None) => sheet_pos.into(),
@@ -83,6 +87,14 @@ impl GridController {
             }
         };
 
+        match &new_code_run {
+            Some(code_run) => {
+                let regions = regions_accessed(&self.grid, sheet_pos, code_run);
+                self.dependency_index.set(sheet_pos, regions);
+            }
+            None => self.dependency_index.remove(sheet_pos),
+        }
+
         transaction.forward_operations.push(Operation::SetCodeRun {
             sheet_pos,
             code_run: new_code_run,
@@ -4,7 +4,7 @@ use crate::{
     controller::{active_transactions::pending_transaction::PendingTransaction, GridController},
     formulas::{parse_formula, Ctx},
     grid::{CodeRun, CodeRunResult},
-    SheetPos,
+    CodeResult, SheetPos,
 };
 
 impl GridController {
@@ -14,37 +14,11 @@ impl GridController {
         sheet_pos: SheetPos,
         code: String,
     ) {
-        let mut ctx = Ctx::new(self.grid(), sheet_pos);
         transaction.current_sheet_pos = Some(sheet_pos);
-        match parse_formula(&code, sheet_pos.into()) {
-            Ok(parsed) => {
-                match parsed.eval(&mut ctx) {
-                    Ok(value) => {
-                        transaction.cells_accessed = ctx.cells_accessed;
-                        let new_code_run = CodeRun {
-                            std_out: None,
-                            std_err: None,
-                            formatted_code_string: None,
-                            spill_error: false,
-                            last_modified: Utc::now(),
-                            cells_accessed: transaction.cells_accessed.clone(),
-                            result: CodeRunResult::Ok(value),
-                        };
-                        self.finalize_code_run(transaction, sheet_pos, Some(new_code_run), None);
-                    }
-                    Err(error) => {
-                        let msg = error.msg.to_string();
-                        let line_number = error.span.map(|span| span.start as i64);
-
-                        // todo: propagate the result
-                        let _ = self.code_cell_sheet_error(
-                            transaction,
-                            msg,
-                            // todo: span should be multiline
-                            line_number,
-                        );
-                    }
-                }
+        match self.evaluate_formula(sheet_pos, &code, false) {
+            Ok(new_code_run) => {
+                transaction.cells_accessed = new_code_run.cells_accessed.clone();
+                self.finalize_code_run(transaction, sheet_pos, Some(new_code_run), None);
             }
             Err(e) => {
                 let msg = e.to_string();
@@ -53,6 +27,43 @@ impl GridController {
             }
         }
     }
+
+    /// Evaluates the formula `code` at `sheet_pos` without changing the grid,
+    /// or returns an error if the formula does not parse. While `iterating`
+    /// over a circular reference, the formula may read its own cell.
+    pub(crate) fn evaluate_formula(
+        &self,
+        sheet_pos: SheetPos,
+        code: &str,
+        iterating: bool,
+    ) -> CodeResult<CodeRun> {
+        let parsed = parse_formula(code, sheet_pos.into())?;
+        let mut ctx = Ctx::new(self.grid(), sheet_pos);
+        ctx.allow_self_reference = iterating;
+        let code_run = match parsed.eval(&mut ctx) {
+            Ok(value) => CodeRun {
+                std_out: None,
+                std_err: None,
+                formatted_code_string: None,
+                spill_error: false,
+                last_modified: Utc::now(),
+                cells_accessed: ctx.cells_accessed,
+                result: CodeRunResult::Ok(value),
+            },
+            // keep the formula's own error (eg, a reference to a deleted
+            // sheet) rather than a generic error message
+            Err(error) => CodeRun {
+                std_out: None,
+                std_err: Some(error.msg.to_string()),
+                formatted_code_string: None,
+                spill_error: false,
+                last_modified: Utc::now(),
+                cells_accessed: ctx.cells_accessed,
+                result: CodeRunResult::Err(error),
+            },
+        };
+        Ok(code_run)
+    }
 }
 
 #[cfg(test)]
@@ -69,7 +80,7 @@ mod test {
             operations::operation::Operation, transaction_types::JsCodeResult, GridController,
         },
         grid::{CodeCellLanguage, CodeRun, CodeRunResult},
-        Array, ArraySize, CellValue, CodeCellValue, Pos, SheetPos, Value,
+        Array, ArraySize, CellValue, CodeCellValue, Pos, RunErrorMsg, SheetPos, Value,
     };
 
     #[test]
@@ -420,4 +431,156 @@ mod test {
         let result = sheet.code_run(pos).unwrap();
         assert!(!result.spill_error);
     }
+
+    #[test]
+    fn test_formula_sheet_reference() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.add_sheet(None);
+        let other_sheet_id = gc.sheet_ids()[1];
+        gc.set_sheet_name(other_sheet_id, "Other".to_string(), None);
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id: other_sheet_id,
+            },
+            "10".into(),
+            None,
+        );
+
+        let sheet_pos = SheetPos {
+            x: 0,
+            y: 0,
+            sheet_id,
+        };
+        let pos: Pos = sheet_pos.into();
+        gc.set_code_cell(
+            sheet_pos,
+            CodeCellLanguage::Formula,
+            "'Other'!A0 + 1".into(),
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(pos),
+            Some(CellValue::Number(11.into()))
+        );
+
+        // the formula keeps working after the sheet is renamed, and displays
+        // the new name
+        gc.set_sheet_name(other_sheet_id, "Renamed".to_string(), None);
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id: other_sheet_id,
+            },
+            "20".into(),
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(pos),
+            Some(CellValue::Number(21.into()))
+        );
+        let Some(CellValue::Code(code_cell)) = gc.sheet(sheet_id).cell_value(pos) else {
+            panic!("expected code cell");
+        };
+        assert_eq!(
+            gc.grid().formula_sheet_ids_to_names(&code_cell.code),
+            "'Renamed'!A0 + 1"
+        );
+
+        // deleting the sheet turns the formula into a bad cell reference
+        gc.delete_sheet(other_sheet_id, None);
+        let code_run = gc.sheet(sheet_id).code_run(pos).unwrap();
+        match &code_run.result {
+            CodeRunResult::Err(error) => assert_eq!(error.msg, RunErrorMsg::BadCellReference),
+            CodeRunResult::Ok(_) => panic!("expected an error"),
+        }
+
+        gc.undo(None);
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(pos),
+            Some(CellValue::Number(21.into()))
+        );
+    }
+
+    #[test]
+    fn test_formula_column_range_recalculates() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_values(
+            SheetPos {
+                x: 1,
+                y: 0,
+                sheet_id,
+            },
+            vec![vec!["1"], vec!["2"]],
+            None,
+        );
+        let sheet_pos = SheetPos {
+            x: 0,
+            y: 0,
+            sheet_id,
+        };
+        gc.set_code_cell(
+            sheet_pos,
+            CodeCellLanguage::Formula,
+            "SUM(B:B)".into(),
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(sheet_pos.into()),
+            Some(CellValue::Number(3.into()))
+        );
+
+        // appending data below the column's current bounds recalculates
+        gc.set_cell_value(
+            SheetPos {
+                x: 1,
+                y: 100,
+                sheet_id,
+            },
+            "10".into(),
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(sheet_pos.into()),
+            Some(CellValue::Number(13.into()))
+        );
+
+        // only the cells within the bounds are recorded as accessed
+        let code_run = gc.sheet(sheet_id).code_run(sheet_pos.into()).unwrap();
+        assert!(code_run
+            .cells_accessed
+            .iter()
+            .all(|rect| rect.min.y >= 0 && rect.max.y <= 100));
+
+        // formulas computed later in the same recalculation read the outputs
+        // of earlier ones, which may be outside the bounds
+        gc.set_code_cell(
+            SheetPos {
+                x: 2,
+                y: 0,
+                sheet_id,
+            },
+            CodeCellLanguage::Formula,
+            "SUM(D:D)".into(),
+            None,
+        );
+        gc.set_code_cell(
+            SheetPos {
+                x: 3,
+                y: 200,
+                sheet_id,
+            },
+            CodeCellLanguage::Formula,
+            "{5; 6}".into(),
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(Pos { x: 2, y: 0 }),
+            Some(CellValue::Number(11.into()))
+        );
+    }
 }
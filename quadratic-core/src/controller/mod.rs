@@ -1,10 +1,14 @@
-use self::{active_transactions::ActiveTransactions, transaction::Transaction};
+use self::{
+    active_transactions::ActiveTransactions, dependency_index::DependencyIndex,
+    transaction::Transaction,
+};
 use crate::grid::Grid;
 #[cfg(feature = "js")]
 use wasm_bindgen::prelude::*;
 
 pub mod active_transactions;
 pub mod dependencies;
+pub mod dependency_index;
 pub mod execution;
 pub mod export;
 pub mod formula;
@@ -26,11 +30,15 @@ pub struct GridController {
 
     // holds information about transactions in progress
     transactions: ActiveTransactions,
+
+    // maps the cells accessed by code runs to the code cells that access them
+    dependency_index: DependencyIndex,
 }
 
 impl GridController {
     pub fn from_grid(grid: Grid, last_sequence_num: u64) -> Self {
         GridController {
+            dependency_index: DependencyIndex::from_grid(&grid),
             grid,
             transactions: ActiveTransactions::new(last_sequence_num),
             ..Default::default()
@@ -0,0 +1,12 @@
+use crate::{controller::GridController, grid::CalculationSettings};
+
+use super::operation::Operation;
+
+impl GridController {
+    pub fn set_calculation_settings_operations(
+        &mut self,
+        settings: CalculationSettings,
+    ) -> Vec<Operation> {
+        vec![Operation::SetCalculationSettings { settings }]
+    }
+}
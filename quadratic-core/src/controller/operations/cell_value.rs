@@ -6,7 +6,7 @@ use crate::{
     cell_values::CellValues,
     controller::GridController,
     grid::{formatting::CellFmtArray, NumericDecimals, NumericFormat, NumericFormatKind},
-    CellValue, RunLengthEncoding, SheetPos, SheetRect,
+    CellValue, Duration, Instant, RunLengthEncoding, SheetPos, SheetRect,
 };
 
 use super::operation::Operation;
@@ -76,12 +76,52 @@ impl GridController {
                 )),
             });
             CellValue::Number(percent)
+        } else if let Some((instant, pattern)) = Instant::parse_with_format(value) {
+            let kind = if pattern.contains("%H") || pattern.contains("%I") {
+                NumericFormatKind::DateTime
+            } else {
+                NumericFormatKind::Date
+            };
+            ops.extend(self.date_time_format_operation(sheet_pos, kind, pattern));
+            CellValue::Instant(instant)
+        } else if let Some((duration, pattern)) = Duration::parse_time_with_format(value) {
+            ops.extend(self.date_time_format_operation(
+                sheet_pos,
+                NumericFormatKind::Time,
+                pattern,
+            ));
+            CellValue::Duration(duration)
         } else {
             CellValue::Text(value.into())
         };
         (ops, cell_value)
     }
 
+    /// Generate an operation that sets a date or time format for a value typed
+    /// as `pattern`, unless the cell already has a date or time format.
+    fn date_time_format_operation(
+        &self,
+        sheet_pos: SheetPos,
+        kind: NumericFormatKind,
+        pattern: String,
+    ) -> Option<Operation> {
+        let sheet = self.try_sheet(sheet_pos.sheet_id)?;
+        if sheet
+            .get_formatting_value::<NumericFormat>(sheet_pos.into())
+            .is_some_and(|numeric_format| numeric_format.kind.is_date_time())
+        {
+            return None;
+        }
+        let symbol = (kind.default_pattern() != Some(pattern.as_str())).then_some(pattern);
+        Some(Operation::SetCellFormats {
+            sheet_rect: sheet_pos.into(),
+            attr: CellFmtArray::NumericFormat(RunLengthEncoding::repeat(
+                Some(NumericFormat { kind, symbol }),
+                1,
+            )),
+        })
+    }
+
     /// Generate operations for a user-initiated change to a cell value
     pub fn set_cell_value_operations(
         &mut self,
@@ -131,7 +171,11 @@ mod test {
 
     use bigdecimal::BigDecimal;
 
-    use crate::{controller::GridController, grid::SheetId, CellValue, SheetPos};
+    use crate::{
+        controller::{operations::operation::Operation, GridController},
+        grid::{formatting::CellFmtArray, CellAlign, NumericFormat, NumericFormatKind, SheetId},
+        CellValue, Rect, RunLengthEncoding, SheetPos,
+    };
 
     #[test]
     fn test() {
@@ -216,4 +260,47 @@ mod test {
             CellValue::Number(BigDecimal::from_str("123456789.01").unwrap())
         );
     }
+
+    #[test]
+    fn date_time_to_cell_value() {
+        let mut gc = GridController::test();
+        let sheet_pos = SheetPos {
+            x: 1,
+            y: 2,
+            sheet_id: gc.sheet_ids()[0],
+        };
+        let (ops, value) = gc.string_to_cell_value(sheet_pos, "2024-03-01");
+        assert_eq!(
+            ops,
+            vec![Operation::SetCellFormats {
+                sheet_rect: sheet_pos.into(),
+                attr: CellFmtArray::NumericFormat(RunLengthEncoding::repeat(
+                    Some(NumericFormat {
+                        kind: NumericFormatKind::Date,
+                        symbol: None,
+                    }),
+                    1,
+                )),
+            }]
+        );
+        assert_eq!(value.to_edit(), "2024-03-01");
+        assert!(matches!(value, CellValue::Instant(_)));
+
+        let (_, value) = gc.string_to_cell_value(sheet_pos, "13:45");
+        assert!(matches!(value, CellValue::Duration(_)));
+
+        let (_, value) = gc.string_to_cell_value(sheet_pos, "2024-03-45");
+        assert_eq!(value, CellValue::Text("2024-03-45".into()));
+
+        // the date is displayed the way it was typed
+        gc.set_cell_value(sheet_pos, "3/1/2024 1:45 PM".into(), None);
+        let sheet = gc.sheet(sheet_pos.sheet_id);
+        let render_cells = sheet.get_render_cells(Rect::single_pos(sheet_pos.into()));
+        assert_eq!(render_cells[0].value, "3/1/2024 1:45 PM");
+        assert_eq!(render_cells[0].align, Some(CellAlign::Right));
+
+        // an existing date format is kept
+        let (ops, _) = gc.string_to_cell_value(sheet_pos, "2024-03-02");
+        assert!(ops.is_empty());
+    }
 }
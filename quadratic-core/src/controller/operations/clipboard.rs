@@ -2,7 +2,10 @@ use super::operation::Operation;
 use crate::{
     cell_values::CellValues,
     controller::{user_actions::clipboard::PasteSpecial, GridController},
-    grid::{formatting::CellFmtArray, generate_borders_full, BorderSelection, CellBorders},
+    grid::{
+        formatting::CellFmtArray, generate_borders_full, BorderSelection, CellBorders,
+        CodeCellLanguage,
+    },
     CellValue, Pos, SheetPos, SheetRect,
 };
 use anyhow::{Error, Result};
@@ -80,9 +83,20 @@ impl GridController {
                 let (values, code) =
                     GridController::cell_values_from_clipboard_cells(&clipboard, special);
                 if let Some(values) = values {
+                    let mut values = values.clone();
+                    for &(x, y) in &code {
+                        if let Some(CellValue::Code(code_cell)) =
+                            values.columns[x as usize].get_mut(&(y as u64))
+                        {
+                            if code_cell.language == CodeCellLanguage::Formula {
+                                code_cell.code =
+                                    self.grid.formula_sheet_names_to_ids(&code_cell.code);
+                            }
+                        }
+                    }
                     ops.push(Operation::SetCellValues {
                         sheet_pos: start_pos,
-                        values: values.clone(),
+                        values,
                     });
                 }
 
@@ -14,6 +14,10 @@ impl GridController {
         language: CodeCellLanguage,
         code: String,
     ) -> Vec<Operation> {
+        let code = match language {
+            CodeCellLanguage::Formula => self.grid.formula_sheet_names_to_ids(&code),
+            _ => code,
+        };
         vec![
             Operation::SetCellValues {
                 sheet_pos,
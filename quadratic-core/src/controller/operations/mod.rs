@@ -4,10 +4,14 @@ pub mod autocomplete;
 /// Only execute_operations should change the Grid.
 ///
 pub mod borders;
+pub mod calculation_settings;
 pub mod cell_value;
 pub mod clipboard;
 pub mod code_cell;
 pub mod formatting;
 pub mod import;
+pub mod named_ranges;
 pub mod operation;
+pub mod rows_columns;
 pub mod sheets;
+pub mod sort;
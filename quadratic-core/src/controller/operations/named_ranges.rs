@@ -0,0 +1,30 @@
+use crate::{controller::GridController, grid::NamedRange, SheetRect};
+
+use super::operation::Operation;
+
+impl GridController {
+    pub fn add_named_range_operations(
+        &mut self,
+        name: String,
+        sheet_rect: SheetRect,
+    ) -> Vec<Operation> {
+        vec![Operation::AddNamedRange {
+            named_range: NamedRange {
+                name,
+                sheet_rect: Some(sheet_rect),
+            },
+        }]
+    }
+
+    pub fn rename_named_range_operations(
+        &mut self,
+        old_name: String,
+        new_name: String,
+    ) -> Vec<Operation> {
+        vec![Operation::RenameNamedRange { old_name, new_name }]
+    }
+
+    pub fn delete_named_range_operations(&mut self, name: String) -> Vec<Operation> {
+        vec![Operation::DeleteNamedRange { name }]
+    }
+}
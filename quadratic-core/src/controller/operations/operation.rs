@@ -3,7 +3,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     cell_values::CellValues,
-    grid::{formatting::CellFmtArray, CodeRun, Sheet, SheetBorders, SheetId},
+    grid::{
+        formatting::CellFmtArray, CalculationSettings, CodeRun, NamedRange, Sheet, SheetBorders,
+        SheetId,
+    },
     SheetPos, SheetRect,
 };
 
@@ -50,6 +53,23 @@ pub enum Operation {
         order: String,
     },
 
+    // Named range operations
+    AddNamedRange {
+        named_range: NamedRange,
+    },
+    RenameNamedRange {
+        old_name: String,
+        new_name: String,
+    },
+    DeleteNamedRange {
+        name: String,
+    },
+
+    // Workbook operations
+    SetCalculationSettings {
+        settings: CalculationSettings,
+    },
+
     // Sheet offsets operations
     ResizeColumn {
         sheet_id: SheetId,
@@ -62,6 +82,24 @@ pub enum Operation {
         new_size: f64,
     },
 
+    // Row and column operations
+    InsertColumn {
+        sheet_id: SheetId,
+        column: i64,
+    },
+    DeleteColumn {
+        sheet_id: SheetId,
+        column: i64,
+    },
+    InsertRow {
+        sheet_id: SheetId,
+        row: i64,
+    },
+    DeleteRow {
+        sheet_id: SheetId,
+        row: i64,
+    },
+
     // used for User transactions to set cursor (eg, Paste)
     SetCursor {
         sheet_rect: SheetRect,
@@ -108,6 +146,22 @@ impl fmt::Display for Operation {
                 "ReorderSheet {{ target: {}, order: {} }}",
                 target, order
             ),
+            Operation::AddNamedRange { named_range } => write!(
+                fmt,
+                "AddNamedRange {{ name: {}, sheet_rect: {:?} }}",
+                named_range.name, named_range.sheet_rect
+            ),
+            Operation::RenameNamedRange { old_name, new_name } => write!(
+                fmt,
+                "RenameNamedRange {{ old_name: {}, new_name: {} }}",
+                old_name, new_name
+            ),
+            Operation::DeleteNamedRange { name } => {
+                write!(fmt, "DeleteNamedRange {{ name: {} }}", name)
+            }
+            Operation::SetCalculationSettings { settings } => {
+                write!(fmt, "SetCalculationSettings {{ settings: {:?} }}", settings)
+            }
             Operation::ResizeColumn {
                 sheet_id,
                 column,
@@ -126,6 +180,22 @@ impl fmt::Display for Operation {
                 "ResizeRow {{ sheet_id: {}, row: {}, new_size: {} }}",
                 sheet_id, row, new_size
             ),
+            Operation::InsertColumn { sheet_id, column } => write!(
+                fmt,
+                "InsertColumn {{ sheet_id: {}, column: {} }}",
+                sheet_id, column
+            ),
+            Operation::DeleteColumn { sheet_id, column } => write!(
+                fmt,
+                "DeleteColumn {{ sheet_id: {}, column: {} }}",
+                sheet_id, column
+            ),
+            Operation::InsertRow { sheet_id, row } => {
+                write!(fmt, "InsertRow {{ sheet_id: {}, row: {} }}", sheet_id, row)
+            }
+            Operation::DeleteRow { sheet_id, row } => {
+                write!(fmt, "DeleteRow {{ sheet_id: {}, row: {} }}", sheet_id, row)
+            }
             Operation::SetBorders { .. } => write!(fmt, "SetBorders {{ todo }}"),
             Operation::SetCursor { sheet_rect } => {
                 write!(fmt, "SetCursor {{ sheet_rect: {} }}", sheet_rect)
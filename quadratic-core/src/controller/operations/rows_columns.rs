@@ -0,0 +1,21 @@
+use crate::{controller::GridController, grid::SheetId};
+
+use super::operation::Operation;
+
+impl GridController {
+    pub fn insert_column_operations(&mut self, sheet_id: SheetId, column: i64) -> Vec<Operation> {
+        vec![Operation::InsertColumn { sheet_id, column }]
+    }
+
+    pub fn delete_column_operations(&mut self, sheet_id: SheetId, column: i64) -> Vec<Operation> {
+        vec![Operation::DeleteColumn { sheet_id, column }]
+    }
+
+    pub fn insert_row_operations(&mut self, sheet_id: SheetId, row: i64) -> Vec<Operation> {
+        vec![Operation::InsertRow { sheet_id, row }]
+    }
+
+    pub fn delete_row_operations(&mut self, sheet_id: SheetId, row: i64) -> Vec<Operation> {
+        vec![Operation::DeleteRow { sheet_id, row }]
+    }
+}
@@ -0,0 +1,171 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cell_values::CellValues, controller::GridController, grid::formatting::CellFmtArray, CellValue,
+    IsBlank, Pos, RunLengthEncoding, SheetPos, SheetRect,
+};
+
+use super::operation::Operation;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// A column to sort by. `column` is the absolute column in the sheet.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub column: i64,
+    pub direction: SortDirection,
+}
+
+impl SortKey {
+    /// Compares two values by this key. Blanks are always sorted last,
+    /// regardless of direction.
+    fn cmp(&self, a: &CellValue, b: &CellValue) -> Ordering {
+        match (a.is_blank(), b.is_blank()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => match self.direction {
+                SortDirection::Ascending => a.sort_cmp(b),
+                SortDirection::Descending => b.sort_cmp(a),
+            },
+        }
+    }
+}
+
+/// Reorders the rows of a row-major run-length encoded array of `width`
+/// columns so that new row `i` is old row `order[i]`.
+fn reorder_rle<T: Eq + Clone>(
+    rle: &RunLengthEncoding<T>,
+    width: usize,
+    order: &[usize],
+) -> RunLengthEncoding<T> {
+    order
+        .iter()
+        .flat_map(|&row| (0..width).filter_map(move |x| rle.get_at(row * width + x).cloned()))
+        .collect()
+}
+
+fn reorder_cell_fmt_array(array: &CellFmtArray, width: usize, order: &[usize]) -> CellFmtArray {
+    match array {
+        CellFmtArray::Align(rle) => CellFmtArray::Align(reorder_rle(rle, width, order)),
+        CellFmtArray::Wrap(rle) => CellFmtArray::Wrap(reorder_rle(rle, width, order)),
+        CellFmtArray::NumericFormat(rle) => {
+            CellFmtArray::NumericFormat(reorder_rle(rle, width, order))
+        }
+        CellFmtArray::NumericDecimals(rle) => {
+            CellFmtArray::NumericDecimals(reorder_rle(rle, width, order))
+        }
+        CellFmtArray::NumericCommas(rle) => {
+            CellFmtArray::NumericCommas(reorder_rle(rle, width, order))
+        }
+        CellFmtArray::Bold(rle) => CellFmtArray::Bold(reorder_rle(rle, width, order)),
+        CellFmtArray::Italic(rle) => CellFmtArray::Italic(reorder_rle(rle, width, order)),
+        CellFmtArray::TextColor(rle) => CellFmtArray::TextColor(reorder_rle(rle, width, order)),
+        CellFmtArray::FillColor(rle) => CellFmtArray::FillColor(reorder_rle(rle, width, order)),
+        CellFmtArray::RenderSize(rle) => CellFmtArray::RenderSize(reorder_rle(rle, width, order)),
+    }
+}
+
+impl GridController {
+    /// Sorts the rows of `sheet_rect` by `keys`, in order of precedence. If
+    /// `has_header` is set, the first row is left in place.
+    ///
+    /// Returns no operations if the sheet does not exist, a key is outside
+    /// the range, or the rows are already in order.
+    pub fn sort_range_operations(
+        &self,
+        sheet_rect: SheetRect,
+        keys: &[SortKey],
+        has_header: bool,
+    ) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_rect.sheet_id) else {
+            return vec![];
+        };
+        if keys.is_empty()
+            || keys
+                .iter()
+                .any(|key| !sheet_rect.x_range().contains(&key.column))
+        {
+            return vec![];
+        }
+
+        let mut data_rect = sheet_rect;
+        if has_header {
+            data_rect.min.y += 1;
+        }
+        if data_rect.min.y > data_rect.max.y {
+            return vec![];
+        }
+
+        let width = data_rect.width();
+        let height = data_rect.height();
+
+        // sort keys use the displayed value so code cells sort by their output
+        let sort_values = data_rect
+            .y_range()
+            .map(|y| {
+                keys.iter()
+                    .map(|key| {
+                        sheet
+                            .display_value(Pos { x: key.column, y })
+                            .unwrap_or(CellValue::Blank)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut order = (0..height).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| {
+            keys.iter()
+                .zip(sort_values[a].iter().zip(sort_values[b].iter()))
+                .map(|(key, (a, b))| key.cmp(a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        if order.iter().enumerate().all(|(i, &row)| i == row) {
+            return vec![];
+        }
+
+        let sheet_pos = SheetPos::from(data_rect);
+        let mut values = CellValues::new(width as u32, height as u32);
+        let mut code_cells = vec![];
+        for (new_y, &old_y) in order.iter().enumerate() {
+            for x in 0..width {
+                let pos = Pos {
+                    x: data_rect.min.x + x as i64,
+                    y: data_rect.min.y + old_y as i64,
+                };
+                if let Some(value) = sheet.cell_value(pos) {
+                    if matches!(value, CellValue::Code(_)) {
+                        code_cells.push(SheetPos {
+                            x: sheet_pos.x + x as i64,
+                            y: sheet_pos.y + new_y as i64,
+                            sheet_id: sheet_pos.sheet_id,
+                        });
+                    }
+                    values.set(x as u32, new_y as u32, value);
+                }
+            }
+        }
+
+        let mut ops = vec![Operation::SetCellValues { sheet_pos, values }];
+        ops.extend(self.get_all_cell_formats(data_rect).iter().map(|attr| {
+            Operation::SetCellFormats {
+                sheet_rect: data_rect,
+                attr: reorder_cell_fmt_array(attr, width, &order),
+            }
+        }));
+        ops.extend(
+            code_cells
+                .into_iter()
+                .map(|sheet_pos| Operation::ComputeCode { sheet_pos }),
+        );
+        ops
+    }
+}
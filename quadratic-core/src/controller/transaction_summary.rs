@@ -43,6 +43,9 @@ pub struct TransactionSummary {
     /// Sheet metadata or order was modified.
     pub sheet_list_modified: bool,
 
+    /// Named ranges were added, renamed, or deleted.
+    pub named_ranges_modified: bool,
+
     /// CellSheet regions that need updating
     pub cell_sheets_modified: HashSet<CellSheetsModified>,
 
@@ -92,6 +95,7 @@ impl TransactionSummary {
         self.border_sheets_modified.clear();
         self.code_cells_modified.clear();
         self.sheet_list_modified = false;
+        self.named_ranges_modified = false;
         self.cell_sheets_modified.clear();
         self.offsets_modified.clear();
         self.cursor = None;
@@ -111,6 +115,7 @@ impl TransactionSummary {
         self.code_cells_modified
             .extend(summary.code_cells_modified.iter().cloned());
         self.sheet_list_modified |= summary.sheet_list_modified;
+        self.named_ranges_modified |= summary.named_ranges_modified;
         self.cell_sheets_modified
             .extend(summary.cell_sheets_modified.iter().cloned());
         self.offsets_modified
@@ -0,0 +1,23 @@
+use crate::{
+    controller::{transaction_summary::TransactionSummary, GridController},
+    error_core::CoreError,
+    grid::CalculationSettings,
+};
+
+impl GridController {
+    /// Changes the workbook's calculation settings, such as whether circular
+    /// references are calculated iteratively.
+    pub fn set_calculation_settings(
+        &mut self,
+        settings: CalculationSettings,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        if !settings.is_valid() {
+            return TransactionSummary::error(CoreError::InvalidCalculationSettings(format!(
+                "{settings:?}"
+            )));
+        }
+        let ops = self.set_calculation_settings_operations(settings);
+        self.start_user_transaction(ops, cursor)
+    }
+}
@@ -2,8 +2,8 @@ use crate::cell_values::CellValues;
 use crate::controller::{
     operations::clipboard::Clipboard, transaction_summary::TransactionSummary, GridController,
 };
-use crate::Rect;
 use crate::{grid::get_cell_borders_in_rect, Pos, SheetPos, SheetRect};
+use crate::{CellValue, Rect};
 use htmlescape;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
@@ -50,8 +50,12 @@ impl GridController {
                 // the CellValue at the cell (ignoring code_runs)
                 let real_value = sheet.cell_value(pos);
 
-                // create quadratic clipboard values
-                if let Some(real_value) = real_value {
+                // create quadratic clipboard values (formulas are copied with
+                // sheet names so that they can be pasted into another file)
+                if let Some(mut real_value) = real_value {
+                    if let CellValue::Code(code_cell) = &mut real_value {
+                        code_cell.code = self.grid.display_code(code_cell);
+                    }
                     cells.set(
                         (x - sheet_rect.min.x) as u32,
                         (y - sheet_rect.min.y) as u32,
@@ -476,6 +480,51 @@ mod test {
         assert_eq!(gc.undo_stack.len(), 0);
     }
 
+    #[test]
+    fn test_copy_formula_with_sheet_reference() {
+        // each grid controller has a "Data" sheet with a different ID
+        let data_sheet = |gc: &mut GridController, value: &str| {
+            gc.add_sheet(None);
+            let data_id = gc.sheet_ids()[1];
+            gc.set_sheet_name(data_id, "Data".to_string(), None);
+            gc.set_cell_value((0, 0, data_id).into(), value.into(), None);
+            data_id
+        };
+
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        let data_id = data_sheet(&mut gc, "1");
+        gc.set_code_cell(
+            (0, 0, sheet_id).into(),
+            CodeCellLanguage::Formula,
+            "'Data'!A0 * 2".into(),
+            None,
+        );
+        let (_, html) = gc.copy_to_clipboard(SheetRect::single_pos(Pos { x: 0, y: 0 }, sheet_id));
+        assert!(!html.contains(&data_id.to_string()));
+
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        let data_id = data_sheet(&mut gc, "10");
+        gc.paste_from_clipboard(
+            (1, 1, sheet_id).into(),
+            None,
+            Some(html),
+            PasteSpecial::None,
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.display_value(Pos { x: 1, y: 1 }),
+            Some(CellValue::Number(BigDecimal::from(20)))
+        );
+        let Some(CellValue::Code(code_cell)) = sheet.cell_value(Pos { x: 1, y: 1 }) else {
+            panic!("expected a code cell");
+        };
+        assert_eq!(code_cell.code, format!("'{data_id}'!A0 * 2"));
+        assert_eq!(gc.grid().display_code(&code_cell), "'Data'!A0 * 2");
+    }
+
     #[test]
     fn test_copy_code_to_clipboard_with_array_output() {
         let mut gc = GridController::default();
@@ -2,10 +2,14 @@
 ///
 pub mod auto_complete;
 pub mod borders;
+pub mod calculation_settings;
 pub mod cells;
 pub mod clipboard;
 pub mod code;
 pub mod formatting;
 pub mod import;
+pub mod named_ranges;
+pub mod rows_columns;
 pub mod sheets;
+pub mod sort;
 pub mod undo;
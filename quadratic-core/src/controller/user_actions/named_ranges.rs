@@ -0,0 +1,45 @@
+use crate::{
+    controller::{transaction_summary::TransactionSummary, GridController},
+    error_core::CoreError,
+    formulas::is_valid_named_range_name,
+    SheetRect,
+};
+
+impl GridController {
+    /// Adds a named range, or changes the cells that an existing named range
+    /// refers to.
+    pub fn add_named_range(
+        &mut self,
+        name: String,
+        sheet_rect: SheetRect,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        if !is_valid_named_range_name(&name) {
+            return TransactionSummary::error(CoreError::InvalidName(name));
+        }
+        let ops = self.add_named_range_operations(name, sheet_rect);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    pub fn rename_named_range(
+        &mut self,
+        old_name: String,
+        new_name: String,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        if !is_valid_named_range_name(&new_name) {
+            return TransactionSummary::error(CoreError::InvalidName(new_name));
+        }
+        let ops = self.rename_named_range_operations(old_name, new_name);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    pub fn delete_named_range(
+        &mut self,
+        name: String,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.delete_named_range_operations(name);
+        self.start_user_transaction(ops, cursor)
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    controller::{transaction_summary::TransactionSummary, GridController},
+    grid::SheetId,
+};
+
+impl GridController {
+    /// Inserts an empty column at `column`, shifting the columns at and to the
+    /// right of it. Returns a [`TransactionSummary`].
+    pub fn insert_column(
+        &mut self,
+        sheet_id: SheetId,
+        column: i64,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.insert_column_operations(sheet_id, column);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Deletes the column at `column`, shifting the columns to the right of
+    /// it. Returns a [`TransactionSummary`].
+    pub fn delete_column(
+        &mut self,
+        sheet_id: SheetId,
+        column: i64,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.delete_column_operations(sheet_id, column);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Inserts an empty row at `row`, shifting the rows at and below it.
+    /// Returns a [`TransactionSummary`].
+    pub fn insert_row(
+        &mut self,
+        sheet_id: SheetId,
+        row: i64,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.insert_row_operations(sheet_id, row);
+        self.start_user_transaction(ops, cursor)
+    }
+
+    /// Deletes the row at `row`, shifting the rows below it. Returns a
+    /// [`TransactionSummary`].
+    pub fn delete_row(
+        &mut self,
+        sheet_id: SheetId,
+        row: i64,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.delete_row_operations(sheet_id, row);
+        self.start_user_transaction(ops, cursor)
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{
+    controller::{
+        operations::sort::SortKey, transaction_summary::TransactionSummary, GridController,
+    },
+    SheetRect,
+};
+
+impl GridController {
+    /// Sorts the rows of `sheet_rect` by `keys`, moving each row's values and
+    /// formats together. If `has_header` is set, the first row is left in
+    /// place. Returns a [`TransactionSummary`].
+    pub fn sort_range(
+        &mut self,
+        sheet_rect: SheetRect,
+        keys: &[SortKey],
+        has_header: bool,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = self.sort_range_operations(sheet_rect, keys, has_header);
+        self.start_user_transaction(ops, cursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        controller::{
+            operations::sort::{SortDirection, SortKey},
+            GridController,
+        },
+        grid::{Bold, SheetId},
+        Pos, SheetPos, SheetRect,
+    };
+
+    fn column(
+        gc: &GridController,
+        sheet_id: SheetId,
+        x: i64,
+        ys: std::ops::Range<i64>,
+    ) -> Vec<String> {
+        let sheet = gc.sheet(sheet_id);
+        ys.map(|y| {
+            sheet
+                .display_value(Pos { x, y })
+                .map(|value| value.to_string())
+                .unwrap_or_default()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_sort_range_multiple_keys() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_values(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            vec![
+                vec!["Name", "Score"],
+                vec!["b", "2"],
+                vec!["a", "2"],
+                vec!["c", "10"],
+                vec!["d", ""],
+            ],
+            None,
+        );
+        let sheet_rect = SheetRect::from_numbers(0, 0, 2, 5, sheet_id);
+        let keys = [
+            SortKey {
+                column: 1,
+                direction: SortDirection::Descending,
+            },
+            SortKey {
+                column: 0,
+                direction: SortDirection::Ascending,
+            },
+        ];
+
+        gc.sort_range(sheet_rect, &keys, true, None);
+        assert_eq!(column(&gc, sheet_id, 0, 0..5), ["Name", "c", "a", "b", "d"]);
+        assert_eq!(
+            column(&gc, sheet_id, 1, 0..5),
+            ["Score", "10", "2", "2", ""]
+        );
+
+        gc.undo(None);
+        assert_eq!(column(&gc, sheet_id, 0, 0..5), ["Name", "b", "a", "c", "d"]);
+
+        gc.redo(None);
+        assert_eq!(column(&gc, sheet_id, 0, 0..5), ["Name", "c", "a", "b", "d"]);
+    }
+
+    #[test]
+    fn test_sort_range_type_precedence() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_values(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            vec![
+                vec!["true"],
+                vec![""],
+                vec!["abc"],
+                vec!["5"],
+                vec!["ABD"],
+                vec!["-1"],
+            ],
+            None,
+        );
+        let sheet_rect = SheetRect::from_numbers(0, 0, 1, 6, sheet_id);
+        let key = SortKey {
+            column: 0,
+            direction: SortDirection::Ascending,
+        };
+        gc.sort_range(sheet_rect, &[key], false, None);
+        assert_eq!(
+            column(&gc, sheet_id, 0, 0..6),
+            ["-1", "5", "abc", "ABD", "TRUE", ""]
+        );
+    }
+
+    #[test]
+    fn test_sort_range_moves_formats() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_values(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            vec![vec!["3"], vec!["1"], vec!["2"]],
+            None,
+        );
+        gc.set_cell_bold(
+            SheetRect::single_pos(Pos { x: 0, y: 0 }, sheet_id),
+            Some(true),
+            None,
+        );
+
+        let sheet_rect = SheetRect::from_numbers(0, 0, 1, 3, sheet_id);
+        let key = SortKey {
+            column: 0,
+            direction: SortDirection::Ascending,
+        };
+        gc.sort_range(sheet_rect, &[key], false, None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.get_formatting_value::<Bold>(Pos { x: 0, y: 0 }), None);
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 0, y: 2 }),
+            Some(true)
+        );
+
+        // sorting already sorted rows is a no-op
+        assert!(gc
+            .sort_range_operations(sheet_rect, &[key], false)
+            .is_empty());
+    }
+}
@@ -27,6 +27,12 @@ pub enum CoreError {
 
     #[error("IndexedDb error: {0}")]
     IndexedDbError(String),
+
+    #[error("Invalid name: {0}")]
+    InvalidName(String),
+
+    #[error("Invalid calculation settings: {0}")]
+    InvalidCalculationSettings(String),
 }
 
 impl From<serde_json::Error> for CoreError {
@@ -100,6 +100,10 @@ pub enum RunErrorMsg {
     IndexOutOfBounds,
     NoMatch,
     InvalidArgument,
+    NoConvergence,
+    InvalidRegex(Cow<'static, str>),
+    EmptyIntersection,
+    TooMuchRecursion,
 }
 
 impl fmt::Display for RunErrorMsg {
@@ -237,6 +241,18 @@ impl fmt::Display for RunErrorMsg {
             Self::InvalidArgument => {
                 write!(f, "Invalid argument")
             }
+            Self::NoConvergence => {
+                write!(f, "Calculation did not converge")
+            }
+            Self::InvalidRegex(e) => {
+                write!(f, "Invalid regular expression: {e}")
+            }
+            Self::EmptyIntersection => {
+                write!(f, "Ranges do not intersect")
+            }
+            Self::TooMuchRecursion => {
+                write!(f, "Too many nested function calls")
+            }
         }
     }
 }
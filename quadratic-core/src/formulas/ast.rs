@@ -1,13 +1,12 @@
 use std::fmt;
 
+use bigdecimal::BigDecimal;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 
 use super::*;
-use crate::{
-    Array, ArraySize, CellValue, CodeResult, CoerceInto, Pos, RunErrorMsg, Spanned, Value,
-};
+use crate::{Array, ArraySize, CellValue, CodeResult, CoerceInto, RunErrorMsg, Spanned, Value};
 
 /// Abstract syntax tree of a formula expression.
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -29,11 +28,19 @@ pub enum AstNodeContents {
         func: Spanned<String>,
         args: Vec<AstNode>,
     },
+    /// Call to the result of an expression, such as `LAMBDA(x, x + 1)(5)`.
+    Call {
+        func: Box<AstNode>,
+        args: Vec<AstNode>,
+    },
     Paren(Box<AstNode>),
     Array(Vec<Vec<AstNode>>),
     CellRef(CellRef),
+    RangeRef(RangeRef),
+    Identifier(String),
+    BadCellRef,
     String(String),
-    Number(f64),
+    Number(BigDecimal),
     Bool(bool),
 }
 impl fmt::Display for AstNodeContents {
@@ -51,6 +58,9 @@ impl fmt::Display for AstNodeContents {
                 write!(f, ")")?;
                 Ok(())
             }
+            AstNodeContents::Call { func, args } => {
+                write!(f, "{func}({})", args.iter().join(", "))
+            }
             AstNodeContents::Paren(contents) => write!(f, "({contents})"),
             AstNodeContents::Array(a) => write!(
                 f,
@@ -58,25 +68,33 @@ impl fmt::Display for AstNodeContents {
                 a.iter().map(|row| row.iter().join(", ")).join("; "),
             ),
             AstNodeContents::CellRef(cellref) => write!(f, "{cellref}"),
+            AstNodeContents::RangeRef(range_ref) => write!(f, "{range_ref}"),
+            AstNodeContents::Identifier(name) => write!(f, "{name}"),
+            AstNodeContents::BadCellRef => write!(f, "#REF!"),
             AstNodeContents::String(s) => write!(f, "{s:?}"),
-            AstNodeContents::Number(n) => write!(f, "{n:?}"),
+            AstNodeContents::Number(n) => write!(f, "{n}"),
             AstNodeContents::Bool(false) => write!(f, "FALSE"),
             AstNodeContents::Bool(true) => write!(f, "TRUE"),
         }
     }
 }
 impl AstNodeContents {
-    fn type_string(&self) -> &'static str {
+    pub(super) fn type_string(&self) -> &'static str {
         match self {
             AstNodeContents::Empty => "empty expression",
             AstNodeContents::FunctionCall { func, .. } => match func.inner.as_str() {
                 "=" | "==" | "<>" | "!=" | "<" | ">" | "<=" | ">=" => "comparison",
-                s if s.chars().all(|c| c.is_alphanumeric() || c == '_') => "function call",
+                "," => "union of ranges",
+                s if is_function_name(s) => "function call",
                 _ => "expression",
             },
+            AstNodeContents::Call { .. } => "function call",
             AstNodeContents::Paren(contents) => contents.inner.type_string(),
             AstNodeContents::Array(_) => "array literal",
             AstNodeContents::CellRef(_) => "cell reference",
+            AstNodeContents::RangeRef(_) => "cell range reference",
+            AstNodeContents::Identifier(_) => "name",
+            AstNodeContents::BadCellRef => "invalid cell reference",
             AstNodeContents::String(_) => "string literal",
             AstNodeContents::Number(_) => "numeric literal",
             AstNodeContents::Bool(_) => "boolean literal",
@@ -97,6 +115,12 @@ impl Spanned<AstNodeContents> {
     }
 }
 
+/// Returns whether `name` is the name of a function rather than an operator.
+pub(super) fn is_function_name(name: &str) -> bool {
+    name.chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+}
+
 impl Formula {
     /// Evaluates a formula.
     pub fn eval(&self, ctx: &mut Ctx<'_>) -> CodeResult<Value> {
@@ -105,64 +129,54 @@ impl Formula {
 }
 
 impl AstNode {
-    fn eval<'ctx: 'a, 'a>(&'a self, ctx: &'a mut Ctx<'ctx>) -> CodeResult {
+    pub(super) fn eval<'ctx: 'a, 'a>(&'a self, ctx: &'a mut Ctx<'ctx>) -> CodeResult {
         let value = match &self.inner {
             AstNodeContents::Empty => CellValue::Blank.into(),
 
-            // Cell range
-            AstNodeContents::FunctionCall { func, args } if func.inner == ":" => {
-                if args.len() != 2 {
-                    internal_error!("invalid arguments to cell range operator");
-                }
-                let ref1 = args[0].to_cell_ref()?;
-                let ref2 = args[1].to_cell_ref()?;
-                let sheet_name = ref1.sheet.clone();
-                let corner1 = ref1.resolve_from(ctx.sheet_pos.into());
-                let corner2 = ref2.resolve_from(ctx.sheet_pos.into());
-
-                let x1 = std::cmp::min(corner1.x, corner2.x);
-                let y1 = std::cmp::min(corner1.y, corner2.y);
-
-                let x2 = std::cmp::max(corner1.x, corner2.x);
-                let y2 = std::cmp::max(corner1.y, corner2.y);
-
-                let width = x2
-                    .saturating_sub(x1)
-                    .saturating_add(1)
-                    .try_into()
-                    .unwrap_or(u32::MAX);
-                let height = y2
-                    .saturating_sub(y1)
-                    .saturating_add(1)
-                    .try_into()
-                    .unwrap_or(u32::MAX);
-                if std::cmp::max(width, height) > crate::limits::CELL_RANGE_LIMIT {
-                    return Err(RunErrorMsg::ArrayTooBig.with_span(self.span));
-                }
+            // Cell range, intersection, union, or implicit intersection
+            AstNodeContents::FunctionCall { func, .. }
+                if reference::is_reference_operator(&func.inner) =>
+            {
+                self.eval_reference_operator(ctx)?
+            }
 
-                // todo: this should call a new ctx.get_cells to push a full SheetRect to cells_accessed instead of an array of SheetPos
-                let mut flat_array = smallvec![];
-                // Reuse the same `CellRef` object so that we don't have to
-                // clone `sheet_name.`
-                let mut cell_ref = CellRef::absolute(sheet_name, Pos::ORIGIN); // We'll overwrite the position.
-                for y in y1..=y2 {
-                    cell_ref.y = CellRefCoord::Absolute(y);
-                    for x in x1..=x2 {
-                        cell_ref.x = CellRefCoord::Absolute(x);
-                        flat_array.push(ctx.get_cell(&cell_ref, self.span)?.inner);
-                    }
-                }
+            // Whole-row or whole-column range
+            AstNodeContents::RangeRef(range_ref) => {
+                ctx.get_row_col_range(range_ref, self.span)?.inner.into()
+            }
 
-                let size = ArraySize::new_or_err(width, height)?;
-                Array::new_row_major(size, flat_array)?.into()
+            // Variable or named range
+            AstNodeContents::Identifier(_) | AstNodeContents::Call { .. } => {
+                self.eval_binding(ctx)?.into_value(self.span)?
+            }
+
+            // `LET`, `LAMBDA`, and functions that take a lambda
+            AstNodeContents::FunctionCall { func, args } if ctx.is_special_form(&func.inner) => {
+                self.eval_special_form(ctx, func, args)?
+            }
+
+            // `IF`, `IFERROR`, and other functions that only evaluate some of
+            // their arguments
+            AstNodeContents::FunctionCall { func, args }
+                if conditional::is_conditional_function(&func.inner) =>
+            {
+                self.eval_conditional(ctx, func, args)?
+            }
+
+            // `INDEX`, `OFFSET`, and functions that take a reference
+            AstNodeContents::FunctionCall { func, args }
+                if reference::is_reference_function(&func.inner) =>
+            {
+                self.eval_reference_function(ctx, func, args)?
             }
 
             // Other operator/function
             AstNodeContents::FunctionCall { func, args } => {
-                let mut arg_values = vec![];
-                for arg in args {
-                    arg_values.push(arg.eval(&mut *ctx)?);
-                }
+                // Errors in arguments are passed to the function as values
+                // so that functions such as `IFERROR` can handle them. Most
+                // functions propagate them when coercing their arguments.
+                let arg_values =
+                    reference::eval_function_args(ctx, args, is_function_name(&func.inner));
 
                 let func_name = &func.inner;
                 match functions::lookup_function(func_name) {
@@ -203,8 +217,12 @@ impl AstNode {
                 Array::from(ctx.get_cell(cell_ref, self.span)?.inner).into()
             }
 
+            AstNodeContents::BadCellRef => {
+                return Err(RunErrorMsg::BadCellReference.with_span(self.span));
+            }
+
             AstNodeContents::String(s) => Value::from(s.to_string()),
-            AstNodeContents::Number(n) => Value::from(*n),
+            AstNodeContents::Number(n) => Value::from(n.clone()),
             AstNodeContents::Bool(b) => Value::from(*b),
         };
 
@@ -213,4 +231,13 @@ impl AstNode {
             inner: value,
         })
     }
+
+    /// Evaluates an expression, returning an error as an error value instead
+    /// so that functions such as `IFERROR` can handle it.
+    pub(super) fn eval_or_error_value(&self, ctx: &mut Ctx<'_>) -> Spanned<Value> {
+        self.eval(ctx).unwrap_or_else(|e| Spanned {
+            span: self.span,
+            inner: CellValue::Error(Box::new(e)).into(),
+        })
+    }
 }
@@ -11,13 +11,11 @@ use crate::Pos;
 #[cfg_attr(feature = "js", derive(ts_rs::TS))]
 #[serde(tag = "type")]
 pub enum RangeRef {
-    // this is not yet used...
     RowRange {
         start: CellRefCoord,
         end: CellRefCoord,
         sheet: Option<String>,
     },
-    // this is not yet used...
     ColRange {
         start: CellRefCoord,
         end: CellRefCoord,
@@ -63,6 +61,110 @@ impl RangeRef {
             RangeRef::Cell { pos } => pos.a1_string(base),
         }
     }
+
+    /// Returns the string representing this range reference in R1C1-style
+    /// notation. Unlike A1-style notation, this does not depend on the location
+    /// of the formula, so a formula copied down a column is written the same
+    /// way in every row.
+    pub fn r1c1_string(&self) -> String {
+        match self {
+            RangeRef::RowRange { start, end, .. } => {
+                format!("{}:{}", start.r1c1_string('R'), end.r1c1_string('R'))
+            }
+            RangeRef::ColRange { start, end, .. } => {
+                format!("{}:{}", start.r1c1_string('C'), end.r1c1_string('C'))
+            }
+            RangeRef::CellRange { start, end } => {
+                format!("{}:{}", start.r1c1_string(), end.r1c1_string())
+            }
+            RangeRef::Cell { pos } => pos.r1c1_string(),
+        }
+    }
+
+    /// Parses an R1C1-style range reference without a sheet name, such as
+    /// `R[-1]C:R[1]C`, `R2:R4`, or `C[1]:C[1]`.
+    pub fn parse_r1c1(s: &str) -> Option<Self> {
+        let Some((start, end)) = s.trim().split_once(':') else {
+            return Some(RangeRef::Cell {
+                pos: CellRef::parse_r1c1(s)?,
+            });
+        };
+        let (start, end) = (start.trim(), end.trim());
+
+        let parse_axis = |s: &str, axis: char| {
+            let rest = s.strip_prefix([axis, axis.to_ascii_lowercase()])?;
+            CellRefCoord::parse_r1c1(rest)
+        };
+
+        if let (Some(start), Some(end)) = (parse_axis(start, 'R'), parse_axis(end, 'R')) {
+            Some(RangeRef::RowRange {
+                start,
+                end,
+                sheet: None,
+            })
+        } else if let (Some(start), Some(end)) = (parse_axis(start, 'C'), parse_axis(end, 'C')) {
+            Some(RangeRef::ColRange {
+                start,
+                end,
+                sheet: None,
+            })
+        } else {
+            Some(RangeRef::CellRange {
+                start: CellRef::parse_r1c1(start)?,
+                end: CellRef::parse_r1c1(end)?,
+            })
+        }
+    }
+
+    /// Parses an A1-style whole-row or whole-column range reference, such as
+    /// `3:5` or `$A:B`, relative to a given location.
+    pub fn parse_a1_rows_or_columns(s: &str, base: Pos, sheet: Option<String>) -> Option<Self> {
+        lazy_static! {
+            /// ^(\$?)(n?)(\d+)$
+            /// ^               $     match full string
+            ///  (\$?)               group 1: optional `$`
+            ///       (n?)           group 2: optional `n`
+            ///           (\d+)      group 3: row number
+            pub static ref A1_ROW_REGEX: Regex = Regex::new(r"^(\$?)(n?)(\d+)$").unwrap();
+
+            /// ^(\$?)(n?[A-Z]+)$
+            /// ^                $     match full string
+            ///  (\$?)                group 1: optional `$`
+            ///       (n?[A-Z]+)      group 2: column name
+            pub static ref A1_COLUMN_REGEX: Regex = Regex::new(r"^(\$?)(n?[A-Z]+)$").unwrap();
+        }
+
+        let (start, end) = s.trim().split_once(':')?;
+
+        let parse_row = |s: &str| {
+            let captures = A1_ROW_REGEX.captures(s.trim())?;
+            let mut row = captures[3].parse::<i64>().ok()?;
+            if !captures[2].is_empty() {
+                row = -row;
+            }
+            Some(if captures[1].is_empty() {
+                CellRefCoord::Relative(row - base.y)
+            } else {
+                CellRefCoord::Absolute(row)
+            })
+        };
+        let parse_column = |s: &str| {
+            let captures = A1_COLUMN_REGEX.captures(s.trim())?;
+            let col = crate::util::column_from_name(&captures[2])?;
+            Some(if captures[1].is_empty() {
+                CellRefCoord::Relative(col - base.x)
+            } else {
+                CellRefCoord::Absolute(col)
+            })
+        };
+
+        if let (Some(start), Some(end)) = (parse_row(start), parse_row(end)) {
+            Some(RangeRef::RowRange { start, end, sheet })
+        } else {
+            let (start, end) = (parse_column(start)?, parse_column(end)?);
+            Some(RangeRef::ColRange { start, end, sheet })
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -108,23 +210,26 @@ impl CellRef {
             None => String::new(),
         };
         let col = self.x.col_string(base.x);
-        let row = self.y.col_string(base.y);
+        let row = self.y.row_string(base.y);
         format!("{sheet_str}{col}{row}")
     }
 
-    /// Parses an A1-style cell reference relative to a given location.
-    pub fn parse_a1(mut s: &str, base: Pos) -> Option<CellRef> {
-        let mut sheet = None;
-        if let Some((sheet_name_str, rest)) = s.split_once('!') {
-            s = rest;
-            if sheet_name_str.starts_with(['\'', '"']) {
-                sheet = crate::formulas::parse_string_literal(sheet_name_str.trim());
-            } else {
-                sheet = Some(sheet_name_str.trim().to_string());
-            }
-        }
+    /// Returns the string representing this cell reference in R1C1-style
+    /// notation, such as `R2C3` or `R[-1]C`. Absolute coordinates are the same
+    /// numbers used by the grid, so column A is column 0.
+    pub fn r1c1_string(&self) -> String {
+        let sheet_str = match &self.sheet {
+            Some(sheet_name) => format!("{}!", crate::formulas::escape_string(sheet_name)),
+            None => String::new(),
+        };
+        let row = self.y.r1c1_string('R');
+        let col = self.x.r1c1_string('C');
+        format!("{sheet_str}{row}{col}")
+    }
 
-        s = s.trim();
+    /// Parses an A1-style cell reference relative to a given location.
+    pub fn parse_a1(s: &str, base: Pos) -> Option<CellRef> {
+        let (sheet, s) = split_sheet_name(s);
 
         lazy_static! {
             /// ^(\$?)(n?[A-Z]+)(\$?)(n?)(\d+)$
@@ -169,6 +274,34 @@ impl CellRef {
             y: row_ref,
         })
     }
+
+    /// Parses an R1C1-style cell reference, such as `R2C3` or `R[-1]C`.
+    pub fn parse_r1c1(s: &str) -> Option<CellRef> {
+        let (sheet, s) = split_sheet_name(s);
+        let rest = s.strip_prefix(['R', 'r'])?;
+        let (row, col) = rest.split_once(['C', 'c'])?;
+        Some(CellRef {
+            sheet,
+            x: CellRefCoord::parse_r1c1(col)?,
+            y: CellRefCoord::parse_r1c1(row)?,
+        })
+    }
+}
+
+/// Splits an optional sheet name, such as `'Sheet 2'!`, from the start of a
+/// cell reference, and returns the sheet name and the rest of the reference.
+fn split_sheet_name(s: &str) -> (Option<String>, &str) {
+    match s.split_once('!') {
+        Some((sheet_name_str, rest)) => {
+            let sheet = if sheet_name_str.starts_with(['\'', '"']) {
+                crate::formulas::parse_string_literal(sheet_name_str.trim())
+            } else {
+                Some(sheet_name_str.trim().to_string())
+            };
+            (sheet, rest.trim())
+        }
+        None => (None, s.trim()),
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -240,7 +373,47 @@ impl CellRefCoord {
     /// a row coordinate.
     fn row_string(self, base: i64) -> String {
         let row = self.resolve_from(base);
-        format!("{}{row}", self.prefix())
+        if row < 0 {
+            format!("{}n{}", self.prefix(), -row)
+        } else {
+            format!("{}{row}", self.prefix())
+        }
+    }
+
+    /// Returns the string representing this coordinate in R1C1-style notation,
+    /// where `axis` is `R` for a row or `C` for a column. A relative coordinate
+    /// with no offset is written as just the axis letter.
+    fn r1c1_string(self, axis: char) -> String {
+        match self {
+            CellRefCoord::Relative(0) => axis.to_string(),
+            CellRefCoord::Relative(delta) => format!("{axis}[{delta}]"),
+            CellRefCoord::Absolute(_) => format!("{axis}{self}"),
+        }
+    }
+    /// Parses an R1C1-style coordinate without its axis letter: the empty
+    /// string or `[delta]` for a relative coordinate, or a number (with `n` for
+    /// negative) for an absolute coordinate.
+    fn parse_r1c1(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Some(CellRefCoord::Relative(0));
+        }
+        if let Some(delta) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            return Some(CellRefCoord::Relative(delta.trim().parse().ok()?));
+        }
+        let (is_negative, digits) = match s.strip_prefix(['n', 'N']) {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let coord = digits.parse::<i64>().ok()?;
+        Some(CellRefCoord::Absolute(if is_negative {
+            -coord
+        } else {
+            coord
+        }))
     }
 
     /// Returns whether the coordinate is relative (i.e., no '$' prefix).
@@ -279,6 +452,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_a1_rows_or_columns_parsing() {
+        let base = pos![B3];
+        assert_eq!(
+            RangeRef::parse_a1_rows_or_columns("A:$C", base, None),
+            Some(RangeRef::ColRange {
+                start: CellRefCoord::Relative(-1),
+                end: CellRefCoord::Absolute(2),
+                sheet: None,
+            })
+        );
+        assert_eq!(
+            RangeRef::parse_a1_rows_or_columns("$4:n1", base, Some("Sheet 2".to_string())),
+            Some(RangeRef::RowRange {
+                start: CellRefCoord::Absolute(4),
+                end: CellRefCoord::Relative(-4),
+                sheet: Some("Sheet 2".to_string()),
+            })
+        );
+        assert_eq!(RangeRef::parse_a1_rows_or_columns("A:1", base, None), None);
+
+        let range = RangeRef::parse_a1_rows_or_columns("B:$D", base, None).unwrap();
+        assert_eq!(range.a1_string(base), "B:$D");
+        let range = RangeRef::parse_a1_rows_or_columns("$3:5", base, None).unwrap();
+        assert_eq!(range.a1_string(base), "$3:5");
+    }
+
+    #[test]
+    fn test_r1c1_parsing() {
+        for s in ["RC", "R2C3", "R[-1]C[2]", "Rn4C[0]", "R0Cn1"] {
+            let cell_ref = CellRef::parse_r1c1(s).expect("invalid cell reference");
+            let expected = s.replace("[0]", "");
+            assert_eq!(cell_ref.r1c1_string(), expected);
+        }
+        assert_eq!(
+            CellRef::parse_r1c1("'Sheet 2'!r[1]c5"),
+            Some(CellRef {
+                sheet: Some("Sheet 2".to_string()),
+                x: CellRefCoord::Absolute(5),
+                y: CellRefCoord::Relative(1),
+            })
+        );
+        for s in ["", "R", "C", "R1", "A1", "R-1C", "R[x]C", "R1C1C"] {
+            assert_eq!(CellRef::parse_r1c1(s), None, "{s:?}");
+        }
+
+        assert_eq!(
+            RangeRef::parse_r1c1("R:R[2]"),
+            Some(RangeRef::RowRange {
+                start: CellRefCoord::Relative(0),
+                end: CellRefCoord::Relative(2),
+                sheet: None,
+            })
+        );
+        assert_eq!(
+            RangeRef::parse_r1c1("C1:Cn1"),
+            Some(RangeRef::ColRange {
+                start: CellRefCoord::Absolute(1),
+                end: CellRefCoord::Absolute(-1),
+                sheet: None,
+            })
+        );
+        let range = RangeRef::parse_r1c1("R1C1:R[1]C[1]").unwrap();
+        assert_eq!(range.r1c1_string(), "R1C1:R[1]C[1]");
+        assert_eq!(range.a1_string(pos![B3]), "$B$1:C4");
+        assert_eq!(RangeRef::parse_r1c1("R1:C1"), None);
+    }
+
     #[test]
     fn test_a1_sheet_parsing() {
         let pos = CellRef::parse_a1("'Sheet 2'!A0", crate::Pos::ORIGIN);
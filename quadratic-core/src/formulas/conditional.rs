@@ -0,0 +1,241 @@
+//! Evaluation of `IF`, `IFS`, `SWITCH`, `IFERROR`, and `IFNA`.
+//!
+//! These are evaluated directly from the syntax tree instead of through
+//! [`super::functions`], so that only the branches that are taken are
+//! evaluated. Errors in other branches are ignored, and cells that are only
+//! referenced in other branches are not recorded in `cells_accessed`.
+
+use super::lambda::{missing_arg, wrong_arg_count};
+use super::*;
+use crate::{CellValue, CodeResult, CoerceInto, RunErrorMsg, Span, Spanned, Value};
+
+/// Functions that are evaluated by [`AstNode::eval_conditional()`].
+const CONDITIONAL_FUNCTIONS: &[&str] = &["IF", "IFS", "SWITCH", "IFERROR", "IFNA"];
+
+/// Returns whether a call to `func_name` must be evaluated by
+/// [`AstNode::eval_conditional()`].
+pub(super) fn is_conditional_function(func_name: &str) -> bool {
+    CONDITIONAL_FUNCTIONS
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(func_name))
+}
+
+impl AstNode {
+    /// Evaluates a call to a function for which [`is_conditional_function()`]
+    /// returns `true`.
+    pub(super) fn eval_conditional(
+        &self,
+        ctx: &mut Ctx<'_>,
+        func: &Spanned<String>,
+        args: &[AstNode],
+    ) -> CodeResult<Value> {
+        let span = self.span;
+        match func.inner.to_ascii_uppercase().as_str() {
+            "IF" => {
+                let [condition, t, f] = args else {
+                    return Err(wrong_arg_count("IF", args, &["condition", "t", "f"], span));
+                };
+                let condition = condition.eval(ctx)?;
+                let no_branch = CellValue::Blank;
+                eval_selected_branches(ctx, &[condition], &[t, f], no_branch, |args| {
+                    let condition = args[0].try_coerce::<bool>()?.inner;
+                    Ok(Some(if condition { 0 } else { 1 }))
+                })
+            }
+
+            "IFS" => {
+                if args.is_empty() || args.len() % 2 == 1 {
+                    let arg_name = if args.is_empty() {
+                        "condition1"
+                    } else {
+                        "value"
+                    };
+                    return Err(missing_arg("IFS", arg_name, span));
+                }
+                let no_match = CellValue::Error(Box::new(RunErrorMsg::NoMatch.with_span(span)));
+
+                // Conditions are evaluated in order until one of them is true.
+                // If one is an array, then the remaining conditions are
+                // evaluated up front and a value is chosen for each element.
+                for (i, pair) in args.chunks(2).enumerate() {
+                    let condition = pair[0].eval(ctx)?;
+                    if let Ok(value) = condition.cell_value() {
+                        if value.try_coerce::<bool>()?.inner {
+                            return Ok(single_if_possible(pair[1].eval(ctx)?.inner));
+                        }
+                        continue;
+                    }
+
+                    let rest = &args[i * 2..];
+                    let mut conditions = vec![condition];
+                    for arg in rest.iter().skip(2).step_by(2) {
+                        conditions.push(arg.eval(ctx)?);
+                    }
+                    let values = rest.iter().skip(1).step_by(2).collect::<Vec<_>>();
+                    return eval_selected_branches(ctx, &conditions, &values, no_match, |args| {
+                        for (j, condition) in args.iter().enumerate() {
+                            if condition.try_coerce::<bool>()?.inner {
+                                return Ok(Some(j));
+                            }
+                        }
+                        Ok(None)
+                    });
+                }
+                Ok(no_match.into())
+            }
+
+            "SWITCH" => {
+                let [expression, cases @ ..] = args else {
+                    return Err(missing_arg("SWITCH", "expression", span));
+                };
+                if cases.len() < 2 {
+                    return Err(wrong_arg_count(
+                        "SWITCH",
+                        args,
+                        &["expression", "value1", "result1"],
+                        span,
+                    ));
+                }
+
+                // Values to compare against are evaluated up front, but only
+                // the result that matches is evaluated.
+                let mut selectors = vec![expression.eval(ctx)?];
+                let mut branches = vec![];
+                for pair in cases.chunks(2) {
+                    match pair {
+                        [value, result] => {
+                            selectors.push(value.eval(ctx)?);
+                            branches.push(result);
+                        }
+                        // An odd argument at the end is the default.
+                        _ => branches.push(&pair[0]),
+                    }
+                }
+                let no_match = CellValue::Error(Box::new(RunErrorMsg::NoMatch.with_span(span)));
+                let has_default = branches.len() == selectors.len();
+                eval_selected_branches(ctx, &selectors, &branches, no_match, |args| {
+                    let [expression, values @ ..] = args else {
+                        internal_error!("missing expression in SWITCH");
+                    };
+                    for (j, value) in values.iter().enumerate() {
+                        if expression.inner.eq(value.inner)? {
+                            return Ok(Some(j));
+                        }
+                    }
+                    Ok(has_default.then_some(values.len()))
+                })
+            }
+
+            "IFERROR" | "IFNA" => {
+                let is_ifna = func.inner.eq_ignore_ascii_case("IFNA");
+                let func_name = if is_ifna { "IFNA" } else { "IFERROR" };
+                let [value, fallback] = args else {
+                    return Err(wrong_arg_count(
+                        func_name,
+                        args,
+                        &["value", "fallback"],
+                        span,
+                    ));
+                };
+                let is_caught = |v: &CellValue| match v {
+                    CellValue::Error(e) => !is_ifna || e.msg == RunErrorMsg::NoMatch,
+                    _ => false,
+                };
+
+                let value = value.eval_or_error_value(ctx);
+                if !value.inner.cell_values_slice().iter().any(is_caught) {
+                    return Ok(single_if_possible(value.inner));
+                }
+                let fallback = fallback.eval_or_error_value(ctx);
+                ctx.zip_map(&[value, fallback], |_ctx, args| {
+                    let [value, fallback] = args else {
+                        internal_error!("wrong number of arguments to zip_map");
+                    };
+                    Ok(if is_caught(value.inner) {
+                        fallback
+                    } else {
+                        value
+                    }
+                    .inner
+                    .clone())
+                })
+            }
+
+            _ => internal_error!("unknown conditional function {:?}", func.inner),
+        }
+    }
+}
+
+/// Evaluates the branch chosen by `select` given the values of `selectors`.
+/// If `select` returns `None`, then the result is `otherwise`.
+///
+/// If any selector is an array, then a branch is chosen for each element, and
+/// each branch that is chosen at least once is evaluated.
+fn eval_selected_branches(
+    ctx: &mut Ctx<'_>,
+    selectors: &[Spanned<Value>],
+    branches: &[&AstNode],
+    otherwise: CellValue,
+    mut select: impl FnMut(&[Spanned<&CellValue>]) -> CodeResult<Option<usize>>,
+) -> CodeResult<Value> {
+    let size = Value::common_array_size(selectors)?;
+
+    let mut args_buffer = Vec::with_capacity(selectors.len());
+    if size.len() == 1 {
+        for selector in selectors {
+            args_buffer.push(selector.cell_value()?);
+        }
+        return match select(&args_buffer)? {
+            Some(i) => Ok(single_if_possible(branches[i].eval(ctx)?.inner)),
+            None => Ok(otherwise.into()),
+        };
+    }
+
+    let mut is_chosen = vec![false; branches.len()];
+    for (x, y) in size.iter() {
+        args_buffer.clear();
+        for selector in selectors {
+            args_buffer.push(selector.get(x, y)?);
+        }
+        if let Some(i) = select(&args_buffer)? {
+            is_chosen[i] = true;
+        }
+    }
+    let branch_values = branches.iter().zip(is_chosen).map(|(branch, is_chosen)| {
+        if is_chosen {
+            branch.eval_or_error_value(ctx)
+        } else {
+            unused_value(branch.span)
+        }
+    });
+    let all_values = selectors
+        .iter()
+        .cloned()
+        .chain(branch_values.collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let n = selectors.len();
+    ctx.zip_map(&all_values, |_ctx, args| {
+        Ok(match select(&args[..n])? {
+            Some(i) => args[n + i].inner.clone(),
+            None => otherwise.clone(),
+        })
+    })
+}
+
+/// Returns a placeholder for a branch that is never chosen.
+fn unused_value(span: Span) -> Spanned<Value> {
+    Spanned {
+        span,
+        inner: CellValue::Blank.into(),
+    }
+}
+
+/// Returns the value in a 1x1 array as a single value, the same as
+/// [`Ctx::zip_map()`] does.
+fn single_if_possible(value: Value) -> Value {
+    match value {
+        Value::Array(a) => a.into_cell_value().map_or_else(Value::Array, Value::Single),
+        other => other,
+    }
+}
@@ -150,6 +150,51 @@ impl Criterion {
             // Ignore blank values
             .filter_map_ok(|v| v.coerce_nonblank::<T>()))
     }
+
+    /// Iterates over values in `output_values_range` for which the
+    /// corresponding value in every range of `criteria` matches its criterion.
+    pub fn iter_matching_all<'a>(
+        criteria: &'a [(Spanned<Array>, Criterion)],
+        output_values_range: &'a Spanned<Array>,
+    ) -> CodeResult<impl 'a + Iterator<Item = Spanned<&'a CellValue>>> {
+        for (eval_range, _) in criteria {
+            if eval_range.inner.size() != output_values_range.inner.size() {
+                return Err(RunErrorMsg::ExactArraySizeMismatch {
+                    expected: output_values_range.inner.size(),
+                    got: eval_range.inner.size(),
+                }
+                .with_span(eval_range.span));
+            }
+        }
+
+        Ok(output_values_range
+            .inner
+            .cell_values_slice()
+            .iter()
+            .enumerate()
+            .filter(|(i, _output_value)| {
+                criteria.iter().all(|(eval_range, criterion)| {
+                    criterion.matches(&eval_range.inner.cell_values_slice()[*i])
+                })
+            })
+            .map(|(_i, output_value)| output_value)
+            .with_all_same_span(output_values_range.span))
+    }
+    /// Iterates over values in `output_values_range` that match all
+    /// `criteria` and coerces each one, excluding those where coercion fails.
+    pub fn iter_matching_all_coerced<'a, T>(
+        criteria: &'a [(Spanned<Array>, Criterion)],
+        output_values_range: &'a Spanned<Array>,
+    ) -> CodeResult<impl 'a + Iterator<Item = CodeResult<T>>>
+    where
+        &'a CellValue: TryInto<T>,
+    {
+        Ok(Self::iter_matching_all(criteria, output_values_range)?
+            // Propagate errors
+            .map(|v| v.into_non_error_value())
+            // Ignore blank values
+            .filter_map_ok(|v| v.coerce_nonblank::<T>()))
+    }
 }
 
 fn strip_compare_fn_prefix(s: &str) -> Option<(CompareFn, &str)> {
@@ -1,11 +1,15 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use smallvec::SmallVec;
+use regex::Regex;
 
+use smallvec::{smallvec, SmallVec};
+
+use super::lambda::Binding;
 use super::*;
 use crate::{
-    grid::Grid, Array, CellValue, CodeResult, RunErrorMsg, SheetPos, SheetRect, Span, Spanned,
-    Value,
+    grid::{Grid, GridBounds, Sheet},
+    Array, ArraySize, CellValue, CodeResult, Pos, Rect, RunErrorMsg, SheetPos, SheetRect, Span,
+    Spanned, Value,
 };
 
 /// Formula execution context.
@@ -16,6 +20,17 @@ pub struct Ctx<'ctx> {
     pub sheet_pos: SheetPos,
     /// Cells that have been accessed in evaluating the formula.
     pub cells_accessed: HashSet<SheetRect>,
+    /// Whether the formula may read its own cell, which holds its value from
+    /// the previous iteration of an iterative calculation.
+    pub allow_self_reference: bool,
+    /// Variables in scope, defined by `LET` or by lambda parameters. Later
+    /// variables shadow earlier ones with the same name.
+    pub(super) variables: Vec<(String, Binding)>,
+    /// Regular expressions that have been compiled in evaluating the formula,
+    /// by pattern.
+    pub(super) regex_cache: HashMap<String, Regex>,
+    /// Number of lambda calls currently being evaluated.
+    pub(super) call_depth: usize,
 }
 impl<'ctx> Ctx<'ctx> {
     /// Constructs a context for evaluating a formula at `pos` in `grid`.
@@ -24,25 +39,97 @@ impl<'ctx> Ctx<'ctx> {
             grid,
             sheet_pos,
             cells_accessed: HashSet::new(),
+            allow_self_reference: false,
+            variables: vec![],
+            regex_cache: HashMap::new(),
+            call_depth: 0,
+        }
+    }
+
+    /// Returns the sheet referred to by `sheet_ref`, or the sheet where the
+    /// formula is being evaluated if there is no sheet reference.
+    pub(super) fn resolve_sheet(
+        &self,
+        sheet_ref: &Option<String>,
+        span: Span,
+    ) -> CodeResult<&'ctx Sheet> {
+        let grid = self.grid;
+        match sheet_ref {
+            Some(sheet_ref) => grid.try_sheet_from_formula_ref(sheet_ref),
+            None => grid.try_sheet(self.sheet_pos.sheet_id),
         }
+        .ok_or(RunErrorMsg::BadCellReference.with_span(span))
+    }
+
+    /// Records every cell reference and named range in `source` in
+    /// `cells_accessed` without evaluating the formula. References that are
+    /// only known when the formula is evaluated, such as the result of
+    /// `OFFSET`, are not recorded.
+    pub fn record_references(&mut self, source: &str) {
+        let base: Pos = self.sheet_pos.into();
+        for range_ref in find_cell_references(source, base) {
+            let span = range_ref.span;
+            let (sheet_ref, rect) = match &range_ref.inner {
+                RangeRef::RowRange { .. } | RangeRef::ColRange { .. } => {
+                    if let Ok((sheet, unbounded)) =
+                        self.resolve_row_col_range(&range_ref.inner, span)
+                    {
+                        self.cells_accessed
+                            .insert(unbounded.to_sheet_rect(sheet.id));
+                    }
+                    continue;
+                }
+                RangeRef::CellRange { start, end } => (
+                    &start.sheet,
+                    Rect::new_span(start.resolve_from(base), end.resolve_from(base)),
+                ),
+                RangeRef::Cell { pos } => (&pos.sheet, Rect::single_pos(pos.resolve_from(base))),
+            };
+            if let Ok(sheet) = self.resolve_sheet(sheet_ref, span) {
+                self.cells_accessed.insert(rect.to_sheet_rect(sheet.id));
+            }
+        }
+        for name in find_named_ranges(source) {
+            if let Some(sheet_rect) = self
+                .grid
+                .named_range(&name.inner)
+                .and_then(|named_range| named_range.sheet_rect)
+            {
+                self.cells_accessed.insert(sheet_rect);
+            }
+        }
+    }
+
+    /// Returns every whole-row and whole-column range in `source`. Only the
+    /// part of these ranges within the sheet's bounds is recorded in
+    /// `cells_accessed` when the formula is evaluated, so the whole ranges
+    /// are tracked separately to find the formulas that read data added
+    /// outside the bounds.
+    pub fn row_col_references(&self, source: &str) -> Vec<SheetRect> {
+        find_cell_references(source, self.sheet_pos.into())
+            .into_iter()
+            .filter(|range_ref| {
+                matches!(
+                    range_ref.inner,
+                    RangeRef::RowRange { .. } | RangeRef::ColRange { .. }
+                )
+            })
+            .filter_map(|range_ref| {
+                let (sheet, unbounded) = self
+                    .resolve_row_col_range(&range_ref.inner, range_ref.span)
+                    .ok()?;
+                Some(unbounded.to_sheet_rect(sheet.id))
+            })
+            .collect()
     }
 
     /// Fetches the contents of the cell at `ref_pos` evaluated at `base_pos`,
     /// or returns an error in the case of a circular reference.
     pub fn get_cell(&mut self, ref_pos: &CellRef, span: Span) -> CodeResult<Spanned<CellValue>> {
-        let sheet = match &ref_pos.sheet {
-            Some(sheet_name) => self
-                .grid
-                .try_sheet_from_name(sheet_name.clone())
-                .ok_or(RunErrorMsg::BadCellReference.with_span(span))?,
-            None => self
-                .grid
-                .try_sheet(self.sheet_pos.sheet_id)
-                .ok_or(RunErrorMsg::BadCellReference.with_span(span))?,
-        };
+        let sheet = self.resolve_sheet(&ref_pos.sheet, span)?;
         let ref_pos = ref_pos.resolve_from(self.sheet_pos.into());
         let ref_pos_with_sheet = ref_pos.to_sheet_pos(sheet.id);
-        if ref_pos_with_sheet == self.sheet_pos {
+        if ref_pos_with_sheet == self.sheet_pos && !self.allow_self_reference {
             return Err(RunErrorMsg::CircularReference.with_span(span));
         }
 
@@ -52,6 +139,172 @@ impl<'ctx> Ctx<'ctx> {
         Ok(Spanned { inner: value, span })
     }
 
+    /// Fetches the contents of the cells in `rect` on the sheet referred to by
+    /// `sheet_ref`, or returns an error in the case of a circular reference.
+    pub fn get_cell_array(
+        &mut self,
+        sheet_ref: &Option<String>,
+        rect: Rect,
+        span: Span,
+    ) -> CodeResult<Spanned<Array>> {
+        let sheet = self.resolve_sheet(sheet_ref, span)?;
+        self.cells_accessed.insert(rect.to_sheet_rect(sheet.id));
+        self.read_cell_array(sheet, rect, span)
+    }
+
+    /// Fetches the contents of the cells in the named range `name`, or returns
+    /// an error if there is no such named range or its cells were deleted.
+    pub fn get_named_range(&mut self, name: &str, span: Span) -> CodeResult<Spanned<Array>> {
+        let sheet_rect = self
+            .grid
+            .named_range(name)
+            .and_then(|named_range| named_range.sheet_rect)
+            .ok_or(RunErrorMsg::BadCellReference.with_span(span))?;
+        self.get_sheet_rect(sheet_rect, span)
+    }
+
+    /// Fetches the contents of the cells in `sheet_rect`, or returns an error
+    /// in the case of a circular reference.
+    pub fn get_sheet_rect(
+        &mut self,
+        sheet_rect: SheetRect,
+        span: Span,
+    ) -> CodeResult<Spanned<Array>> {
+        let sheet = self
+            .grid
+            .try_sheet(sheet_rect.sheet_id)
+            .ok_or(RunErrorMsg::BadCellReference.with_span(span))?;
+        self.cells_accessed.insert(sheet_rect);
+        self.read_cell_array(sheet, sheet_rect.into(), span)
+    }
+
+    /// Fetches the contents of a whole-row or whole-column range. Only the part
+    /// of the range within the sheet's data bounds is read and recorded in
+    /// `cells_accessed`; see [`Self::row_col_references`] for how data added
+    /// outside the bounds is tracked.
+    pub fn get_row_col_range(
+        &mut self,
+        range_ref: &RangeRef,
+        span: Span,
+    ) -> CodeResult<Spanned<Array>> {
+        let (sheet, unbounded) = self.resolve_row_col_range(range_ref, span)?;
+
+        let GridBounds::NonEmpty(bounds) = sheet.bounds(true) else {
+            return Ok(Spanned {
+                inner: Array::from(CellValue::Blank),
+                span,
+            });
+        };
+        let min = Pos {
+            x: unbounded.min.x.max(bounds.min.x),
+            y: unbounded.min.y.max(bounds.min.y),
+        };
+        let max = Pos {
+            x: unbounded.max.x.min(bounds.max.x),
+            y: unbounded.max.y.min(bounds.max.y),
+        };
+        if min.x > max.x || min.y > max.y {
+            return Ok(Spanned {
+                inner: Array::from(CellValue::Blank),
+                span,
+            });
+        }
+        let rect = Rect { min, max };
+        self.cells_accessed.insert(rect.to_sheet_rect(sheet.id));
+        self.read_cell_array(sheet, rect, span)
+    }
+
+    /// Returns the sheet and the unbounded rectangle of a whole-row or
+    /// whole-column range.
+    pub(super) fn resolve_row_col_range(
+        &self,
+        range_ref: &RangeRef,
+        span: Span,
+    ) -> CodeResult<(&'ctx Sheet, Rect)> {
+        let base: Pos = self.sheet_pos.into();
+        let (sheet_ref, unbounded) = match range_ref {
+            RangeRef::RowRange { start, end, sheet } => {
+                let (y1, y2) = (start.resolve_from(base.y), end.resolve_from(base.y));
+                let rect = Rect::new_span(Pos { x: i64::MIN, y: y1 }, Pos { x: i64::MAX, y: y2 });
+                (sheet, rect)
+            }
+            RangeRef::ColRange { start, end, sheet } => {
+                let (x1, x2) = (start.resolve_from(base.x), end.resolve_from(base.x));
+                let rect = Rect::new_span(Pos { x: x1, y: i64::MIN }, Pos { x: x2, y: i64::MAX });
+                (sheet, rect)
+            }
+            RangeRef::CellRange { .. } | RangeRef::Cell { .. } => {
+                internal_error!("expected a row or column range")
+            }
+        };
+
+        let sheet = self.resolve_sheet(sheet_ref, span)?;
+        Ok((sheet, unbounded))
+    }
+
+    /// Reads the contents of the cells in `rect` without recording them in
+    /// `cells_accessed`.
+    fn read_cell_array(&self, sheet: &Sheet, rect: Rect, span: Span) -> CodeResult<Spanned<Array>> {
+        let width = rect
+            .max
+            .x
+            .saturating_sub(rect.min.x)
+            .saturating_add(1)
+            .try_into()
+            .unwrap_or(u32::MAX);
+        let height = rect
+            .max
+            .y
+            .saturating_sub(rect.min.y)
+            .saturating_add(1)
+            .try_into()
+            .unwrap_or(u32::MAX);
+        if std::cmp::max(width, height) > crate::limits::CELL_RANGE_LIMIT {
+            return Err(RunErrorMsg::ArrayTooBig.with_span(span));
+        }
+        if !self.allow_self_reference
+            && sheet.id == self.sheet_pos.sheet_id
+            && rect.contains(self.sheet_pos.into())
+        {
+            return Err(RunErrorMsg::CircularReference.with_span(span));
+        }
+
+        let mut flat_array = smallvec![];
+        for y in rect.y_range() {
+            for x in rect.x_range() {
+                flat_array.push(
+                    sheet
+                        .display_value(Pos { x, y })
+                        .unwrap_or(CellValue::Blank),
+                );
+            }
+        }
+
+        let size = ArraySize::new_or_err(width, height)?;
+        Ok(Spanned {
+            inner: Array::new_row_major(size, flat_array)?,
+            span,
+        })
+    }
+
+    /// Returns the compiled regular expression for `pattern`, or an error if
+    /// it is invalid. Each pattern is only compiled once per evaluation, so
+    /// that applying a pattern to a large array is fast.
+    pub(super) fn get_regex(&mut self, pattern: &str, span: Span) -> CodeResult<Regex> {
+        if let Some(regex) = self.regex_cache.get(pattern) {
+            return Ok(regex.clone());
+        }
+        let regex = Regex::new(pattern).map_err(|e| {
+            let message = match e {
+                regex::Error::Syntax(s) => s,
+                other => other.to_string(),
+            };
+            RunErrorMsg::InvalidRegex(message.into()).with_span(span)
+        })?;
+        self.regex_cache.insert(pattern.to_string(), regex.clone());
+        Ok(regex)
+    }
+
     /// Evaluates a function once for each corresponding set of values from
     /// `arrays`.
     ///
@@ -66,7 +319,7 @@ impl<'ctx> Ctx<'ctx> {
     pub fn zip_map<'a, I: Copy + IntoIterator<Item = &'a Spanned<Value>>>(
         &mut self,
         arrays: I,
-        f: impl for<'b> Fn(&'b mut Ctx<'_>, &[Spanned<&CellValue>]) -> CodeResult<CellValue>,
+        mut f: impl for<'b> FnMut(&'b mut Ctx<'_>, &[Spanned<&CellValue>]) -> CodeResult<CellValue>,
     ) -> CodeResult<Value>
     where
         I::IntoIter: ExactSizeIterator,
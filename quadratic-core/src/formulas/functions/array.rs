@@ -0,0 +1,604 @@
+use std::cmp::Ordering;
+
+use smallvec::SmallVec;
+
+use crate::{limits::CELL_RANGE_LIMIT, ArraySize};
+
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Array functions",
+    docs: "These functions construct, rearrange, or select from arrays. The \
+           resulting array spills into the cells below and to the right of \
+           the formula.\
+           \n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns the rows or columns of `array` for which `include` is
+            /// `TRUE`.
+            ///
+            /// If `include` is a column, then it must have the same height as
+            /// `array` and selects rows. If `include` is a row, then it must
+            /// have the same width as `array` and selects columns.
+            ///
+            /// If nothing is selected, returns `if_empty`, or an error if
+            /// `if_empty` is omitted.
+            #[examples(
+                "FILTER(A1:C10, B1:B10 > 5)",
+                "FILTER(A1:C10, A1:A10 = \"apple\", \"none\")"
+            )]
+            fn FILTER(
+                span: Span,
+                array: (Spanned<Array>),
+                include: (Spanned<Array>),
+                if_empty: (Option<Spanned<Value>>),
+            ) {
+                let axis = include.array_linear_axis()?.unwrap_or(Axis::Y);
+                include.check_array_size_on(axis, array.inner.size()[axis].get())?;
+                let mut indices = vec![];
+                for (i, value) in include.inner.cell_values_slice().iter().enumerate() {
+                    let keep: bool = Spanned {
+                        span: include.span,
+                        inner: value,
+                    }
+                    .try_coerce()?
+                    .inner;
+                    if keep {
+                        indices.push(i as u32);
+                    }
+                }
+                match (indices.is_empty(), if_empty) {
+                    (true, Some(if_empty)) => if_empty.inner,
+                    (true, None) => return Err(RunErrorMsg::EmptyArray.with_span(span)),
+                    (false, _) => select(&array.inner, axis, &indices)?.into(),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Sorts the rows of `array` by the values in the column
+            /// `sort_index`, which defaults to 1.
+            ///
+            /// If `sort_order` is 1 or omitted, values are sorted in ascending
+            /// order. If `sort_order` is -1, values are sorted in descending
+            /// order.
+            ///
+            /// If `by_col` is `TRUE`, then the columns of `array` are sorted
+            /// by the values in the row `sort_index` instead.
+            ///
+            /// Numbers come before text, which comes before logical values,
+            /// errors, and then blank cells.
+            #[examples("SORT(A1:C10)", "SORT(A1:C10, 2, -1)", "SORT(A1:E2, 1, 1, TRUE)")]
+            fn SORT(
+                array: (Spanned<Array>),
+                sort_index: (Option<Spanned<i64>>),
+                sort_order: (Option<Spanned<i64>>),
+                by_col: (Option<bool>),
+            ) {
+                let axis = if by_col.unwrap_or(false) {
+                    Axis::X
+                } else {
+                    Axis::Y
+                };
+                let key_index = match sort_index {
+                    Some(index) => resolve_index(index, array.inner.size()[axis.other_axis()])?,
+                    None => 0,
+                };
+                let key = slice(&array.inner, axis.other_axis(), key_index)?;
+                let sort_order = SortOrder::try_from(sort_order)?;
+                let mut indices = (0..key.len() as u32).collect_vec();
+                indices.sort_by(|&a, &b| sort_order.cmp(key[a as usize], key[b as usize]));
+                select(&array.inner, axis, &indices)?
+            }
+        ),
+        formula_fn!(
+            /// Sorts the rows of `array` by the values in `by_array`, which
+            /// must be a column with the same height as `array`.
+            ///
+            /// If `by_array` is a row with the same width as `array`, then the
+            /// columns of `array` are sorted instead.
+            ///
+            /// If `sort_order` is 1 or omitted, values are sorted in ascending
+            /// order. If `sort_order` is -1, values are sorted in descending
+            /// order.
+            ///
+            /// Additional pairs of `by_array` and `sort_order` may be given to
+            /// sort rows or columns that are equal by earlier arrays.
+            #[examples("SORTBY(A1:B10, C1:C10)", "SORTBY(A1:B10, C1:C10, -1, D1:D10, 1)")]
+            fn SORTBY(
+                array: (Spanned<Array>),
+                by_array: (Spanned<Array>),
+                sort_order: (Option<Spanned<i64>>),
+                more_arrays_and_orders: (Iter<Spanned<Value>>),
+            ) {
+                let axis = by_array.array_linear_axis()?.unwrap_or(Axis::Y);
+                let len = array.inner.size()[axis].get();
+
+                let mut keys = vec![(by_array, SortOrder::try_from(sort_order)?)];
+                let more_arrays_and_orders =
+                    more_arrays_and_orders.collect::<CodeResult<Vec<_>>>()?;
+                for pair in more_arrays_and_orders.chunks(2) {
+                    let by_array = pair[0].clone().map(Array::from);
+                    let sort_order = match pair.get(1) {
+                        Some(v) => Some(v.clone().try_coerce::<i64>()?),
+                        None => None,
+                    };
+                    keys.push((by_array, SortOrder::try_from(sort_order)?));
+                }
+                for (by_array, _) in &keys {
+                    by_array.check_array_size_on(axis.other_axis(), 1)?;
+                    by_array.check_array_size_on(axis, len)?;
+                }
+
+                let mut indices = (0..len).collect_vec();
+                indices.sort_by(|&a, &b| {
+                    keys.iter()
+                        .map(|(by_array, sort_order)| {
+                            let values = by_array.inner.cell_values_slice();
+                            sort_order.cmp(&values[a as usize], &values[b as usize])
+                        })
+                        .find(|ordering| ordering.is_ne())
+                        .unwrap_or(Ordering::Equal)
+                });
+                select(&array.inner, axis, &indices)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the unique rows of `array`, in the order that they
+            /// first appear. Text is compared ignoring case.
+            ///
+            /// If `by_col` is `TRUE`, returns the unique columns instead.
+            ///
+            /// If `exactly_once` is `TRUE`, only returns the rows or columns
+            /// that appear exactly once.
+            #[examples("UNIQUE(A1:A10)", "UNIQUE(A1:C10, FALSE, TRUE)")]
+            fn UNIQUE(
+                span: Span,
+                array: (Spanned<Array>),
+                by_col: (Option<bool>),
+                exactly_once: (Option<bool>),
+            ) {
+                let axis = if by_col.unwrap_or(false) {
+                    Axis::X
+                } else {
+                    Axis::Y
+                };
+                let len = array.inner.size()[axis].get();
+                let slices = (0..len)
+                    .map(|i| slice(&array.inner, axis, i))
+                    .collect::<CodeResult<Vec<_>>>()?;
+                let cmp_slices = |a: u32, b: u32| {
+                    std::iter::zip(&slices[a as usize], &slices[b as usize])
+                        .map(|(a, b)| a.sort_cmp(b))
+                        .find(|ordering| ordering.is_ne())
+                        .unwrap_or(Ordering::Equal)
+                };
+
+                // Group equal rows or columns by sorting them. The sort is
+                // stable, so the first of each group is its first occurrence.
+                let mut sorted = (0..len).collect_vec();
+                sorted.sort_by(|&a, &b| cmp_slices(a, b));
+                let mut indices = vec![];
+                let mut i = 0;
+                while i < sorted.len() {
+                    let mut j = i + 1;
+                    while j < sorted.len() && cmp_slices(sorted[i], sorted[j]).is_eq() {
+                        j += 1;
+                    }
+                    if !exactly_once.unwrap_or(false) || j - i == 1 {
+                        indices.push(sorted[i]);
+                    }
+                    i = j;
+                }
+                indices.sort();
+
+                if indices.is_empty() {
+                    return Err(RunErrorMsg::EmptyArray.with_span(span));
+                }
+                select(&array.inner, axis, &indices)?
+            }
+        ),
+        formula_fn!(
+            /// Returns an array of sequential numbers with `rows` rows and
+            /// `columns` columns, starting at `start` and increasing by `step`
+            /// along each row.
+            ///
+            /// `columns`, `start`, and `step` all default to 1.
+            #[examples("SEQUENCE(10)", "SEQUENCE(3, 4, 0, 5)", "SEQUENCE(1, 5, 10, -1)")]
+            fn SEQUENCE(
+                rows: u32,
+                columns: (Option<u32>),
+                start: (Option<f64>),
+                step: (Option<f64>),
+            ) {
+                let size = new_array_size(columns.unwrap_or(1).into(), rows.into())?;
+                let start = start.unwrap_or(1.0);
+                let step = step.unwrap_or(1.0);
+                let values = (0..size.len())
+                    .map(|i| CellValue::from(start + step * i as f64))
+                    .collect();
+                Array::new_row_major(size, values)?
+            }
+        ),
+        formula_fn!(
+            /// Returns `array` with its rows and columns swapped.
+            #[examples("TRANSPOSE(A1:C2)", "TRANSPOSE({1, 2, 3})")]
+            fn TRANSPOSE(array: Array) {
+                array.transpose()
+            }
+        ),
+        formula_fn!(
+            /// Joins `arrays` horizontally, with each array to the right of
+            /// the previous one.
+            ///
+            /// Arrays with fewer rows are padded with "no match" errors
+            /// (`#N/A`).
+            #[examples("HSTACK(A1:A10, C1:C10)", "HSTACK({1, 2}, {3; 4})")]
+            fn HSTACK(span: Span, arrays: (Iter<Array>)) {
+                stack(span, arrays.try_collect()?)?
+            }
+        ),
+        formula_fn!(
+            /// Joins `arrays` vertically, with each array below the previous
+            /// one.
+            ///
+            /// Arrays with fewer columns are padded with "no match" errors
+            /// (`#N/A`).
+            #[examples("VSTACK(A1:C1, A5:C10)", "VSTACK({1, 2}, {3; 4})")]
+            fn VSTACK(span: Span, arrays: (Iter<Array>)) {
+                let arrays = arrays
+                    .map(|array| Ok(array?.transpose()))
+                    .collect::<CodeResult<Vec<_>>>()?;
+                stack(span, arrays)?.transpose()
+            }
+        ),
+        formula_fn!(
+            /// Returns the first `rows` rows and `columns` columns of `array`.
+            ///
+            /// If `rows` or `columns` is negative, returns the last rows or
+            /// columns instead. If either is omitted, all rows or columns are
+            /// returned.
+            #[examples("TAKE(A1:C10, 3)", "TAKE(A1:C10, -2, 1)", "TAKE(A1:C10, , 2)")]
+            fn TAKE(
+                span: Span,
+                array: (Spanned<Array>),
+                rows: (Option<i64>),
+                columns: (Option<i64>),
+            ) {
+                let mut array = array.inner;
+                for (axis, count) in [(Axis::Y, rows), (Axis::X, columns)] {
+                    let Some(count) = count else { continue };
+                    let len = i64::from(array.size()[axis].get());
+                    let range = if count >= 0 {
+                        0..count.min(len)
+                    } else {
+                        (len + count).max(0)..len
+                    };
+                    array = select_range(span, &array, axis, range)?;
+                }
+                array
+            }
+        ),
+        formula_fn!(
+            /// Returns `array` without its first `rows` rows and `columns`
+            /// columns.
+            ///
+            /// If `rows` or `columns` is negative, removes the last rows or
+            /// columns instead. If either is omitted, no rows or columns are
+            /// removed.
+            #[examples("DROP(A1:C10, 1)", "DROP(A1:C10, -2, 1)", "DROP(A1:C10, , 2)")]
+            fn DROP(
+                span: Span,
+                array: (Spanned<Array>),
+                rows: (Option<i64>),
+                columns: (Option<i64>),
+            ) {
+                let mut array = array.inner;
+                for (axis, count) in [(Axis::Y, rows), (Axis::X, columns)] {
+                    let Some(count) = count else { continue };
+                    let len = i64::from(array.size()[axis].get());
+                    let range = if count >= 0 {
+                        count.min(len)..len
+                    } else {
+                        0..(len + count).max(0)
+                    };
+                    array = select_range(span, &array, axis, range)?;
+                }
+                array
+            }
+        ),
+        formula_fn!(
+            /// Returns the columns of `array` with the given column numbers,
+            /// in order.
+            ///
+            /// Column numbers start at 1. Negative numbers count from the
+            /// last column, so -1 is the last column.
+            #[examples("CHOOSECOLS(A1:E10, 1, 3)", "CHOOSECOLS(A1:E10, -1)")]
+            fn CHOOSECOLS(array: (Spanned<Array>), col_nums: (Iter<Spanned<i64>>)) {
+                let len = array.inner.size()[Axis::X];
+                let indices = col_nums
+                    .map(|n| resolve_index(n?, len))
+                    .collect::<CodeResult<Vec<_>>>()?;
+                select(&array.inner, Axis::X, &indices)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the rows of `array` with the given row numbers, in
+            /// order.
+            ///
+            /// Row numbers start at 1. Negative numbers count from the last
+            /// row, so -1 is the last row.
+            #[examples("CHOOSEROWS(A1:E10, 1, 3)", "CHOOSEROWS(A1:E10, -1)")]
+            fn CHOOSEROWS(array: (Spanned<Array>), row_nums: (Iter<Spanned<i64>>)) {
+                let len = array.inner.size()[Axis::Y];
+                let indices = row_nums
+                    .map(|n| resolve_index(n?, len))
+                    .collect::<CodeResult<Vec<_>>>()?;
+                select(&array.inner, Axis::Y, &indices)?
+            }
+        ),
+    ]
+}
+
+/// Returns the size of a new array, or an error if it is empty or has more
+/// than `CELL_RANGE_LIMIT` values.
+fn new_array_size(w: u64, h: u64) -> Result<ArraySize, RunErrorMsg> {
+    if w.saturating_mul(h) > u64::from(CELL_RANGE_LIMIT) {
+        return Err(RunErrorMsg::ArrayTooBig);
+    }
+    // Both dimensions are at most `CELL_RANGE_LIMIT`, so they fit in a `u32`.
+    ArraySize::new_or_err(w as u32, h as u32)
+}
+
+/// Returns the values in row `index` (for `Axis::Y`) or column `index` (for
+/// `Axis::X`) of `array`.
+fn slice(array: &Array, axis: Axis, index: u32) -> CodeResult<Vec<&CellValue>> {
+    let len = array.size()[axis.other_axis()].get();
+    (0..len)
+        .map(|i| match axis {
+            Axis::X => array.get(index, i),
+            Axis::Y => array.get(i, index),
+        })
+        .map(|result| Ok(result?))
+        .collect()
+}
+
+/// Returns the rows (for `Axis::Y`) or columns (for `Axis::X`) of `array` at
+/// `indices`, in order.
+fn select(array: &Array, axis: Axis, indices: &[u32]) -> CodeResult<Array> {
+    let mut size = array.size();
+    size[axis] = ArraySize::new_or_err(indices.len() as u32, 1)?.w;
+    let values = size
+        .iter()
+        .map(|(x, y)| match axis {
+            Axis::X => array.get(indices[x as usize], y).cloned(),
+            Axis::Y => array.get(x, indices[y as usize]).cloned(),
+        })
+        .collect::<Result<SmallVec<_>, _>>()?;
+    Array::new_row_major(size, values)
+}
+
+/// Returns the rows or columns of `array` in `range`, or an error if `range`
+/// is empty.
+fn select_range(
+    span: Span,
+    array: &Array,
+    axis: Axis,
+    range: std::ops::Range<i64>,
+) -> CodeResult<Array> {
+    if range.is_empty() {
+        return Err(RunErrorMsg::EmptyArray.with_span(span));
+    }
+    let indices = range.map(|i| i as u32).collect_vec();
+    select(array, axis, &indices)
+}
+
+/// Converts a 1-based index, which counts from the end if negative, to a
+/// 0-based index less than `len`.
+fn resolve_index(index: Spanned<i64>, len: std::num::NonZeroU32) -> CodeResult<u32> {
+    let len = i64::from(len.get());
+    let i = match index.inner {
+        1.. => index.inner - 1,
+        ..=-1 => len + index.inner,
+        0 => -1,
+    };
+    if (0..len).contains(&i) {
+        Ok(i as u32)
+    } else {
+        Err(RunErrorMsg::IndexOutOfBounds.with_span(index.span))
+    }
+}
+
+/// Joins arrays horizontally, padding shorter arrays with `#N/A`.
+fn stack(span: Span, arrays: Vec<Array>) -> CodeResult<Array> {
+    let w = arrays.iter().map(|a| u64::from(a.width())).sum();
+    let h = arrays.iter().map(|a| a.height()).max().unwrap_or(0);
+    let size = new_array_size(w, h.into()).map_err(|e| e.with_span(span))?;
+
+    let no_match = CellValue::Error(Box::new(RunErrorMsg::NoMatch.with_span(span)));
+    let mut values = SmallVec::with_capacity(size.len());
+    for y in 0..h {
+        for array in &arrays {
+            match array.rows().nth(y as usize) {
+                Some(row) => values.extend(row.iter().cloned()),
+                None => values.extend(itertools::repeat_n(
+                    no_match.clone(),
+                    array.width() as usize,
+                )),
+            }
+        }
+    }
+    Array::new_row_major(size, values)
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+enum SortOrder {
+    #[default]
+    Ascending = 1,
+    Descending = -1,
+}
+impl SortOrder {
+    fn cmp(self, a: &CellValue, b: &CellValue) -> Ordering {
+        match self {
+            SortOrder::Ascending => a.sort_cmp(b),
+            SortOrder::Descending => b.sort_cmp(a),
+        }
+    }
+}
+impl TryFrom<Option<Spanned<i64>>> for SortOrder {
+    type Error = RunError;
+
+    fn try_from(value: Option<Spanned<i64>>) -> Result<Self, Self::Error> {
+        match value {
+            None => Ok(SortOrder::default()),
+            Some(v) => match v.inner {
+                1 => Ok(SortOrder::Ascending),
+                -1 => Ok(SortOrder::Descending),
+                _ => Err(RunErrorMsg::InvalidArgument.with_span(v.span)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{formulas::tests::*, Pos};
+
+    fn grid_with_table() -> Grid {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        let rows = [("pear", 3), ("apple", 5), ("fig", 1), ("Apple", 5)];
+        for (y, (fruit, count)) in rows.into_iter().enumerate() {
+            let _ = sheet.set_cell_value(
+                Pos {
+                    x: 0,
+                    y: y as i64 + 1,
+                },
+                fruit,
+            );
+            let _ = sheet.set_cell_value(
+                Pos {
+                    x: 1,
+                    y: y as i64 + 1,
+                },
+                count,
+            );
+        }
+        g
+    }
+
+    #[test]
+    fn test_formula_filter() {
+        let g = grid_with_table();
+        assert_eq!(
+            "{pear, 3; apple, 5; Apple, 5}",
+            eval_to_string(&g, "FILTER(A1:B4, B1:B4 > 2)"),
+        );
+        assert_eq!("{3, 5}", eval_to_string(&g, "FILTER({1, 3, 5}, {0, 1, 1})"));
+        assert_eq!(
+            "none",
+            eval_to_string(&g, "FILTER(A1:B4, B1:B4 > 9, \"none\")")
+        );
+        expect_err(&RunErrorMsg::EmptyArray, &g, "FILTER(A1:B4, B1:B4 > 9)");
+        expect_err(
+            &RunErrorMsg::ExactArrayAxisMismatch {
+                axis: Axis::Y,
+                expected: 4,
+                got: 3,
+            },
+            &g,
+            "FILTER(A1:B4, B1:B3 > 2)",
+        );
+    }
+
+    #[test]
+    fn test_formula_sort() {
+        let g = grid_with_table();
+        assert_eq!(
+            "{apple, 5; Apple, 5; fig, 1; pear, 3}",
+            eval_to_string(&g, "SORT(A1:B4)"),
+        );
+        assert_eq!(
+            "{apple, 5; Apple, 5; pear, 3; fig, 1}",
+            eval_to_string(&g, "SORT(A1:B4, 2, -1)"),
+        );
+        assert_eq!(
+            "{1, 2, 3}",
+            eval_to_string(&g, "SORT({3, 1, 2}, 1, 1, TRUE)"),
+        );
+        assert_eq!("{1; a; TRUE}", eval_to_string(&g, "SORT({TRUE; \"a\"; 1})"));
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "SORT(A1:B4, 3)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "SORT(A1:B4, 1, 2)");
+
+        assert_eq!(
+            "{fig; pear; apple; Apple}",
+            eval_to_string(&g, "SORTBY(A1:A4, B1:B4)"),
+        );
+        assert_eq!(
+            "{apple; Apple; pear; fig}",
+            eval_to_string(&g, "SORTBY(A1:A4, B1:B4, -1, A1:A4, -1)"),
+        );
+    }
+
+    #[test]
+    fn test_formula_unique() {
+        let g = grid_with_table();
+        assert_eq!("{pear; apple; fig}", eval_to_string(&g, "UNIQUE(A1:A4)"));
+        assert_eq!("{3; 1}", eval_to_string(&g, "UNIQUE(B1:B4, FALSE, TRUE)"));
+        assert_eq!("{1, 2}", eval_to_string(&g, "UNIQUE({1, 2, 1, 2}, TRUE)"));
+        expect_err(&RunErrorMsg::EmptyArray, &g, "UNIQUE({1, 1}, TRUE, TRUE)");
+    }
+
+    #[test]
+    fn test_formula_sequence() {
+        let g = Grid::new();
+        assert_eq!("{1; 2; 3}", eval_to_string(&g, "SEQUENCE(3)"));
+        assert_eq!("{0, 5; 10, 15}", eval_to_string(&g, "SEQUENCE(2, 2, 0, 5)"));
+        assert_eq!("{10, 9.5}", eval_to_string(&g, "SEQUENCE(1, 2, 10, -0.5)"));
+        expect_err(&RunErrorMsg::EmptyArray, &g, "SEQUENCE(0)");
+        expect_err(&RunErrorMsg::ArrayTooBig, &g, "SEQUENCE(10000, 10000)");
+    }
+
+    #[test]
+    fn test_formula_transpose_and_stack() {
+        let g = Grid::new();
+        assert_eq!(
+            "{1, 4; 2, 5; 3, 6}",
+            eval_to_string(&g, "TRANSPOSE({1, 2, 3; 4, 5, 6})"),
+        );
+        assert_eq!(
+            "{1, 2, 3; TRUE, TRUE, 4}",
+            eval_to_string(&g, "IFNA(HSTACK({1, 2}, {3; 4}), TRUE)"),
+        );
+        assert_eq!(
+            "{1, 2; 3, TRUE; 4, TRUE}",
+            eval_to_string(&g, "IFNA(VSTACK({1, 2}, {3; 4}), TRUE)"),
+        );
+    }
+
+    #[test]
+    fn test_formula_take_drop_choose() {
+        let g = Grid::new();
+        let array = "{1, 2, 3; 4, 5, 6; 7, 8, 9}";
+        let eval = |s: &str| eval_to_string(&g, &s.replace("ARRAY", array));
+
+        assert_eq!("{1, 2, 3; 4, 5, 6}", eval("TAKE(ARRAY, 2)"));
+        assert_eq!("{8, 9}", eval("TAKE(ARRAY, -1, -2)"));
+        assert_eq!("{1; 4; 7}", eval("TAKE(ARRAY, , 1)"));
+        assert_eq!("{1, 2, 3; 4, 5, 6; 7, 8, 9}", eval("TAKE(ARRAY, 10)"));
+        assert_eq!("{5, 6; 8, 9}", eval("DROP(ARRAY, 1, 1)"));
+        assert_eq!("{1, 2}", eval("DROP(ARRAY, -2, -1)"));
+        expect_err(&RunErrorMsg::EmptyArray, &g, "TAKE({1, 2}, 0)");
+        expect_err(&RunErrorMsg::EmptyArray, &g, "DROP({1, 2}, , 2)");
+
+        assert_eq!("{3, 1; 6, 4; 9, 7}", eval("CHOOSECOLS(ARRAY, 3, 1)"));
+        assert_eq!("{7, 8, 9; 7, 8, 9}", eval("CHOOSEROWS(ARRAY, -1, {3})"));
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "CHOOSECOLS({1, 2}, 0)");
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "CHOOSEROWS({1, 2}, 2)");
+    }
+}
@@ -0,0 +1,490 @@
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveDateTime, Timelike, Utc, Weekday};
+
+use super::*;
+use crate::{Duration, Instant};
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Date and time functions",
+    docs: "Dates may be given as date values, as text such as `\"2024-03-01\"`, \
+           or as serial numbers counting days since December 30, 1899. \
+           All dates and times are in UTC.\
+           \n\n\
+           Adding or subtracting a number to a date moves it by that many days, \
+           and subtracting two dates gives the number of days between them.\
+           \n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns a date from a year, month, and day. Months and days
+            /// outside the usual range carry over into the next or previous
+            /// month or year, so `DATE(2024, 14, 1)` is February 1, 2025. Years
+            /// from `0` to `1899` are offset by `1900`.
+            #[examples("DATE(2024, 3, 1)", "DATE(A1, B1, C1)")]
+            #[zip_map]
+            fn DATE([year]: i64, [month]: i64, [day]: i64) {
+                let year = if (0..1900).contains(&year) {
+                    year + 1900
+                } else {
+                    year
+                };
+                let date = i32::try_from(year)
+                    .ok()
+                    .and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1))
+                    .and_then(|date| add_months(date, month - 1))
+                    .and_then(|date| add_days(date, day - 1))
+                    .ok_or(RunErrorMsg::Overflow)?;
+                CodeResult::Ok(Instant::from_date(date))
+            }
+        ),
+        formula_fn!(
+            /// Returns a duration from a number of hours, minutes, and seconds.
+            /// The duration may be added to a date to get a date and time.
+            #[examples("TIME(13, 45, 0)", "DATE(2024, 3, 1) + TIME(9, 30, 0)")]
+            #[zip_map]
+            fn TIME([hour]: f64, [minute]: f64, [second]: f64) {
+                Duration::from_seconds(hour * 3600.0 + minute * 60.0 + second)
+            }
+        ),
+        formula_fn!(
+            /// Returns the current date and time.
+            #[examples("NOW()")]
+            fn NOW() {
+                Instant::now()
+            }
+        ),
+        formula_fn!(
+            /// Returns the current date.
+            #[examples("TODAY()")]
+            fn TODAY() {
+                Instant::from_date(Utc::now().date_naive())
+            }
+        ),
+        formula_fn!(
+            /// Returns the year of a date.
+            #[examples("YEAR(A1)", "YEAR(\"2024-03-01\")")]
+            #[zip_map]
+            fn YEAR([date]: Instant) {
+                CodeResult::Ok(to_date(date)?.year())
+            }
+        ),
+        formula_fn!(
+            /// Returns the month of a date, from `1` (January) to `12`
+            /// (December).
+            #[examples("MONTH(A1)", "MONTH(\"2024-03-01\")")]
+            #[zip_map]
+            fn MONTH([date]: Instant) {
+                CodeResult::Ok(to_date(date)?.month())
+            }
+        ),
+        formula_fn!(
+            /// Returns the day of the month of a date, from `1` to `31`.
+            #[examples("DAY(A1)", "DAY(\"2024-03-01\")")]
+            #[zip_map]
+            fn DAY([date]: Instant) {
+                CodeResult::Ok(to_date(date)?.day())
+            }
+        ),
+        formula_fn!(
+            /// Returns the hour of a time, from `0` to `23`.
+            #[examples("HOUR(A1)", "HOUR(TIME(13, 45, 0))")]
+            #[zip_map]
+            fn HOUR([time]: Instant) {
+                CodeResult::Ok(to_datetime(time)?.hour())
+            }
+        ),
+        formula_fn!(
+            /// Returns the minute of a time, from `0` to `59`.
+            #[examples("MINUTE(A1)", "MINUTE(TIME(13, 45, 0))")]
+            #[zip_map]
+            fn MINUTE([time]: Instant) {
+                CodeResult::Ok(to_datetime(time)?.minute())
+            }
+        ),
+        formula_fn!(
+            /// Returns the second of a time, from `0` to `59`.
+            #[examples("SECOND(A1)", "SECOND(TIME(13, 45, 30))")]
+            #[zip_map]
+            fn SECOND([time]: Instant) {
+                CodeResult::Ok(to_datetime(time)?.second())
+            }
+        ),
+        formula_fn!(
+            /// Returns the day of the week of a date as a number.
+            ///
+            /// `return_type` determines the numbering:
+            ///
+            /// - `1` (default): Sunday is `1` and Saturday is `7`.
+            /// - `2`: Monday is `1` and Sunday is `7`.
+            /// - `3`: Monday is `0` and Sunday is `6`.
+            /// - `11` to `17`: The week begins on Monday (`11`) through Sunday
+            ///   (`17`), which is numbered `1`.
+            #[examples("WEEKDAY(A1)", "WEEKDAY(\"2024-03-01\", 2)")]
+            #[zip_map]
+            fn WEEKDAY([date]: Instant, [return_type]: (Option<i64>)) {
+                let weekday = to_date(date)?.weekday();
+                let days_from_monday = weekday.num_days_from_monday() as i64;
+                CodeResult::Ok(match return_type.unwrap_or(1) {
+                    1 => weekday.number_from_sunday() as i64,
+                    2 => weekday.number_from_monday() as i64,
+                    3 => days_from_monday,
+                    t @ 11..=17 => (days_from_monday - (t - 11)).rem_euclid(7) + 1,
+                    _ => return Err(RunErrorMsg::InvalidArgument.without_span()),
+                })
+            }
+        ),
+        formula_fn!(
+            /// Returns the date that is `months` months before or after
+            /// `start_date`. If the day of the month does not exist in the
+            /// resulting month, the last day of that month is used instead.
+            #[examples("EDATE(\"2024-01-31\", 1)", "EDATE(A1, -12)")]
+            #[zip_map]
+            fn EDATE([start_date]: Instant, [months]: i64) {
+                let date = add_months(to_date(start_date)?, months).ok_or(RunErrorMsg::Overflow)?;
+                CodeResult::Ok(Instant::from_date(date))
+            }
+        ),
+        formula_fn!(
+            /// Returns the last day of the month that is `months` months
+            /// before or after `start_date`.
+            #[examples("EOMONTH(\"2024-02-10\", 0)", "EOMONTH(A1, 1)")]
+            #[zip_map]
+            fn EOMONTH([start_date]: Instant, [months]: i64) {
+                let date = to_date(start_date)?;
+                let date = date
+                    .with_day(1)
+                    .and_then(|first| add_months(first, months.checked_add(1)?))
+                    .and_then(|first_of_next| first_of_next.pred_opt())
+                    .ok_or(RunErrorMsg::Overflow)?;
+                CodeResult::Ok(Instant::from_date(date))
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of whole days, months, or years between two
+            /// dates, depending on `unit`:
+            ///
+            /// - `"D"`: days
+            /// - `"M"`: whole months
+            /// - `"Y"`: whole years
+            /// - `"MD"`: days, ignoring whole months
+            /// - `"YM"`: whole months, ignoring whole years
+            /// - `"YD"`: days, ignoring whole years
+            ///
+            /// Returns an error if `start_date` is after `end_date`.
+            #[examples(
+                "DATEDIF(A1, B1, \"Y\")",
+                "DATEDIF(\"2024-01-15\", \"2024-03-01\", \"MD\")"
+            )]
+            #[zip_map]
+            fn DATEDIF([start_date]: Instant, [end_date]: Instant, [unit]: String) {
+                let start = to_date(start_date)?;
+                let end = to_date(end_date)?;
+                if start > end {
+                    return Err(RunErrorMsg::InvalidArgument.without_span());
+                }
+                let months = whole_months_between(start, end);
+                let days_after = |months: i64| -> CodeResult<i64> {
+                    let anchor = add_months(start, months).ok_or(RunErrorMsg::Overflow)?;
+                    Ok((end - anchor).num_days())
+                };
+                CodeResult::Ok(match unit.to_ascii_uppercase().as_str() {
+                    "D" => (end - start).num_days(),
+                    "M" => months,
+                    "Y" => months / 12,
+                    "MD" => days_after(months)?,
+                    "YM" => months % 12,
+                    "YD" => days_after(months / 12 * 12)?,
+                    _ => return Err(RunErrorMsg::InvalidArgument.without_span()),
+                })
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of weekdays (Monday through Friday) from
+            /// `start_date` to `end_date`, including both. Dates in `holidays`
+            /// are not counted. If `start_date` is after `end_date`, the result
+            /// is negative.
+            #[examples("NETWORKDAYS(A1, B1)", "NETWORKDAYS(A1, B1, C1:C10)")]
+            #[zip_map]
+            fn NETWORKDAYS([start_date]: Instant, [end_date]: Instant, holidays: (Iter<Instant>)) {
+                let start = to_date(start_date)?;
+                let end = to_date(end_date)?;
+                let (first, last, sign) = if start <= end {
+                    (start, end, 1)
+                } else {
+                    (end, start, -1)
+                };
+                let mut holiday_dates = holidays
+                    .iter()
+                    .map(|&holiday| to_date(holiday))
+                    .filter_ok(|&date| first <= date && date <= last && is_weekday(date))
+                    .collect::<CodeResult<Vec<_>>>()?;
+                holiday_dates.sort();
+                holiday_dates.dedup();
+                CodeResult::Ok(sign * (weekdays_between(first, last) - holiday_dates.len() as i64))
+            }
+        ),
+        formula_fn!(
+            /// Converts text to a date, ignoring any time. The text may be in
+            /// a format such as `"2024-03-01"`, `"3/1/2024"`, or
+            /// `"March 1, 2024"`.
+            #[examples("DATEVALUE(\"2024-03-01\")", "DATEVALUE(A1)")]
+            #[zip_map]
+            fn DATEVALUE([s]: String) {
+                let date = Instant::parse(&s)
+                    .and_then(|instant| instant.date())
+                    .ok_or_else(|| RunErrorMsg::Expected {
+                        expected: "date".into(),
+                        got: Some("text".into()),
+                    })?;
+                CodeResult::Ok(Instant::from_date(date))
+            }
+        ),
+    ]
+}
+
+fn to_datetime(instant: Instant) -> CodeResult<NaiveDateTime> {
+    instant
+        .datetime()
+        .ok_or_else(|| RunErrorMsg::Overflow.without_span())
+}
+
+fn to_date(instant: Instant) -> CodeResult<NaiveDate> {
+    Ok(to_datetime(instant)?.date())
+}
+
+/// Adds a (possibly negative) number of months, clamping to the end of the
+/// month if necessary.
+fn add_months(date: NaiveDate, months: i64) -> Option<NaiveDate> {
+    let n = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    if months >= 0 {
+        date.checked_add_months(n)
+    } else {
+        date.checked_sub_months(n)
+    }
+}
+
+/// Adds a (possibly negative) number of days.
+fn add_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    let n = Days::new(days.unsigned_abs());
+    if days >= 0 {
+        date.checked_add_days(n)
+    } else {
+        date.checked_sub_days(n)
+    }
+}
+
+/// Returns the number of whole months from `start` to `end`, where
+/// `start <= end`.
+fn whole_months_between(start: NaiveDate, end: NaiveDate) -> i64 {
+    let months =
+        (end.year() as i64 - start.year() as i64) * 12 + end.month() as i64 - start.month() as i64;
+    if end.day() < start.day() {
+        months - 1
+    } else {
+        months
+    }
+}
+
+fn is_weekday(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Returns the number of weekdays from `first` to `last`, including both.
+fn weekdays_between(first: NaiveDate, last: NaiveDate) -> i64 {
+    let days = (last - first).num_days() + 1;
+    let full_weeks = days / 7;
+    let remainder = (0..days % 7)
+        .filter_map(|i| add_days(first, full_weeks * 7 + i))
+        .filter(|&date| is_weekday(date))
+        .count();
+    full_weeks * 5 + remainder as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+
+    #[test]
+    fn test_formula_date() {
+        let g = Grid::new();
+        assert_eq!("2024-03-01", eval_to_string(&g, "DATE(2024, 3, 1)"));
+        assert_eq!("2025-02-01", eval_to_string(&g, "DATE(2024, 14, 1)"));
+        assert_eq!("2024-02-29", eval_to_string(&g, "DATE(2024, 3, 0)"));
+        assert_eq!("2023-12-31", eval_to_string(&g, "DATE(2024, 1, 0)"));
+        assert_eq!("2023-11-01", eval_to_string(&g, "DATE(2024, -1, 1)"));
+        assert_eq!("2000-01-01", eval_to_string(&g, "DATE(100, 1, 1)"));
+        expect_err(&RunErrorMsg::Overflow, &g, "DATE(9999999, 1, 1)");
+
+        assert_eq!("13:45:30", eval_to_string(&g, "TIME(13, 45, 30)"));
+        assert_eq!("1 day, 02:00:00", eval_to_string(&g, "TIME(26, 0, 0)"));
+
+        // The source code of a duration evaluates to the same duration.
+        for seconds in [0.0, 49500.0, -5400.0, 172801.5] {
+            let duration = crate::Duration::from_seconds(seconds);
+            assert_eq!(duration.to_string(), eval_to_string(&g, &duration.repr()));
+        }
+    }
+
+    #[test]
+    fn test_formula_now_today() {
+        let g = Grid::new();
+        let Value::Single(CellValue::Instant(now)) = eval(&g, "NOW()") else {
+            panic!("expected instant");
+        };
+        let Value::Single(CellValue::Instant(today)) = eval(&g, "TODAY()") else {
+            panic!("expected instant");
+        };
+        assert!(today <= now);
+        assert!(now.seconds - today.seconds < 86400.0);
+    }
+
+    #[test]
+    fn test_formula_date_parts() {
+        let g = Grid::new();
+        let date = "DATE(2024, 3, 1) + TIME(13, 45, 30)";
+        assert_eq!("2024", eval_to_string(&g, &format!("YEAR({date})")));
+        assert_eq!("3", eval_to_string(&g, &format!("MONTH({date})")));
+        assert_eq!("1", eval_to_string(&g, &format!("DAY({date})")));
+        assert_eq!("13", eval_to_string(&g, &format!("HOUR({date})")));
+        assert_eq!("45", eval_to_string(&g, &format!("MINUTE({date})")));
+        assert_eq!("30", eval_to_string(&g, &format!("SECOND({date})")));
+
+        // text and serial numbers
+        assert_eq!("2024", eval_to_string(&g, "YEAR(\"March 1, 2024\")"));
+        assert_eq!("2024", eval_to_string(&g, "YEAR(45352)"));
+        assert_eq!("12", eval_to_string(&g, "HOUR(45352.5)"));
+        assert_eq!("13", eval_to_string(&g, "HOUR(TIME(13, 0, 0))"));
+        assert_eq!(
+            "{1, 2}",
+            eval_to_string(&g, "DAY({\"2024-03-01\", \"2024-03-02\"})")
+        );
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "date".into(),
+                got: Some("text".into()),
+            },
+            &g,
+            "YEAR(\"hello\")",
+        );
+    }
+
+    #[test]
+    fn test_formula_weekday() {
+        let g = Grid::new();
+        // 2024-03-01 is a Friday
+        let d = "\"2024-03-01\"";
+        assert_eq!("6", eval_to_string(&g, &format!("WEEKDAY({d})")));
+        assert_eq!("6", eval_to_string(&g, &format!("WEEKDAY({d}, 1)")));
+        assert_eq!("5", eval_to_string(&g, &format!("WEEKDAY({d}, 2)")));
+        assert_eq!("4", eval_to_string(&g, &format!("WEEKDAY({d}, 3)")));
+        assert_eq!("5", eval_to_string(&g, &format!("WEEKDAY({d}, 11)")));
+        assert_eq!("1", eval_to_string(&g, &format!("WEEKDAY({d}, 15)")));
+        assert_eq!("6", eval_to_string(&g, &format!("WEEKDAY({d}, 17)")));
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("WEEKDAY({d}, 4)"),
+        );
+    }
+
+    #[test]
+    fn test_formula_edate_eomonth() {
+        let g = Grid::new();
+        assert_eq!("2024-02-29", eval_to_string(&g, "EDATE(\"2024-01-31\", 1)"));
+        assert_eq!(
+            "2023-01-31",
+            eval_to_string(&g, "EDATE(\"2024-01-31\", -12)")
+        );
+        assert_eq!(
+            "2024-02-29",
+            eval_to_string(&g, "EOMONTH(\"2024-02-10\", 0)")
+        );
+        assert_eq!(
+            "2024-04-30",
+            eval_to_string(&g, "EOMONTH(\"2024-02-10\", 2)")
+        );
+        assert_eq!(
+            "2023-12-31",
+            eval_to_string(&g, "EOMONTH(\"2024-02-10\", -2)")
+        );
+    }
+
+    #[test]
+    fn test_formula_datedif() {
+        let g = Grid::new();
+        let datedif = |start: &str, end: &str, unit: &str| {
+            eval_to_string(&g, &format!("DATEDIF(\"{start}\", \"{end}\", \"{unit}\")"))
+        };
+        assert_eq!("46", datedif("2024-01-15", "2024-03-01", "D"));
+        assert_eq!("1", datedif("2024-01-15", "2024-03-01", "M"));
+        assert_eq!("15", datedif("2024-01-15", "2024-03-01", "MD"));
+        assert_eq!("1", datedif("2022-06-15", "2024-06-14", "Y"));
+        assert_eq!("11", datedif("2022-06-15", "2024-06-14", "YM"));
+        assert_eq!("365", datedif("2022-06-15", "2024-06-14", "YD"));
+        assert_eq!("0", datedif("2024-03-01", "2024-03-01", "y"));
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "DATEDIF(\"2024-03-01\", \"2024-01-01\", \"D\")",
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "DATEDIF(\"2024-01-01\", \"2024-03-01\", \"W\")",
+        );
+    }
+
+    #[test]
+    fn test_formula_networkdays() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        let _ = sheet.set_cell_value(pos![A1], "2024-03-04");
+        let _ = sheet.set_cell_value(pos![A2], "2024-03-09");
+        let _ = sheet.set_cell_value(pos![A3], "2024-03-04");
+
+        // 2024-03-01 is a Friday
+        let (start, end) = ("\"2024-03-01\"", "\"2024-03-14\"");
+        assert_eq!(
+            "10",
+            eval_to_string(&g, &format!("NETWORKDAYS({start}, {end})"))
+        );
+        assert_eq!(
+            "-10",
+            eval_to_string(&g, &format!("NETWORKDAYS({end}, {start})"))
+        );
+        assert_eq!(
+            "9",
+            eval_to_string(&g, &format!("NETWORKDAYS({start}, {end}, A1:A3)")),
+        );
+        assert_eq!(
+            "0",
+            eval_to_string(&g, "NETWORKDAYS(\"2024-03-02\", \"2024-03-03\")"),
+        );
+    }
+
+    #[test]
+    fn test_formula_datevalue() {
+        let g = Grid::new();
+        assert_eq!(
+            "2024-03-01",
+            eval_to_string(&g, "DATEVALUE(\"2024-03-01\")")
+        );
+        assert_eq!("2024-03-01", eval_to_string(&g, "DATEVALUE(\"3/1/2024\")"));
+        assert_eq!(
+            "2024-03-01",
+            eval_to_string(&g, "DATEVALUE(\"March 1, 2024 13:45\")"),
+        );
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "date".into(),
+                got: Some("text".into()),
+            },
+            &g,
+            "DATEVALUE(\"hello\")",
+        );
+    }
+}
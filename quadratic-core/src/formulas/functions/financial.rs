@@ -0,0 +1,693 @@
+use super::*;
+use crate::Instant;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Financial functions",
+    docs: "In these functions, money paid out (such as a deposit or a loan \
+           payment) is negative, and money received (such as a loan or a \
+           dividend) is positive. `rate` is the interest rate per period, so \
+           a loan with 6% annual interest and monthly payments has a `rate` of \
+           `6%/12`.\
+           \n\n\
+           If `at_start` is true, payments are due at the start of each \
+           period; otherwise they are due at the end. If `at_start` is \
+           omitted, it is assumed to be false.\
+           \n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns the payment per period for a loan or investment with
+            /// constant payments and a constant interest rate.
+            #[examples("PMT(6%/12, 360, 200000)", "PMT(5%/12, 120, 0, 50000)")]
+            #[zip_map]
+            fn PMT(
+                span: Span,
+                [rate]: f64,
+                [nper]: f64,
+                [pv]: f64,
+                [fv]: (Option<f64>),
+                [at_start]: (Option<bool>),
+            ) {
+                let payment = pmt(rate, nper, pv, fv.unwrap_or(0.0), at_start.unwrap_or(false));
+                util::checked_float(span, payment)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the interest part of the payment for period `per` of a
+            /// loan or investment with constant payments and a constant
+            /// interest rate. `per` must be between `1` and `nper`.
+            #[examples("IPMT(6%/12, 1, 360, 200000)")]
+            #[zip_map]
+            fn IPMT(
+                span: Span,
+                [rate]: f64,
+                [per]: (Spanned<f64>),
+                [nper]: f64,
+                [pv]: f64,
+                [fv]: (Option<f64>),
+                [at_start]: (Option<bool>),
+            ) {
+                check_period(per, nper)?;
+                let at_start = at_start.unwrap_or(false);
+                let interest = ipmt(rate, per.inner, nper, pv, fv.unwrap_or(0.0), at_start);
+                util::checked_float(span, interest)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the principal part of the payment for period `per` of a
+            /// loan or investment with constant payments and a constant
+            /// interest rate. `per` must be between `1` and `nper`.
+            #[examples("PPMT(6%/12, 1, 360, 200000)")]
+            #[zip_map]
+            fn PPMT(
+                span: Span,
+                [rate]: f64,
+                [per]: (Spanned<f64>),
+                [nper]: f64,
+                [pv]: f64,
+                [fv]: (Option<f64>),
+                [at_start]: (Option<bool>),
+            ) {
+                check_period(per, nper)?;
+                let fv = fv.unwrap_or(0.0);
+                let at_start = at_start.unwrap_or(false);
+                let principal = pmt(rate, nper, pv, fv, at_start)
+                    - ipmt(rate, per.inner, nper, pv, fv, at_start);
+                util::checked_float(span, principal)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the future value of an investment with constant
+            /// payments and a constant interest rate.
+            #[examples("FV(5%/12, 120, -100)", "FV(5%/12, 120, -100, -1000, TRUE)")]
+            #[zip_map]
+            fn FV(
+                span: Span,
+                [rate]: f64,
+                [nper]: f64,
+                [pmt]: f64,
+                [pv]: (Option<f64>),
+                [at_start]: (Option<bool>),
+            ) {
+                let future_value = fv(
+                    rate,
+                    nper,
+                    pmt,
+                    pv.unwrap_or(0.0),
+                    at_start.unwrap_or(false),
+                );
+                util::checked_float(span, future_value)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the present value of an investment with constant
+            /// payments and a constant interest rate, which is the total
+            /// amount that the future payments are worth now.
+            #[examples("PV(8%/12, 240, 500)", "PV(8%/12, 240, 500, 10000)")]
+            #[zip_map]
+            fn PV(
+                span: Span,
+                [rate]: f64,
+                [nper]: f64,
+                [pmt]: f64,
+                [fv]: (Option<f64>),
+                [at_start]: (Option<bool>),
+            ) {
+                let fv = fv.unwrap_or(0.0);
+                let at_start = at_start.unwrap_or(false);
+                let present_value = if rate == 0.0 {
+                    -(fv + pmt * nper)
+                } else {
+                    let growth = (1.0 + rate).powf(nper);
+                    let payments =
+                        pmt * payment_factor(rate, at_start) * growth_minus_one(rate, nper);
+                    -(fv + payments / rate) / growth
+                };
+                util::checked_float(span, present_value)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the net present value of a series of cash flows that
+            /// occur at the end of each period, discounted at `rate` per
+            /// period.
+            #[examples("NPV(10%, -10000, 3000, 4200, 6800)", "NPV(8%, B2:B6) + B1")]
+            fn NPV(span: Span, rate: f64, values: (Iter<f64>)) {
+                let mut discount = 1.0;
+                let mut total = 0.0;
+                for value in values {
+                    discount *= 1.0 + rate;
+                    total += value? / discount;
+                }
+                util::checked_float(span, total)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the net present value of cash flows that occur on the
+            /// given dates, discounted at the annual `rate`. `values` and
+            /// `dates` must be the same size, and no date may be before the
+            /// first one.
+            ///
+            /// Dates may be date values or serial numbers.
+            #[examples("XNPV(9%, B1:B5, A1:A5)")]
+            fn XNPV(span: Span, rate: f64, values: (Spanned<Array>), dates: (Spanned<Array>)) {
+                let cash_flows = dated_cash_flows(&values, &dates)?;
+                if rate <= -1.0 {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                util::checked_float(span, xnpv(rate, &cash_flows))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the internal rate of return of a series of cash flows
+            /// that occur at the end of each period, which is the rate at
+            /// which their net present value is zero.
+            ///
+            /// `values` must contain at least one positive and one negative
+            /// value. `guess` is the starting point for the search, and is
+            /// assumed to be `10%` if omitted.
+            #[examples("IRR(A1:A6)", "IRR(A1:A6, 5%)")]
+            fn IRR(span: Span, values: (Spanned<Value>), guess: (Option<f64>)) {
+                let values = values
+                    .into_iter::<f64>()
+                    .without_spans()
+                    .collect::<CodeResult<Vec<f64>>>()?;
+                check_has_positive_and_negative(span, values.iter().copied())?;
+                solve(span, guess.unwrap_or(0.1), |rate| {
+                    values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, value)| value / (1.0 + rate).powi(i as i32))
+                        .sum()
+                })?
+            }
+        ),
+        formula_fn!(
+            /// Returns the annual internal rate of return of cash flows that
+            /// occur on the given dates, which is the rate at which their net
+            /// present value is zero. `values` and `dates` must be the same
+            /// size, and no date may be before the first one.
+            ///
+            /// `values` must contain at least one positive and one negative
+            /// value. `guess` is the starting point for the search, and is
+            /// assumed to be `10%` if omitted.
+            #[examples("XIRR(B1:B5, A1:A5)", "XIRR(B1:B5, A1:A5, 20%)")]
+            fn XIRR(
+                span: Span,
+                values: (Spanned<Array>),
+                dates: (Spanned<Array>),
+                guess: (Option<f64>),
+            ) {
+                let cash_flows = dated_cash_flows(&values, &dates)?;
+                check_has_positive_and_negative(span, cash_flows.iter().map(|&(v, _)| v))?;
+                solve(span, guess.unwrap_or(0.1), |rate| xnpv(rate, &cash_flows))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the interest rate per period of a loan or investment
+            /// with constant payments. `guess` is the starting point for the
+            /// search, and is assumed to be `10%` if omitted.
+            #[examples("RATE(48, -200, 8000)", "RATE(120, -100, 0, 15000)")]
+            #[zip_map]
+            fn RATE(
+                span: Span,
+                [nper]: f64,
+                [pmt]: f64,
+                [pv]: f64,
+                [fv]: (Option<f64>),
+                [at_start]: (Option<bool>),
+                [guess]: (Option<f64>),
+            ) {
+                let fv = fv.unwrap_or(0.0);
+                let at_start = at_start.unwrap_or(false);
+                solve(span, guess.unwrap_or(0.1), |rate| {
+                    self::fv(rate, nper, pmt, pv, at_start) - fv
+                })?
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of periods needed for a loan or investment
+            /// with constant payments and a constant interest rate.
+            #[examples("NPER(1%, -100, -1000, 10000)", "NPER(5%/12, -500, 20000)")]
+            #[zip_map]
+            fn NPER(
+                span: Span,
+                [rate]: f64,
+                [pmt]: f64,
+                [pv]: f64,
+                [fv]: (Option<f64>),
+                [at_start]: (Option<bool>),
+            ) {
+                let fv = fv.unwrap_or(0.0);
+                let periods = if rate == 0.0 {
+                    util::checked_div(span, -(pv + fv), pmt)?
+                } else {
+                    let payment = pmt * payment_factor(rate, at_start.unwrap_or(false));
+                    ((payment - fv * rate) / (payment + pv * rate)).ln() / rate.ln_1p()
+                };
+                util::checked_float(span, periods)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the depreciation of an asset for one period using the
+            /// straight-line method, in which the asset loses the same value
+            /// each period.
+            #[examples("SLN(30000, 7500, 10)")]
+            #[zip_map]
+            fn SLN(span: Span, [cost]: f64, [salvage]: f64, [life]: f64) {
+                util::checked_div(span, cost - salvage, life)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the depreciation of an asset for period `period` using
+            /// the fixed-declining balance method, in which the asset loses
+            /// the same fraction of its remaining value each period.
+            ///
+            /// `month` is the number of months in the first year, and is
+            /// assumed to be `12` if omitted. If it is less than `12`, the
+            /// last period is the partial year after `life` full years.
+            #[examples("DB(1000000, 100000, 6, 1, 7)", "DB(A1, A2, 5, 3)")]
+            #[zip_map]
+            fn DB(
+                span: Span,
+                [cost]: f64,
+                [salvage]: f64,
+                [life]: i64,
+                [period]: (Spanned<i64>),
+                [month]: (Option<Spanned<i64>>),
+            ) {
+                let month = month.map_or(12, |m| m.inner);
+                if !(1..=12).contains(&month) || life < 1 || cost <= 0.0 {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                let last_period = if month < 12 { life + 1 } else { life };
+                if !(1..=last_period).contains(&period.inner) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(period.span));
+                }
+
+                // Excel rounds the rate to three decimal places.
+                let rate =
+                    ((1.0 - (salvage / cost).powf(1.0 / life as f64)) * 1000.0).round() / 1000.0;
+                let first_year_rate = rate * month as f64 / 12.0;
+                let depreciation = if period.inner == 1 {
+                    cost * first_year_rate
+                } else {
+                    // value at the start of the period, after the first year
+                    // and then `period - 2` full years
+                    let value = cost
+                        * (1.0 - first_year_rate)
+                        * (1.0 - rate).powf((period.inner - 2) as f64);
+                    if period.inner == life + 1 {
+                        value * rate * (12 - month) as f64 / 12.0
+                    } else {
+                        value * rate
+                    }
+                };
+                util::checked_float(span, depreciation)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the depreciation of an asset for period `period` using
+            /// the double-declining balance method, in which the asset loses
+            /// `factor` times the straight-line rate of its remaining value
+            /// each period. The asset never depreciates below `salvage`.
+            ///
+            /// `factor` is assumed to be `2` if omitted.
+            #[examples("DDB(2400, 300, 10, 1)", "DDB(2400, 300, 120, 1, 1.5)")]
+            #[zip_map]
+            fn DDB(
+                span: Span,
+                [cost]: f64,
+                [salvage]: f64,
+                [life]: f64,
+                [period]: (Spanned<i64>),
+                [factor]: (Option<f64>),
+            ) {
+                let factor = factor.unwrap_or(2.0);
+                if life <= 0.0 || cost < 0.0 || salvage < 0.0 || factor <= 0.0 {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                if period.inner < 1 || period.inner as f64 > life {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(period.span));
+                }
+
+                let rate = (factor / life).min(1.0);
+                // value at the start of the period, which never goes below
+                // `salvage` once it is reached
+                let value = (cost * (1.0 - rate).powf((period.inner - 1) as f64)).max(salvage);
+                (value * rate).min(value - salvage).max(0.0)
+            }
+        ),
+    ]
+}
+
+/// Returns the multiplier for payments due at the start of each period, which
+/// earn one extra period of interest.
+fn payment_factor(rate: f64, at_start: bool) -> f64 {
+    if at_start {
+        1.0 + rate
+    } else {
+        1.0
+    }
+}
+
+/// Returns `(1 + rate)^nper - 1`, without losing precision when `rate` is
+/// close to zero.
+fn growth_minus_one(rate: f64, nper: f64) -> f64 {
+    (nper * rate.ln_1p()).exp_m1()
+}
+
+fn fv(rate: f64, nper: f64, pmt: f64, pv: f64, at_start: bool) -> f64 {
+    if rate == 0.0 {
+        -(pv + pmt * nper)
+    } else {
+        let growth = (1.0 + rate).powf(nper);
+        -(pv * growth + pmt * payment_factor(rate, at_start) * growth_minus_one(rate, nper) / rate)
+    }
+}
+
+fn pmt(rate: f64, nper: f64, pv: f64, fv: f64, at_start: bool) -> f64 {
+    if rate == 0.0 {
+        -(pv + fv) / nper
+    } else {
+        let growth = (1.0 + rate).powf(nper);
+        -rate * (fv + pv * growth) / (payment_factor(rate, at_start) * growth_minus_one(rate, nper))
+    }
+}
+
+fn ipmt(rate: f64, per: f64, nper: f64, pv: f64, fv: f64, at_start: bool) -> f64 {
+    let payment = pmt(rate, nper, pv, fv, at_start);
+    // The interest is charged on the balance at the start of the period,
+    // which is the future value of the payments so far.
+    let balance = match (per == 1.0, at_start) {
+        (true, true) => 0.0,
+        (true, false) => -pv,
+        (false, true) => self::fv(rate, per - 2.0, payment, pv, true) - payment,
+        (false, false) => self::fv(rate, per - 1.0, payment, pv, false),
+    };
+    balance * rate
+}
+
+fn check_period(per: Spanned<f64>, nper: f64) -> CodeResult<()> {
+    if per.inner < 1.0 || per.inner > nper {
+        return Err(RunErrorMsg::InvalidArgument.with_span(per.span));
+    }
+    Ok(())
+}
+
+fn check_has_positive_and_negative(
+    span: Span,
+    mut values: impl Clone + Iterator<Item = f64>,
+) -> CodeResult<()> {
+    if values.clone().any(|v| v > 0.0) && values.any(|v| v < 0.0) {
+        Ok(())
+    } else {
+        Err(RunErrorMsg::InvalidArgument.with_span(span))
+    }
+}
+
+/// Returns pairs of cash flows and the number of years since the first date.
+fn dated_cash_flows(
+    values: &Spanned<Array>,
+    dates: &Spanned<Array>,
+) -> CodeResult<Vec<(f64, f64)>> {
+    dates.check_array_size_exact(values.inner.size())?;
+    let mut first_date = None;
+    let mut ret = vec![];
+    for (value, date) in std::iter::zip(
+        values.inner.cell_values_slice(),
+        dates.inner.cell_values_slice(),
+    ) {
+        let value = f64::try_from(value).map_err(|e| e.with_span(values.span))?;
+        let date = Instant::try_from(date)
+            .map_err(|e| e.with_span(dates.span))?
+            .serial_number();
+        let first_date = *first_date.get_or_insert(date);
+        if date < first_date {
+            return Err(RunErrorMsg::InvalidArgument.with_span(dates.span));
+        }
+        ret.push((value, (date - first_date) / 365.0));
+    }
+    Ok(ret)
+}
+
+fn xnpv(rate: f64, cash_flows: &[(f64, f64)]) -> f64 {
+    cash_flows
+        .iter()
+        .map(|&(value, years)| value / (1.0 + rate).powf(years))
+        .sum()
+}
+
+/// Returns a rate near `guess` at which `f` is zero, or an error if none can
+/// be found.
+///
+/// Uses Newton's method, and falls back to bisection if that does not
+/// converge, since Newton's method can diverge when `f` is nearly flat. A rate
+/// is only returned if `f` is close to zero there, relative to `f(guess)`, so
+/// that a jump in the sign of `f` is not mistaken for a root.
+fn solve(span: impl Into<Span>, guess: f64, f: impl Fn(f64) -> f64) -> CodeResult<f64> {
+    const MAX_ITERATIONS: usize = 100;
+    // Rates at or below -100% are meaningless.
+    const MIN_RATE: f64 = -1.0 + 1e-10;
+
+    let scale = f(guess).abs();
+    let max_residual = 1e-6
+        * if scale.is_finite() {
+            scale.max(1.0)
+        } else {
+            1.0
+        };
+    let converged = |a: f64, b: f64| (a - b).abs() <= 1e-12 * a.abs().max(1.0);
+    let is_root = |x: f64| f(x).abs() <= max_residual;
+
+    let mut rate = guess;
+    for _ in 0..MAX_ITERATIONS {
+        let y = f(rate);
+        let h = 1e-7 * rate.abs().max(1.0);
+        let slope = (f(rate + h) - f(rate - h)) / (2.0 * h);
+        let next = rate - y / slope;
+        if !next.is_finite() || next < MIN_RATE {
+            break;
+        }
+        if converged(next, rate) {
+            if is_root(next) {
+                return Ok(next);
+            }
+            break;
+        }
+        rate = next;
+    }
+
+    // Look for an interval around the guess in which `f` changes sign.
+    let mut candidates = vec![
+        MIN_RATE, -0.99, -0.9, -0.5, 0.0, 0.5, 1.0, 10.0, 100.0, 1e4, 1e6,
+    ];
+    candidates.push(guess.max(MIN_RATE));
+    candidates.sort_by(|a, b| f64::total_cmp(&(a - guess).abs(), &(b - guess).abs()));
+    let bracket = candidates
+        .iter()
+        .flat_map(|&a| candidates.iter().map(move |&b| (a, b)))
+        .filter(|(a, b)| a < b)
+        .find(|&(a, b)| {
+            let (fa, fb) = (f(a), f(b));
+            fa.is_finite() && fb.is_finite() && fa.signum() != fb.signum()
+        });
+    let Some((mut lo, mut hi)) = bracket else {
+        return Err(RunErrorMsg::NoConvergence.with_span(span));
+    };
+    let lo_sign = f(lo).signum();
+    for _ in 0..1000 {
+        let mid = (lo + hi) / 2.0;
+        if converged(lo, hi) {
+            if is_root(mid) {
+                return Ok(mid);
+            }
+            break;
+        }
+        if f(mid).signum() == lo_sign {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Err(RunErrorMsg::NoConvergence.with_span(span))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+
+    #[test]
+    fn test_pmt_ipmt_ppmt() {
+        let g = Grid::new();
+        let round = |s: &str| eval_to_string(&g, &format!("ROUND({s}, 2)"));
+
+        assert_eq!("-1037.03", round("PMT(8%/12, 10, 10000)"));
+        assert_eq!("-1030.16", round("PMT(8%/12, 10, 10000, 0, 1)"));
+        assert_eq!("-129.08", round("PMT(6%/12, 18*12, 0, 50000)"));
+        assert_eq!("-100", round("PMT(0, 10, 1000)"));
+
+        assert_eq!("-66.67", round("IPMT(10%/12, 1, 3*12, 8000)"));
+        assert_eq!("-292.45", round("IPMT(10%, 3, 3, 8000)"));
+        assert_eq!("0", round("IPMT(10%, 1, 3, 8000, 0, TRUE)"));
+        assert_eq!("-75.62", round("PPMT(10%/12, 1, 2*12, 2000)"));
+        assert_eq!("-27598.05", round("PPMT(8%, 10, 10, 200000)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "IPMT(10%, 4, 3, 8000)").msg,
+        );
+    }
+
+    #[test]
+    fn test_fv_pv() {
+        let g = Grid::new();
+        let round = |s: &str| eval_to_string(&g, &format!("ROUND({s}, 2)"));
+
+        assert_eq!("2581.4", round("FV(6%/12, 10, -200, -500, 1)"));
+        assert_eq!("12682.5", round("FV(12%/12, 12, -1000)"));
+        assert_eq!("1500", round("FV(0, 10, -100, -500)"));
+        assert_eq!("-59777.15", round("PV(8%/12, 12*20, 500)"));
+        assert_eq!("-1500", round("PV(0, 10, 100, 500)"));
+    }
+
+    #[test]
+    fn test_npv_xnpv() {
+        let g = Grid::new();
+        let round = |s: &str| eval_to_string(&g, &format!("ROUND({s}, 2)"));
+
+        assert_eq!("1188.44", round("NPV(10%, -10000, 3000, 4200, 6800)"));
+        assert_eq!("1188.44", round("NPV(10%, {-10000, 3000}, 4200, 6800)"));
+
+        let values = "{-10000, 2750, 4250, 3250, 2750}";
+        let dates =
+            "{\"2008-01-01\", \"2008-03-01\", \"2008-10-30\", \"2009-02-15\", \"2009-04-01\"}";
+        assert_eq!("2086.65", round(&format!("XNPV(9%, {values}, {dates})")));
+        // Dates may also be serial numbers.
+        let serial_dates = "{39448, 39508, 39751, 39859, 39904}";
+        assert_eq!(
+            "2086.65",
+            round(&format!("XNPV(9%, {values}, {serial_dates})"))
+        );
+        assert_eq!(
+            "2086.65",
+            round(&format!("XNPV(9%, {values}, DATEVALUE({dates}))")),
+        );
+
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "XNPV(9%, {-100, 110}, {39448, 39000})").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(2, 1).unwrap(),
+                got: ArraySize::new(1, 1).unwrap(),
+            },
+            eval_to_err(&g, "XNPV(9%, {-100, 110}, 39448)").msg,
+        );
+    }
+
+    #[test]
+    fn test_irr_xirr() {
+        let g = Grid::new();
+        let round = |s: &str| eval_to_string(&g, &format!("ROUND({s}, 9)"));
+
+        assert_eq!(
+            "-0.021244848",
+            round("IRR({-70000, 12000, 15000, 18000, 21000})"),
+        );
+        assert_eq!(
+            "0.086630948",
+            round("IRR({-70000, 12000, 15000, 18000, 21000, 26000})"),
+        );
+        assert_eq!("-0.443506941", round("IRR({-70000, 12000, 15000}, -10%)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "IRR({100, 200})").msg,
+        );
+
+        let values = "{-10000, 2750, 4250, 3250, 2750}";
+        let dates =
+            "{\"2008-01-01\", \"2008-03-01\", \"2008-10-30\", \"2009-02-15\", \"2009-04-01\"}";
+        let round = |s: &str| eval_to_string(&g, &format!("ROUND({s}, 8)"));
+        assert_eq!("0.37336253", round(&format!("XIRR({values}, {dates})")));
+        assert_eq!(
+            "0.37336253",
+            round(&format!("XIRR({values}, {dates}, 500%)")),
+        );
+    }
+
+    #[test]
+    fn test_rate_nper() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "0.0077014725",
+            eval_to_string(&g, "ROUND(RATE(4*12, -200, 8000), 10)"),
+        );
+        assert_eq!(
+            "0.0924",
+            eval_to_string(&g, "ROUND(RATE(4*12, -200, 8000) * 12, 4)"),
+        );
+        assert_eq!("0", eval_to_string(&g, "ROUND(RATE(10, -100, 1000), 10)"));
+        assert_eq!(
+            RunErrorMsg::NoConvergence,
+            eval_to_err(&g, "RATE(10, 100, 1000)").msg,
+        );
+
+        // a sign change across a discontinuity is not a root
+        assert_eq!(
+            RunErrorMsg::NoConvergence,
+            super::solve(crate::Span::empty(0), 0.0, |x| 1.0 / (x - 0.3))
+                .unwrap_err()
+                .msg,
+        );
+        let root = super::solve(crate::Span::empty(0), 0.0, |x| x - 0.3).unwrap();
+        assert!((root - 0.3).abs() < 1e-12);
+
+        assert_eq!(
+            "59.6738657",
+            eval_to_string(&g, "ROUND(NPER(12%/12, -100, -1000, 10000, 1), 7)"),
+        );
+        assert_eq!("10", eval_to_string(&g, "NPER(0, -100, 1000)"));
+        assert_eq!(
+            RunErrorMsg::NotANumber,
+            eval_to_err(&g, "NPER(1%, -100, 20000)").msg
+        );
+    }
+
+    #[test]
+    fn test_depreciation() {
+        let g = Grid::new();
+        let round = |s: &str| eval_to_string(&g, &format!("ROUND({s}, 2)"));
+
+        assert_eq!("2250", round("SLN(30000, 7500, 10)"));
+
+        assert_eq!(
+            "{186083.33, 259639.42, 176814.44, 120410.64, 81999.64, 55841.76, 15845.1}",
+            round("DB(1000000, 100000, 6, {1, 2, 3, 4, 5, 6, 7}, 7)"),
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "DB(1000000, 100000, 6, 7)").msg,
+        );
+
+        assert_eq!("1.32", round("DDB(2400, 300, 10*365, 1)"));
+        assert_eq!("40", round("DDB(2400, 300, 10*12, 1, 2)"));
+        assert_eq!("480", round("DDB(2400, 300, 10, 1, 2)"));
+        assert_eq!("306", round("DDB(2400, 300, 10, 2, 1.5)"));
+        assert_eq!("22.12", round("DDB(2400, 300, 10, 10)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "DDB(2400, 300, 10, 11)").msg,
+        );
+
+        // huge lives are computed without iterating over every period
+        assert_eq!("0", round("DB(1000, 100, 1e15, 1e15)"));
+        assert_eq!("0", round("DDB(2400, 300, 1e12, 1e12)"));
+        assert_eq!("0", round("DDB(2400, 300, 1e12, 1e12 - 1, 1e12)"));
+    }
+}
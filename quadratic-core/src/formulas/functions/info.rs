@@ -0,0 +1,269 @@
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Information functions",
+    docs: "These functions return information about a value, such as \
+           its type or whether it is an error. Unlike most functions, \
+           they accept errors as input instead of returning them.\
+           \n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns `TRUE` if `value` is an error, and `FALSE` otherwise.
+            #[examples("ISERROR(1/A1)", "ISERROR(B2:B10)")]
+            #[zip_map]
+            fn ISERROR([value]: (Spanned<CellValue>)) {
+                matches!(value.inner, CellValue::Error(_))
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is a "no match" error (`#N/A`), and
+            /// `FALSE` otherwise.
+            #[examples("ISNA(XLOOKUP(A1, B1:B10, C1:C10))")]
+            #[zip_map]
+            fn ISNA([value]: (Spanned<CellValue>)) {
+                matches!(value.inner, CellValue::Error(e) if e.msg == RunErrorMsg::NoMatch)
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is a blank cell, and `FALSE`
+            /// otherwise.
+            ///
+            /// An empty string is not blank.
+            #[examples("ISBLANK(A1)", "ISBLANK(A1:A10)")]
+            #[zip_map]
+            fn ISBLANK([value]: (Spanned<CellValue>)) {
+                matches!(value.inner, CellValue::Blank)
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is a number, date, time, or
+            /// duration, and `FALSE` otherwise.
+            ///
+            /// Text that looks like a number is not a number.
+            #[examples("ISNUMBER(A1)", "ISNUMBER(SEARCH(\"a\", A1))")]
+            #[zip_map]
+            fn ISNUMBER([value]: (Spanned<CellValue>)) {
+                matches!(
+                    value.inner,
+                    CellValue::Number(_) | CellValue::Instant(_) | CellValue::Duration(_),
+                )
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is text, and `FALSE` otherwise.
+            #[examples("ISTEXT(A1)")]
+            #[zip_map]
+            fn ISTEXT([value]: (Spanned<CellValue>)) {
+                matches!(value.inner, CellValue::Text(_))
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is `TRUE` or `FALSE`, and `FALSE`
+            /// otherwise.
+            #[examples("ISLOGICAL(A1)")]
+            #[zip_map]
+            fn ISLOGICAL([value]: (Spanned<CellValue>)) {
+                matches!(value.inner, CellValue::Logical(_))
+            }
+        ),
+        formula_fn!(
+            /// Returns a "no match" error (`#N/A`).
+            ///
+            /// This can be used to mark missing data.
+            #[include_args_in_completion(false)]
+            #[examples("NA()", "IF(A1=\"\", NA(), A1)")]
+            fn NA(span: Span) {
+                CellValue::Error(Box::new(RunErrorMsg::NoMatch.with_span(span)))
+            }
+        ),
+        formula_fn!(
+            /// Returns a number corresponding to the kind of error in
+            /// `error_value`:
+            ///
+            /// | Number | Excel error | Meaning |
+            /// |---|---|---|
+            /// | 1 | `#NULL!` | ranges that do not intersect |
+            /// | 2 | `#DIV/0!` | division by zero |
+            /// | 3 | `#VALUE!` | wrong type of value or other error |
+            /// | 4 | `#REF!` | invalid cell reference |
+            /// | 5 | `#NAME?` | unknown function name |
+            /// | 6 | `#NUM!` | invalid or out-of-range number |
+            /// | 7 | `#N/A` | no match |
+            /// | 9 | `#SPILL!` | spill error |
+            ///
+            /// Returns a "no match" error (`#N/A`) if `error_value` is not an
+            /// error.
+            #[name("ERROR.TYPE")]
+            #[examples("ERROR.TYPE(1/0)", "IF(ERROR.TYPE(A1)=2, \"divided by zero\", A1)")]
+            #[zip_map]
+            fn ERROR_TYPE([error_value]: (Spanned<CellValue>)) {
+                match error_value.inner {
+                    CellValue::Error(e) => Ok(error_type_number(&e.msg)),
+                    _ => Err(RunErrorMsg::NoMatch.with_span(error_value.span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns a number corresponding to the type of `value`:
+            ///
+            /// | Number | Type |
+            /// |---|---|
+            /// | 1 | number, date, time, duration, or blank |
+            /// | 2 | text |
+            /// | 4 | logical (`TRUE` or `FALSE`) |
+            /// | 16 | error |
+            /// | 64 | array |
+            ///
+            /// A reference to a single cell returns the type of its value.
+            #[examples("TYPE(A1)", "TYPE({1, 2, 3})")]
+            fn TYPE(value: (Spanned<Value>)) {
+                match value.inner {
+                    Value::Single(v) => type_number(&v),
+                    Value::Array(a) => match a.cell_values_slice() {
+                        [v] => type_number(v),
+                        _ => 64,
+                    },
+                }
+            }
+        ),
+    ]
+}
+
+/// Returns the number used by `ERROR.TYPE` for an error, matching the closest
+/// Excel error type.
+fn error_type_number(msg: &RunErrorMsg) -> i64 {
+    match msg {
+        RunErrorMsg::EmptyIntersection => 1,
+
+        RunErrorMsg::DivideByZero => 2,
+
+        RunErrorMsg::BadCellReference
+        | RunErrorMsg::CircularReference
+        | RunErrorMsg::IndexOutOfBounds => 4,
+
+        RunErrorMsg::BadFunctionName => 5,
+
+        RunErrorMsg::BadNumber
+        | RunErrorMsg::Overflow
+        | RunErrorMsg::NegativeExponent
+        | RunErrorMsg::NotANumber
+        | RunErrorMsg::Infinity
+        | RunErrorMsg::NoConvergence
+        | RunErrorMsg::TooMuchRecursion => 6,
+
+        RunErrorMsg::NoMatch => 7,
+
+        RunErrorMsg::Spill => 9,
+
+        RunErrorMsg::PythonError(_)
+        | RunErrorMsg::Unimplemented
+        | RunErrorMsg::UnknownError
+        | RunErrorMsg::InternalError(_)
+        | RunErrorMsg::Unterminated(_)
+        | RunErrorMsg::Expected { .. }
+        | RunErrorMsg::Unexpected(_)
+        | RunErrorMsg::TooManyArguments { .. }
+        | RunErrorMsg::MissingRequiredArgument { .. }
+        | RunErrorMsg::ExactArraySizeMismatch { .. }
+        | RunErrorMsg::ExactArrayAxisMismatch { .. }
+        | RunErrorMsg::ArrayAxisMismatch { .. }
+        | RunErrorMsg::EmptyArray
+        | RunErrorMsg::NonRectangularArray
+        | RunErrorMsg::NonLinearArray
+        | RunErrorMsg::ArrayTooBig
+        | RunErrorMsg::InvalidArgument
+        | RunErrorMsg::InvalidRegex(_) => 3,
+    }
+}
+
+/// Returns the number used by `TYPE` for a single value.
+fn type_number(value: &CellValue) -> i64 {
+    match value {
+        CellValue::Blank
+        | CellValue::Number(_)
+        | CellValue::Instant(_)
+        | CellValue::Duration(_) => 1,
+        CellValue::Text(_) | CellValue::Html(_) | CellValue::Code(_) => 2,
+        CellValue::Logical(_) => 4,
+        CellValue::Error(_) => 16,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{formulas::tests::*, Pos};
+
+    #[test]
+    fn test_formula_is_functions() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        let _ = sheet.set_cell_value(Pos { x: 0, y: 1 }, "text");
+        let _ = sheet.set_cell_value(Pos { x: 0, y: 2 }, 12);
+        let _ = sheet.set_cell_value(Pos { x: 0, y: 3 }, true);
+
+        assert_eq!(
+            "{FALSE; FALSE; FALSE; TRUE}",
+            eval_to_string(&g, "ISBLANK(A1:A4)"),
+        );
+        assert_eq!("FALSE", eval_to_string(&g, "ISBLANK(\"\")"));
+        assert_eq!(
+            "{FALSE; TRUE; FALSE; FALSE}",
+            eval_to_string(&g, "ISNUMBER(A1:A4)"),
+        );
+        assert_eq!("TRUE", eval_to_string(&g, "ISNUMBER(DATE(2024, 3, 1))"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISNUMBER(\"12\")"));
+        assert_eq!(
+            "{TRUE; FALSE; FALSE; FALSE}",
+            eval_to_string(&g, "ISTEXT(A1:A4)"),
+        );
+        assert_eq!(
+            "{FALSE; FALSE; TRUE; FALSE}",
+            eval_to_string(&g, "ISLOGICAL(A1:A4)"),
+        );
+
+        assert_eq!("TRUE", eval_to_string(&g, "ISERROR(1/0)"));
+        assert_eq!("TRUE", eval_to_string(&g, "ISERROR(NA())"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISERROR(A1)"));
+        assert_eq!("{FALSE, TRUE}", eval_to_string(&g, "ISERROR(1/{1, 0})"));
+        assert_eq!("TRUE", eval_to_string(&g, "ISNA(NA())"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISNA(1/0)"));
+    }
+
+    #[test]
+    fn test_formula_na() {
+        let g = Grid::new();
+        expect_err(&RunErrorMsg::NoMatch, &g, "NA()");
+        expect_err(&RunErrorMsg::NoMatch, &g, "NA() + 1");
+    }
+
+    #[test]
+    fn test_formula_error_type() {
+        let g = Grid::new();
+        assert_eq!("2", eval_to_string(&g, "ERROR.TYPE(1/0)"));
+        assert_eq!("7", eval_to_string(&g, "ERROR.TYPE(NA())"));
+        assert_eq!("5", eval_to_string(&g, "ERROR.TYPE(NOSUCHFUNCTION())"));
+        assert_eq!("3", eval_to_string(&g, "error.type(\"a\" + 1)"));
+        expect_err(&RunErrorMsg::NoMatch, &g, "ERROR.TYPE(1)");
+    }
+
+    #[test]
+    fn test_formula_type() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        let _ = sheet.set_cell_value(Pos { x: 0, y: 1 }, "text");
+
+        assert_eq!("1", eval_to_string(&g, "TYPE(12)"));
+        assert_eq!("1", eval_to_string(&g, "TYPE(B1)"));
+        assert_eq!("2", eval_to_string(&g, "TYPE(A1)"));
+        assert_eq!("4", eval_to_string(&g, "TYPE(FALSE)"));
+        assert_eq!("16", eval_to_string(&g, "TYPE(1/0)"));
+        assert_eq!("64", eval_to_string(&g, "TYPE({1, 2})"));
+        assert_eq!("64", eval_to_string(&g, "TYPE(A1:A2)"));
+    }
+}
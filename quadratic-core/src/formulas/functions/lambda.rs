@@ -0,0 +1,339 @@
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Lambda functions",
+    docs: "These functions define variables and custom functions, and apply \
+           custom functions to arrays. A custom function is written as \
+           `LAMBDA(x, x + 1)` and can be called immediately, as in \
+           `LAMBDA(x, x + 1)(5)`, or given a name using `LET`.\
+           \n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        FormulaFunction {
+            name: "LET",
+            arg_completion: Some("${1:name1}, ${2:value1}, ${3:calculation}"),
+            usage: "name1, value1, [name2, value2...], calculation",
+            examples: &[
+                "LET(x, A1 * 2, x + 1)",
+                "LET(rate, 0.07, price, B2, price * (1 + rate))",
+            ],
+            doc: "Assigns each name to the value after it, and then returns \
+                  `calculation`, which can use the names.\n\n\
+                  Each value can use the names defined before it. Names are \
+                  not case-sensitive and must not look like cell references.",
+            params: vec![
+                Param::new("name1", ParamKind::Required),
+                Param::new("value1", ParamKind::Required),
+                Param::new("name2", ParamKind::Repeating),
+                Param::new("calculation", ParamKind::Required),
+            ],
+            eval: eval_special_form,
+        },
+        FormulaFunction {
+            name: "LAMBDA",
+            arg_completion: Some("${1:parameter1}, ${2:calculation}"),
+            usage: "[parameters...], calculation",
+            examples: &[
+                "LAMBDA(x, x * 2)(5)",
+                "LET(hypot, LAMBDA(a, b, SQRT(a^2 + b^2)), hypot(3, 4))",
+            ],
+            doc: "Returns a function that takes the given parameters and \
+                  returns `calculation`.\n\n\
+                  The function can be called by following it with arguments in \
+                  parentheses, or given a name using `LET` and called like any \
+                  other function. It can use names defined outside it.",
+            params: vec![
+                Param::new("parameters", ParamKind::Repeating),
+                Param::new("calculation", ParamKind::Required),
+            ],
+            eval: eval_special_form,
+        },
+        FormulaFunction {
+            name: "MAP",
+            arg_completion: Some("${1:array1}, ${2:lambda}"),
+            usage: "array1, [arrays...], lambda",
+            examples: &[
+                "MAP(A1:A10, LAMBDA(x, x * 2))",
+                "MAP(A1:A5, B1:B5, LAMBDA(a, b, a + b))",
+            ],
+            doc: "Calls `lambda` on each value in the arrays and returns an \
+                  array of the results.\n\n\
+                  `lambda` must take one parameter for each array, and the \
+                  arrays are expanded to the same size in the same way as \
+                  for operators such as `+`.",
+            params: vec![
+                Param::new("array1", ParamKind::Required),
+                Param::new("arrays", ParamKind::Repeating),
+                Param::new("lambda", ParamKind::Required),
+            ],
+            eval: eval_special_form,
+        },
+        FormulaFunction {
+            name: "REDUCE",
+            arg_completion: Some("${1:initial_value}, ${2:array}, ${3:lambda}"),
+            usage: "initial_value, array, lambda",
+            examples: &["REDUCE(0, A1:A10, LAMBDA(total, x, total + x^2))"],
+            doc: "Calls `lambda` on each value in `array`, starting with \
+                  `initial_value` and passing the previous result and the \
+                  value each time. Returns the final result.\n\n\
+                  `lambda` must take two parameters.",
+            params: vec![
+                Param::new("initial_value", ParamKind::Required),
+                Param::new("array", ParamKind::Required),
+                Param::new("lambda", ParamKind::Required),
+            ],
+            eval: eval_special_form,
+        },
+        FormulaFunction {
+            name: "SCAN",
+            arg_completion: Some("${1:initial_value}, ${2:array}, ${3:lambda}"),
+            usage: "initial_value, array, lambda",
+            examples: &["SCAN(0, A1:A10, LAMBDA(total, x, total + x))"],
+            doc: "Same as `REDUCE`, but returns an array of every intermediate \
+                  result instead of only the final one.",
+            params: vec![
+                Param::new("initial_value", ParamKind::Required),
+                Param::new("array", ParamKind::Required),
+                Param::new("lambda", ParamKind::Required),
+            ],
+            eval: eval_special_form,
+        },
+        FormulaFunction {
+            name: "BYROW",
+            arg_completion: Some("${1:array}, ${2:lambda}"),
+            usage: "array, lambda",
+            examples: &["BYROW(A1:C10, LAMBDA(row, SUM(row)))"],
+            doc: "Calls `lambda` on each row of `array` and returns a column \
+                  of the results.\n\n\
+                  `lambda` must take one parameter and return a single value.",
+            params: vec![
+                Param::new("array", ParamKind::Required),
+                Param::new("lambda", ParamKind::Required),
+            ],
+            eval: eval_special_form,
+        },
+        FormulaFunction {
+            name: "BYCOL",
+            arg_completion: Some("${1:array}, ${2:lambda}"),
+            usage: "array, lambda",
+            examples: &["BYCOL(A1:C10, LAMBDA(col, MAX(col)))"],
+            doc: "Calls `lambda` on each column of `array` and returns a row \
+                  of the results.\n\n\
+                  `lambda` must take one parameter and return a single value.",
+            params: vec![
+                Param::new("array", ParamKind::Required),
+                Param::new("lambda", ParamKind::Required),
+            ],
+            eval: eval_special_form,
+        },
+    ]
+}
+
+/// Calls to these functions are evaluated from the syntax tree because some
+/// of their arguments are names or lambdas, so this is never called.
+fn eval_special_form(_ctx: &mut Ctx<'_>, _args: FormulaFnArgs) -> CodeResult<Value> {
+    internal_error!("function should have been evaluated from the syntax tree")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{formulas::tests::*, Pos};
+
+    #[test]
+    fn test_formula_let() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        let _ = sheet.set_cell_value(Pos { x: 0, y: 1 }, 10);
+
+        assert_eq!("21", eval_to_string(&g, "LET(x, A1 * 2, x + 1)"));
+        assert_eq!("7", eval_to_string(&g, "LET(x, 3, y, x + 1, x + y)"));
+        assert_eq!("3", eval_to_string(&g, "let(X, 1, x + LET(x, 2, x))"));
+        assert_eq!("{2, 4}", eval_to_string(&g, "LET(a, {1, 2}, a * 2)"));
+        assert_eq!("0", eval_to_string(&g, "LET(x, 1/0, IFERROR(x, 0))"));
+
+        expect_err(
+            &RunErrorMsg::MissingRequiredArgument {
+                func_name: "LET".into(),
+                arg_name: "calculation".into(),
+            },
+            &g,
+            "LET(x, 1)",
+        );
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "name".into(),
+                got: Some("cell reference".into()),
+            },
+            &g,
+            "LET(A2, 1, A2)",
+        );
+        // Names that aren't variables refer to named ranges.
+        expect_err(&RunErrorMsg::BadCellReference, &g, "LET(x, 1, y)");
+    }
+
+    #[test]
+    fn test_formula_lambda() {
+        let g = Grid::new();
+
+        assert_eq!("6", eval_to_string(&g, "LAMBDA(x, x + 1)(5)"));
+        assert_eq!(
+            "5",
+            eval_to_string(&g, "LAMBDA(a, b, SQRT(a^2 + b^2))(3, 4)")
+        );
+        assert_eq!("42", eval_to_string(&g, "LAMBDA(42)()"));
+        assert_eq!("5", eval_to_string(&g, "LAMBDA(x, LAMBDA(y, x + y))(2)(3)"));
+        assert_eq!(
+            "10",
+            eval_to_string(&g, "LET(double, LAMBDA(x, x * 2), double(5))"),
+        );
+        assert_eq!(
+            "9",
+            eval_to_string(
+                &g,
+                "LET(apply, LAMBDA(f, x, f(f(x))), apply(LAMBDA(n, n + 3), 3))"
+            ),
+        );
+
+        // Lambdas use variables from where they are defined.
+        assert_eq!(
+            "11",
+            eval_to_string(&g, "LET(n, 10, f, LAMBDA(x, x + n), LET(n, 100, f(1)))"),
+        );
+
+        // Variables don't shadow built-in functions unless they are lambdas.
+        assert_eq!("3", eval_to_string(&g, "LET(sum, 5, SUM(1, 2))"));
+        assert_eq!(
+            "2",
+            eval_to_string(&g, "LET(sum, LAMBDA(a, b, a * b), SUM(1, 2))")
+        );
+
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "value".into(),
+                got: Some("function".into()),
+            },
+            &g,
+            "LAMBDA(x, x + 1)",
+        );
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "function".into(),
+                got: Some("value".into()),
+            },
+            &g,
+            "LET(x, 1, MAP({1, 2}, x))",
+        );
+        expect_err(
+            &RunErrorMsg::MissingRequiredArgument {
+                func_name: "LAMBDA".into(),
+                arg_name: "y".into(),
+            },
+            &g,
+            "LAMBDA(x, y, x + y)(1)",
+        );
+        expect_err(
+            &RunErrorMsg::TooManyArguments {
+                func_name: "LAMBDA".into(),
+                max_arg_count: 1,
+            },
+            &g,
+            "LAMBDA(x, x)(1, 2)",
+        );
+
+        // Recursion is limited instead of overflowing the stack.
+        assert_eq!(
+            "120",
+            eval_to_string(
+                &g,
+                "LET(fact, LAMBDA(f, n, IF(n <= 1, 1, n * f(f, n - 1))), fact(fact, 5))",
+            ),
+        );
+        expect_err(
+            &RunErrorMsg::TooMuchRecursion,
+            &g,
+            "LET(f, LAMBDA(g, g(g)), f(f))",
+        );
+        expect_err(
+            &RunErrorMsg::TooMuchRecursion,
+            &g,
+            "LET(f, LAMBDA(g, n, 1 + SUM(g(g, n + 1))), f(f, 0))",
+        );
+    }
+
+    #[test]
+    fn test_formula_map_reduce_scan() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "{2, 4; 6, 8}",
+            eval_to_string(&g, "MAP({1, 2; 3, 4}, LAMBDA(x, x * 2))"),
+        );
+        assert_eq!(
+            "{11, 21; 12, 22}",
+            eval_to_string(&g, "MAP({1; 2}, {10, 20}, LAMBDA(a, b, a + b))"),
+        );
+        assert_eq!("3", eval_to_string(&g, "MAP(1, LAMBDA(x, x + 2))"));
+
+        assert_eq!(
+            "30",
+            eval_to_string(&g, "REDUCE(0, {1, 2, 3, 4}, LAMBDA(acc, x, acc + x^2))"),
+        );
+        assert_eq!(
+            "abc",
+            eval_to_string(
+                &g,
+                "REDUCE(, {\"a\", \"b\", \"c\"}, LAMBDA(acc, x, acc & x))"
+            ),
+        );
+
+        assert_eq!(
+            "{1, 3, 6, 10}",
+            eval_to_string(&g, "SCAN(0, {1, 2, 3, 4}, LAMBDA(acc, x, acc + x))"),
+        );
+        assert_eq!(
+            "{1, 2; 6, 24}",
+            eval_to_string(&g, "SCAN(1, {1, 2; 3, 4}, LAMBDA(acc, x, acc * x))"),
+        );
+
+        expect_err(
+            &RunErrorMsg::MissingRequiredArgument {
+                func_name: "REDUCE".into(),
+                arg_name: "lambda".into(),
+            },
+            &g,
+            "REDUCE(0, {1, 2})",
+        );
+    }
+
+    #[test]
+    fn test_formula_byrow_bycol() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "{3; 7}",
+            eval_to_string(&g, "BYROW({1, 2; 3, 4}, LAMBDA(row, SUM(row)))"),
+        );
+        assert_eq!(
+            "{4, 6}",
+            eval_to_string(&g, "BYCOL({1, 2; 3, 4}, LAMBDA(col, SUM(col)))"),
+        );
+        assert_eq!(
+            "{2, 2}",
+            eval_to_string(&g, "BYCOL({1, 2; 3, 4}, LAMBDA(col, COUNT(col)))"),
+        );
+
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "single value".into(),
+                got: Some("array".into()),
+            },
+            &g,
+            "BYROW({1, 2; 3, 4}, LAMBDA(row, row))",
+        );
+    }
+}
@@ -69,21 +69,103 @@ fn get_functions() -> Vec<FormulaFunction> {
                 booleans.try_fold(false, |a, b| Ok(a ^ b?))
             }
         ),
-        formula_fn!(
-            /// Returns `t` if `condition` is truthy and `f` if `condition` is
-            /// falsey.
-            #[examples(
+        FormulaFunction {
+            name: "IF",
+            arg_completion: Some("${1:condition}, ${2:t}, ${3:f}"),
+            usage: "condition, t, f",
+            examples: &[
                 "IF(A2<0, \"A2 is negative\", \"A2 is nonnegative\")",
-                "IF(A2<0, \"A2 is negative\", IF(A2>0, \"A2 is positive\", \"A2 is zero\"))"
-            )]
-            #[zip_map]
-            fn IF([condition]: bool, [t]: CellValue, [f]: CellValue) {
-                if condition { t } else { f }.clone()
-            }
-        ),
+                "IF(A2<0, \"A2 is negative\", IF(A2>0, \"A2 is positive\", \"A2 is zero\"))",
+            ],
+            doc: "Returns `t` if `condition` is truthy and `f` if `condition` \
+                  is falsey.\n\n\
+                  Only the branch that is returned is evaluated. If \
+                  `condition` is an array, then a branch is chosen for each \
+                  element.",
+            params: vec![
+                Param::new("condition", ParamKind::Required),
+                Param::new("t", ParamKind::Required),
+                Param::new("f", ParamKind::Required),
+            ],
+            eval: eval_conditional,
+        },
+        FormulaFunction {
+            name: "IFS",
+            arg_completion: Some("${1:condition1}, ${2:value1}"),
+            usage: "condition1, value1, [condition2, value2...]",
+            examples: &["IFS(A1>90, \"A\", A1>80, \"B\", A1>70, \"C\", TRUE, \"F\")"],
+            doc: "Returns the value after the first condition that is \
+                  truthy, or an error if no condition is truthy.\n\n\
+                  Conditions are evaluated in order until one is truthy, and \
+                  only the value that is returned is evaluated.",
+            params: vec![
+                Param::new("condition1", ParamKind::Required),
+                Param::new("value1", ParamKind::Required),
+                Param::new("condition2", ParamKind::Repeating),
+            ],
+            eval: eval_conditional,
+        },
+        FormulaFunction {
+            name: "SWITCH",
+            arg_completion: Some("${1:expression}, ${2:value1}, ${3:result1}"),
+            usage: "expression, value1, result1, [value2, result2...], [default]",
+            examples: &[
+                "SWITCH(A1, 1, \"one\", 2, \"two\", \"many\")",
+                "SWITCH(WEEKDAY(B2), 1, \"weekend\", 7, \"weekend\", \"weekday\")",
+            ],
+            doc: "Returns the result after the first value that is equal to \
+                  `expression`. If no value is equal, returns `default`, or \
+                  an error if `default` is omitted.\n\n\
+                  Only the result that is returned is evaluated.",
+            params: vec![
+                Param::new("expression", ParamKind::Required),
+                Param::new("value1", ParamKind::Required),
+                Param::new("result1", ParamKind::Required),
+                Param::new("value2", ParamKind::Repeating),
+            ],
+            eval: eval_conditional,
+        },
+        FormulaFunction {
+            name: "IFERROR",
+            arg_completion: Some("${1:value}, ${2:fallback}"),
+            usage: "value, fallback",
+            examples: &[
+                "IFERROR(1/A6, \"error: A6 is zero\")",
+                "IFERROR(VLOOKUP(A1, B1:C10, 2), 0)",
+            ],
+            doc: "Returns `fallback` if `value` is an error, and `value` \
+                  otherwise.\n\n\
+                  `fallback` is only evaluated if `value` is an error.",
+            params: vec![
+                Param::new("value", ParamKind::Required),
+                Param::new("fallback", ParamKind::Required),
+            ],
+            eval: eval_conditional,
+        },
+        FormulaFunction {
+            name: "IFNA",
+            arg_completion: Some("${1:value}, ${2:fallback}"),
+            usage: "value, fallback",
+            examples: &["IFNA(XLOOKUP(A1, B1:B10, C1:C10), \"not found\")"],
+            doc: "Returns `fallback` if `value` is a \"no match\" error \
+                  (`#N/A`), and `value` otherwise.\n\n\
+                  Other errors are returned unchanged. `fallback` is only \
+                  evaluated if `value` is a \"no match\" error.",
+            params: vec![
+                Param::new("value", ParamKind::Required),
+                Param::new("fallback", ParamKind::Required),
+            ],
+            eval: eval_conditional,
+        },
     ]
 }
 
+/// Calls to these functions are evaluated from the syntax tree so that only
+/// the branches that are taken are evaluated, so this is never called.
+fn eval_conditional(_ctx: &mut Ctx<'_>, _args: FormulaFnArgs) -> CodeResult<Value> {
+    internal_error!("function should have been evaluated from the syntax tree")
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{formulas::tests::*, Pos};
@@ -103,4 +185,185 @@ mod tests {
         let mut ctx = Ctx::new(&g, pos![B0].to_sheet_pos(sheet_id));
         assert_eq!("nope".to_string(), form.eval(&mut ctx).unwrap().to_string());
     }
+
+    #[test]
+    fn test_formula_iferror() {
+        let g = Grid::new();
+        assert_eq!("ok", eval_to_string(&g, "IFERROR(1/0, \"ok\")"));
+        assert_eq!("0.5", eval_to_string(&g, "IFERROR(1/2, 1/0)"));
+        assert_eq!(
+            "ok",
+            eval_to_string(&g, "IFERROR(NOSUCHFUNCTION(), \"ok\")")
+        );
+        assert_eq!(
+            "{1, ok, 0.5}",
+            eval_to_string(&g, "IFERROR(1/{1, 0, 2}, \"ok\")"),
+        );
+        expect_err(&RunErrorMsg::DivideByZero, &g, "IFERROR(1/0, 2/0)");
+
+        // Errors are trapped when they come from another cell.
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        let _ = sheet.set_cell_value(
+            Pos { x: 0, y: 1 },
+            CellValue::Error(Box::new(RunErrorMsg::DivideByZero.without_span())),
+        );
+        assert_eq!("trapped", eval_to_string(&g, "IFERROR(A1, \"trapped\")"));
+    }
+
+    #[test]
+    fn test_formula_ifna() {
+        let g = Grid::new();
+        assert_eq!("missing", eval_to_string(&g, "IFNA(NA(), \"missing\")"));
+        assert_eq!(
+            "missing",
+            eval_to_string(&g, "IFNA(XLOOKUP(5, {1, 2}, {3, 4}), \"missing\")"),
+        );
+        assert_eq!("1", eval_to_string(&g, "IFNA(1, \"missing\")"));
+        expect_err(&RunErrorMsg::DivideByZero, &g, "IFNA(1/0, \"missing\")");
+    }
+
+    #[test]
+    fn test_formula_if_arrays() {
+        let g = Grid::new();
+        assert_eq!(
+            "{yes, no, yes}",
+            eval_to_string(&g, "IF({1, 0, 2}, \"yes\", \"no\")"),
+        );
+        assert_eq!(
+            "{1, 20, 3}",
+            eval_to_string(&g, "IF({TRUE, FALSE, TRUE}, {1, 2, 3}, {10, 20, 30})"),
+        );
+        // Errors in a branch only affect the elements that take it.
+        assert_eq!(
+            "{1, 2}",
+            eval_to_string(&g, "IF({TRUE, TRUE}, {1, 2}, 1/0)"),
+        );
+        expect_err(&RunErrorMsg::DivideByZero, &g, "IF(FALSE, 1, 1/0)");
+        expect_err(
+            &RunErrorMsg::MissingRequiredArgument {
+                func_name: "IF".into(),
+                arg_name: "f".into(),
+            },
+            &g,
+            "IF(TRUE, 1)",
+        );
+    }
+
+    #[test]
+    fn test_formula_ifs() {
+        let mut g = Grid::new();
+        let _ = g.sheets_mut()[0].set_cell_value(pos![A1], 85);
+
+        let formula = "IFS(A1>90, \"A\", A1>80, \"B\", A1>70, \"C\", TRUE, \"F\")";
+        assert_eq!("B", eval_to_string(&g, formula));
+        assert_eq!("yes", eval_to_string(&g, "IFS(FALSE, 1/0, TRUE, \"yes\")"));
+        assert_eq!(
+            "{small, big, small}",
+            eval_to_string(&g, "IFS({1, 5, 2}>3, \"big\", TRUE, \"small\")"),
+        );
+        // Conditions after the first truthy one are not evaluated.
+        assert_eq!("1", eval_to_string(&g, "IFS(TRUE, 1, 1/0, 2)"));
+
+        expect_err(&RunErrorMsg::NoMatch, &g, "IFS(A1>90, \"A\")");
+        expect_err(&RunErrorMsg::DivideByZero, &g, "IFS(1/0, 1)");
+        expect_err(
+            &RunErrorMsg::MissingRequiredArgument {
+                func_name: "IFS".into(),
+                arg_name: "value".into(),
+            },
+            &g,
+            "IFS(TRUE, 1, FALSE)",
+        );
+        // Elements with no truthy condition are errors.
+        assert_eq!(
+            "{1, none}",
+            eval_to_string(&g, "IFERROR(IFS({TRUE, FALSE}, 1), \"none\")"),
+        );
+    }
+
+    #[test]
+    fn test_formula_switch() {
+        let g = Grid::new();
+        assert_eq!(
+            "two",
+            eval_to_string(&g, "SWITCH(2, 1, \"one\", 2, \"two\")")
+        );
+        assert_eq!(
+            "many",
+            eval_to_string(&g, "SWITCH(5, 1, \"one\", 2, \"two\", \"many\")"),
+        );
+        assert_eq!(
+            "B",
+            eval_to_string(&g, "SWITCH(\"b\", \"a\", \"A\", \"B\", \"B\")")
+        );
+        assert_eq!(
+            "{one, many, two}",
+            eval_to_string(&g, "SWITCH({1, 3, 2}, 1, \"one\", 2, \"two\", \"many\")"),
+        );
+        // Results that are not chosen are not evaluated.
+        assert_eq!(
+            "ok",
+            eval_to_string(&g, "SWITCH(1, 1, \"ok\", 2, 1/0, 1/0)")
+        );
+
+        expect_err(
+            &RunErrorMsg::NoMatch,
+            &g,
+            "SWITCH(3, 1, \"one\", 2, \"two\")",
+        );
+        expect_err(
+            &RunErrorMsg::MissingRequiredArgument {
+                func_name: "SWITCH".into(),
+                arg_name: "result1".into(),
+            },
+            &g,
+            "SWITCH(3, 1)",
+        );
+    }
+
+    #[test]
+    fn test_formula_conditionals_access_only_taken_branch() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        let _ = sheet.set_cell_value(pos![A1], 1);
+        let _ = sheet.set_cell_value(pos![B1], 2);
+        let sheet_id = sheet.id;
+        let a1 = crate::SheetRect::single_pos(pos![A1], sheet_id);
+        let b1 = crate::SheetRect::single_pos(pos![B1], sheet_id);
+        let c1 = crate::SheetRect::single_pos(pos![C1], sheet_id);
+
+        for (formula, expected, accessed) in [
+            ("IF(A1=1, B1, C1)", "2", vec![a1, b1]),
+            ("IF(A1=2, B1, C1)", "", vec![a1, c1]),
+            ("IFS(A1=1, B1, TRUE, C1)", "2", vec![a1, b1]),
+            ("SWITCH(A1, 1, B1, C1)", "2", vec![a1, b1]),
+            ("IFERROR(A1, B1)", "1", vec![a1]),
+            ("IFERROR(1/0, B1)", "2", vec![b1]),
+            ("IFNA(A1, B1)", "1", vec![a1]),
+        ] {
+            let mut ctx = Ctx::new(&g, pos![D5].to_sheet_pos(sheet_id));
+            let value = parse_formula(formula, pos![D5])
+                .unwrap()
+                .eval(&mut ctx)
+                .unwrap();
+            assert_eq!(expected, value.to_string(), "{formula}");
+            assert_eq!(
+                accessed
+                    .into_iter()
+                    .collect::<std::collections::HashSet<_>>(),
+                ctx.cells_accessed,
+                "{formula}",
+            );
+        }
+
+        // An untaken branch that refers to the cell containing the formula is
+        // not a circular reference.
+        let mut ctx = Ctx::new(&g, pos![C1].to_sheet_pos(sheet_id));
+        let value = parse_formula("IF(A1=1, B1, C1)", pos![C1])
+            .unwrap()
+            .eval(&mut ctx)
+            .unwrap();
+        assert_eq!("2", value.to_string());
+    }
 }
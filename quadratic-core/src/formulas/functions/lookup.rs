@@ -191,16 +191,7 @@ fn get_functions() -> Vec<FormulaFunction> {
                 let match_mode = LookupMatchMode::try_from(match_mode)?;
                 let search_mode = LookupSearchMode::try_from(search_mode)?;
 
-                // Check for invalid combination
-                if match_mode == LookupMatchMode::Wildcard {
-                    match search_mode {
-                        LookupSearchMode::LinearForward | LookupSearchMode::LinearReverse => (), //ok
-                        LookupSearchMode::BinaryAscending | LookupSearchMode::BinaryDescending => {
-                            // not ok -- can't do binary search with wildcard
-                            return Err(RunErrorMsg::InvalidArgument.with_span(search_mode_span));
-                        }
-                    }
-                }
+                check_lookup_modes(match_mode, search_mode, search_mode_span)?;
 
                 // Give more concise names so it's easier to keep track of them
                 // while reading this code.
@@ -274,9 +265,231 @@ fn get_functions() -> Vec<FormulaFunction> {
                 Array::new_row_major(result_size, final_output_array)?
             }
         ),
+        formula_fn!(
+            /// Searches for a value in a linear range and returns its
+            /// position, starting from 1.
+            ///
+            /// `search_range` must be either a single row or a single column.
+            ///
+            /// # Match types
+            ///
+            /// - 1 = largest value less than or equal to `search_key`
+            ///   (default). `search_range` must be sorted with smaller values
+            ///   first.
+            /// - 0 = exact match. If `search_key` is text, it may contain
+            ///   wildcards.
+            /// - -1 = smallest value greater than or equal to `search_key`.
+            ///   `search_range` must be sorted with larger values first.
+            ///
+            #[doc = see_docs_for_more_about_wildcards!()]
+            ///
+            /// If no match is found, returns an error.
+            ///
+            /// If `search_key` or `match_type` is an array, then a match is
+            /// found for each element.
+            #[examples(
+                "MATCH(17, A1:A10)",
+                "MATCH(\"b*\", A1:Z1, 0)",
+                "INDEX(B1:B10, MATCH(\"zebra\", A1:A10, 0))"
+            )]
+            #[zip_map]
+            fn MATCH(
+                span: Span,
+                [search_key]: CellValue,
+                search_range: (Spanned<Array>),
+                [match_type]: (Option<Spanned<i64>>),
+            ) {
+                search_range.array_linear_axis()?;
+                let (match_mode, search_mode) = match match_type.map(|t| t.inner) {
+                    Some(1) | None => (
+                        LookupMatchMode::NextSmaller,
+                        LookupSearchMode::BinaryAscending,
+                    ),
+                    Some(0) if matches!(search_key, CellValue::Text(_)) => {
+                        (LookupMatchMode::Wildcard, LookupSearchMode::LinearForward)
+                    }
+                    Some(0) => (LookupMatchMode::Exact, LookupSearchMode::LinearForward),
+                    Some(-1) => (
+                        LookupMatchMode::NextLarger,
+                        LookupSearchMode::BinaryDescending,
+                    ),
+                    Some(_) => {
+                        return Err(
+                            RunErrorMsg::InvalidArgument.with_span(match_type.unwrap().span)
+                        );
+                    }
+                };
+                let haystack = search_range.inner.cell_values_slice().iter().collect_vec();
+                let i = lookup(search_key, &haystack, match_mode, search_mode)?
+                    .ok_or_else(|| RunErrorMsg::NoMatch.with_span(span))?;
+                i as i64 + 1
+            }
+        ),
+        formula_fn!(
+            /// Searches for a value in a linear range and returns its
+            /// position, starting from 1.
+            ///
+            /// `search_range` must be either a single row or a single column.
+            /// The match modes and search modes are the same as for
+            /// `XLOOKUP`.
+            ///
+            /// If no match is found, returns an error.
+            ///
+            /// If any of `search_key`, `match_mode`, or `search_mode` is an
+            /// array, then a match is found for each element.
+            #[examples(
+                "XMATCH(\"zebra\", A1:A10)",
+                "XMATCH(50, C4:C834, -1, 2)",
+                "INDEX(B1:B10, XMATCH(\"zebra\", A1:A10))"
+            )]
+            #[zip_map]
+            fn XMATCH(
+                span: Span,
+                [search_key]: CellValue,
+                search_range: (Spanned<Array>),
+                [match_mode]: (Option<Spanned<i64>>),
+                [search_mode]: (Option<Spanned<i64>>),
+            ) {
+                search_range.array_linear_axis()?;
+                let search_mode_span = search_mode.map_or(*span, |arg| arg.span);
+                let match_mode = LookupMatchMode::try_from(match_mode)?;
+                let search_mode = LookupSearchMode::try_from(search_mode)?;
+                check_lookup_modes(match_mode, search_mode, search_mode_span)?;
+
+                let haystack = search_range.inner.cell_values_slice().iter().collect_vec();
+                let i = lookup(search_key, &haystack, match_mode, search_mode)?
+                    .ok_or_else(|| RunErrorMsg::NoMatch.with_span(span))?;
+                i as i64 + 1
+            }
+        ),
+        FormulaFunction {
+            name: "INDEX",
+            arg_completion: Some("${1:array}, ${2:row}${3:, ${4:[column]}}"),
+            usage: "array, row, [column]",
+            examples: &[
+                "INDEX(A1:C10, 3, 2)",
+                "INDEX(A1:A10, MATCH(\"zebra\", B1:B10, 0))",
+                "SUM(INDEX(A1:C10, 0, 2))",
+            ],
+            doc: "Returns the cell at row `row` and column `column` of \
+                  `array`, starting from 1.\n\n\
+                  If `row` or `column` is 0, then the whole column or row is \
+                  returned. If `column` is omitted and `array` is a single \
+                  row, then `row` is used as the column number.\n\n\
+                  If `array` is a cell range, then the result is also a cell \
+                  range, so it can be used in a range such as \
+                  `A1:INDEX(A1:A10, 5)` or passed to `ROW`. Returns an error \
+                  if `row` or `column` is out of range.",
+            params: vec![
+                Param::new("array", ParamKind::Required),
+                Param::new("row", ParamKind::Required),
+                Param::new("column", ParamKind::Optional),
+            ],
+            eval: eval_reference_function,
+        },
+        FormulaFunction {
+            name: "OFFSET",
+            arg_completion: Some(
+                "${1:reference}, ${2:rows}, ${3:columns}${4:, ${5:[height]}${6:, ${7:[width]}}}",
+            ),
+            usage: "reference, rows, columns, [height], [width]",
+            examples: &["OFFSET(A1, 2, 3)", "SUM(OFFSET(A1:A5, 0, B1))"],
+            doc: "Returns the cell range that is `rows` rows down and \
+                  `columns` columns right from `reference`.\n\n\
+                  The result has the same size as `reference` unless \
+                  `height` or `width` is given, which must be at least 1. \
+                  The result can be used anywhere a cell range can be used.",
+            params: vec![
+                Param::new("reference", ParamKind::Required),
+                Param::new("rows", ParamKind::Required),
+                Param::new("columns", ParamKind::Required),
+                Param::new("height", ParamKind::Optional),
+                Param::new("width", ParamKind::Optional),
+            ],
+            eval: eval_reference_function,
+        },
+        formula_fn!(
+            /// Returns the value at position `index` in `values`, starting
+            /// from 1, or an error if there is no such value.
+            #[examples(
+                "CHOOSE(2, \"red\", \"green\", \"blue\")",
+                "SUM(CHOOSE(B1, A1:A10, C1:C10))"
+            )]
+            fn CHOOSE(index: (Spanned<i64>), values: (Iter<Spanned<Value>>)) {
+                // Errors in values that are not chosen are ignored.
+                let mut values = values;
+                let value = usize::try_from(index.inner - 1)
+                    .ok()
+                    .and_then(|i| values.nth(i))
+                    .ok_or(RunErrorMsg::IndexOutOfBounds.with_span(index.span))??;
+                value.inner
+            }
+        ),
+        FormulaFunction {
+            name: "ROW",
+            arg_completion: Some("${1:[reference]}"),
+            usage: "[reference]",
+            examples: &["ROW()", "ROW(B7)", "ROW(A3:A5)"],
+            doc: "Returns the row number of `reference`, or of the cell \
+                  containing the formula if `reference` is omitted.\n\n\
+                  If `reference` has more than one row, then returns a \
+                  column of row numbers.",
+            params: vec![Param::new("reference", ParamKind::Optional)],
+            eval: eval_reference_function,
+        },
+        FormulaFunction {
+            name: "COLUMN",
+            arg_completion: Some("${1:[reference]}"),
+            usage: "[reference]",
+            examples: &["COLUMN()", "COLUMN(B7)", "COLUMN(A1:C1)"],
+            doc: "Returns the column number of `reference`, where column A \
+                  is 1, or of the cell containing the formula if `reference` \
+                  is omitted.\n\n\
+                  If `reference` has more than one column, then returns a row \
+                  of column numbers.",
+            params: vec![Param::new("reference", ParamKind::Optional)],
+            eval: eval_reference_function,
+        },
+        formula_fn!(
+            /// Returns the number of rows in an array or range.
+            #[examples("ROWS(A1:C10)", "ROWS({1, 2; 3, 4; 5, 6})")]
+            fn ROWS(array: Array) {
+                array.height()
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of columns in an array or range.
+            #[examples("COLUMNS(A1:C10)", "COLUMNS({1, 2; 3, 4; 5, 6})")]
+            fn COLUMNS(array: Array) {
+                array.width()
+            }
+        ),
     ]
 }
 
+/// Calls to these functions are evaluated from the syntax tree because they
+/// take or return a cell range rather than its values, so this is never
+/// called.
+fn eval_reference_function(_ctx: &mut Ctx<'_>, _args: FormulaFnArgs) -> CodeResult<Value> {
+    internal_error!("function should have been evaluated from the syntax tree")
+}
+
+/// Returns an error if `match_mode` and `search_mode` cannot be used together.
+fn check_lookup_modes(
+    match_mode: LookupMatchMode,
+    search_mode: LookupSearchMode,
+    search_mode_span: Span,
+) -> CodeResult<()> {
+    match (match_mode, search_mode) {
+        // Can't do binary search with wildcard
+        (
+            LookupMatchMode::Wildcard,
+            LookupSearchMode::BinaryAscending | LookupSearchMode::BinaryDescending,
+        ) => Err(RunErrorMsg::InvalidArgument.with_span(search_mode_span)),
+        _ => Ok(()),
+    }
+}
+
 /// Performs a `LOOKUP` and returns the index of the best match.
 fn lookup<V: ToString + AsRef<CellValue>>(
     needle: &CellValue,
@@ -1026,4 +1239,225 @@ mod tests {
             );
         }
     }
+
+    fn grid_with_letters() -> Grid {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        for (y, letter) in (1..=5).zip(["a", "b", "c", "d", "e"]) {
+            let _ = sheet.set_cell_value(Pos { x: 0, y }, y * 10);
+            let _ = sheet.set_cell_value(Pos { x: 1, y }, letter);
+        }
+        g
+    }
+
+    #[test]
+    fn test_formula_match() {
+        let g = grid_with_letters();
+
+        assert_eq!("3", eval_to_string(&g, "MATCH(30, A1:A5)"));
+        assert_eq!("3", eval_to_string(&g, "MATCH(35, A1:A5, 1)"));
+        assert_eq!("3", eval_to_string(&g, "MATCH(\"c\", B1:B5, 0)"));
+        assert_eq!("4", eval_to_string(&g, "MATCH(\"d*\", B1:B5, 0)"));
+        assert_eq!(
+            "3",
+            eval_to_string(&g, "MATCH(25, {50, 40, 30, 20, 10}, -1)")
+        );
+        assert_eq!("{2; 4}", eval_to_string(&g, "MATCH({20; 40}, A1:A5, 0)"));
+
+        assert_eq!(RunErrorMsg::NoMatch, eval_to_err(&g, "MATCH(5, A1:A5)").msg);
+        assert_eq!(
+            RunErrorMsg::NoMatch,
+            eval_to_err(&g, "MATCH(35, A1:A5, 0)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "MATCH(30, A1:A5, 2)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::NonLinearArray,
+            eval_to_err(&g, "MATCH(30, A1:B5)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_xmatch() {
+        let g = grid_with_letters();
+
+        assert_eq!("3", eval_to_string(&g, "XMATCH(\"c\", B1:B5)"));
+        assert_eq!("4", eval_to_string(&g, "XMATCH(35, A1:A5, 1)"));
+        assert_eq!("3", eval_to_string(&g, "XMATCH(35, A1:A5, -1)"));
+        assert_eq!("2", eval_to_string(&g, "XMATCH(\"b*\", B1:B5, 2)"));
+        assert_eq!("3", eval_to_string(&g, "XMATCH(30, A1:A5, 0, 2)"));
+        assert_eq!("2", eval_to_string(&g, "XMATCH(20, {10, 20, 20}, 0, 1)"));
+        assert_eq!("3", eval_to_string(&g, "XMATCH(20, {10, 20, 20}, 0, -1)"));
+
+        assert_eq!(
+            RunErrorMsg::NoMatch,
+            eval_to_err(&g, "XMATCH(35, A1:A5)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "XMATCH(\"b*\", B1:B5, 2, 2)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_index() {
+        let g = grid_with_letters();
+
+        assert_eq!("{c}", eval_to_string(&g, "INDEX(A1:B5, 3, 2)"));
+        assert_eq!("{40}", eval_to_string(&g, "INDEX(A1:A5, 4)"));
+        assert_eq!("{a}", eval_to_string(&g, "INDEX(A1:B1, 2)"));
+        assert_eq!("{20, b}", eval_to_string(&g, "INDEX(A1:B5, 2)"));
+        assert_eq!("150", eval_to_string(&g, "SUM(INDEX(A1:B5, 0, 1))"));
+
+        // The result of `INDEX` on a cell range is a cell range.
+        assert_eq!("60", eval_to_string(&g, "SUM(A1:INDEX(A1:A5, 3))"));
+        assert_eq!("{3}", eval_to_string(&g, "ROW(INDEX(A1:A5, 3))"));
+        assert_eq!(
+            "{b}",
+            eval_to_string(&g, "INDEX(B1:B5, MATCH(\"b\", B1:B5, 0))"),
+        );
+
+        // `INDEX` also works on arrays.
+        assert_eq!("{3}", eval_to_string(&g, "INDEX({1, 2; 3, 4}, 2, 1)"));
+        assert_eq!("{2; 4}", eval_to_string(&g, "INDEX({1, 2; 3, 4}, 0, 2)"));
+
+        for formula in [
+            "INDEX(A1:A5, 6)",
+            "INDEX(A1:B5, 1, 3)",
+            "INDEX(A1:B5, -1, 1)",
+            "INDEX({1, 2}, 3)",
+        ] {
+            assert_eq!(RunErrorMsg::IndexOutOfBounds, eval_to_err(&g, formula).msg,);
+        }
+        assert_eq!(
+            RunErrorMsg::MissingRequiredArgument {
+                func_name: "INDEX".into(),
+                arg_name: "row".into(),
+            },
+            eval_to_err(&g, "INDEX(A1:A5)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_offset() {
+        let g = grid_with_letters();
+        let sheet_id = g.sheets()[0].id;
+
+        assert_eq!("{c}", eval_to_string(&g, "OFFSET(A1, 2, 1)"));
+        assert_eq!("90", eval_to_string(&g, "SUM(OFFSET(A1:A2, 1, 0, 3))"));
+        assert_eq!("{20, b}", eval_to_string(&g, "OFFSET(A1, 1, 0, , 2)"));
+        assert_eq!("150", eval_to_string(&g, "SUM(A1:OFFSET(A1, 4, 0))"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "OFFSET(A1, 0, 0, 0)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "cell reference".into(),
+                got: Some("array literal".into()),
+            },
+            eval_to_err(&g, "OFFSET({1, 2}, 0, 0)").msg,
+        );
+
+        // Only the cells that `OFFSET` refers to are accessed.
+        let mut ctx = Ctx::new(&g, pos![D1].to_sheet_pos(sheet_id));
+        let value = parse_formula("SUM(OFFSET(A1, 1, 0, 2))", pos![D1])
+            .unwrap()
+            .eval(&mut ctx)
+            .unwrap();
+        assert_eq!("50", value.to_string());
+        assert_eq!(
+            ctx.cells_accessed,
+            [crate::SheetRect::new_pos_span(pos![A2], pos![A3], sheet_id)]
+                .into_iter()
+                .collect(),
+        );
+
+        // The same goes for references built from text by `INDIRECT`.
+        let mut ctx = Ctx::new(&g, pos![D1].to_sheet_pos(sheet_id));
+        let value = parse_formula("SUM(OFFSET(INDIRECT(\"A3:A4\"), 1, 0))", pos![D1])
+            .unwrap()
+            .eval(&mut ctx)
+            .unwrap();
+        assert_eq!("90", value.to_string());
+        assert_eq!(
+            ctx.cells_accessed,
+            [crate::SheetRect::new_pos_span(pos![A4], pos![A5], sheet_id)]
+                .into_iter()
+                .collect(),
+        );
+
+        // A formula inside the range it refers to is circular.
+        let mut ctx = Ctx::new(&g, pos![A3].to_sheet_pos(sheet_id));
+        assert_eq!(
+            RunErrorMsg::CircularReference,
+            parse_formula("SUM(OFFSET(A1, 1, 0, 3))", pos![A3])
+                .unwrap()
+                .eval(&mut ctx)
+                .unwrap_err()
+                .msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_row_column() {
+        let g = Grid::new();
+        let sheet_id = g.sheets()[0].id;
+
+        assert_eq!("{7}", eval_to_string(&g, "ROW(B7)"));
+        assert_eq!("{4}", eval_to_string(&g, "COLUMN(D2)"));
+        assert_eq!("{3; 4; 5}", eval_to_string(&g, "ROW(A3:A5)"));
+        assert_eq!("{1, 2, 3}", eval_to_string(&g, "COLUMN(A1:C1)"));
+        assert_eq!("{3; 4; 5}", eval_to_string(&g, "ROW(3:5)"));
+        for formula in [
+            "ROW(A1:A1000000000)",
+            "ROW(1:1000000000)",
+            "COLUMN(A1:ZZZZZZ1)",
+        ] {
+            assert_eq!(RunErrorMsg::ArrayTooBig, eval_to_err(&g, formula).msg);
+        }
+
+        // Without an argument, the cell containing the formula is used, which
+        // is not a circular reference.
+        let pos = pos![C7].to_sheet_pos(sheet_id);
+        assert_eq!("{7}", eval_to_string_at(&g, pos, "ROW()"));
+        assert_eq!("{3}", eval_to_string_at(&g, pos, "COLUMN()"));
+        assert_eq!("{7}", eval_to_string_at(&g, pos, "ROW($C$7)"));
+
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "cell reference".into(),
+                got: Some("expression".into()),
+            },
+            eval_to_err(&g, "ROW(1 + 1)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::TooManyArguments {
+                func_name: "COLUMN".into(),
+                max_arg_count: 1,
+            },
+            eval_to_err(&g, "COLUMN(A1, B1)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_choose_rows_columns() {
+        let g = grid_with_letters();
+
+        assert_eq!(
+            "green",
+            eval_to_string(&g, "CHOOSE(2, \"red\", \"green\", \"blue\")"),
+        );
+        assert_eq!("150", eval_to_string(&g, "SUM(CHOOSE(1, A1:A5, B1:B5))"));
+        for formula in ["CHOOSE(0, \"red\")", "CHOOSE(2, \"red\")"] {
+            assert_eq!(RunErrorMsg::IndexOutOfBounds, eval_to_err(&g, formula).msg,);
+        }
+
+        assert_eq!("5", eval_to_string(&g, "ROWS(A1:B5)"));
+        assert_eq!("2", eval_to_string(&g, "COLUMNS(A1:B5)"));
+        assert_eq!("3", eval_to_string(&g, "ROWS({1, 2; 3, 4; 5, 6})"));
+        assert_eq!("2", eval_to_string(&g, "COLUMNS({1, 2; 3, 4; 5, 6})"));
+    }
 }
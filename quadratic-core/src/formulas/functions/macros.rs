@@ -75,6 +75,7 @@ macro_rules! see_docs_for_more_about_wildcards {
 ///
 /// - `#[doc = "..."]` (or doc comments using `///`) - user-facing documentation
 /// - `#[operator]` - removes the function from documentation
+/// - `#[name("ERROR.TYPE")]` - function name, if not a valid Rust identifier
 /// - `#[examples("EXAMPLE()", "EXAMPLE(A, B)")]` - example usages
 /// - `#[zip_map]` - if certain arguments are arrays, **zip** them together
 ///                       and **map** a **pure** function over them.
@@ -124,6 +125,7 @@ macro_rules! formula_fn {
             usage: "",
             examples: &[],
             doc: "",
+            params: params_list!($($params)*),
             eval: formula_fn_eval!(
                 { $($body)* };
                 $(#[$($attr)*])*
@@ -135,6 +137,7 @@ macro_rules! formula_fn {
     (
         #[doc = $doc:expr]
         $(#[doc = $additional_doc:expr])*
+        $(#[name($name:literal)])?
         $(#[include_args_in_completion($include_args_in_completion:expr)])?
         #[examples($($example_str:expr),+ $(,)?)]
         $(#[$($attr:tt)*])*
@@ -146,13 +149,15 @@ macro_rules! formula_fn {
         let include_args_in_completion = [$($include_args_in_completion, )? true][0];
 
         $crate::formulas::functions::FormulaFunction {
-            name: stringify!($fn_name),
+            // Default to the name of the Rust function
+            name: [$($name, )? stringify!($fn_name)][0],
             arg_completion: include_args_in_completion.then(|| {
                 $crate::formulas::params::arg_completion_string(&params_list)
             }),
             usage: $crate::formulas::params::usage_string(&params_list),
             examples: &[$($example_str),+],
             doc: concat!($doc $(, "\n", $additional_doc)*),
+            params: params_list,
             eval: formula_fn_eval!(
                 { $($body)* };
                 $(#[$($attr)*])*
@@ -297,13 +302,19 @@ macro_rules! formula_fn_arg {
     };
 
     // Repeating argument
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Value >>) => {
+        formula_fn_arg!(@assign($ctx, $args); $arg_name: Iter< Spanned< Value > >);
+    };
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Value > >) => {
         // Do not flatten `Value`s.
         let mut $arg_name = $args.take_rest().map(CodeResult::Ok);
     };
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Array >>) => {
+        formula_fn_arg!(@assign($ctx, $args); $arg_name: Iter< Spanned< Array > >);
+    };
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Array > >) => {
         // Do not flatten arrays.
-        let mut $arg_name = $args.take_rest().map(Array::from).map(CodeResult::Ok);
+        let mut $arg_name = $args.take_rest().map(|v| v.map(Array::from)).map(CodeResult::Ok);
     };
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< $($arg_type:tt)*) => {
         // Flatten into iterator over non-array type.
@@ -473,6 +484,7 @@ macro_rules! params_list {
             name: stringify!($arg_name),
             kind: params_list!(@get_kind $($arg_type)*),
             zip_mapped: params_list!(@get_is_zip_mapped $arg_name),
+            numeric: $crate::formulas::params::is_numeric_type(stringify!($($arg_type)*)),
         })
     };
 
@@ -486,6 +498,8 @@ macro_rules! params_list {
     // Entry points (at the bottom so that the other rules take priority)
     () => { vec![] };
     ($($arg_name:tt: $arg_type:tt),+ $(,)?) => {{
+        // `Ctx` and `Span` parameters are not appended
+        #[allow(unused_mut)]
         let mut result = vec![];
 
         $(
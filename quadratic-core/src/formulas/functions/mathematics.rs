@@ -1,3 +1,6 @@
+use bigdecimal::{BigDecimal, RoundingMode, Signed, Zero};
+use rand::Rng;
+
 use super::*;
 
 pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
@@ -14,8 +17,8 @@ fn get_functions() -> Vec<FormulaFunction> {
             /// Adds all values.
             /// Returns `0` if given no values.
             #[examples("SUM(B2:C6, 15, E1)")]
-            fn SUM(numbers: (Iter<f64>)) {
-                numbers.sum::<CodeResult<f64>>()
+            fn SUM(numbers: (Iter<BigDecimal>)) {
+                numbers.sum::<CodeResult<BigDecimal>>()
             }
         ),
         formula_fn!(
@@ -36,17 +39,92 @@ fn get_functions() -> Vec<FormulaFunction> {
                 numbers_range: (Option<Spanned<Array>>),
             ) {
                 let criteria = Criterion::try_from(*criteria)?;
+                let numbers = criteria
+                    .iter_matching_coerced::<BigDecimal>(eval_range, numbers_range.as_ref())?;
+                numbers.sum::<CodeResult<BigDecimal>>()
+            }
+        ),
+        formula_fn!(
+            /// Evaluates each value in `numbers_range` based on multiple
+            /// criteria, and then adds up the ones that meet all the criteria.
+            /// Each `eval_range` must be the same size as `numbers_range`, and
+            /// a value is included only if the corresponding value in every
+            /// `eval_range` meets its criteria.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "SUMIFS(C1:C10, A1:A10, \">0\")",
+                "SUMIFS(C1:C10, A1:A10, \"<>INVALID\", B1:B10, \"2\")"
+            )]
+            fn SUMIFS(
+                span: Span,
+                numbers_range: (Spanned<Array>),
+                eval_range1: (Spanned<Array>),
+                criteria1: (Spanned<CellValue>),
+                more_eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs(
+                    "SUMIFS",
+                    span,
+                    eval_range1,
+                    criteria1,
+                    more_eval_ranges_and_criteria,
+                )?;
                 let numbers =
-                    criteria.iter_matching_coerced::<f64>(eval_range, numbers_range.as_ref())?;
-                numbers.sum::<CodeResult<f64>>()
+                    Criterion::iter_matching_all_coerced::<BigDecimal>(&criteria, &numbers_range)?;
+                numbers.sum::<CodeResult<BigDecimal>>()
             }
         ),
         formula_fn!(
             /// Multiplies all values.
             /// Returns `1` if given no values.
             #[examples("PRODUCT(B2:C6, 0.002, E1)")]
-            fn PRODUCT(numbers: (Iter<f64>)) {
-                numbers.product::<CodeResult<f64>>()
+            fn PRODUCT(numbers: (Iter<BigDecimal>)) {
+                numbers.try_fold(BigDecimal::from(1), |product, n| {
+                    CodeResult::Ok(util::decimal_mul(&product, &n?))
+                })
+            }
+        ),
+        formula_fn!(
+            /// Multiplies corresponding values in arrays of the same size, and
+            /// then adds up the products.
+            ///
+            /// Values that are not numbers are treated as zero.
+            #[examples("SUMPRODUCT(A1:A10, B1:B10)", "SUMPRODUCT(A1:A10, B1:B10, C1:C10)")]
+            fn SUMPRODUCT(span: Span, arrays: (Iter<Spanned<Array>>)) {
+                let arrays = arrays.collect::<CodeResult<Vec<_>>>()?;
+                let Some(first) = arrays.first() else {
+                    return Err(RunErrorMsg::MissingRequiredArgument {
+                        func_name: "SUMPRODUCT".into(),
+                        arg_name: "array1".into(),
+                    }
+                    .with_span(span));
+                };
+                for array in &arrays {
+                    array.check_array_size_exact(first.inner.size())?;
+                }
+                let mut sum = BigDecimal::zero();
+                for i in 0..first.inner.cell_values_slice().len() {
+                    let mut product = BigDecimal::from(1);
+                    for array in &arrays {
+                        product = match &array.inner.cell_values_slice()[i] {
+                            CellValue::Error(e) => return Err((**e).clone()),
+                            CellValue::Number(n) => util::decimal_mul(&product, n),
+                            _ => BigDecimal::zero(),
+                        };
+                    }
+                    sum += product;
+                }
+                util::without_trailing_zeros(sum)
+            }
+        ),
+        formula_fn!(
+            /// Adds the squares of all values.
+            /// Returns `0` if given no values.
+            #[examples("SUMSQ(A1:A10)", "SUMSQ(3, 4)")]
+            fn SUMSQ(numbers: (Iter<BigDecimal>)) {
+                numbers
+                    .map(|n| n.map(|n| util::decimal_mul(&n, &n)))
+                    .sum::<CodeResult<BigDecimal>>()
             }
         ),
         formula_fn!(
@@ -65,6 +143,270 @@ fn get_functions() -> Vec<FormulaFunction> {
                 number.sqrt()
             }
         ),
+        formula_fn!(
+            /// Rounds a number to the nearest multiple of 10^-`digits`,
+            /// rounding halfway values away from zero. If `digits` is
+            /// omitted, it is assumed to be zero, which rounds to the nearest
+            /// integer. If `digits` is negative, rounds to the left of the
+            /// decimal point.
+            #[examples("ROUND(6.553, 2)", "ROUND(1234, -2)")]
+            #[zip_map]
+            fn ROUND([number]: BigDecimal, [digits]: (Option<i64>)) {
+                round_to_digits(&number, digits.unwrap_or(0), RoundingMode::HalfUp)
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number away from zero to a multiple of
+            /// 10^-`digits`. If `digits` is omitted, it is assumed to be zero.
+            #[examples("ROUNDUP(6.551, 2)", "ROUNDUP(-1201, -2)")]
+            #[zip_map]
+            fn ROUNDUP([number]: BigDecimal, [digits]: (Option<i64>)) {
+                round_to_digits(&number, digits.unwrap_or(0), RoundingMode::Up)
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number toward zero to a multiple of 10^-`digits`. If
+            /// `digits` is omitted, it is assumed to be zero.
+            #[examples("ROUNDDOWN(6.559, 2)", "ROUNDDOWN(-1299, -2)")]
+            #[zip_map]
+            fn ROUNDDOWN([number]: BigDecimal, [digits]: (Option<i64>)) {
+                round_to_digits(&number, digits.unwrap_or(0), RoundingMode::Down)
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number to the nearest multiple of `multiple`, rounding
+            /// halfway values away from zero.
+            ///
+            /// Returns an error if `number` and `multiple` have different
+            /// signs.
+            #[examples("MROUND(17, 5)", "MROUND(1.37, 0.05)")]
+            #[zip_map]
+            fn MROUND(span: Span, [number]: BigDecimal, [multiple]: BigDecimal) {
+                if multiple.is_zero() {
+                    BigDecimal::zero()
+                } else if number.is_negative() != multiple.is_negative() && !number.is_zero() {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                } else {
+                    round_to_multiple(&number, &multiple, RoundingMode::HalfUp)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number down to the next integer. Always rounds toward
+            /// negative infinity.
+            #[examples("INT(8.9)", "INT(-8.9)")]
+            #[zip_map]
+            fn INT([number]: BigDecimal) {
+                round_to_digits(&number, 0, RoundingMode::Floor)
+            }
+        ),
+        formula_fn!(
+            /// Removes the fractional part of a number, or truncates it to
+            /// `digits` decimal places. Always rounds toward zero.
+            #[examples("TRUNC(8.9)", "TRUNC(-8.9)", "TRUNC(3.14159, 2)")]
+            #[zip_map]
+            fn TRUNC([number]: BigDecimal, [digits]: (Option<i64>)) {
+                round_to_digits(&number, digits.unwrap_or(0), RoundingMode::Down)
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number up to the next multiple of `significance`. If
+            /// `significance` is omitted, it is assumed to be `1`.
+            ///
+            /// If `number` and `significance` are both negative, rounds away
+            /// from zero. Returns an error if `number` is positive and
+            /// `significance` is negative.
+            #[examples("CEILING(6.12, 0.5)", "CEILING(-6.12, -2)")]
+            #[zip_map]
+            fn CEILING(span: Span, [number]: BigDecimal, [significance]: (Option<BigDecimal>)) {
+                let significance = significance.unwrap_or(BigDecimal::from(1));
+                if significance.is_zero() {
+                    BigDecimal::zero()
+                } else if number.is_positive() && significance.is_negative() {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                } else {
+                    round_to_multiple(&number, &significance, RoundingMode::Ceiling)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number down to the next multiple of `significance`. If
+            /// `significance` is omitted, it is assumed to be `1`.
+            ///
+            /// If `number` and `significance` are both negative, rounds toward
+            /// zero. Returns an error if `number` is positive and
+            /// `significance` is negative.
+            #[examples("FLOOR(6.12, 0.5)", "FLOOR(-6.12, -2)")]
+            #[zip_map]
+            fn FLOOR(span: Span, [number]: BigDecimal, [significance]: (Option<BigDecimal>)) {
+                let significance = significance.unwrap_or(BigDecimal::from(1));
+                if significance.is_zero() {
+                    BigDecimal::zero()
+                } else if number.is_positive() && significance.is_negative() {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                } else {
+                    round_to_multiple(&number, &significance, RoundingMode::Floor)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the remainder after dividing `number` by `divisor`. The
+            /// result always has the same sign as `divisor`.
+            #[examples("MOD(7, 3)", "MOD(-7, 3)")]
+            #[zip_map]
+            fn MOD(span: Span, [number]: BigDecimal, [divisor]: BigDecimal) {
+                if divisor.is_zero() {
+                    return Err(RunErrorMsg::DivideByZero.with_span(span));
+                }
+                to_significant_digits(&number)
+                    - round_to_multiple(&number, &divisor, RoundingMode::Floor)
+            }
+        ),
+        formula_fn!(
+            /// Returns `base` raised to the power of `exponent`. This is the
+            /// same as the `^` operator.
+            #[examples("POWER(2, 10)", "POWER(8, 1/3)")]
+            #[zip_map]
+            fn POWER(span: Span, [base]: f64, [exponent]: f64) {
+                if base == 0.0 && exponent < 0.0 {
+                    return Err(RunErrorMsg::DivideByZero.with_span(span));
+                }
+                util::checked_float(span, base.powf(exponent))?
+            }
+        ),
+        formula_fn!(
+            /// Returns *e* raised to the power of `exponent`.
+            #[examples("EXP(1)", "EXP(2.5)")]
+            #[zip_map]
+            fn EXP(span: Span, [exponent]: f64) {
+                util::checked_float(span, exponent.exp())?
+            }
+        ),
+        formula_fn!(
+            /// Returns the natural logarithm of a number.
+            ///
+            /// Returns an error if `number` is not positive.
+            #[examples("LN(50)", "LN(EXP(3))")]
+            #[zip_map]
+            fn LN(span: Span, [number]: f64) {
+                util::checked_float(span, number.ln())?
+            }
+        ),
+        formula_fn!(
+            /// Returns the logarithm of a number to the given `base`. If
+            /// `base` is omitted, it is assumed to be `10`.
+            ///
+            /// Returns an error if `number` is not positive.
+            #[examples("LOG(100)", "LOG(64, 2)")]
+            #[zip_map]
+            fn LOG(span: Span, [number]: f64, [base]: (Option<f64>)) {
+                let base = base.unwrap_or(10.0);
+                if base == 1.0 {
+                    return Err(RunErrorMsg::DivideByZero.with_span(span));
+                }
+                util::checked_float(span, number.ln() / base.ln())?
+            }
+        ),
+        formula_fn!(
+            /// Returns the base-10 logarithm of a number.
+            ///
+            /// Returns an error if `number` is not positive.
+            #[examples("LOG10(1000)")]
+            #[zip_map]
+            fn LOG10(span: Span, [number]: f64) {
+                util::checked_float(span, number.log10())?
+            }
+        ),
+        formula_fn!(
+            /// Returns `1` if a number is positive, `-1` if it is negative,
+            /// and `0` if it is zero.
+            #[examples("SIGN(-4)", "SIGN(A1 - B1)")]
+            #[zip_map]
+            fn SIGN([number]: BigDecimal) {
+                number.signum()
+            }
+        ),
+        formula_fn!(
+            /// Returns the greatest common divisor of all values, which is the
+            /// largest integer that divides all of them. Values are truncated
+            /// to integers.
+            ///
+            /// Returns an error if any value is negative.
+            #[examples("GCD(24, 36)", "GCD(A1:A10)")]
+            fn GCD(numbers: (Iter<Spanned<f64>>)) {
+                let mut ret = 0;
+                for n in numbers {
+                    ret = gcd(ret, to_nonnegative_integer(n?)?);
+                }
+                ret as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the least common multiple of all values, which is the
+            /// smallest positive integer that is a multiple of all of them.
+            /// Values are truncated to integers.
+            ///
+            /// Returns `0` if any value is zero, and an error if any value is
+            /// negative.
+            #[examples("LCM(4, 6)", "LCM(A1:A10)")]
+            fn LCM(numbers: (Iter<Spanned<f64>>)) {
+                let mut ret = 1.0_f64;
+                for n in numbers {
+                    let n = to_nonnegative_integer(n?)?;
+                    if n == 0 || ret == 0.0 {
+                        ret = 0.0;
+                    } else {
+                        let d = gcd(ret as u64, n);
+                        ret = ret / d as f64 * n as f64;
+                    }
+                }
+                ret
+            }
+        ),
+        formula_fn!(
+            /// Returns the factorial of a number, which is the product of all
+            /// positive integers up to and including it. The number is
+            /// truncated to an integer.
+            ///
+            /// Returns an error if `number` is negative.
+            #[examples("FACT(5)")]
+            #[zip_map]
+            fn FACT(span: Span, [number]: (Spanned<f64>)) {
+                let n = to_nonnegative_integer(number)?;
+                let product = (1..=n).try_fold(1.0, |product, i| {
+                    let product = product * i as f64;
+                    product.is_finite().then_some(product)
+                });
+                product.ok_or(RunErrorMsg::Overflow.with_span(span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of ways to choose `k` items from a set of
+            /// `n` items, ignoring order. Both numbers are truncated to
+            /// integers.
+            ///
+            /// Returns an error if either number is negative or if `k` is
+            /// greater than `n`.
+            #[examples("COMBIN(8, 2)")]
+            #[zip_map]
+            fn COMBIN(span: Span, [n]: (Spanned<f64>), [k]: (Spanned<f64>)) {
+                let n = to_nonnegative_integer(n)?;
+                let k_span = k.span;
+                let k = to_nonnegative_integer(k)?;
+                if k > n {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(k_span));
+                }
+                // Multiply and divide alternately so that intermediate results
+                // are always integers. These only grow, so stop as soon as one
+                // overflows.
+                let k = k.min(n - k);
+                let ret = (0..k).try_fold(1.0_f64, |ret, i| {
+                    let ret = ret * (n - i) as f64 / (i + 1) as f64;
+                    ret.is_finite().then_some(ret)
+                });
+                ret.ok_or(RunErrorMsg::Overflow.with_span(span))?.round()
+            }
+        ),
         // Constants
         formula_fn!(
             /// Returns π, the circle constant.
@@ -80,9 +422,77 @@ fn get_functions() -> Vec<FormulaFunction> {
                 std::f64::consts::TAU
             }
         ),
+        // Random numbers
+        formula_fn!(
+            /// Returns a random number between `0` (inclusive) and `1`
+            /// (exclusive).
+            #[examples("RAND()", "RAND() * 100")]
+            fn RAND() {
+                rand::thread_rng().gen::<f64>()
+            }
+        ),
+        formula_fn!(
+            /// Returns a random integer between `low` and `high`, inclusive.
+            ///
+            /// Returns an error if `low` is greater than `high`.
+            #[examples("RANDBETWEEN(1, 6)")]
+            #[zip_map]
+            fn RANDBETWEEN(span: Span, [low]: f64, [high]: f64) {
+                let low = low.ceil();
+                let high = high.floor();
+                if low > high {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                rand::thread_rng().gen_range(low as i64..=high as i64)
+            }
+        ),
     ]
 }
 
+/// Rounds a number to a multiple of 10^-`digits`.
+fn round_to_digits(number: &BigDecimal, digits: i64, mode: RoundingMode) -> BigDecimal {
+    let number = to_significant_digits(number);
+    let (_, scale) = number.as_bigint_and_exponent();
+    if digits >= scale {
+        return number;
+    }
+    // Avoid rounding to absurdly large powers of 10.
+    let digits = digits.max(-(f64::MAX_10_EXP as i64) - 1);
+    util::without_trailing_zeros(number.with_scale_round(digits, mode))
+}
+
+/// Rounds a number to a multiple of `multiple`.
+fn round_to_multiple(number: &BigDecimal, multiple: &BigDecimal, mode: RoundingMode) -> BigDecimal {
+    let multiple = to_significant_digits(multiple);
+    let quotient = (to_significant_digits(number) / &multiple).with_scale_round(0, mode);
+    util::without_trailing_zeros(quotient * multiple)
+}
+
+/// Rounds a number to 15 significant digits, like Excel does before rounding,
+/// so that artifacts from floating-point arithmetic don't affect the result.
+fn to_significant_digits(number: &BigDecimal) -> BigDecimal {
+    if number.is_zero() {
+        return BigDecimal::zero();
+    }
+    let digits = 14 - util::magnitude(number);
+    util::without_trailing_zeros(number.with_scale_round(digits, RoundingMode::HalfUp))
+}
+
+/// Truncates a number to an integer, returning an error if it is negative.
+fn to_nonnegative_integer(number: Spanned<f64>) -> CodeResult<u64> {
+    if number.inner < 0.0 || !number.inner.is_finite() {
+        return Err(RunErrorMsg::InvalidArgument.with_span(number.span));
+    }
+    Ok(number.inner.trunc() as u64)
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{formulas::tests::*, Pos};
@@ -137,6 +547,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sumifs() {
+        let g = Grid::new();
+        assert_eq!("15", eval_to_string(&g, "SUMIFS(0..10, 0..10, \"<=5\")"));
+        assert_eq!(
+            "12",
+            eval_to_string(&g, "SUMIFS(0..10, 0..10, \"<=5\", 0..10, \">2\")"),
+        );
+        assert_eq!(
+            "1",
+            eval_to_string(
+                &g,
+                "SUMIFS({1, 2, 3}, {\"a\", \"b\", \"a\"}, \"a\", {1, 1, 0}, 1)",
+            ),
+        );
+        expect_err(
+            &RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 3).unwrap(),
+                got: ArraySize::new(1, 2).unwrap(),
+            },
+            &g,
+            "SUMIFS({1; 2; 3}, {1; 2; 3}, \">0\", {1; 2}, \">0\")",
+        );
+        expect_err(
+            &RunErrorMsg::MissingRequiredArgument {
+                func_name: "SUMIFS".into(),
+                arg_name: "criteria".into(),
+            },
+            &g,
+            "SUMIFS({1, 2}, {1, 2}, 1, {1, 2})",
+        );
+    }
+
     #[test]
     fn test_product() {
         let g = Grid::new();
@@ -260,4 +703,194 @@ mod tests {
                 .msg,
         );
     }
+
+    #[test]
+    fn test_sumproduct_sumsq() {
+        let g = Grid::new();
+        assert_eq!("32", eval_to_string(&g, "SUMPRODUCT({1, 2, 3}, {4, 5, 6})"));
+        assert_eq!(
+            "95",
+            eval_to_string(&g, "SUMPRODUCT({1; 2}, {3; 4}, {5; 10})"),
+        );
+        assert_eq!("6", eval_to_string(&g, "SUMPRODUCT({1, 2, 3})"));
+        // Values that are not numbers are treated as zero.
+        assert_eq!(
+            "15",
+            eval_to_string(&g, "SUMPRODUCT({1, \"a\", 3}, {3, 4, 4})"),
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(3, 1).unwrap(),
+                got: ArraySize::new(2, 1).unwrap(),
+            },
+            eval_to_err(&g, "SUMPRODUCT({1, 2, 3}, {1, 2})").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "SUMPRODUCT({1, 2}, {1/0, 2})").msg,
+        );
+
+        assert_eq!("25", eval_to_string(&g, "SUMSQ(3, 4)"));
+        assert_eq!("14", eval_to_string(&g, "SUMSQ({1, 2, 3})"));
+        assert_eq!("0", eval_to_string(&g, "SUMSQ()"));
+    }
+
+    #[test]
+    fn test_round() {
+        let g = Grid::new();
+        assert_eq!("6.55", eval_to_string(&g, "ROUND(6.553, 2)"));
+        assert_eq!("2.68", eval_to_string(&g, "ROUND(2.675, 2)"));
+        assert_eq!("-2.68", eval_to_string(&g, "ROUND(-2.675, 2)"));
+        assert_eq!("3", eval_to_string(&g, "ROUND(2.5)"));
+        assert_eq!("-3", eval_to_string(&g, "ROUND(-2.5)"));
+        assert_eq!("1200", eval_to_string(&g, "ROUND(1234, -2)"));
+        assert_eq!("2.5", eval_to_string(&g, "ROUND(2.5, 3)"));
+        assert_eq!("0", eval_to_string(&g, "ROUND(0.4)"));
+        assert_eq!("{1, 1.5}", eval_to_string(&g, "ROUND(1.46, {0, 1})"));
+
+        assert_eq!("6.56", eval_to_string(&g, "ROUNDUP(6.551, 2)"));
+        assert_eq!("-1300", eval_to_string(&g, "ROUNDUP(-1201, -2)"));
+        assert_eq!("6.55", eval_to_string(&g, "ROUNDDOWN(6.559, 2)"));
+        assert_eq!("-1200", eval_to_string(&g, "ROUNDDOWN(-1299, -2)"));
+
+        assert_eq!("15", eval_to_string(&g, "MROUND(17, 5)"));
+        assert_eq!("1.35", eval_to_string(&g, "MROUND(1.37, 0.05)"));
+        assert_eq!("1.4", eval_to_string(&g, "MROUND(1.3, 0.2)"));
+        assert_eq!("-10", eval_to_string(&g, "MROUND(-7.5, -5)"));
+        assert_eq!("0", eval_to_string(&g, "MROUND(7, 0)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "MROUND(-7, 5)").msg,
+        );
+    }
+
+    #[test]
+    fn test_int_trunc() {
+        let g = Grid::new();
+        assert_eq!("8", eval_to_string(&g, "INT(8.9)"));
+        assert_eq!("-9", eval_to_string(&g, "INT(-8.9)"));
+        assert_eq!("8", eval_to_string(&g, "TRUNC(8.9)"));
+        assert_eq!("-8", eval_to_string(&g, "TRUNC(-8.9)"));
+        assert_eq!("3.14", eval_to_string(&g, "TRUNC(3.14159, 2)"));
+        assert_eq!("1", eval_to_string(&g, "INT(TRUE)"));
+    }
+
+    #[test]
+    fn test_ceiling_floor_mod() {
+        let g = Grid::new();
+        assert_eq!("6.5", eval_to_string(&g, "CEILING(6.12, 0.5)"));
+        assert_eq!("-8", eval_to_string(&g, "CEILING(-6.12, -2)"));
+        assert_eq!("-6", eval_to_string(&g, "CEILING(-6.12, 2)"));
+        assert_eq!("7", eval_to_string(&g, "CEILING(6.12)"));
+        assert_eq!("6", eval_to_string(&g, "FLOOR(6.12, 0.5)"));
+        assert_eq!("-6", eval_to_string(&g, "FLOOR(-6.12, -2)"));
+        assert_eq!("-8", eval_to_string(&g, "FLOOR(-6.12, 2)"));
+        assert_eq!("0", eval_to_string(&g, "FLOOR(6.12, 0)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "CEILING(6, -2)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "FLOOR(6, -2)").msg,
+        );
+
+        assert_eq!("1", eval_to_string(&g, "MOD(7, 3)"));
+        assert_eq!("2", eval_to_string(&g, "MOD(-7, 3)"));
+        assert_eq!("-2", eval_to_string(&g, "MOD(7, -3)"));
+        assert_eq!("0.1", eval_to_string(&g, "MOD(1.1, 1)"));
+        assert_eq!(RunErrorMsg::DivideByZero, eval_to_err(&g, "MOD(7, 0)").msg);
+    }
+
+    #[test]
+    fn test_power_exp_log() {
+        let g = Grid::new();
+        assert_eq!("1024", eval_to_string(&g, "POWER(2, 10)"));
+        assert_eq!("0.25", eval_to_string(&g, "POWER(2, -2)"));
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "POWER(0, -1)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::NotANumber,
+            eval_to_err(&g, "POWER(-1, 0.5)").msg,
+        );
+
+        assert_eq!("1", eval_to_string(&g, "EXP(0)"));
+        assert!(eval_to_string(&g, "EXP(1)").starts_with("2.71828182845904"));
+        assert_eq!("0", eval_to_string(&g, "LN(1)"));
+        assert_eq!("3", eval_to_string(&g, "LN(EXP(3))"));
+        assert_eq!(RunErrorMsg::NotANumber, eval_to_err(&g, "LN(-1)").msg);
+        assert_eq!(RunErrorMsg::Overflow, eval_to_err(&g, "LN(0)").msg);
+
+        assert_eq!("2", eval_to_string(&g, "LOG(100)"));
+        assert_eq!("6", eval_to_string(&g, "LOG(64, 2)"));
+        assert_eq!("3", eval_to_string(&g, "LOG10(1000)"));
+        assert_eq!(RunErrorMsg::DivideByZero, eval_to_err(&g, "LOG(5, 1)").msg);
+        assert_eq!(RunErrorMsg::NotANumber, eval_to_err(&g, "LOG10(-5)").msg);
+    }
+
+    #[test]
+    fn test_sign() {
+        let g = Grid::new();
+        assert_eq!("{-1, 0, 1}", eval_to_string(&g, "SIGN({-4.5, 0, 0.1})"));
+    }
+
+    #[test]
+    fn test_gcd_lcm() {
+        let g = Grid::new();
+        assert_eq!("12", eval_to_string(&g, "GCD(24, 36)"));
+        assert_eq!("4", eval_to_string(&g, "GCD({24, 36, 8.9})"));
+        assert_eq!("5", eval_to_string(&g, "GCD(0, 5)"));
+        assert_eq!("12", eval_to_string(&g, "LCM(4, 6)"));
+        assert_eq!("60", eval_to_string(&g, "LCM(1..5)"));
+        assert_eq!("0", eval_to_string(&g, "LCM(4, 0)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "GCD(4, -2)").msg
+        );
+        assert_eq!(RunErrorMsg::InvalidArgument, eval_to_err(&g, "LCM(-4)").msg);
+    }
+
+    #[test]
+    fn test_fact_combin() {
+        let g = Grid::new();
+        assert_eq!("1", eval_to_string(&g, "FACT(0)"));
+        assert_eq!("120", eval_to_string(&g, "FACT(5.9)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "FACT(-1)").msg
+        );
+        assert_eq!(RunErrorMsg::Overflow, eval_to_err(&g, "FACT(171)").msg);
+
+        assert_eq!("28", eval_to_string(&g, "COMBIN(8, 2)"));
+        assert_eq!("1", eval_to_string(&g, "COMBIN(8, 0)"));
+        assert_eq!("1", eval_to_string(&g, "COMBIN(8, 8)"));
+        assert_eq!("155117520", eval_to_string(&g, "COMBIN(30, 15)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "COMBIN(2, 3)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::Overflow,
+            eval_to_err(&g, "COMBIN(1e15, 5e14)").msg,
+        );
+    }
+
+    #[test]
+    fn test_rand() {
+        let g = Grid::new();
+        for _ in 0..20 {
+            let n: f64 = eval_to_string(&g, "RAND()").parse().unwrap();
+            assert!((0.0..1.0).contains(&n));
+
+            let n: i64 = eval_to_string(&g, "RANDBETWEEN(-2.5, 3)").parse().unwrap();
+            assert!((-2..=3).contains(&n));
+        }
+        assert_eq!("4", eval_to_string(&g, "RANDBETWEEN(3.5, 4.5)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "RANDBETWEEN(5, 1)").msg,
+        );
+    }
 }
@@ -6,6 +6,11 @@ use lazy_static::lazy_static;
 
 #[macro_use]
 mod macros;
+mod array;
+mod datetime;
+mod financial;
+mod info;
+mod lambda;
 mod logic;
 mod lookup;
 mod mathematics;
@@ -13,7 +18,7 @@ mod operators;
 mod statistics;
 mod string;
 mod trigonometry;
-mod util;
+pub(super) mod util;
 
 use super::{CellRef, Criterion, Ctx, Param, ParamKind};
 use crate::{
@@ -31,8 +36,13 @@ pub const CATEGORIES: &[FormulaFunctionCategory] = &[
     trigonometry::CATEGORY,
     statistics::CATEGORY,
     logic::CATEGORY,
+    info::CATEGORY,
     string::CATEGORY,
+    datetime::CATEGORY,
+    financial::CATEGORY,
     lookup::CATEGORY,
+    array::CATEGORY,
+    lambda::CATEGORY,
 ];
 
 lazy_static! {
@@ -130,6 +140,9 @@ pub struct FormulaFunction {
     pub usage: &'static str,
     pub examples: &'static [&'static str],
     pub doc: &'static str,
+    /// Parameters, used to check the number and types of arguments before
+    /// evaluation.
+    pub(super) params: Vec<Param>,
     pub eval: FormulaFn,
 }
 impl FormulaFunction {
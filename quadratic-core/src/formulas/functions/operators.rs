@@ -1,4 +1,16 @@
-use crate::ArraySize;
+//! Operators, such as `+` and `&`.
+//!
+//! `+`, `-`, `*`, `/`, and `%` operate on [`BigDecimal`]s, so that they are as
+//! precise as the numbers stored in cells and `0.1 + 0.2` is exactly `0.3`.
+//! Products and quotients are rounded to 34 significant digits. Like
+//! transcendental functions such as `SQRT()` and `LN()`, `^` falls back to
+//! `f64`, since its result usually has no exact decimal representation anyway.
+//! Functions that add, multiply, or compare numbers, such as `SUM()`,
+//! `SUMIF()`, `PRODUCT()`, `AVERAGE()`, and `MIN()`, use decimals too.
+
+use bigdecimal::BigDecimal;
+
+use crate::{ArraySize, Duration, Instant};
 
 use super::*;
 
@@ -25,32 +37,35 @@ fn get_functions() -> Vec<FormulaFunction> {
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "+"([a]: f64, [b]: (Option<f64>)) {
-                a + b.unwrap_or(0.0)
+            fn "+"([a]: CellValue, [b]: (Option<CellValue>)) {
+                match b {
+                    Some(b) => add(a, b),
+                    None => add(a, &CellValue::Blank),
+                }
             }
         ),
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "-"([a]: f64, [b]: (Option<f64>)) {
+            fn "-"([a]: CellValue, [b]: (Option<CellValue>)) {
                 match b {
-                    Some(b) => a - b,
-                    None => -a
+                    Some(b) => subtract(a, b),
+                    None => negate(a),
                 }
             }
         ),
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "*"([a]: f64, [b]: f64) {
-                a * b
+            fn "*"([a]: BigDecimal, [b]: BigDecimal) {
+                util::decimal_mul(&a, &b)
             }
         ),
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "/"(span: Span, [dividend]: f64, [divisor]: f64) {
-                util::checked_div(span, dividend, divisor)
+            fn "/"(span: Span, [dividend]: BigDecimal, [divisor]: BigDecimal) {
+                util::checked_decimal_div(span, &dividend, &divisor)
             }
         ),
         formula_fn!(
@@ -63,8 +78,8 @@ fn get_functions() -> Vec<FormulaFunction> {
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "%"([percentage]: f64) {
-                percentage / 100.0
+            fn "%"([percentage]: BigDecimal) {
+                util::without_trailing_zeros(percentage / BigDecimal::from(100))
             }
         ),
         formula_fn!(
@@ -95,6 +110,52 @@ fn get_functions() -> Vec<FormulaFunction> {
     ]
 }
 
+/// Adds two values. Numbers added to an instant or duration are treated as a
+/// number of days.
+fn add(a: &CellValue, b: &CellValue) -> CodeResult<CellValue> {
+    Ok(match (a, b) {
+        (CellValue::Instant(i), CellValue::Duration(d))
+        | (CellValue::Duration(d), CellValue::Instant(i)) => {
+            CellValue::Instant(i.add_duration(*d).ok_or(RunErrorMsg::Overflow)?)
+        }
+        (CellValue::Instant(i), other) | (other, CellValue::Instant(i)) => {
+            CellValue::Instant(i.add_days(f64::try_from(other)?))
+        }
+        (CellValue::Duration(d1), CellValue::Duration(d2)) => CellValue::Duration(*d1 + *d2),
+        (CellValue::Duration(d), other) | (other, CellValue::Duration(d)) => {
+            CellValue::Duration(*d + days_to_duration(f64::try_from(other)?))
+        }
+        _ => CellValue::from(util::without_trailing_zeros(
+            BigDecimal::try_from(a)? + BigDecimal::try_from(b)?,
+        )),
+    })
+}
+
+/// Subtracts one value from another. The difference between two instants is a
+/// number of days.
+fn subtract(a: &CellValue, b: &CellValue) -> CodeResult<CellValue> {
+    Ok(match (a, b) {
+        (CellValue::Instant(i1), CellValue::Instant(i2)) => {
+            CellValue::from(Instant::serial_number(*i1) - Instant::serial_number(*i2))
+        }
+        (CellValue::Instant(_) | CellValue::Duration(_), _) => add(a, &negate(b)?)?,
+        _ => CellValue::from(util::without_trailing_zeros(
+            BigDecimal::try_from(a)? - BigDecimal::try_from(b)?,
+        )),
+    })
+}
+
+fn negate(a: &CellValue) -> CodeResult<CellValue> {
+    Ok(match a {
+        CellValue::Duration(d) => CellValue::Duration(-*d),
+        _ => CellValue::from(-BigDecimal::try_from(a)?),
+    })
+}
+
+fn days_to_duration(days: f64) -> Duration {
+    Duration::from_seconds(days * 86400.0)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::formulas::tests::*;
@@ -113,6 +174,81 @@ mod tests {
         assert_eq!(RunErrorMsg::DivideByZero, eval_to_err(&g, "0/ 0").msg);
     }
 
+    #[test]
+    fn test_formula_decimal_arithmetic() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        for (y, n) in ["19.99", "5.01", "0.07"].into_iter().enumerate() {
+            let n = CellValue::Number(n.parse().unwrap());
+            let _ = sheet.set_cell_value(crate::Pos { x: 1, y: y as i64 }, n);
+        }
+
+        assert_eq!("0.3", eval_to_string(&g, "0.1 + 0.2"));
+        assert_eq!("TRUE", eval_to_string(&g, "0.1 + 0.2 = 0.3"));
+        assert_eq!("0.1", eval_to_string(&g, "0.3 - 0.2"));
+        assert_eq!("-0.1", eval_to_string(&g, "-(0.3 - 0.2)"));
+        assert_eq!("18.99", eval_to_string(&g, "3 * 6.33"));
+        assert_eq!("1.21", eval_to_string(&g, "1.1 * 1.1"));
+        assert_eq!("25.07", eval_to_string(&g, "B0 + B1 + B2"));
+        assert_eq!("1.75", eval_to_string(&g, "B2 * 25"));
+
+        // Trailing zeros are removed.
+        assert_eq!("3", eval_to_string(&g, "1.5 + 1.5"));
+        assert_eq!("1.5", eval_to_string(&g, ".5 + 1."));
+        assert_eq!("5000", eval_to_string(&g, "2.5e3 * 2"));
+        assert_eq!("100", eval_to_string(&g, "1000 / 10"));
+
+        // Quotients that don't terminate are rounded.
+        assert_eq!("0.125", eval_to_string(&g, "1 / 8"));
+        assert_eq!(
+            "0.3333333333333333333333333333333333",
+            eval_to_string(&g, "1 / 3"),
+        );
+        assert_eq!("3", eval_to_string(&g, "0.3 / 0.1"));
+
+        // Products are rounded too.
+        assert_eq!(
+            "1.000000000000000002",
+            eval_to_string(&g, "1.000000000000000001 * 1.000000000000000001"),
+        );
+
+        // Functions that add, multiply, and compare numbers use decimals.
+        assert_eq!("0.3", eval_to_string(&g, "SUM(0.1, 0.2)"));
+        assert_eq!("TRUE", eval_to_string(&g, "SUM(0.1, 0.2) = 0.3"));
+        assert_eq!(
+            "8.356666666666666666666666666666667",
+            eval_to_string(&g, "AVERAGE(B0:B2)")
+        );
+        assert_eq!("0.2", eval_to_string(&g, "AVERAGE(0.1, 0.2, 0.3)"));
+        assert_eq!(
+            "TRUE",
+            eval_to_string(&g, "SUMIF({0.1, 0.2, 5}, \"<1\") = 0.3"),
+        );
+        assert_eq!(
+            "0.3",
+            eval_to_string(&g, "SUMIFS({0.1, 0.2, 5}, {1, 1, 2}, 1)"),
+        );
+        assert_eq!("0.021", eval_to_string(&g, "PRODUCT(0.1, 0.7, 0.3)"));
+        assert_eq!("0.05", eval_to_string(&g, "SUMSQ(0.1, 0.2)"));
+        assert_eq!(
+            "0.11",
+            eval_to_string(&g, "SUMPRODUCT({0.1, 0.3}, {0.2, 0.3})"),
+        );
+        assert_eq!("TRUE", eval_to_string(&g, "MIN(0.1 + 0.2, 0.5) = 0.3"));
+        assert_eq!("25.07", eval_to_string(&g, "MAX(B0 + B1 + B2, 1)"));
+        assert_eq!("0", eval_to_string(&g, "MIN()"));
+
+        // Literals must fit in a float.
+        assert_eq!("10", eval_to_string(&g, "1e308 / 1e307"));
+        assert_eq!(RunErrorMsg::Overflow, eval_to_err(&g, "1e309").msg);
+        assert_eq!(RunErrorMsg::Overflow, eval_to_err(&g, "1e999999999").msg);
+        assert_eq!(RunErrorMsg::BadNumber, eval_to_err(&g, "1e-999999999").msg);
+
+        // Exponentiation falls back to floating-point.
+        assert_eq!("1024", eval_to_string(&g, "2 ^ 10"));
+        assert_eq!("0.5", eval_to_string(&g, "4 ^ -0.5"));
+    }
+
     #[test]
     fn test_formula_math_operators_on_empty_string() {
         // Empty string should coerce to zero
@@ -138,4 +274,51 @@ mod tests {
         // Test string concatenation
         assert_eq!("apple", eval_to_string(&g, "C6 & \"apple\" & D6"));
     }
+
+    #[test]
+    fn test_formula_date_arithmetic() {
+        let g = Grid::new();
+
+        assert_eq!("2024-03-02", eval_to_string(&g, "DATE(2024, 3, 1) + 1"));
+        assert_eq!("2024-03-02", eval_to_string(&g, "1 + DATE(2024, 3, 1)"));
+        assert_eq!("2024-02-29", eval_to_string(&g, "DATE(2024, 3, 1) - 1"));
+        assert_eq!(
+            "2024-03-01 12:00:00",
+            eval_to_string(&g, "DATE(2024, 3, 1) + 0.5"),
+        );
+        assert_eq!(
+            "2024-03-01 13:45:00",
+            eval_to_string(&g, "DATE(2024, 3, 1) + TIME(13, 45, 0)"),
+        );
+        assert_eq!(
+            "2024-02-29 22:00:00",
+            eval_to_string(&g, "DATE(2024, 3, 1) - TIME(2, 0, 0)"),
+        );
+        assert_eq!(
+            "366",
+            eval_to_string(&g, "DATE(2025, 1, 1) - DATE(2024, 1, 1)")
+        );
+        assert_eq!(
+            "15:00:00",
+            eval_to_string(&g, "TIME(13, 0, 0) + TIME(2, 0, 0)")
+        );
+        assert_eq!("-02:00:00", eval_to_string(&g, "-TIME(2, 0, 0)"));
+        assert_eq!("3", eval_to_string(&g, "1 + 2"));
+        assert_eq!("-1", eval_to_string(&g, "1 - 2"));
+
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "number".into(),
+                got: Some("time instant".into()),
+            },
+            eval_to_err(&g, "1 - DATE(2024, 3, 1)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "number".into(),
+                got: Some("time instant".into()),
+            },
+            eval_to_err(&g, "DATE(2024, 3, 1) + DATE(2024, 3, 1)").msg,
+        );
+    }
 }
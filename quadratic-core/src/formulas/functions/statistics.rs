@@ -1,3 +1,5 @@
+use bigdecimal::BigDecimal;
+
 use super::*;
 
 pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
@@ -13,7 +15,7 @@ fn get_functions() -> Vec<FormulaFunction> {
         formula_fn!(
             /// Returns the arithmetic mean of all values.
             #[examples("AVERAGE(A1:A6)", "AVERAGE(A1, A3, A5, B1:B6)")]
-            fn AVERAGE(span: Span, numbers: (Iter<f64>)) {
+            fn AVERAGE(span: Span, numbers: (Iter<BigDecimal>)) {
                 util::average(span, numbers)
             }
         ),
@@ -37,8 +39,37 @@ fn get_functions() -> Vec<FormulaFunction> {
                 numbers_range: (Option<Spanned<Array>>),
             ) {
                 let criteria = Criterion::try_from(*criteria)?;
+                let numbers = criteria
+                    .iter_matching_coerced::<BigDecimal>(eval_range, numbers_range.as_ref())?;
+                util::average(span, numbers)
+            }
+        ),
+        formula_fn!(
+            /// Evaluates each value in `numbers_range` based on multiple
+            /// criteria, and then computes the arithmetic mean of the ones
+            /// that meet all the criteria. Each `eval_range` must be the same
+            /// size as `numbers_range`.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "AVERAGEIFS(C1:C10, A1:A10, \">0\")",
+                "AVERAGEIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn AVERAGEIFS(
+                span: Span,
+                numbers_range: (Spanned<Array>),
+                eval_range1: (Spanned<Array>),
+                criteria1: (Spanned<CellValue>),
+                more_eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs(
+                    "AVERAGEIFS",
+                    span,
+                    eval_range1,
+                    criteria1,
+                    more_eval_ranges_and_criteria,
+                )?;
                 let numbers =
-                    criteria.iter_matching_coerced::<f64>(eval_range, numbers_range.as_ref())?;
+                    Criterion::iter_matching_all_coerced::<BigDecimal>(&criteria, &numbers_range)?;
                 util::average(span, numbers)
             }
         ),
@@ -85,6 +116,33 @@ fn get_functions() -> Vec<FormulaFunction> {
                 count as f64
             }
         ),
+        formula_fn!(
+            /// Evaluates values based on multiple criteria, and then counts
+            /// how many positions meet all the criteria. Every `eval_range`
+            /// must be the same size.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "COUNTIFS(A1:A10, \">0\")",
+                "COUNTIFS(A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn COUNTIFS(
+                span: Span,
+                eval_range1: (Spanned<Array>),
+                criteria1: (Spanned<CellValue>),
+                more_eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs(
+                    "COUNTIFS",
+                    span,
+                    eval_range1,
+                    criteria1,
+                    more_eval_ranges_and_criteria,
+                )?;
+                let range = &criteria[0].0;
+                let count = Criterion::iter_matching_all(&criteria, range)?.count();
+                count as f64
+            }
+        ),
         formula_fn!(
             /// Counts how many values in the range are empty.
             ///
@@ -103,18 +161,264 @@ fn get_functions() -> Vec<FormulaFunction> {
         ),
         formula_fn!(
             /// Returns the smallest value.
-            /// Returns +∞ if given no values.
+            /// Returns `0` if given no values.
             #[examples("MIN(A1:A6)", "MIN(0, A1:A6)")]
-            fn MIN(numbers: (Iter<f64>)) {
-                numbers.try_fold(f64::INFINITY, |a, b| Ok(f64::min(a, b?)))
+            fn MIN(numbers: (Iter<BigDecimal>)) {
+                let min = util::fold_numbers(numbers, BigDecimal::min)?;
+                min.unwrap_or_default()
+            }
+        ),
+        formula_fn!(
+            /// Evaluates each value in `numbers_range` based on multiple
+            /// criteria, and then returns the smallest of the ones that meet
+            /// all the criteria. Each `eval_range` must be the same size as
+            /// `numbers_range`.
+            ///
+            /// Returns `0` if no values meet the criteria.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples("MINIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")")]
+            fn MINIFS(
+                span: Span,
+                numbers_range: (Spanned<Array>),
+                eval_range1: (Spanned<Array>),
+                criteria1: (Spanned<CellValue>),
+                more_eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs(
+                    "MINIFS",
+                    span,
+                    eval_range1,
+                    criteria1,
+                    more_eval_ranges_and_criteria,
+                )?;
+                let numbers =
+                    Criterion::iter_matching_all_coerced::<BigDecimal>(&criteria, &numbers_range)?;
+                let min = util::fold_numbers(numbers, BigDecimal::min)?;
+                min.unwrap_or_default()
             }
         ),
         formula_fn!(
             /// Returns the largest value.
-            /// Returns -∞ if given no values.
+            /// Returns `0` if given no values.
             #[examples("MAX(A1:A6)", "MAX(0, A1:A6)")]
-            fn MAX(numbers: (Iter<f64>)) {
-                numbers.try_fold(-f64::INFINITY, |a, b| Ok(f64::max(a, b?)))
+            fn MAX(numbers: (Iter<BigDecimal>)) {
+                let max = util::fold_numbers(numbers, BigDecimal::max)?;
+                max.unwrap_or_default()
+            }
+        ),
+        formula_fn!(
+            /// Evaluates each value in `numbers_range` based on multiple
+            /// criteria, and then returns the largest of the ones that meet
+            /// all the criteria. Each `eval_range` must be the same size as
+            /// `numbers_range`.
+            ///
+            /// Returns `0` if no values meet the criteria.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples("MAXIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")")]
+            fn MAXIFS(
+                span: Span,
+                numbers_range: (Spanned<Array>),
+                eval_range1: (Spanned<Array>),
+                criteria1: (Spanned<CellValue>),
+                more_eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs(
+                    "MAXIFS",
+                    span,
+                    eval_range1,
+                    criteria1,
+                    more_eval_ranges_and_criteria,
+                )?;
+                let numbers =
+                    Criterion::iter_matching_all_coerced::<BigDecimal>(&criteria, &numbers_range)?;
+                let max = util::fold_numbers(numbers, BigDecimal::max)?;
+                max.unwrap_or_default()
+            }
+        ),
+        formula_fn!(
+            /// Returns the median of all values, which is the middle value
+            /// when they are sorted. If there is an even number of values,
+            /// returns the arithmetic mean of the two middle values.
+            #[examples("MEDIAN(A1:A6)", "MEDIAN(A1, A3, A5, B1:B6)")]
+            fn MEDIAN(span: Span, numbers: (Iter<f64>)) {
+                let numbers = util::sorted_numbers(numbers)?;
+                util::percentile(span, &numbers, 0.5)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the value that appears most often. If several values
+            /// appear equally often, returns the one that appears first.
+            ///
+            /// Returns a "no match" error (`#N/A`) if no value appears more
+            /// than once.
+            #[examples("MODE(A1:A6)", "MODE(A1, A3, A5, B1:B6)")]
+            fn MODE(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                let mut counts = HashMap::<u64, usize>::new();
+                for &n in &numbers {
+                    // Treat `-0.0` the same as `0.0`.
+                    *counts.entry((n + 0.0).to_bits()).or_default() += 1;
+                }
+                let max_count = counts.values().copied().max().unwrap_or(0);
+                numbers
+                    .into_iter()
+                    .find(|&n| max_count > 1 && counts[&(n + 0.0).to_bits()] == max_count)
+                    .ok_or(RunErrorMsg::NoMatch.with_span(span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the variance of all values, treating them as a sample
+            /// of a larger population.
+            ///
+            /// Returns a divide-by-zero error if given fewer than two values.
+            #[name("VAR.S")]
+            #[examples("VAR.S(A1:A6)", "VAR.S(A1, A3, A5, B1:B6)")]
+            fn VAR_S(span: Span, numbers: (Iter<f64>)) {
+                util::variance(span, numbers, true)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the variance of all values, treating them as the whole
+            /// population.
+            ///
+            /// Returns a divide-by-zero error if given no values.
+            #[name("VAR.P")]
+            #[examples("VAR.P(A1:A6)", "VAR.P(A1, A3, A5, B1:B6)")]
+            fn VAR_P(span: Span, numbers: (Iter<f64>)) {
+                util::variance(span, numbers, false)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the standard deviation of all values, treating them as
+            /// a sample of a larger population.
+            ///
+            /// Returns a divide-by-zero error if given fewer than two values.
+            #[name("STDEV.S")]
+            #[examples("STDEV.S(A1:A6)", "STDEV.S(A1, A3, A5, B1:B6)")]
+            fn STDEV_S(span: Span, numbers: (Iter<f64>)) {
+                util::variance(span, numbers, true)?.sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns the standard deviation of all values, treating them as
+            /// the whole population.
+            ///
+            /// Returns a divide-by-zero error if given no values.
+            #[name("STDEV.P")]
+            #[examples("STDEV.P(A1:A6)", "STDEV.P(A1, A3, A5, B1:B6)")]
+            fn STDEV_P(span: Span, numbers: (Iter<f64>)) {
+                util::variance(span, numbers, false)?.sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns the `k`th percentile of the values in `array`, where `k`
+            /// is between `0` and `1` inclusive. Interpolates between values
+            /// when `k` falls between them.
+            #[examples("PERCENTILE(A1:A10, 0.9)", "PERCENTILE(A1:A10, 0.25)")]
+            #[zip_map]
+            fn PERCENTILE(span: Span, array: (Spanned<Value>), [k]: (Spanned<f64>)) {
+                let numbers = util::sorted_numbers(array.clone().into_iter().without_spans())?;
+                if !(0.0..=1.0).contains(&k.inner) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(k.span));
+                }
+                util::percentile(span, &numbers, k.inner)?
+            }
+        ),
+        formula_fn!(
+            /// Returns a quartile of the values in `array`, where `quart` is
+            /// one of the following:
+            ///
+            /// | `quart` | Result                      |
+            /// | ------- | --------------------------- |
+            /// | `0`     | smallest value              |
+            /// | `1`     | 25th percentile             |
+            /// | `2`     | median (50th percentile)    |
+            /// | `3`     | 75th percentile             |
+            /// | `4`     | largest value               |
+            #[examples("QUARTILE(A1:A10, 1)", "QUARTILE(A1:A10, 3)")]
+            #[zip_map]
+            fn QUARTILE(span: Span, array: (Spanned<Value>), [quart]: (Spanned<i64>)) {
+                let numbers = util::sorted_numbers(array.clone().into_iter().without_spans())?;
+                if !(0..=4).contains(&quart.inner) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(quart.span));
+                }
+                util::percentile(span, &numbers, quart.inner as f64 / 4.0)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the rank of `number` among the values in `array`. The
+            /// largest value has rank `1` unless `ascending` is true, in which
+            /// case the smallest value has rank `1`. Equal values have the same
+            /// rank.
+            ///
+            /// Returns a "no match" error (`#N/A`) if `number` does not appear
+            /// in `array`.
+            #[examples("RANK(A1, A1:A10)", "RANK(A1, A1:A10, TRUE)")]
+            #[zip_map]
+            fn RANK(span: Span, [number]: f64, array: (Spanned<Value>), ascending: (Option<bool>)) {
+                let numbers = util::sorted_numbers(array.clone().into_iter().without_spans())?;
+                if !numbers.contains(&number) {
+                    return Err(RunErrorMsg::NoMatch.with_span(span));
+                }
+                let higher_ranked = match ascending.unwrap_or(false) {
+                    false => numbers.iter().filter(|&&n| n > number).count(),
+                    true => numbers.iter().filter(|&&n| n < number).count(),
+                };
+                (higher_ranked + 1) as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the `k`th largest value in `array`.
+            #[examples("LARGE(A1:A10, 2)")]
+            #[zip_map]
+            fn LARGE(array: (Spanned<Value>), [k]: (Spanned<i64>)) {
+                let mut numbers = util::sorted_numbers(array.clone().into_iter().without_spans())?;
+                numbers.reverse();
+                util::kth_number(&numbers, k)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the `k`th smallest value in `array`.
+            #[examples("SMALL(A1:A10, 2)")]
+            #[zip_map]
+            fn SMALL(array: (Spanned<Value>), [k]: (Spanned<i64>)) {
+                let numbers = util::sorted_numbers(array.clone().into_iter().without_spans())?;
+                util::kth_number(&numbers, k)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the Pearson correlation coefficient of two arrays of
+            /// the same size. Only positions where both arrays contain a number
+            /// are used.
+            #[examples("CORREL(A1:A10, B1:B10)")]
+            fn CORREL(span: Span, array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                let pairs = util::number_pairs(&array1, &array2)?;
+                let covariance = util::covariance(span, &pairs, false)?;
+                let variance1 = util::variance(span, pairs.iter().map(|&(x, _)| Ok(x)), false)?;
+                let variance2 = util::variance(span, pairs.iter().map(|&(_, y)| Ok(y)), false)?;
+                util::checked_div(span, covariance, (variance1 * variance2).sqrt())?
+            }
+        ),
+        formula_fn!(
+            /// Returns the covariance of two arrays of the same size, treating
+            /// them as the whole population. Only positions where both arrays
+            /// contain a number are used.
+            #[name("COVARIANCE.P")]
+            #[examples("COVARIANCE.P(A1:A10, B1:B10)")]
+            fn COVARIANCE_P(span: Span, array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                let pairs = util::number_pairs(&array1, &array2)?;
+                util::covariance(span, &pairs, false)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the covariance of two arrays of the same size, treating
+            /// them as a sample of a larger population. Only positions where
+            /// both arrays contain a number are used.
+            #[name("COVARIANCE.S")]
+            #[examples("COVARIANCE.S(A1:A10, B1:B10)")]
+            fn COVARIANCE_S(span: Span, array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                let pairs = util::number_pairs(&array1, &array2)?;
+                util::covariance(span, &pairs, true)?
             }
         ),
     ]
@@ -124,6 +428,10 @@ fn get_functions() -> Vec<FormulaFunction> {
 mod tests {
     use crate::{formulas::tests::*, Pos};
 
+    fn eval_to_f64(g: &Grid, s: &str) -> f64 {
+        eval_to_string(g, s).parse().unwrap()
+    }
+
     #[test]
     fn test_formula_average() {
         let form = parse_formula("AVERAGE(3, B1:D3)", pos![nAn1]).unwrap();
@@ -205,6 +513,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_averageifs() {
+        let g = Grid::new();
+        assert_eq!(
+            "3.5",
+            eval_to_string(&g, "AVERAGEIFS(0..10, 0..10, \"<=5\", 0..10, \">1\")"),
+        );
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "AVERAGEIFS(0..10, 0..10, \">20\")").msg,
+        );
+    }
+
     #[test]
     fn test_count() {
         let g = Grid::new();
@@ -253,6 +574,37 @@ mod tests {
         assert_eq!("6", eval_to_string(&g, "COUNTIF(Bn5:B10, \"<=5\")"));
     }
 
+    #[test]
+    fn test_countifs() {
+        let g = Grid::new();
+        assert_eq!("6", eval_to_string(&g, "COUNTIFS(0..10, \"<=5\")"));
+        assert_eq!(
+            "4",
+            eval_to_string(&g, "COUNTIFS(0..10, \"<=5\", 10..20, \">11\")"),
+        );
+        assert_eq!(
+            "2",
+            eval_to_string(
+                &g,
+                "COUNTIFS({\"a\", \"b\", \"a\", \"a\"}, \"a\", {1, 2, 3, 4}, \"<>3\")",
+            ),
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 11).unwrap(),
+                got: ArraySize::new(1, 10).unwrap(),
+            },
+            eval_to_err(&g, "COUNTIFS(0..10, \"<=5\", 1..10, \">1\")").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::MissingRequiredArgument {
+                func_name: "COUNTIFS".into(),
+                arg_name: "criteria".into(),
+            },
+            eval_to_err(&g, "COUNTIFS(0..10, \"<=5\", 0..10)").msg,
+        );
+    }
+
     #[test]
     fn test_countblank() {
         let g = Grid::new();
@@ -277,4 +629,134 @@ mod tests {
         let g = Grid::new();
         assert_eq!("3", eval_to_string(&g, "MAX(1, 3, 2)"));
     }
+
+    #[test]
+    fn test_minifs_maxifs() {
+        let g = Grid::new();
+        let ranges = "{5, -2, 9, 4}, {\"a\", \"b\", \"a\", \"a\"}, \"a\"";
+        assert_eq!("4", eval_to_string(&g, &format!("MINIFS({ranges})")));
+        assert_eq!("9", eval_to_string(&g, &format!("MAXIFS({ranges})")));
+        assert_eq!(
+            "5",
+            eval_to_string(&g, &format!("MAXIFS({ranges}, {{1, 2, 3, 4}}, \"<3\")")),
+        );
+        assert_eq!(
+            "0",
+            eval_to_string(&g, &format!("MINIFS({ranges}, {{1, 2, 3, 4}}, 0)"))
+        );
+    }
+
+    #[test]
+    fn test_median_mode() {
+        let g = Grid::new();
+        assert_eq!("3", eval_to_string(&g, "MEDIAN(5, 1, 3)"));
+        assert_eq!("2.5", eval_to_string(&g, "MEDIAN({4, 1; 2, 3})"));
+        assert_eq!("2", eval_to_string(&g, "MEDIAN(2)"));
+        assert_eq!(RunErrorMsg::EmptyArray, eval_to_err(&g, "MEDIAN()").msg);
+
+        assert_eq!("3", eval_to_string(&g, "MODE(1, 3, 2, 3, 1, 3)"));
+        assert_eq!("2", eval_to_string(&g, "MODE({2, 1, 1, 2})"));
+        assert_eq!(RunErrorMsg::NoMatch, eval_to_err(&g, "MODE(1, 2, 3)").msg);
+    }
+
+    #[test]
+    fn test_variance_stdev() {
+        let g = Grid::new();
+        let data = "{2, 4, 4, 4, 5, 5, 7, 9}";
+        assert_eq!("4", eval_to_string(&g, &format!("VAR.P({data})")));
+        assert_eq!("2", eval_to_string(&g, &format!("STDEV.P({data})")));
+        assert_eq!(
+            4.571428571428571,
+            eval_to_f64(&g, &format!("VAR.S({data})")),
+        );
+        assert_eq!(
+            2.138089935299395,
+            eval_to_f64(&g, &format!("STDEV.S({data})")),
+        );
+        assert_eq!("0", eval_to_string(&g, "VAR.P(3)"));
+        assert_eq!(RunErrorMsg::DivideByZero, eval_to_err(&g, "VAR.S(3)").msg);
+        assert_eq!(RunErrorMsg::DivideByZero, eval_to_err(&g, "STDEV.P()").msg);
+    }
+
+    #[test]
+    fn test_percentile_quartile() {
+        let g = Grid::new();
+        assert_eq!(1.9, eval_to_f64(&g, "PERCENTILE(1..4, 0.3)"));
+        assert_eq!("{1, 4}", eval_to_string(&g, "PERCENTILE(1..4, {0, 1})"));
+        assert_eq!(
+            "{1, 1.75, 2.5, 3.25, 4}",
+            eval_to_string(&g, "QUARTILE({4; 2; 3; 1}, {0, 1, 2, 3, 4})"),
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "PERCENTILE(1..4, 1.5)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "QUARTILE(1..4, 5)").msg,
+        );
+    }
+
+    #[test]
+    fn test_rank_large_small() {
+        let g = Grid::new();
+        let data = "{7, 3.5, 3.5, 1, 2}";
+        assert_eq!("1", eval_to_string(&g, &format!("RANK(7, {data})")));
+        assert_eq!("2", eval_to_string(&g, &format!("RANK(3.5, {data})")));
+        assert_eq!("4", eval_to_string(&g, &format!("RANK(2, {data})")));
+        assert_eq!("3", eval_to_string(&g, &format!("RANK(3.5, {data}, TRUE)")));
+        assert_eq!(
+            RunErrorMsg::NoMatch,
+            eval_to_err(&g, &format!("RANK(4, {data})")).msg,
+        );
+
+        assert_eq!("7", eval_to_string(&g, &format!("LARGE({data}, 1)")));
+        assert_eq!("3.5", eval_to_string(&g, &format!("LARGE({data}, 3)")));
+        assert_eq!(
+            "{1, 2}",
+            eval_to_string(&g, &format!("SMALL({data}, {{1, 2}})"))
+        );
+        assert_eq!(
+            RunErrorMsg::IndexOutOfBounds,
+            eval_to_err(&g, &format!("SMALL({data}, 6)")).msg,
+        );
+        assert_eq!(
+            RunErrorMsg::IndexOutOfBounds,
+            eval_to_err(&g, &format!("LARGE({data}, 0)")).msg,
+        );
+    }
+
+    #[test]
+    fn test_correl_covariance() {
+        let g = Grid::new();
+        assert_eq!("1", eval_to_string(&g, "CORREL(1..5, 2*(1..5))"));
+        assert_eq!(
+            0.9970544855015816,
+            eval_to_f64(&g, "CORREL({3, 2, 4, 5, 6}, {9, 7, 12, 15, 17})"),
+        );
+        assert_eq!(
+            5.2,
+            eval_to_f64(&g, "COVARIANCE.P({3, 2, 4, 5, 6}, {9, 7, 12, 15, 17})"),
+        );
+        assert_eq!(
+            6.5,
+            eval_to_f64(&g, "COVARIANCE.S({3, 2, 4, 5, 6}, {9, 7, 12, 15, 17})"),
+        );
+        // Pairs with a non-number are ignored.
+        assert_eq!(
+            "2",
+            eval_to_string(&g, "COVARIANCE.S({1, \"x\", 3}, {1, 100, 3})"),
+        );
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "CORREL({1, 1, 1}, {1, 2, 3})").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(3, 1).unwrap(),
+                got: ArraySize::new(2, 1).unwrap(),
+            },
+            eval_to_err(&g, "CORREL({1, 2, 3}, {1, 2})").msg,
+        );
+    }
 }
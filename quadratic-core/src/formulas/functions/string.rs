@@ -1,27 +1,887 @@
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, RoundingMode, Signed, Zero};
+use regex::{Regex, RegexBuilder};
+use smallvec::smallvec;
+
 use super::*;
+use crate::formulas::wildcard_search_regex;
+use crate::ArraySize;
 
 pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
     include_in_docs: true,
     include_in_completions: true,
     name: "String functions",
-    docs: "",
+    docs: "Positions and lengths in these functions count characters, \
+           and the first character in a string is at position `1`.",
     get_functions,
 };
 
+/// Maximum number of characters in a string produced by `REPT`.
+const MAX_REPT_LEN: usize = 32767;
+
 fn get_functions() -> Vec<FormulaFunction> {
-    vec![formula_fn!(
-        /// [Concatenates](https://en.wikipedia.org/wiki/Concatenation) all
-        /// values as strings.
-        #[examples("CONCAT(\"Hello, \", C0, \"!\")")]
-        fn CONCAT(strings: (Iter<String>)) {
-            strings.try_fold(String::new(), |a, b| Ok(a + &b?))
+    vec![
+        formula_fn!(
+            /// [Concatenates](https://en.wikipedia.org/wiki/Concatenation) all
+            /// values as strings.
+            #[examples("CONCAT(\"Hello, \", C0, \"!\")")]
+            fn CONCAT(strings: (Iter<String>)) {
+                strings.try_fold(String::new(), |a, b| Ok(a + &b?))
+            }
+        ),
+        formula_fn!(
+            /// Concatenates all values as strings, with `delimiter` between
+            /// each one. If `ignore_empty` is true, then blank cells and empty
+            /// strings are skipped.
+            #[examples(
+                "TEXTJOIN(\", \", TRUE, A1:A10)",
+                "TEXTJOIN(\"-\", FALSE, \"a\", \"\", \"b\")"
+            )]
+            fn TEXTJOIN(delimiter: String, ignore_empty: bool, strings: (Iter<String>)) {
+                let strings = strings.collect::<CodeResult<Vec<String>>>()?;
+                strings
+                    .iter()
+                    .filter(|s| !(ignore_empty && s.is_empty()))
+                    .join(&delimiter)
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of characters in a string.
+            #[examples("LEN(\"abc\")", "LEN(A1)")]
+            #[zip_map]
+            fn LEN([s]: String) {
+                s.chars().count() as i64
+            }
+        ),
+        formula_fn!(
+            /// Returns the first `char_count` characters of a string, or the
+            /// first character if `char_count` is omitted.
+            #[examples("LEFT(\"Hello, world!\", 5)", "LEFT(A1)")]
+            #[zip_map]
+            fn LEFT([s]: String, [char_count]: (Option<i64>)) {
+                let char_count = non_negative_count(char_count.unwrap_or(1))?;
+                CodeResult::Ok(s.chars().take(char_count).collect::<String>())
+            }
+        ),
+        formula_fn!(
+            /// Returns the last `char_count` characters of a string, or the
+            /// last character if `char_count` is omitted.
+            #[examples("RIGHT(\"Hello, world!\", 6)", "RIGHT(A1)")]
+            #[zip_map]
+            fn RIGHT([s]: String, [char_count]: (Option<i64>)) {
+                let char_count = non_negative_count(char_count.unwrap_or(1))?;
+                let skip = s.chars().count().saturating_sub(char_count);
+                CodeResult::Ok(s.chars().skip(skip).collect::<String>())
+            }
+        ),
+        formula_fn!(
+            /// Returns `char_count` characters of a string, beginning at
+            /// position `start_char`.
+            #[examples("MID(\"Hello, world!\", 8, 5)")]
+            #[zip_map]
+            fn MID([s]: String, [start_char]: i64, [char_count]: i64) {
+                let start = start_index(start_char)?;
+                let char_count = non_negative_count(char_count)?;
+                CodeResult::Ok(s.chars().skip(start).take(char_count).collect::<String>())
+            }
+        ),
+        formula_fn!(
+            /// Converts a string to uppercase.
+            #[examples("UPPER(\"Hello, world!\")")]
+            #[zip_map]
+            fn UPPER([s]: String) {
+                s.to_uppercase()
+            }
+        ),
+        formula_fn!(
+            /// Converts a string to lowercase.
+            #[examples("LOWER(\"Hello, world!\")")]
+            #[zip_map]
+            fn LOWER([s]: String) {
+                s.to_lowercase()
+            }
+        ),
+        formula_fn!(
+            /// Capitalizes the first letter of each word in a string and
+            /// converts all other letters to lowercase. A word begins with
+            /// any letter that does not follow another letter.
+            #[examples("PROPER(\"hello, WORLD!\")")]
+            #[zip_map]
+            fn PROPER([s]: String) {
+                let mut prev_is_letter = false;
+                s.chars()
+                    .flat_map(|c| {
+                        let is_first_letter = !prev_is_letter;
+                        prev_is_letter = c.is_alphabetic();
+                        if is_first_letter {
+                            c.to_uppercase().collect_vec()
+                        } else {
+                            c.to_lowercase().collect_vec()
+                        }
+                    })
+                    .collect::<String>()
+            }
+        ),
+        formula_fn!(
+            /// Removes spaces from the beginning and end of a string, and
+            /// replaces each run of spaces within the string with a single
+            /// space.
+            #[examples("TRIM(\"   Hello,    world!  \")")]
+            #[zip_map]
+            fn TRIM([s]: String) {
+                s.split(' ').filter(|word| !word.is_empty()).join(" ")
+            }
+        ),
+        formula_fn!(
+            /// Replaces occurrences of `search_for` in a string with
+            /// `replace_with`. If `occurrence` is given, then only that
+            /// occurrence (counting from `1`) is replaced; otherwise all
+            /// occurrences are replaced. The search is case-sensitive.
+            #[examples(
+                "SUBSTITUTE(\"a-b-c\", \"-\", \"+\")",
+                "SUBSTITUTE(\"a-b-c\", \"-\", \"+\", 2)"
+            )]
+            #[zip_map]
+            fn SUBSTITUTE(
+                [s]: String,
+                [search_for]: String,
+                [replace_with]: String,
+                [occurrence]: (Option<i64>),
+            ) {
+                match occurrence {
+                    _ if search_for.is_empty() => CodeResult::Ok(s),
+                    None => Ok(s.replace(&search_for, &replace_with)),
+                    Some(n) if n < 1 => Err(RunErrorMsg::InvalidArgument)?,
+                    Some(n) => Ok(match s.match_indices(&search_for).nth(n as usize - 1) {
+                        Some((i, _)) => {
+                            let end = i + search_for.len();
+                            format!("{}{}{}", &s[..i], replace_with, &s[end..])
+                        }
+                        None => s,
+                    }),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Replaces `char_count` characters of a string, beginning at
+            /// position `start_char`, with `replace_with`.
+            #[examples("REPLACE(\"Hello, world!\", 8, 5, \"there\")")]
+            #[zip_map]
+            fn REPLACE([s]: String, [start_char]: i64, [char_count]: i64, [replace_with]: String) {
+                let start = start_index(start_char)?;
+                let char_count = non_negative_count(char_count)?;
+                let before = s.chars().take(start);
+                let after = s.chars().skip(start.saturating_add(char_count));
+                CodeResult::Ok(
+                    before
+                        .chain(replace_with.chars())
+                        .chain(after)
+                        .collect::<String>(),
+                )
+            }
+        ),
+        formula_fn!(
+            /// Returns the position of the first occurrence of `search_for`
+            /// in a string, starting the search at position `start_char`
+            /// (which defaults to `1`). The search is case-sensitive and does
+            /// not support wildcards; see `SEARCH` for a case-insensitive
+            /// search.
+            ///
+            /// Returns an error if there is no match.
+            #[examples("FIND(\"o\", \"Hello, world!\")", "FIND(\"o\", \"Hello, world!\", 6)")]
+            #[zip_map]
+            fn FIND([search_for]: String, [s]: String, [start_char]: (Option<i64>)) {
+                let (start, rest) = substring_from(&s, start_char)?;
+                let i = rest.find(&search_for).ok_or(RunErrorMsg::NoMatch)?;
+                CodeResult::Ok((start + rest[..i].chars().count() + 1) as i64)
+            }
+        ),
+        formula_fn!(
+            /// Returns the position of the first occurrence of `search_for`
+            /// in a string, starting the search at position `start_char`
+            /// (which defaults to `1`). The search is case-insensitive and
+            /// `search_for` may contain wildcards.
+            #[doc = see_docs_for_more_about_wildcards!()]
+            ///
+            /// Returns an error if there is no match.
+            #[examples(
+                "SEARCH(\"WORLD\", \"Hello, world!\")",
+                "SEARCH(\"w?r\", \"Hello, world!\")"
+            )]
+            #[zip_map]
+            fn SEARCH([search_for]: String, [s]: String, [start_char]: (Option<i64>)) {
+                let (start, rest) = substring_from(&s, start_char)?;
+                let regex = wildcard_search_regex(&search_for)?;
+                let i = regex.find(rest).ok_or(RunErrorMsg::NoMatch)?.start();
+                CodeResult::Ok((start + rest[..i].chars().count() + 1) as i64)
+            }
+        ),
+        formula_fn!(
+            /// Returns whether a [regular
+            /// expression](https://docs.rs/regex/latest/regex/#syntax)
+            /// matches any part of a string.
+            ///
+            /// Use `^` and `$` to match the whole string. Matching is
+            /// case-sensitive unless `regular_expression` begins with `(?i)`.
+            ///
+            /// Backslashes in string literals must be escaped, so the pattern
+            /// `\d` is written as `"\\d"`.
+            #[examples(
+                "REGEXMATCH(A1, \"^[0-9]{3}-[0-9]{4}$\")",
+                "REGEXMATCH(A1, \"(?i)^quadratic\")"
+            )]
+            #[zip_map]
+            fn REGEXMATCH(ctx: Ctx, [s]: String, [regular_expression]: (Spanned<String>)) {
+                let regex = ctx.get_regex(&regular_expression.inner, regular_expression.span)?;
+                regex.is_match(&s)
+            }
+        ),
+        formula_fn!(
+            /// Returns the first part of a string that matches a [regular
+            /// expression](https://docs.rs/regex/latest/regex/#syntax).
+            ///
+            /// If `regular_expression` contains capture groups, then the text
+            /// matched by each group is returned instead, as a row. Groups
+            /// that do not participate in the match are blank.
+            ///
+            /// If `s` is an array, then a match is extracted from each
+            /// string. If there are several capture groups, `s` must be a
+            /// single column, and each string gives a row of the result.
+            ///
+            /// Returns an error if there is no match.
+            #[examples(
+                "REGEXEXTRACT(\"Order #1234\", \"[0-9]+\")",
+                "REGEXEXTRACT(A1:A100, \"([a-z]+)@([a-z.]+)\")"
+            )]
+            fn REGEXEXTRACT(ctx: Ctx, s: (Spanned<Array>), regular_expression: (Spanned<String>)) {
+                let regex = ctx.get_regex(&regular_expression.inner, regular_expression.span)?;
+                let group_count = regex.captures_len() - 1;
+                let size = s.inner.size();
+
+                if size.len() == 1 {
+                    let row = regex_extract(&regex, s.inner.get(0, 0)?, s.span)?;
+                    Array::from(vec![row])
+                } else {
+                    let out_width = if group_count > 1 {
+                        s.check_array_size_on(Axis::X, 1)?;
+                        group_count as u32
+                    } else {
+                        size.w.get()
+                    };
+                    let mut values = smallvec![];
+                    for value in s.inner.cell_values_slice() {
+                        match regex_extract(&regex, value, s.span) {
+                            Ok(row) => values.extend(row),
+                            Err(e) => values.extend(std::iter::repeat_n(
+                                CellValue::Error(Box::new(e)),
+                                group_count.max(1),
+                            )),
+                        }
+                    }
+                    let out_size = ArraySize::new_or_err(out_width, size.h.get())?;
+                    Array::new_row_major(out_size, values)?
+                }
+            }
+        ),
+        formula_fn!(
+            /// Replaces each part of a string that matches a [regular
+            /// expression](https://docs.rs/regex/latest/regex/#syntax) with
+            /// `replacement`.
+            ///
+            /// `replacement` may refer to capture groups using `$1`, `$2`,
+            /// etc., or by name using `${name}`. Use `$$` for a literal `$`.
+            #[examples(
+                "REGEXREPLACE(\"Hello, world!\", \"[aeiou]\", \"*\")",
+                "REGEXREPLACE(A1, \"([A-Za-z]+), ([A-Za-z]+)\", \"$2 $1\")"
+            )]
+            #[zip_map]
+            fn REGEXREPLACE(
+                ctx: Ctx,
+                [s]: String,
+                [regular_expression]: (Spanned<String>),
+                [replacement]: String,
+            ) {
+                let regex = ctx.get_regex(&regular_expression.inner, regular_expression.span)?;
+                regex.replace_all(&s, replacement.as_str()).into_owned()
+            }
+        ),
+        formula_fn!(
+            /// Splits a string into a row of values, breaking it at each
+            /// occurrence of `delimiter`.
+            ///
+            /// If `split_by_each` is true (the default), then each character
+            /// of `delimiter` is treated as a separate delimiter. If
+            /// `remove_empty` is true (the default), then empty values are
+            /// removed from the result.
+            #[examples("SPLIT(\"a,b;c\", \",;\")", "SPLIT(\"a, b, c\", \", \", FALSE)")]
+            fn SPLIT(
+                s: String,
+                delimiter: String,
+                split_by_each: (Option<bool>),
+                remove_empty: (Option<bool>),
+            ) {
+                let delimiters = if split_by_each.unwrap_or(true) {
+                    delimiter.chars().map(String::from).collect()
+                } else {
+                    vec![delimiter]
+                };
+                let row = split_text(&s, &delimiters, remove_empty.unwrap_or(true), false)?;
+                if row.is_empty() {
+                    return Err(RunErrorMsg::EmptyArray.without_span());
+                }
+                Array::from(vec![row.into_iter().map(CellValue::from).collect_vec()])
+            }
+        ),
+        formula_fn!(
+            /// Splits a string into an array, breaking it into columns at each
+            /// occurrence of `column_delimiter` and into rows at each
+            /// occurrence of `row_delimiter`. Each delimiter may be an array of
+            /// several delimiters.
+            ///
+            /// If `ignore_empty` is true, then empty values are removed from
+            /// the result. If `match_mode` is `1`, then delimiters are matched
+            /// case-insensitively. Rows shorter than the longest row are padded
+            /// with `pad_with`, which defaults to a "no match" error.
+            #[examples(
+                "TEXTSPLIT(\"a,b,c\", \",\")",
+                "TEXTSPLIT(\"a=1;b=2\", \"=\", \";\")",
+                "TEXTSPLIT(\"a b,c\", {\" \", \",\"}, , TRUE)"
+            )]
+            fn TEXTSPLIT(
+                s: String,
+                column_delimiter: (Spanned<Array>),
+                row_delimiter: (Option<Spanned<Array>>),
+                ignore_empty: (Option<bool>),
+                match_mode: (Option<Spanned<i64>>),
+                pad_with: (Option<CellValue>),
+            ) {
+                let ignore_empty = ignore_empty.unwrap_or(false);
+                let case_insensitive = match match_mode {
+                    None => false,
+                    Some(Spanned { inner: 0, .. }) => false,
+                    Some(Spanned { inner: 1, .. }) => true,
+                    Some(Spanned { span, .. }) => {
+                        return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                    }
+                };
+                let pad_with = pad_with.unwrap_or(CellValue::Error(Box::new(
+                    RunErrorMsg::NoMatch.without_span(),
+                )));
+
+                let column_delimiters = delimiter_list(&column_delimiter.inner)?;
+                let rows = match &row_delimiter {
+                    Some(row_delimiter) => {
+                        let row_delimiters = delimiter_list(&row_delimiter.inner)?;
+                        split_text(&s, &row_delimiters, ignore_empty, case_insensitive)?
+                    }
+                    None => vec![s],
+                };
+                let rows = rows
+                    .iter()
+                    .map(|row| split_text(row, &column_delimiters, ignore_empty, case_insensitive))
+                    .filter_ok(|row| !row.is_empty())
+                    .collect::<CodeResult<Vec<_>>>()?;
+                let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+                if width == 0 {
+                    return Err(RunErrorMsg::EmptyArray.without_span());
+                }
+                Array::from(
+                    rows.into_iter()
+                        .map(|row| {
+                            let padding = width - row.len();
+                            row.into_iter()
+                                .map(CellValue::from)
+                                .chain(std::iter::repeat_n(pad_with.clone(), padding))
+                                .collect_vec()
+                        })
+                        .collect_vec(),
+                )
+            }
+        ),
+        formula_fn!(
+            /// Repeats a string `count` times.
+            #[examples("REPT(\"ab\", 3)")]
+            #[zip_map]
+            fn REPT([s]: String, [count]: i64) {
+                let count = non_negative_count(count)?;
+                if s.chars().count().saturating_mul(count) > MAX_REPT_LEN {
+                    return Err(RunErrorMsg::InvalidArgument.without_span());
+                }
+                CodeResult::Ok(s.repeat(count))
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if two strings are exactly the same, including
+            /// case, and `FALSE` otherwise.
+            #[examples("EXACT(\"abc\", \"ABC\")", "EXACT(A1, B1)")]
+            #[zip_map]
+            fn EXACT([s1]: String, [s2]: String) {
+                s1 == s2
+            }
+        ),
+        formula_fn!(
+            /// Converts a string to a number. The string may contain a
+            /// currency symbol, commas, or a trailing percent sign.
+            #[examples("VALUE(\"$1,234.50\")", "VALUE(\"15%\")")]
+            #[zip_map]
+            fn VALUE([s]: CellValue) {
+                let n = match s {
+                    CellValue::Blank => BigDecimal::zero(),
+                    CellValue::Number(n) => n.clone(),
+                    CellValue::Text(text) => {
+                        parse_number(text).ok_or_else(|| RunErrorMsg::Expected {
+                            expected: "number".into(),
+                            got: Some("text".into()),
+                        })?
+                    }
+                    other => {
+                        return Err(RunErrorMsg::Expected {
+                            expected: "number".into(),
+                            got: Some(other.type_name().into()),
+                        }
+                        .without_span());
+                    }
+                };
+                CodeResult::Ok(CellValue::Number(n.normalized()))
+            }
+        ),
+        formula_fn!(
+            /// Formats a number as a string using a number format, such as
+            /// `"0.00"`, `"#,##0"`, `"0%"`, or `"0.00E+00"`.
+            ///
+            /// In the format, `0` is a digit that is always shown, `#` is a
+            /// digit that is only shown if needed, and `?` is a digit that is
+            /// replaced by a space if not needed. A comma between digits adds
+            /// thousands separators, and each comma after the last digit
+            /// divides the number by 1,000. Text in double quotes or following
+            /// a backslash is included as-is.
+            ///
+            /// The format may contain up to three sections separated by
+            /// semicolons, which are used for positive numbers, negative
+            /// numbers, and zero respectively. Values that are not numbers are
+            /// returned unchanged.
+            ///
+            /// Dates, times, and durations are formatted using a date or time
+            /// format instead, such as `"yyyy-mm-dd"` or `"[h]:mm:ss"`.
+            #[examples(
+                "TEXT(1234.567, \"#,##0.00\")",
+                "TEXT(0.256, \"0.0%\")",
+                "TEXT(A1, \"$0.00;($0.00)\")",
+                "TEXT(DATE(2024, 3, 1), \"mmm d, yyyy\")"
+            )]
+            #[zip_map]
+            fn TEXT([value]: CellValue, [format]: String) {
+                let formatted = match value {
+                    CellValue::Instant(i) if crate::is_time_pattern(&format) => i.format(&format),
+                    CellValue::Duration(d) if crate::is_time_pattern(&format) => d.format(&format),
+                    _ => None,
+                };
+                if let Some(formatted) = formatted {
+                    return Ok(formatted.into());
+                }
+                let number = match value {
+                    CellValue::Number(n) => Some(n.clone()),
+                    CellValue::Text(_) => None,
+                    other => Some(BigDecimal::try_from(f64::try_from(other)?).map_err(|_| {
+                        RunErrorMsg::Expected {
+                            expected: "number".into(),
+                            got: Some(other.type_name().into()),
+                        }
+                    })?),
+                };
+                match number {
+                    Some(n) => CodeResult::Ok(format_number(&n, &format)),
+                    None => Ok(String::try_from(value)?),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the character with the given Unicode code point.
+            #[examples("CHAR(65)", "CHAR(9731)")]
+            #[zip_map]
+            fn CHAR([code_point]: i64) {
+                let c = u32::try_from(code_point)
+                    .ok()
+                    .filter(|&c| c != 0)
+                    .and_then(char::from_u32)
+                    .ok_or(RunErrorMsg::InvalidArgument)?;
+                CodeResult::Ok(c.to_string())
+            }
+        ),
+        formula_fn!(
+            /// Returns the Unicode code point of the first character in a
+            /// string.
+            #[examples("CODE(\"A\")")]
+            #[zip_map]
+            fn CODE([s]: String) {
+                CodeResult::Ok(first_code_point(&s)?)
+            }
+        ),
+        formula_fn!(
+            /// Returns the Unicode code point of the first character in a
+            /// string. This is the same as `CODE`.
+            #[examples("UNICODE(\"☃\")")]
+            #[zip_map]
+            fn UNICODE([s]: String) {
+                CodeResult::Ok(first_code_point(&s)?)
+            }
+        ),
+    ]
+}
+
+/// Checks that a character count is not negative.
+fn non_negative_count(n: i64) -> Result<usize, RunErrorMsg> {
+    usize::try_from(n).map_err(|_| RunErrorMsg::InvalidArgument)
+}
+
+/// Converts a 1-based character position to a 0-based index.
+fn start_index(start_char: i64) -> Result<usize, RunErrorMsg> {
+    match start_char {
+        ..=0 => Err(RunErrorMsg::InvalidArgument),
+        _ => non_negative_count(start_char - 1),
+    }
+}
+
+/// Returns the 0-based index of the character at the 1-based position
+/// `start_char` (default `1`), along with the rest of the string beginning at
+/// that character.
+fn substring_from(s: &str, start_char: Option<i64>) -> Result<(usize, &str), RunErrorMsg> {
+    let start = start_index(start_char.unwrap_or(1))?;
+    if start == 0 {
+        return Ok((0, s));
+    }
+    match s.char_indices().nth(start) {
+        Some((i, _)) => Ok((start, &s[i..])),
+        None if start == s.chars().count() => Ok((start, "")),
+        None => Err(RunErrorMsg::InvalidArgument),
+    }
+}
+
+fn first_code_point(s: &str) -> Result<u32, RunErrorMsg> {
+    s.chars()
+        .next()
+        .map(u32::from)
+        .ok_or(RunErrorMsg::InvalidArgument)
+}
+
+/// Returns the nonempty strings in an array of delimiters.
+fn delimiter_list(array: &Array) -> CodeResult<Vec<String>> {
+    array
+        .cell_values_slice()
+        .iter()
+        .map(|v| String::try_from(v).map_err(|e| e.without_span()))
+        .filter_ok(|s| !s.is_empty())
+        .collect()
+}
+
+/// Returns the match of `regex` in a value, or the text matched by each of its
+/// capture groups if it has any.
+fn regex_extract(regex: &Regex, value: &CellValue, span: Span) -> CodeResult<Vec<CellValue>> {
+    let s = String::try_from(value).map_err(|e| e.with_span(span))?;
+    let captures = regex
+        .captures(&s)
+        .ok_or(RunErrorMsg::NoMatch.with_span(span))?;
+    // Group 0 is the whole match.
+    let skip = if captures.len() > 1 { 1 } else { 0 };
+    Ok(captures
+        .iter()
+        .skip(skip)
+        .map(|m| m.map_or(CellValue::Blank, |m| m.as_str().into()))
+        .collect())
+}
+
+/// Splits a string at each occurrence of any of `delimiters`.
+fn split_text(
+    s: &str,
+    delimiters: &[String],
+    remove_empty: bool,
+    case_insensitive: bool,
+) -> CodeResult<Vec<String>> {
+    let delimiters = delimiters.iter().filter(|d| !d.is_empty()).collect_vec();
+    let parts = if delimiters.is_empty() {
+        vec![s.to_string()]
+    } else {
+        let pattern = delimiters.iter().map(|d| regex::escape(d)).join("|");
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(case_insensitive)
+            .build()
+            .map_err(|e| {
+                RunErrorMsg::InternalError(format!("error building regex for split: {e}").into())
+                    .without_span()
+            })?;
+        regex.split(s).map(String::from).collect()
+    };
+    Ok(parts
+        .into_iter()
+        .filter(|part| !(remove_empty && part.is_empty()))
+        .collect())
+}
+
+/// Parses a number from a string that may contain a currency symbol, commas,
+/// or a trailing percent sign.
+fn parse_number(s: &str) -> Option<BigDecimal> {
+    let s = s.trim();
+    if let Some(n) = CellValue::unpack_percentage(s) {
+        return Some(n);
+    }
+    if let Some((_, n)) = CellValue::unpack_currency(s) {
+        return Some(n);
+    }
+    BigDecimal::from_str(&CellValue::strip_commas(s)).ok()
+}
+
+/// Token in a number format string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FormatToken {
+    Literal(String),
+    /// Digit placeholder: `0`, `#`, or `?`.
+    Digit(char),
+    DecimalPoint,
+    Comma,
+    Percent,
+    Exponent {
+        show_plus: bool,
+    },
+}
+
+/// Splits a number format into sections separated by semicolons and tokenizes
+/// each section.
+fn parse_number_format(format: &str) -> Vec<Vec<FormatToken>> {
+    let mut sections = vec![vec![]];
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        let tokens = sections.last_mut().expect("at least one section");
+        let token = match c {
+            ';' => {
+                sections.push(vec![]);
+                continue;
+            }
+            '"' => FormatToken::Literal(chars.by_ref().take_while(|&c| c != '"').collect()),
+            '\\' => FormatToken::Literal(chars.next().map(String::from).unwrap_or_default()),
+            // `_x` adds a space the width of `x`.
+            '_' => {
+                chars.next();
+                FormatToken::Literal(" ".to_string())
+            }
+            // `*x` fills the cell with `x`, which doesn't apply to text.
+            '*' => {
+                chars.next();
+                continue;
+            }
+            '0' | '#' | '?' => FormatToken::Digit(c),
+            '.' if !tokens.contains(&FormatToken::DecimalPoint) => FormatToken::DecimalPoint,
+            ',' => FormatToken::Comma,
+            '%' => FormatToken::Percent,
+            'E' | 'e' if matches!(chars.peek(), Some('+' | '-')) => FormatToken::Exponent {
+                show_plus: chars.next() == Some('+'),
+            },
+            _ => FormatToken::Literal(c.to_string()),
+        };
+        tokens.push(token);
+    }
+    sections
+}
+
+/// Formats a number using a spreadsheet number format. See `TEXT` for the
+/// supported syntax.
+fn format_number(n: &BigDecimal, format: &str) -> String {
+    if format.eq_ignore_ascii_case("general") {
+        return n.normalized().to_string();
+    }
+    let sections = parse_number_format(format);
+    let (tokens, show_minus) = match sections.len() {
+        _ if n.is_zero() && sections.len() >= 3 => (&sections[2], false),
+        _ if n.is_negative() && sections.len() >= 2 => (&sections[1], false),
+        _ => (&sections[0], n.is_negative()),
+    };
+
+    let end_of_int = tokens
+        .iter()
+        .position(|t| matches!(t, FormatToken::DecimalPoint | FormatToken::Exponent { .. }))
+        .unwrap_or(tokens.len());
+    let exponent_index = tokens
+        .iter()
+        .position(|t| matches!(t, FormatToken::Exponent { .. }));
+    let is_digit_at = |i: usize| matches!(tokens.get(i), Some(FormatToken::Digit(_)));
+    let int_digit_indices = (0..end_of_int).filter(|&i| is_digit_at(i)).collect_vec();
+    let frac_digit_indices = (end_of_int..exponent_index.unwrap_or(tokens.len()))
+        .filter(|&i| is_digit_at(i))
+        .collect_vec();
+    let exp_digit_indices = exponent_index
+        .map(|e| (e..tokens.len()).filter(|&i| is_digit_at(i)).collect_vec())
+        .unwrap_or_default();
+
+    // Commas between integer digits add thousands separators. Commas directly
+    // after the last integer digit scale the number.
+    let first_int_digit = int_digit_indices.first().copied();
+    let last_int_digit = int_digit_indices.last().copied();
+    let mut use_thousands = false;
+    let mut scale_commas = vec![];
+    for i in 0..end_of_int {
+        if tokens[i] == FormatToken::Comma {
+            match (first_int_digit, last_int_digit) {
+                (Some(first), Some(last)) if first < i && i < last => use_thousands = true,
+                (Some(_), Some(last)) if i > last && (last..i).all(|j| !is_literal(&tokens[j])) => {
+                    scale_commas.push(i);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let mut value = n.abs();
+    let percent_count = tokens
+        .iter()
+        .filter(|&t| *t == FormatToken::Percent)
+        .count();
+    let scale = 2 * percent_count as i64 - 3 * scale_commas.len() as i64;
+    value *= BigDecimal::new(1.into(), -scale);
+
+    let decimals = frac_digit_indices.len() as i64;
+    let mut exponent = 0;
+    if exponent_index.is_some() && !value.is_zero() {
+        let int_digits = int_digit_indices.len().max(1) as i64;
+        exponent = util::magnitude(&value) - (int_digits - 1);
+        let mut mantissa = round_to(&(&value * BigDecimal::new(1.into(), exponent)), decimals);
+        if util::magnitude(&mantissa) >= int_digits {
+            exponent += 1;
+            mantissa = round_to(&(&value * BigDecimal::new(1.into(), exponent)), decimals);
+        }
+        value = mantissa;
+    } else {
+        value = round_to(&value, decimals);
+    }
+
+    // Split the rounded value into integer and fractional digits.
+    let (digits, scale) = value.as_bigint_and_exponent();
+    let digits = digits.to_string();
+    let scale = scale.max(0) as usize;
+    let digits = format!("{digits:0>width$}", width = scale + 1);
+    let (int_digits, frac_digits) = digits.split_at(digits.len() - scale);
+    let int_digits = int_digits.trim_start_matches('0');
+    let frac_digits = format!("{frac_digits:0<width$}", width = decimals as usize);
+
+    let mut output = vec![String::new(); tokens.len()];
+    place_int_digits(
+        &mut output,
+        tokens,
+        &int_digit_indices,
+        int_digits,
+        use_thousands,
+    );
+    place_frac_digits(&mut output, tokens, &frac_digit_indices, &frac_digits);
+    let exp_digits = exponent.unsigned_abs().to_string();
+    place_int_digits(&mut output, tokens, &exp_digit_indices, &exp_digits, false);
+
+    let mut ret = String::new();
+    if show_minus && (!value.is_zero() || exponent != 0) {
+        ret.push('-');
+    }
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            FormatToken::Literal(s) => ret.push_str(s),
+            FormatToken::Digit(_) => ret.push_str(&output[i]),
+            FormatToken::DecimalPoint => ret.push('.'),
+            FormatToken::Comma if !scale_commas.contains(&i) && i >= end_of_int => ret.push(','),
+            FormatToken::Comma => (),
+            FormatToken::Percent => ret.push('%'),
+            FormatToken::Exponent { show_plus } => {
+                ret.push('E');
+                if exponent < 0 {
+                    ret.push('-');
+                } else if *show_plus {
+                    ret.push('+');
+                }
+            }
+        }
+    }
+    ret
+}
+
+fn is_literal(token: &FormatToken) -> bool {
+    matches!(token, FormatToken::Literal(_))
+}
+
+fn round_to(n: &BigDecimal, decimals: i64) -> BigDecimal {
+    n.with_scale_round(decimals, RoundingMode::HalfUp)
+}
+
+/// Distributes integer digits among digit placeholders, from right to left.
+/// Any extra digits are placed at the leftmost placeholder.
+fn place_int_digits(
+    output: &mut [String],
+    tokens: &[FormatToken],
+    indices: &[usize],
+    digits: &str,
+    use_thousands: bool,
+) {
+    let Some(&first) = indices.first() else {
+        return;
+    };
+    let min_len = indices
+        .iter()
+        .skip_while(|&&i| tokens[i] == FormatToken::Digit('#'))
+        .count();
+    let mut chars = digits.chars().rev().collect_vec();
+    for (n, &i) in indices.iter().rev().enumerate() {
+        let c = chars.get(n).copied().or(match tokens[i] {
+            FormatToken::Digit('0') => Some('0'),
+            FormatToken::Digit('?') => Some(' '),
+            _ => None,
+        });
+        if chars.get(n).is_none() && n < min_len {
+            chars.push(c.unwrap_or('0'));
+        }
+        if !use_thousands {
+            output[i] = c.map(String::from).unwrap_or_default();
         }
-    )]
+    }
+    if use_thousands {
+        // Thousands separators don't make sense with literals between digits,
+        // so put everything at the first placeholder.
+        let digits = chars.iter().rev().collect::<String>();
+        let (spaces, digits) = digits.split_at(digits.len() - digits.trim_start().len());
+        let mut grouped = String::new();
+        for (n, c) in digits.chars().enumerate() {
+            if n > 0 && (digits.len() - n) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+        output[first] = format!("{spaces}{grouped}");
+    } else if chars.len() > indices.len() {
+        let extra = chars[indices.len()..].iter().rev().collect::<String>();
+        output[first].insert_str(0, &extra);
+    }
+}
+
+/// Distributes fractional digits among digit placeholders, from left to right,
+/// dropping trailing zeros where the format allows.
+fn place_frac_digits(
+    output: &mut [String],
+    tokens: &[FormatToken],
+    indices: &[usize],
+    digits: &str,
+) {
+    let digits = digits.chars().collect_vec();
+    let mut trailing = true;
+    for (&i, &c) in indices.iter().zip(&digits).rev() {
+        trailing &= c == '0';
+        output[i] = match tokens[i] {
+            FormatToken::Digit('#') if trailing => String::new(),
+            FormatToken::Digit('?') if trailing => " ".to_string(),
+            _ => {
+                trailing = false;
+                c.to_string()
+            }
+        };
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::formulas::tests::*;
+    use crate::{formulas::tests::*, Pos};
 
     #[test]
     fn test_formula_concat() {
@@ -31,4 +891,358 @@ mod tests {
             eval_to_string(&g, "'Hello, ' & 14000605 & ' worlds!'"),
         );
     }
+
+    #[test]
+    fn test_formula_textjoin() {
+        let g = Grid::new();
+        assert_eq!(
+            "a, b, c",
+            eval_to_string(&g, "TEXTJOIN(\", \", TRUE, \"a\", \"\", {\"b\", \"c\"})"),
+        );
+        assert_eq!(
+            "a--b",
+            eval_to_string(&g, "TEXTJOIN(\"-\", FALSE, \"a\", \"\", \"b\")"),
+        );
+        assert_eq!("", eval_to_string(&g, "TEXTJOIN(\"-\", TRUE)"));
+    }
+
+    #[test]
+    fn test_formula_substrings() {
+        let g = Grid::new();
+        assert_eq!("3", eval_to_string(&g, "LEN(\"a☃c\")"));
+        assert_eq!("0", eval_to_string(&g, "LEN(\"\")"));
+        assert_eq!("{1, 2}", eval_to_string(&g, "LEN({\"a\", \"ab\"})"));
+
+        assert_eq!("H", eval_to_string(&g, "LEFT(\"Hello\")"));
+        assert_eq!("Hel", eval_to_string(&g, "LEFT(\"Hello\", 3)"));
+        assert_eq!("Hello", eval_to_string(&g, "LEFT(\"Hello\", 99)"));
+        assert_eq!("o", eval_to_string(&g, "RIGHT(\"Hello\")"));
+        assert_eq!("llo", eval_to_string(&g, "RIGHT(\"Hello\", 3)"));
+        assert_eq!("", eval_to_string(&g, "RIGHT(\"Hello\", 0)"));
+        assert_eq!("ell", eval_to_string(&g, "MID(\"Hello\", 2, 3)"));
+        assert_eq!("", eval_to_string(&g, "MID(\"Hello\", 10, 3)"));
+
+        let e = &RunErrorMsg::InvalidArgument;
+        expect_err(e, &g, "LEFT(\"Hello\", -1)");
+        expect_err(e, &g, "MID(\"Hello\", 0, 1)");
+        expect_err(e, &g, "MID(\"Hello\", 1, -1)");
+    }
+
+    #[test]
+    fn test_formula_case() {
+        let g = Grid::new();
+        assert_eq!(
+            "HELLO, WORLD!",
+            eval_to_string(&g, "UPPER(\"Hello, world!\")")
+        );
+        assert_eq!(
+            "hello, world!",
+            eval_to_string(&g, "LOWER(\"Hello, World!\")")
+        );
+        assert_eq!(
+            "Hello, World! 2Nd Try-Again",
+            eval_to_string(&g, "PROPER(\"hello, WORLD! 2nd try-again\")"),
+        );
+    }
+
+    #[test]
+    fn test_formula_trim() {
+        let g = Grid::new();
+        assert_eq!(
+            "Hello, world!",
+            eval_to_string(&g, "TRIM(\"   Hello,    world!  \")"),
+        );
+        assert_eq!("", eval_to_string(&g, "TRIM(\"   \")"));
+    }
+
+    #[test]
+    fn test_formula_substitute_replace() {
+        let g = Grid::new();
+        assert_eq!(
+            "a+b+c",
+            eval_to_string(&g, "SUBSTITUTE(\"a-b-c\", \"-\", \"+\")")
+        );
+        assert_eq!(
+            "a-b+c",
+            eval_to_string(&g, "SUBSTITUTE(\"a-b-c\", \"-\", \"+\", 2)"),
+        );
+        assert_eq!(
+            "a-b-c",
+            eval_to_string(&g, "SUBSTITUTE(\"a-b-c\", \"-\", \"+\", 3)"),
+        );
+        assert_eq!(
+            "abc",
+            eval_to_string(&g, "SUBSTITUTE(\"abc\", \"\", \"+\")")
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "SUBSTITUTE(\"a-b-c\", \"-\", \"+\", 0)",
+        );
+
+        assert_eq!(
+            "Hello, there!",
+            eval_to_string(&g, "REPLACE(\"Hello, world!\", 8, 5, \"there\")"),
+        );
+        assert_eq!(
+            "abcXYZ",
+            eval_to_string(&g, "REPLACE(\"abc\", 10, 2, \"XYZ\")")
+        );
+        assert_eq!("☃bc", eval_to_string(&g, "REPLACE(\"abc\", 1, 1, \"☃\")"));
+    }
+
+    #[test]
+    fn test_formula_find_search() {
+        let g = Grid::new();
+        assert_eq!("5", eval_to_string(&g, "FIND(\"o\", \"Hello, world!\")"));
+        assert_eq!("9", eval_to_string(&g, "FIND(\"o\", \"Hello, world!\", 6)"));
+        assert_eq!("3", eval_to_string(&g, "FIND(\"c\", \"☃☃c\")"));
+        assert_eq!("2", eval_to_string(&g, "FIND(\"\", \"abc\", 2)"));
+        expect_err(&RunErrorMsg::NoMatch, &g, "FIND(\"O\", \"Hello, world!\")");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "FIND(\"a\", \"abc\", 5)");
+
+        assert_eq!(
+            "8",
+            eval_to_string(&g, "SEARCH(\"WORLD\", \"Hello, world!\")")
+        );
+        assert_eq!(
+            "8",
+            eval_to_string(&g, "SEARCH(\"w?r\", \"Hello, world!\")")
+        );
+        assert_eq!(
+            "3",
+            eval_to_string(&g, "SEARCH(\"l*o\", \"Hello, world!\")")
+        );
+        assert_eq!(
+            "9",
+            eval_to_string(&g, "SEARCH(\"o\", \"Hello, world!\", 6)")
+        );
+        assert_eq!("4", eval_to_string(&g, "SEARCH(\"~?\", \"abc?\")"));
+        expect_err(
+            &RunErrorMsg::NoMatch,
+            &g,
+            "SEARCH(\"z\", \"Hello, world!\")",
+        );
+    }
+
+    #[test]
+    fn test_formula_regexmatch() {
+        let g = Grid::new();
+        assert_eq!(
+            "TRUE",
+            eval_to_string(&g, r#"REGEXMATCH("555-1234", "^[0-9]{3}-[0-9]{4}$")"#),
+        );
+        assert_eq!(
+            "FALSE",
+            eval_to_string(&g, r#"REGEXMATCH("Quadratic", "quad")"#)
+        );
+        assert_eq!(
+            "TRUE",
+            eval_to_string(&g, r#"REGEXMATCH("Quadratic", "(?i)quad")"#)
+        );
+        assert_eq!(
+            "{TRUE, FALSE, TRUE}",
+            eval_to_string(&g, r#"REGEXMATCH({"a1", "b", "c3"}, "\\d")"#),
+        );
+        assert!(matches!(
+            eval_to_err(&g, r#"REGEXMATCH("abc", "(")"#).msg,
+            RunErrorMsg::InvalidRegex(_),
+        ));
+
+        // The pattern is only compiled once for the whole array.
+        let mut ctx = Ctx::new(&g, Pos::ORIGIN.to_sheet_pos(g.sheets()[0].id));
+        parse_formula(
+            r#"REGEXMATCH({"a1", "b", "c3"; "d", "e5", "f"}, "\\d")"#,
+            Pos::ORIGIN,
+        )
+        .unwrap()
+        .eval(&mut ctx)
+        .unwrap();
+        assert_eq!(1, ctx.regex_cache.len());
+    }
+
+    #[test]
+    fn test_formula_regexextract() {
+        let g = Grid::new();
+        assert_eq!(
+            "{1234}",
+            eval_to_string(&g, r#"REGEXEXTRACT("Order #1234", "[0-9]+")"#),
+        );
+        assert_eq!(
+            "{alice, example.com}",
+            eval_to_string(
+                &g,
+                r#"REGEXEXTRACT("alice@example.com", "([a-z]+)@([a-z.]+)")"#
+            ),
+        );
+        assert_eq!(
+            "{a, }",
+            eval_to_string(&g, r#"REGEXEXTRACT("a", "(a)(b)?")"#),
+        );
+
+        // Capture groups spill into a row for each string in a column.
+        assert_eq!(
+            "{a, 1; b, 2}",
+            eval_to_string(&g, r#"REGEXEXTRACT({"a=1"; "b=2"}, "([a-z])=([0-9])")"#),
+        );
+        // Strings that don't match give errors without affecting the others.
+        assert_eq!(
+            "{1, none, 3}",
+            eval_to_string(
+                &g,
+                r#"IFERROR(REGEXEXTRACT({"a1", "b", "c3"}, "\\d"), "none")"#,
+            ),
+        );
+
+        expect_err(&RunErrorMsg::NoMatch, &g, r#"REGEXEXTRACT("abc", "\\d")"#);
+        expect_err(
+            &RunErrorMsg::ExactArrayAxisMismatch {
+                axis: Axis::X,
+                expected: 1,
+                got: 2,
+            },
+            &g,
+            r#"REGEXEXTRACT({"a=1", "b=2"}, "([a-z])=([0-9])")"#,
+        );
+    }
+
+    #[test]
+    fn test_formula_regexreplace() {
+        let g = Grid::new();
+        assert_eq!(
+            "H*ll*, w*rld!",
+            eval_to_string(&g, r#"REGEXREPLACE("Hello, world!", "[aeiou]", "*")"#),
+        );
+        assert_eq!(
+            "Ada Lovelace",
+            eval_to_string(
+                &g,
+                r#"REGEXREPLACE("Lovelace, Ada", "([A-Za-z]+), ([A-Za-z]+)", "$2 $1")"#
+            ),
+        );
+        assert_eq!(
+            "{x-y, z}",
+            eval_to_string(&g, r#"REGEXREPLACE({"x_y", "z"}, "_", "-")"#),
+        );
+    }
+
+    #[test]
+    fn test_formula_split() {
+        let g = Grid::new();
+        assert_eq!("{a, b, c}", eval_to_string(&g, "SPLIT(\"a,b;;c\", \",;\")"));
+        assert_eq!(
+            "{a, , b}",
+            eval_to_string(&g, "SPLIT(\"a, , b\", \", \", FALSE, FALSE)"),
+        );
+        expect_err(&RunErrorMsg::EmptyArray, &g, "SPLIT(\",,\", \",\")");
+
+        assert_eq!(
+            "{a, b, c}",
+            eval_to_string(&g, "TEXTSPLIT(\"a,b,c\", \",\")")
+        );
+        assert_eq!(
+            "{a, 1; b, 2}",
+            eval_to_string(&g, "TEXTSPLIT(\"a=1;b=2\", \"=\", \";\")"),
+        );
+        assert_eq!(
+            "{a, b, c}",
+            eval_to_string(&g, "TEXTSPLIT(\"a b,,c\", {\" \", \",\"}, , TRUE)"),
+        );
+        assert_eq!(
+            "{a, b, c}",
+            eval_to_string(&g, "TEXTSPLIT(\"aXbxc\", \"x\", , FALSE, 1)"),
+        );
+        assert_eq!(
+            "{a, b; c, -}",
+            eval_to_string(&g, "TEXTSPLIT(\"a,b;c\", \",\", \";\", FALSE, 0, \"-\")"),
+        );
+        let Value::Array(array) = eval(&g, "TEXTSPLIT(\"a,b;c\", \",\", \";\")") else {
+            panic!("expected array");
+        };
+        assert_eq!(
+            Some(&RunErrorMsg::NoMatch),
+            array.get(1, 1).unwrap().error().map(|e| &e.msg),
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "TEXTSPLIT(\"a,b\", \",\", , , 2)",
+        );
+    }
+
+    #[test]
+    fn test_formula_rept_exact() {
+        let g = Grid::new();
+        assert_eq!("ababab", eval_to_string(&g, "REPT(\"ab\", 3)"));
+        assert_eq!("", eval_to_string(&g, "REPT(\"ab\", 0)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "REPT(\"ab\", -1)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "REPT(\"ab\", 100000)");
+
+        assert_eq!("TRUE", eval_to_string(&g, "EXACT(\"abc\", \"abc\")"));
+        assert_eq!("FALSE", eval_to_string(&g, "EXACT(\"abc\", \"ABC\")"));
+    }
+
+    #[test]
+    fn test_formula_value() {
+        let g = Grid::new();
+        assert_eq!("1234.5", eval_to_string(&g, "VALUE(\"$1,234.50\")"));
+        assert_eq!("0.15", eval_to_string(&g, "VALUE(\"15%\")"));
+        assert_eq!("-3", eval_to_string(&g, "VALUE(\" -3 \")"));
+        assert_eq!("42", eval_to_string(&g, "VALUE(42)"));
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "number".into(),
+                got: Some("text".into()),
+            },
+            &g,
+            "VALUE(\"abc\")",
+        );
+    }
+
+    #[test]
+    fn test_formula_text() {
+        let g = Grid::new();
+        let text =
+            |value: &str, format: &str| eval_to_string(&g, &format!("TEXT({value}, '{format}')"));
+        assert_eq!("1,234.57", text("1234.567", "#,##0.00"));
+        assert_eq!("1,234,568", text("1234567.8", "#,##0"));
+        assert_eq!("0.50", text("0.5", "0.00"));
+        assert_eq!(".5", text("0.5", "#.##"));
+        assert_eq!("3", text("2.5", "0"));
+        assert_eq!("-3", text("-2.5", "0"));
+        assert_eq!("25.6%", text("0.256", "0.0%"));
+        assert_eq!("007", text("7", "000"));
+        assert_eq!("1.23E+03", text("1234", "0.00E+00"));
+        assert_eq!("1.2E-3", text("0.0012", "0.0E+0"));
+        assert_eq!("1,235K", text("1234567", "#,##0,\"K\""));
+        assert_eq!("555-1234", text("5551234", "000-0000"));
+        assert_eq!("$1.50", text("1.5", "$0.00;($0.00);\"zero\""));
+        assert_eq!("($1.50)", text("-1.5", "$0.00;($0.00);\"zero\""));
+        assert_eq!("zero", text("0", "$0.00;($0.00);\"zero\""));
+        assert_eq!("1.5", text("1.5", "General"));
+        assert_eq!("abc", text("\"abc\"", "0.00"));
+        assert_eq!("1", text("TRUE", "0"));
+
+        // Dates and durations use date and time formats.
+        assert_eq!("2024-03-01", text("DATE(2024, 3, 1)", "yyyy-mm-dd"));
+        assert_eq!("Mar 1, 2024", text("DATE(2024, 3, 1)", "mmm d, yyyy"));
+        assert_eq!(
+            "2024-03-01 09:30",
+            text("DATE(2024, 3, 1) + TIME(9, 30, 0)", "%Y-%m-%d %H:%M"),
+        );
+        assert_eq!("26:15:00", text("TIME(26, 15, 0)", "[h]:mm:ss"));
+        assert_eq!("05:30.5", text("TIME(0, 5, 30.5)", "mm:ss.0"));
+    }
+
+    #[test]
+    fn test_formula_char_code() {
+        let g = Grid::new();
+        assert_eq!("A", eval_to_string(&g, "CHAR(65)"));
+        assert_eq!("☃", eval_to_string(&g, "CHAR(9731)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "CHAR(0)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "CHAR(-1)");
+        assert_eq!("65", eval_to_string(&g, "CODE(\"ABC\")"));
+        assert_eq!("9731", eval_to_string(&g, "UNICODE(\"☃\")"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "CODE(\"\")");
+    }
 }
@@ -1,5 +1,13 @@
+use bigdecimal::{BigDecimal, Zero};
+
 use super::*;
 
+/// Number of significant digits kept in the result of multiplying or dividing
+/// decimal numbers, so that quotients that don't terminate and repeated
+/// products don't grow without bound. This is the precision of IEEE 754
+/// decimal128.
+const DECIMAL_PRECISION: u64 = 34;
+
 /// Divides one number by another, handling the error case of division by zero.
 pub fn checked_div(span: impl Into<Span>, dividend: f64, divisor: f64) -> CodeResult<f64> {
     let result = dividend / divisor;
@@ -9,15 +17,191 @@ pub fn checked_div(span: impl Into<Span>, dividend: f64, divisor: f64) -> CodeRe
     }
 }
 
+/// Multiplies two decimal numbers, rounding the product to 34 significant
+/// digits.
+pub fn decimal_mul(a: &BigDecimal, b: &BigDecimal) -> BigDecimal {
+    without_trailing_zeros((a * b).with_prec(DECIMAL_PRECISION))
+}
+
+/// Divides one decimal number by another, handling the error case of division
+/// by zero. Quotients that don't terminate are rounded to 34 significant
+/// digits.
+pub fn checked_decimal_div(
+    span: impl Into<Span>,
+    dividend: &BigDecimal,
+    divisor: &BigDecimal,
+) -> CodeResult<BigDecimal> {
+    if divisor.is_zero() {
+        return Err(RunErrorMsg::DivideByZero.with_span(span));
+    }
+    Ok(without_trailing_zeros(
+        (dividend / divisor).with_prec(DECIMAL_PRECISION),
+    ))
+}
+
+/// Returns a number, or an error if it is infinite or NaN.
+pub fn checked_float(span: impl Into<Span>, number: f64) -> CodeResult<f64> {
+    if number.is_nan() {
+        Err(RunErrorMsg::NotANumber.with_span(span))
+    } else if number.is_infinite() {
+        Err(RunErrorMsg::Overflow.with_span(span))
+    } else {
+        Ok(number)
+    }
+}
+
+/// Returns the power of 10 of the most significant digit of a nonzero number.
+pub fn magnitude(n: &BigDecimal) -> i64 {
+    let (digits, scale) = n.normalized().as_bigint_and_exponent();
+    digits.magnitude().to_string().len() as i64 - 1 - scale
+}
+
+/// Removes trailing zeros, so that a result such as `2.50` is displayed as
+/// `2.5`.
+///
+/// Trailing zeros before the decimal point are removed too, which is cheap
+/// even for numbers with a huge exponent.
+pub fn without_trailing_zeros(number: BigDecimal) -> BigDecimal {
+    number.normalized()
+}
+
 pub fn average(
     span: impl Into<Span>,
-    numbers: impl IntoIterator<Item = CodeResult<f64>>,
-) -> CodeResult<f64> {
-    let mut sum = 0.0;
+    numbers: impl IntoIterator<Item = CodeResult<BigDecimal>>,
+) -> CodeResult<BigDecimal> {
+    let mut sum = BigDecimal::zero();
     let mut count = 0;
     for n in numbers {
         sum += n?;
         count += 1;
     }
-    util::checked_div(span, sum, count as f64)
+    util::checked_decimal_div(span, &sum, &BigDecimal::from(count))
+}
+
+/// Combines `numbers` using `f`, such as [`BigDecimal::min`], or returns
+/// `None` if there are no numbers.
+pub fn fold_numbers(
+    numbers: impl IntoIterator<Item = CodeResult<BigDecimal>>,
+    f: impl Fn(BigDecimal, BigDecimal) -> BigDecimal,
+) -> CodeResult<Option<BigDecimal>> {
+    let mut ret = None;
+    for n in numbers {
+        let n = n?;
+        ret = Some(match ret {
+            Some(ret) => f(ret, n),
+            None => n,
+        });
+    }
+    Ok(ret)
+}
+
+/// Returns the eval ranges and criteria for a function such as `SUMIFS`, which
+/// takes one or more pairs of an eval range and criteria.
+pub fn criteria_pairs(
+    func_name: &'static str,
+    span: Span,
+    eval_range1: Spanned<Array>,
+    criteria1: Spanned<CellValue>,
+    more_eval_ranges_and_criteria: impl Iterator<Item = CodeResult<Spanned<Value>>>,
+) -> CodeResult<Vec<(Spanned<Array>, Criterion)>> {
+    let mut ret = vec![(eval_range1, Criterion::try_from(criteria1.as_ref())?)];
+    let more = more_eval_ranges_and_criteria.collect::<CodeResult<Vec<_>>>()?;
+    for pair in more.chunks(2) {
+        let [eval_range, criteria] = pair else {
+            return Err(RunErrorMsg::MissingRequiredArgument {
+                func_name: func_name.into(),
+                arg_name: "criteria".into(),
+            }
+            .with_span(span));
+        };
+        let criteria = criteria.clone().into_cell_value()?;
+        let criterion = Criterion::try_from(criteria.as_ref())?;
+        ret.push((eval_range.clone().map(Array::from), criterion));
+    }
+    Ok(ret)
+}
+
+/// Returns the numbers in `values`, sorted in ascending order.
+pub fn sorted_numbers(values: impl IntoIterator<Item = CodeResult<f64>>) -> CodeResult<Vec<f64>> {
+    let mut numbers = values.into_iter().collect::<CodeResult<Vec<f64>>>()?;
+    numbers.sort_by(f64::total_cmp);
+    Ok(numbers)
+}
+
+/// Returns the variance of `numbers`, either of a sample (dividing by `n - 1`)
+/// or of a whole population (dividing by `n`).
+pub fn variance(
+    span: impl Into<Span>,
+    numbers: impl IntoIterator<Item = CodeResult<f64>>,
+    is_sample: bool,
+) -> CodeResult<f64> {
+    let numbers = numbers.into_iter().collect::<CodeResult<Vec<f64>>>()?;
+    let n = numbers.len() as f64;
+    let mean = numbers.iter().sum::<f64>() / n;
+    let sum_of_squares = numbers.iter().map(|x| (x - mean).powi(2)).sum::<f64>();
+    let divisor = if is_sample { n - 1.0 } else { n };
+    if divisor <= 0.0 {
+        return Err(RunErrorMsg::DivideByZero.with_span(span));
+    }
+    Ok(sum_of_squares / divisor)
+}
+
+/// Returns the numeric pairs of values in two arrays of the same size, ignoring
+/// pairs where either value is not a number.
+pub fn number_pairs(
+    array1: &Spanned<Array>,
+    array2: &Spanned<Array>,
+) -> CodeResult<Vec<(f64, f64)>> {
+    array2.check_array_size_exact(array1.inner.size())?;
+    let mut ret = vec![];
+    for (a, b) in std::iter::zip(
+        array1.inner.cell_values_slice(),
+        array2.inner.cell_values_slice(),
+    ) {
+        if let Some(e) = a.error().or(b.error()) {
+            return Err(e.clone());
+        }
+        if let (CellValue::Number(_), CellValue::Number(_)) = (a, b) {
+            ret.push((f64::try_from(a)?, f64::try_from(b)?));
+        }
+    }
+    Ok(ret)
+}
+
+/// Returns the value at fraction `k` of the way through the sorted `numbers`,
+/// interpolating between adjacent values.
+pub fn percentile(span: impl Into<Span>, numbers: &[f64], k: f64) -> CodeResult<f64> {
+    if numbers.is_empty() {
+        return Err(RunErrorMsg::EmptyArray.with_span(span));
+    }
+    let rank = k * (numbers.len() - 1) as f64;
+    let lo = rank.floor() as usize;
+    let hi = rank.ceil() as usize;
+    Ok(numbers[lo] + (rank - lo as f64) * (numbers[hi] - numbers[lo]))
+}
+
+/// Returns the `k`th number in `numbers`, counting from 1.
+pub fn kth_number(numbers: &[f64], k: Spanned<i64>) -> CodeResult<f64> {
+    usize::try_from(k.inner)
+        .ok()
+        .and_then(|k| numbers.get(k.checked_sub(1)?))
+        .copied()
+        .ok_or(RunErrorMsg::IndexOutOfBounds.with_span(k.span))
+}
+
+/// Returns the covariance of pairs of numbers, either of a sample (dividing by
+/// `n - 1`) or of a whole population (dividing by `n`).
+pub fn covariance(span: impl Into<Span>, pairs: &[(f64, f64)], is_sample: bool) -> CodeResult<f64> {
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let sum_of_products = pairs
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum::<f64>();
+    let divisor = if is_sample { n - 1.0 } else { n };
+    if divisor <= 0.0 {
+        return Err(RunErrorMsg::DivideByZero.with_span(span));
+    }
+    Ok(sum_of_products / divisor)
 }
@@ -0,0 +1,369 @@
+//! Evaluation of `LET`, `LAMBDA`, and functions that take a lambda as an
+//! argument.
+//!
+//! These are evaluated directly from the syntax tree instead of through
+//! [`super::functions`], because some of their arguments are names or
+//! lambdas rather than values.
+
+use std::rc::Rc;
+
+use itertools::Itertools;
+
+use super::ast::AstNodeContents;
+use super::*;
+use crate::{
+    Array, ArraySize, CellValue, CodeResult, CodeResultExt, RunError, RunErrorMsg, Span, Spanned,
+    Value,
+};
+
+/// Functions that are evaluated by [`AstNode::eval_special_form()`].
+const SPECIAL_FORMS: &[&str] = &["LET", "LAMBDA", "MAP", "REDUCE", "SCAN", "BYROW", "BYCOL"];
+
+/// Maximum number of nested lambda calls, so that a lambda that calls itself
+/// forever returns an error instead of overflowing the stack.
+const MAX_CALL_DEPTH: usize = 32;
+
+/// Value of a variable, which may be a lambda.
+#[derive(Debug, Clone)]
+pub enum Binding {
+    Value(Value),
+    Lambda(Rc<Lambda>),
+}
+impl Binding {
+    pub(super) fn into_value(self, span: Span) -> CodeResult<Value> {
+        match self {
+            Binding::Value(value) => Ok(value),
+            Binding::Lambda(_) => Err(RunErrorMsg::Expected {
+                expected: "value".into(),
+                got: Some("function".into()),
+            }
+            .with_span(span)),
+        }
+    }
+    fn into_lambda(self, span: Span) -> CodeResult<Rc<Lambda>> {
+        match self {
+            Binding::Lambda(lambda) => Ok(lambda),
+            Binding::Value(_) => Err(RunErrorMsg::Expected {
+                expected: "function".into(),
+                got: Some("value".into()),
+            }
+            .with_span(span)),
+        }
+    }
+}
+
+/// User-defined function created using `LAMBDA`.
+#[derive(Debug)]
+pub struct Lambda {
+    params: Vec<String>,
+    body: AstNode,
+    /// Variables in scope where the lambda was defined.
+    captured: Vec<(String, Binding)>,
+}
+impl Lambda {
+    /// Calls the lambda with `args`, which must match its parameters.
+    fn call(&self, ctx: &mut Ctx<'_>, args: Vec<Binding>, span: Span) -> CodeResult<Binding> {
+        if args.len() > self.params.len() {
+            return Err(RunErrorMsg::TooManyArguments {
+                func_name: "LAMBDA".into(),
+                max_arg_count: self.params.len(),
+            }
+            .with_span(span));
+        }
+        if let Some(missing_param) = self.params.get(args.len()) {
+            return Err(RunErrorMsg::MissingRequiredArgument {
+                func_name: "LAMBDA".into(),
+                arg_name: missing_param.clone().into(),
+            }
+            .with_span(span));
+        }
+        if ctx.call_depth >= MAX_CALL_DEPTH {
+            return Err(RunErrorMsg::TooMuchRecursion.with_span(span));
+        }
+
+        // The body only sees the variables captured where the lambda was
+        // defined, not those where it is called.
+        let scope = self
+            .captured
+            .iter()
+            .cloned()
+            .chain(self.params.iter().cloned().zip(args))
+            .collect();
+        let outer_scope = std::mem::replace(&mut ctx.variables, scope);
+        ctx.call_depth += 1;
+        let result = self.body.eval_binding(ctx);
+        ctx.call_depth -= 1;
+        ctx.variables = outer_scope;
+        result
+    }
+
+    /// Calls the lambda with `args` and returns a single value.
+    fn call_for_cell_value(
+        &self,
+        ctx: &mut Ctx<'_>,
+        args: Vec<Binding>,
+        span: Span,
+    ) -> CodeResult<CellValue> {
+        let value = self.call(ctx, args, span)?.into_value(span)?;
+        Ok(value.into_cell_value().with_span(span)?.inner)
+    }
+}
+
+impl Ctx<'_> {
+    /// Returns the innermost variable called `name`, ignoring case.
+    pub(super) fn variable(&self, name: &str) -> Option<&Binding> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(var_name, _)| var_name.eq_ignore_ascii_case(name))
+            .map(|(_, binding)| binding)
+    }
+
+    /// Returns whether a call to `func_name` must be evaluated by
+    /// [`AstNode::eval_special_form()`].
+    pub(super) fn is_special_form(&self, func_name: &str) -> bool {
+        SPECIAL_FORMS
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(func_name))
+            || matches!(self.variable(func_name), Some(Binding::Lambda(_)))
+    }
+}
+
+impl AstNode {
+    /// Evaluates a call to a function for which
+    /// [`Ctx::is_special_form()`] returns `true`.
+    pub(super) fn eval_special_form(
+        &self,
+        ctx: &mut Ctx<'_>,
+        func: &Spanned<String>,
+        args: &[AstNode],
+    ) -> CodeResult<Value> {
+        let span = self.span;
+        if matches!(ctx.variable(&func.inner), Some(Binding::Lambda(_))) {
+            return self.eval_binding(ctx)?.into_value(span);
+        }
+        match func.inner.to_ascii_uppercase().as_str() {
+            "MAP" => {
+                let [arrays @ .., lambda] = args else {
+                    return Err(missing_arg("MAP", "array", span));
+                };
+                if arrays.is_empty() {
+                    return Err(missing_arg("MAP", "lambda", span));
+                }
+                let mut array_values = vec![];
+                for array in arrays {
+                    array_values.push(array.eval(ctx)?);
+                }
+                let lambda = lambda.eval_lambda(ctx)?;
+                ctx.zip_map(&array_values, |ctx, values| {
+                    let args = values
+                        .iter()
+                        .map(|v| Binding::Value(v.inner.clone().into()))
+                        .collect();
+                    lambda.call_for_cell_value(ctx, args, span)
+                })
+            }
+
+            "REDUCE" | "SCAN" => {
+                let func_name = if func.inner.eq_ignore_ascii_case("REDUCE") {
+                    "REDUCE"
+                } else {
+                    "SCAN"
+                };
+                let [initial_value, array, lambda] = args else {
+                    return Err(wrong_arg_count(
+                        func_name,
+                        args,
+                        &["initial_value", "array", "lambda"],
+                        span,
+                    ));
+                };
+                let mut accumulator = initial_value.eval(ctx)?.inner;
+                let array = array.eval(ctx)?;
+                let lambda = lambda.eval_lambda(ctx)?;
+
+                if func_name == "REDUCE" {
+                    for value in array.inner.cell_values_slice() {
+                        let args = vec![
+                            Binding::Value(accumulator),
+                            Binding::Value(value.clone().into()),
+                        ];
+                        accumulator = lambda.call(ctx, args, span)?.into_value(span)?;
+                    }
+                    Ok(accumulator)
+                } else {
+                    ctx.zip_map(&[array], |ctx, values| {
+                        let args = vec![
+                            Binding::Value(accumulator.clone()),
+                            Binding::Value(values[0].inner.clone().into()),
+                        ];
+                        let value = lambda.call_for_cell_value(ctx, args, span)?;
+                        accumulator = value.clone().into();
+                        Ok(value)
+                    })
+                }
+            }
+
+            "BYROW" | "BYCOL" => {
+                let is_byrow = func.inner.eq_ignore_ascii_case("BYROW");
+                let func_name = if is_byrow { "BYROW" } else { "BYCOL" };
+                let [array, lambda] = args else {
+                    return Err(wrong_arg_count(func_name, args, &["array", "lambda"], span));
+                };
+                let mut array = Array::from(array.eval(ctx)?.inner);
+                let lambda = lambda.eval_lambda(ctx)?;
+
+                // Handle columns as rows of the transposed array.
+                if !is_byrow {
+                    array = array.transpose();
+                }
+                let row_size = ArraySize::new_or_err(array.width(), 1)?;
+                let mut results = smallvec::smallvec![];
+                for row in array.rows() {
+                    let mut row = Array::new_row_major(row_size, row.iter().cloned().collect())?;
+                    if !is_byrow {
+                        row = row.transpose();
+                    }
+                    let args = vec![Binding::Value(row.into())];
+                    results.push(lambda.call_for_cell_value(ctx, args, span)?);
+                }
+                let results_size = ArraySize::new_or_err(1, array.height())?;
+                let mut results = Array::new_row_major(results_size, results)?;
+                if !is_byrow {
+                    results = results.transpose();
+                }
+                Ok(results.into())
+            }
+
+            // `LET` and `LAMBDA`
+            _ => self.eval_binding(ctx)?.into_value(span),
+        }
+    }
+
+    /// Evaluates an expression that may result in a lambda instead of a
+    /// value.
+    pub(super) fn eval_binding(&self, ctx: &mut Ctx<'_>) -> CodeResult<Binding> {
+        match &self.inner {
+            AstNodeContents::Paren(expr) => expr.eval_binding(ctx),
+
+            AstNodeContents::Identifier(name) => match ctx.variable(name) {
+                Some(binding) => Ok(binding.clone()),
+                None => Ok(Binding::Value(
+                    ctx.get_named_range(name, self.span)?.inner.into(),
+                )),
+            },
+
+            AstNodeContents::FunctionCall { func, args } => {
+                if let Some(Binding::Lambda(lambda)) = ctx.variable(&func.inner) {
+                    let lambda = Rc::clone(lambda);
+                    let args = eval_lambda_args(ctx, args);
+                    return lambda.call(ctx, args, self.span);
+                }
+                match func.inner.to_ascii_uppercase().as_str() {
+                    "LET" => {
+                        let outer_scope_len = ctx.variables.len();
+                        let result = eval_let(ctx, args, self.span);
+                        ctx.variables.truncate(outer_scope_len);
+                        result
+                    }
+                    "LAMBDA" => {
+                        let [params @ .., body] = args.as_slice() else {
+                            return Err(missing_arg("LAMBDA", "calculation", self.span));
+                        };
+                        let lambda = Lambda {
+                            params: params.iter().map(to_variable_name).try_collect()?,
+                            body: body.clone(),
+                            captured: ctx.variables.clone(),
+                        };
+                        Ok(Binding::Lambda(Rc::new(lambda)))
+                    }
+                    _ => Ok(Binding::Value(self.eval(ctx)?.inner)),
+                }
+            }
+
+            AstNodeContents::Call { func, args } => {
+                let lambda = func.eval_lambda(ctx)?;
+                let args = eval_lambda_args(ctx, args);
+                lambda.call(ctx, args, self.span)
+            }
+
+            _ => Ok(Binding::Value(self.eval(ctx)?.inner)),
+        }
+    }
+
+    /// Evaluates an expression that must result in a lambda.
+    fn eval_lambda(&self, ctx: &mut Ctx<'_>) -> CodeResult<Rc<Lambda>> {
+        self.eval_binding(ctx)?.into_lambda(self.span)
+    }
+}
+
+/// Evaluates `LET(name1, value1, ..., calculation)`, leaving its variables in
+/// `ctx`.
+fn eval_let(ctx: &mut Ctx<'_>, args: &[AstNode], span: Span) -> CodeResult<Binding> {
+    let [pairs @ .., calculation] = args else {
+        return Err(missing_arg("LET", "name1", span));
+    };
+    if pairs.is_empty() {
+        return Err(missing_arg("LET", "value1", span));
+    }
+    if pairs.len() % 2 != 0 {
+        return Err(missing_arg("LET", "calculation", span));
+    }
+    for pair in pairs.chunks(2) {
+        let name = to_variable_name(&pair[0])?;
+        let value = eval_binding_or_error(ctx, &pair[1]);
+        ctx.variables.push((name, value));
+    }
+    calculation.eval_binding(ctx)
+}
+
+/// Evaluates the arguments to a lambda. As with other functions, errors are
+/// passed as values.
+fn eval_lambda_args(ctx: &mut Ctx<'_>, args: &[AstNode]) -> Vec<Binding> {
+    args.iter()
+        .map(|arg| eval_binding_or_error(ctx, arg))
+        .collect()
+}
+
+fn eval_binding_or_error(ctx: &mut Ctx<'_>, expr: &AstNode) -> Binding {
+    expr.eval_binding(ctx)
+        .unwrap_or_else(|e| Binding::Value(CellValue::Error(Box::new(e)).into()))
+}
+
+/// Returns the name of a variable defined by `LET` or a lambda parameter.
+fn to_variable_name(expr: &AstNode) -> CodeResult<String> {
+    match &expr.inner {
+        AstNodeContents::Identifier(name) if is_valid_named_range_name(name) => Ok(name.clone()),
+        other => Err(RunErrorMsg::Expected {
+            expected: "name".into(),
+            got: Some(other.type_string().into()),
+        }
+        .with_span(expr.span)),
+    }
+}
+
+pub(super) fn missing_arg(func_name: &'static str, arg_name: &'static str, span: Span) -> RunError {
+    RunErrorMsg::MissingRequiredArgument {
+        func_name: func_name.into(),
+        arg_name: arg_name.into(),
+    }
+    .with_span(span)
+}
+
+/// Returns an error for a function that takes exactly `params.len()`
+/// arguments.
+pub(super) fn wrong_arg_count(
+    func_name: &'static str,
+    args: &[AstNode],
+    params: &[&'static str],
+    span: Span,
+) -> RunError {
+    match params.get(args.len()) {
+        Some(missing_param) => missing_arg(func_name, missing_param, span),
+        None => RunErrorMsg::TooManyArguments {
+            func_name: func_name.into(),
+            max_arg_count: params.len(),
+        }
+        .with_span(args[params.len()].span),
+    }
+}
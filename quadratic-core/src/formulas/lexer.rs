@@ -25,8 +25,12 @@ fn new_fullmatch_regex(s: &str) -> Regex {
 }
 
 /// Function call consisting of a letter or underscore followed by any letters,
-/// digits, and/or underscores terminated with a `(`.
-const FUNCTION_CALL_PATTERN: &str = r"[A-Za-z_][A-Za-z_\d]*\(";
+/// digits, underscores, and/or periods terminated with a `(`.
+const FUNCTION_CALL_PATTERN: &str = r"[A-Za-z_][A-Za-z_\d.]*\(";
+
+/// Identifier consisting of a letter or underscore followed by any letters,
+/// digits, and/or underscores, such as the name of a named range.
+const IDENTIFIER_PATTERN: &str = r"[A-Za-z_][A-Za-z_\d]*";
 
 /// A1-style cell reference.
 ///
@@ -37,6 +41,29 @@ const FUNCTION_CALL_PATTERN: &str = r"[A-Za-z_][A-Za-z_\d]*\(";
 ///                 \d+       digits
 const A1_CELL_REFERENCE_PATTERN: &str = r"\$?n?[A-Z]+\$?n?\d+";
 
+/// A1-style cell reference that is not followed by more of an identifier, so
+/// that an identifier such as `Q1_Sales` is not lexed as `Q1` followed by
+/// `_Sales`.
+const A1_CELL_REFERENCE_TOKEN_PATTERN: &str = r"\$?n?[A-Z]+\$?n?\d+\b";
+
+/// A1-style whole-column range reference, such as `A:A` or `$B:$D`.
+///
+/// \$?n?[A-Z]+:\$?n?[A-Z]+\b
+/// \$?n?[A-Z]+                 first column
+///            :                range operator
+///             \$?n?[A-Z]+     last column
+///                        \b   not followed by a row number
+const A1_COLUMN_RANGE_PATTERN: &str = r"\$?n?[A-Z]+:\$?n?[A-Z]+\b";
+
+/// A1-style whole-row range reference, such as `3:3` or `$1:$5`.
+///
+/// \$?n?\d+:\$?n?\d+\b
+/// \$?n?\d+                   first row
+///         :                  range operator
+///          \$?n?\d+          last row
+///                  \b         not followed by anything else
+const A1_ROW_RANGE_PATTERN: &str = r"\$?n?\d+:\$?n?\d+\b";
+
 /// Floating-point or integer number, without leading sign.
 ///
 /// (\d+(\.\d*)?|\.\d+)([eE][+-]?\d+)?
@@ -79,14 +106,19 @@ const TOKEN_PATTERNS: &[&str] = &[
     SINGLE_QUOTE_STRING_LITERAL_PATTERN,
     DOUBLE_QUOTE_STRING_LITERAL_PATTERN,
     UNTERMINATED_STRING_LITERAL_PATTERN,
+    // Reference to whole columns or rows.
+    A1_COLUMN_RANGE_PATTERN,
+    A1_ROW_RANGE_PATTERN,
     // Numeric literal.
     NUMERIC_LITERAL_PATTERN,
     // Function call.
     FUNCTION_CALL_PATTERN,
-    // Boolean literal (case-insensitive).
-    r#"false|true"#,
     // Reference to a cell.
-    A1_CELL_REFERENCE_PATTERN,
+    A1_CELL_REFERENCE_TOKEN_PATTERN,
+    // Identifier or boolean literal (case-insensitive).
+    IDENTIFIER_PATTERN,
+    // Reference to a cell that has been deleted.
+    r"#REF!",
     // Whitespace.
     r"\s+",
     // Any other single Unicode character.
@@ -111,6 +143,18 @@ lazy_static! {
     pub static ref A1_CELL_REFERENCE_REGEX: Regex =
         new_fullmatch_regex(A1_CELL_REFERENCE_PATTERN);
 
+    /// Regex that matches an identifier.
+    pub static ref IDENTIFIER_REGEX: Regex =
+        new_fullmatch_regex(IDENTIFIER_PATTERN);
+
+    /// Regex that matches a valid A1-style whole-column range reference.
+    pub static ref A1_COLUMN_RANGE_REGEX: Regex =
+        new_fullmatch_regex(A1_COLUMN_RANGE_PATTERN);
+
+    /// Regex that matches a valid A1-style whole-row range reference.
+    pub static ref A1_ROW_RANGE_REGEX: Regex =
+        new_fullmatch_regex(A1_ROW_RANGE_PATTERN);
+
     /// Regex that matches all valid numeric literals and some invalid ones.
     pub static ref NUMERIC_LITERAL_REGEX: Regex =
         new_fullmatch_regex(NUMERIC_LITERAL_PATTERN);
@@ -188,6 +232,8 @@ pub enum Token {
     SheetRefOp, // !
     #[strum(to_string = "ellipsis")]
     Ellipsis, // ...
+    #[strum(to_string = "implicit intersection operator")]
+    At, // @
 
     // Booleans
     #[strum(to_string = "FALSE")]
@@ -214,6 +260,14 @@ pub enum Token {
     NumericLiteral,
     #[strum(to_string = "cell reference")]
     CellRef,
+    #[strum(to_string = "invalid cell reference")]
+    BadCellRef,
+    #[strum(to_string = "identifier")]
+    Identifier,
+    #[strum(to_string = "column range reference")]
+    ColumnRangeRef,
+    #[strum(to_string = "row range reference")]
+    RowRangeRef,
     #[strum(to_string = "whitespace")]
     Whitespace,
     #[strum(to_string = "unknown symbol")]
@@ -254,6 +308,7 @@ impl Token {
             ":" => Self::CellRangeOp,
             "!" => Self::SheetRefOp,
             "..." => Self::Ellipsis,
+            "@" => Self::At,
             s if s.eq_ignore_ascii_case("false") => Self::False,
             s if s.eq_ignore_ascii_case("true") => Self::True,
 
@@ -287,6 +342,8 @@ impl Token {
             }
 
             // Match anything else.
+            s if A1_COLUMN_RANGE_REGEX.is_match(s) => Self::ColumnRangeRef,
+            s if A1_ROW_RANGE_REGEX.is_match(s) => Self::RowRangeRef,
             s if FUNCTION_CALL_REGEX.is_match(s) => Self::FunctionCall,
             s if UNQUOTED_SHEET_REFERENCE.is_match(s) => Self::UnquotedSheetReference,
             s if STRING_LITERAL_REGEX.is_match(s) => Self::StringLiteral,
@@ -294,7 +351,14 @@ impl Token {
             s if s.eq_ignore_ascii_case("false") => Self::False,
             s if s.eq_ignore_ascii_case("true") => Self::True,
             s if NUMERIC_LITERAL_REGEX.is_match(s) => Self::NumericLiteral,
-            s if A1_CELL_REFERENCE_REGEX.is_match(s) => Self::CellRef,
+            s if A1_CELL_REFERENCE_REGEX
+                .find(s)
+                .is_some_and(|m| m.end() == s.len()) =>
+            {
+                Self::CellRef
+            }
+            s if IDENTIFIER_REGEX.is_match(s) => Self::Identifier,
+            s if s.eq_ignore_ascii_case("#REF!") => Self::BadCellRef,
             s if s.trim().is_empty() => Self::Whitespace,
 
             // Give up.
@@ -342,6 +406,68 @@ mod tests {
         test_block_comment(false, "/* /*");
         test_block_comment(false, "/*/");
     }
+    #[test]
+    fn test_lex_row_col_ranges() {
+        let kinds = |s: &str| tokenize(s).map(|t| t.inner).collect_vec();
+        assert_eq!(kinds("A:A"), [Token::ColumnRangeRef]);
+        assert_eq!(kinds("$B:$AA"), [Token::ColumnRangeRef]);
+        assert_eq!(kinds("3:3"), [Token::RowRangeRef]);
+        assert_eq!(kinds("$1:n5"), [Token::RowRangeRef]);
+        assert_eq!(
+            kinds("A1:B2"),
+            [Token::CellRef, Token::CellRangeOp, Token::CellRef],
+        );
+        assert_eq!(
+            kinds("A:B2"),
+            [Token::Identifier, Token::CellRangeOp, Token::CellRef],
+        );
+    }
+    #[test]
+    fn test_lex_identifiers() {
+        let kinds = |s: &str| tokenize(s).map(|t| t.inner).collect_vec();
+        assert_eq!(kinds("TaxRate"), [Token::Identifier]);
+        assert_eq!(kinds("Q1_Sales"), [Token::Identifier]);
+        assert_eq!(kinds("_total2"), [Token::Identifier]);
+        assert_eq!(kinds("A1B"), [Token::Identifier]);
+        assert_eq!(kinds("AB12"), [Token::CellRef]);
+        assert_eq!(kinds("A$1"), [Token::CellRef]);
+        assert_eq!(kinds("nB2"), [Token::CellRef]);
+        assert_eq!(kinds("true"), [Token::True]);
+        assert_eq!(
+            kinds("Revenue*A1"),
+            [Token::Identifier, Token::Mult, Token::CellRef],
+        );
+    }
+
+    #[test]
+    fn test_lex_reference_operators() {
+        let kinds = |s: &str| tokenize(s).map(|t| t.inner).collect_vec();
+        assert_eq!(
+            kinds("A1*10%"),
+            [
+                Token::CellRef,
+                Token::Mult,
+                Token::NumericLiteral,
+                Token::Percent
+            ],
+        );
+        assert_eq!(kinds("@A:A"), [Token::At, Token::ColumnRangeRef],);
+        assert_eq!(
+            kinds("A1 B2"),
+            [Token::CellRef, Token::Whitespace, Token::CellRef],
+        );
+        assert_eq!(
+            kinds("(A1,B2)"),
+            [
+                Token::LParen,
+                Token::CellRef,
+                Token::ArgSep,
+                Token::CellRef,
+                Token::RParen,
+            ],
+        );
+    }
+
     fn test_block_comment(expected_to_end: bool, s: &str) {
         let tokens = tokenize(s).collect_vec();
         if expected_to_end {
@@ -0,0 +1,143 @@
+//! Mapping from a cursor position in a formula to a location in its syntax
+//! tree, for signature help and completion while the user is typing.
+
+use itertools::Itertools;
+
+use crate::formulas::ast::{is_function_name, AstNode, AstNodeContents};
+use crate::formulas::lexer::{self, Token};
+use crate::formulas::parse_formula;
+use crate::Pos;
+
+/// Function call containing the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct CallAtCursor {
+    /// Name of the function, as written.
+    pub func_name: String,
+    /// Index of the argument containing the cursor.
+    pub arg_index: usize,
+}
+
+/// Returns the innermost function call whose argument list contains the byte
+/// index `cursor`, or `None` if the cursor is not inside a function call.
+///
+/// A formula that is still being typed usually has unclosed parentheses or
+/// strings, so if the formula does not parse then it is closed first.
+pub(super) fn call_at_cursor(source: &str, cursor: u32) -> Option<CallAtCursor> {
+    let ast = match parse_formula(source, Pos::ORIGIN) {
+        Ok(formula) => formula.ast,
+        Err(_) => {
+            parse_formula(&(source.to_string() + &closing_text(source)), Pos::ORIGIN)
+                .ok()?
+                .ast
+        }
+    };
+    innermost_call(&ast, cursor)
+}
+
+/// Returns the text that closes every unterminated string and unclosed
+/// bracket in a formula.
+fn closing_text(source: &str) -> String {
+    let mut ret = String::new();
+    let mut closers = vec![];
+    for token in lexer::tokenize(source) {
+        match token.inner {
+            Token::LParen | Token::FunctionCall => closers.push(')'),
+            Token::LBracket => closers.push(']'),
+            Token::LBrace => closers.push('}'),
+            Token::RParen | Token::RBracket | Token::RBrace => {
+                closers.pop();
+            }
+            Token::UnterminatedStringLiteral => {
+                // The token is only the opening quote, but everything after
+                // it is part of the string.
+                ret.extend(token.span.of_str(source).chars().next());
+                return ret + &closers.iter().rev().collect::<String>();
+            }
+            _ => (),
+        }
+    }
+    closers.iter().rev().collect()
+}
+
+fn innermost_call(node: &AstNode, cursor: u32) -> Option<CallAtCursor> {
+    let children = match &node.inner {
+        AstNodeContents::FunctionCall { args, .. } => args.iter().collect_vec(),
+        AstNodeContents::Call { func, args } => std::iter::once(&**func).chain(args).collect(),
+        AstNodeContents::Paren(contents) => vec![&**contents],
+        AstNodeContents::Array(rows) => rows.iter().flatten().collect(),
+        _ => vec![],
+    };
+    if let Some(call) = children
+        .iter()
+        .find_map(|child| innermost_call(child, cursor))
+    {
+        return Some(call);
+    }
+
+    let AstNodeContents::FunctionCall { func, args } = &node.inner else {
+        return None;
+    };
+    // The span of the function name includes the opening paren, and the span
+    // of the whole call includes the closing paren.
+    let is_in_args = func.span.end <= cursor && cursor < node.span.end;
+    if !is_function_name(&func.inner) || !is_in_args {
+        return None;
+    }
+    let arg_index = args
+        .iter()
+        .position(|arg| cursor <= arg.span.end)
+        .unwrap_or(args.len().saturating_sub(1));
+    Some(CallAtCursor {
+        func_name: func.inner.clone(),
+        arg_index,
+    })
+}
+
+/// Returns the opening quote and its byte index if the byte index `cursor`
+/// is inside a string literal.
+pub(super) fn open_quote_before_cursor(source: &str, cursor: u32) -> Option<(char, u32)> {
+    let before = &source[..(cursor as usize).min(source.len())];
+    lexer::tokenize(before)
+        .find(|token| token.inner == Token::UnterminatedStringLiteral)
+        .and_then(|token| Some((token.span.of_str(before).chars().next()?, token.span.start)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the call containing the cursor, which is marked with `|`.
+    fn call_at(source_with_cursor: &str) -> Option<(String, usize)> {
+        let cursor = source_with_cursor.find('|').unwrap() as u32;
+        let source = source_with_cursor.replacen('|', "", 1);
+        call_at_cursor(&source, cursor).map(|call| (call.func_name, call.arg_index))
+    }
+
+    #[test]
+    fn test_call_at_cursor() {
+        let call = |name: &str, i| Some((name.to_string(), i));
+
+        assert_eq!(call_at("SUM(|"), call("SUM", 0));
+        assert_eq!(call_at("SUM(A1|"), call("SUM", 0));
+        assert_eq!(call_at("SUM(A1,|"), call("SUM", 1));
+        assert_eq!(call_at("SUM(A1, |)"), call("SUM", 1));
+        assert_eq!(call_at("SUM(A1, B2|) + 1"), call("SUM", 1));
+        assert_eq!(call_at("IF(A1, ROUND(B2, |), 3"), call("ROUND", 1));
+        assert_eq!(call_at("IF(A1, ROUND(B2, 1), |"), call("IF", 2));
+        assert_eq!(call_at("IF(A1 = \"a|"), call("IF", 0));
+        assert_eq!(call_at("SUM({1, |"), call("SUM", 0));
+
+        assert_eq!(call_at("SU|M(A1)"), None);
+        assert_eq!(call_at("SUM(A1)|"), None);
+        assert_eq!(call_at("(1 + |"), None);
+        assert_eq!(call_at("1 + |"), None);
+    }
+
+    #[test]
+    fn test_open_quote_before_cursor() {
+        assert_eq!(open_quote_before_cursor("SUM('Sheet", 10), Some(('\'', 4)));
+        assert_eq!(open_quote_before_cursor("\"a\" & \"b", 9), Some(('"', 6)));
+        assert_eq!(open_quote_before_cursor("\"a\" & \"b\"", 10), None);
+        assert_eq!(open_quote_before_cursor("'Sheet 1'!A1", 12), None);
+    }
+}
@@ -0,0 +1,406 @@
+//! Static checks on formulas, so that the editor can show problems before the
+//! formula is evaluated.
+
+use std::collections::HashSet;
+
+use super::{expects_reference, param_at, range_of_span, MarkerData, MarkerSeverity};
+use crate::formulas::ast::{is_function_name, AstNode, AstNodeContents};
+use crate::formulas::{functions, parse_formula, Param, ParamKind, RangeRef};
+use crate::grid::Grid;
+use crate::{CellValue, Pos, RunErrorMsg, Span};
+
+/// Problem found in a formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub severity: MarkerSeverity,
+    pub message: String,
+}
+impl Diagnostic {
+    fn error(span: Span, message: impl ToString) -> Self {
+        Diagnostic {
+            span,
+            severity: MarkerSeverity::Error,
+            message: message.to_string(),
+        }
+    }
+    fn warning(span: Span, message: impl ToString) -> Self {
+        Diagnostic {
+            span,
+            severity: MarkerSeverity::Warning,
+            message: message.to_string(),
+        }
+    }
+
+    /// Converts the diagnostic to a Monaco marker, which uses 1-based line and
+    /// column numbers instead of byte indices.
+    pub fn to_marker_data(&self, source: &str) -> MarkerData {
+        let range = range_of_span(source, self.span);
+        MarkerData {
+            severity: self.severity,
+            message: self.message.clone(),
+            start_line_number: range.start_line_number,
+            start_column: range.start_column,
+            end_line_number: range.end_line_number,
+            end_column: range.end_column,
+        }
+    }
+}
+
+/// Parses a formula at `pos` and returns the problems that can be found
+/// without evaluating it: syntax errors, unknown functions, wrong argument
+/// counts, references to sheets that do not exist, and literals of the wrong
+/// type. Names that are not defined are reported as warnings, because a named
+/// range may be added later.
+pub fn diagnose_formula(grid: &Grid, source: &str, pos: Pos) -> Vec<Diagnostic> {
+    let ast = match parse_formula(source, pos) {
+        Ok(formula) => formula.ast,
+        Err(e) => {
+            let span = e.span.unwrap_or(Span {
+                start: 0,
+                end: source.len() as u32,
+            });
+            return vec![Diagnostic::error(span, e.msg)];
+        }
+    };
+
+    let mut local_names = HashSet::new();
+    collect_local_names(&ast, &mut local_names);
+
+    let mut diagnostics = vec![];
+    diagnose_node(grid, &local_names, &ast, &mut diagnostics);
+    diagnostics
+}
+
+/// Collects the names defined anywhere in the formula by `LET` or as lambda
+/// parameters, in uppercase. Scopes are not tracked, so a name defined
+/// anywhere is never reported as undefined.
+fn collect_local_names(node: &AstNode, names: &mut HashSet<String>) {
+    match &node.inner {
+        AstNodeContents::FunctionCall { func, args } => {
+            let defined_names = match func.inner.to_ascii_uppercase().as_str() {
+                "LET" => args.iter().rev().skip(1).rev().step_by(2).collect(),
+                "LAMBDA" => args.iter().rev().skip(1).collect(),
+                _ => vec![],
+            };
+            for arg in defined_names {
+                if let AstNodeContents::Identifier(name) = &arg.inner {
+                    names.insert(name.to_ascii_uppercase());
+                }
+            }
+            for arg in args {
+                collect_local_names(arg, names);
+            }
+        }
+        AstNodeContents::Call { func, args } => {
+            collect_local_names(func, names);
+            for arg in args {
+                collect_local_names(arg, names);
+            }
+        }
+        AstNodeContents::Paren(contents) => collect_local_names(contents, names),
+        AstNodeContents::Array(rows) => {
+            for elem in rows.iter().flatten() {
+                collect_local_names(elem, names);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn diagnose_node(
+    grid: &Grid,
+    local_names: &HashSet<String>,
+    node: &AstNode,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    match &node.inner {
+        AstNodeContents::FunctionCall { func, args } => {
+            let is_local = local_names.contains(&func.inner.to_ascii_uppercase());
+            match functions::lookup_function(&func.inner) {
+                _ if is_local && is_function_name(&func.inner) => (),
+                Some(f) => {
+                    diagnose_arg_count(f.name, &f.params, node.span, args, diagnostics);
+                    diagnose_arg_types(f.name, &f.params, args, diagnostics);
+                }
+                None if is_function_name(&func.inner) => {
+                    diagnostics.push(Diagnostic::error(func.span, RunErrorMsg::BadFunctionName));
+                }
+                // Reference operators such as `:` are not functions.
+                None => (),
+            }
+            for arg in args {
+                diagnose_node(grid, local_names, arg, diagnostics);
+            }
+        }
+        AstNodeContents::Call { func, args } => {
+            diagnose_node(grid, local_names, func, diagnostics);
+            for arg in args {
+                diagnose_node(grid, local_names, arg, diagnostics);
+            }
+        }
+        AstNodeContents::Paren(contents) => {
+            diagnose_node(grid, local_names, contents, diagnostics);
+        }
+        AstNodeContents::Array(rows) => {
+            for elem in rows.iter().flatten() {
+                diagnose_node(grid, local_names, elem, diagnostics);
+            }
+        }
+        AstNodeContents::CellRef(cell_ref) => {
+            diagnose_sheet_name(grid, &cell_ref.sheet, node.span, diagnostics);
+        }
+        AstNodeContents::RangeRef(range_ref) => {
+            if let RangeRef::RowRange { sheet, .. } | RangeRef::ColRange { sheet, .. } = range_ref {
+                diagnose_sheet_name(grid, sheet, node.span, diagnostics);
+            }
+        }
+        AstNodeContents::Identifier(name) => {
+            if !local_names.contains(&name.to_ascii_uppercase()) && grid.named_range(name).is_none()
+            {
+                let message = format!("There is no named range called {name:?}");
+                diagnostics.push(Diagnostic::warning(node.span, message));
+            }
+        }
+        AstNodeContents::BadCellRef => {
+            diagnostics.push(Diagnostic::error(node.span, RunErrorMsg::BadCellReference));
+        }
+        AstNodeContents::Empty
+        | AstNodeContents::String(_)
+        | AstNodeContents::Number(_)
+        | AstNodeContents::Bool(_) => (),
+    }
+}
+
+fn diagnose_sheet_name(
+    grid: &Grid,
+    sheet: &Option<String>,
+    span: Span,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if let Some(sheet_name) = sheet {
+        if grid.try_sheet_from_formula_ref(sheet_name).is_none() {
+            let message = format!("There is no sheet called {sheet_name:?}");
+            diagnostics.push(Diagnostic::error(span, message));
+        }
+    }
+}
+
+/// Checks the number of arguments passed to a function, using the same errors
+/// that evaluation would return.
+fn diagnose_arg_count(
+    func_name: &'static str,
+    params: &[Param],
+    span: Span,
+    args: &[AstNode],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    // A union such as `(A1, B2)` is passed as several arguments.
+    if args.iter().any(is_union) {
+        return;
+    }
+
+    let mut required_params = params.iter().filter(|param| param.is_required());
+    if let Some(missing) = required_params.nth(args.len()) {
+        let msg = RunErrorMsg::MissingRequiredArgument {
+            func_name: func_name.into(),
+            arg_name: missing.name.into(),
+        };
+        diagnostics.push(Diagnostic::error(span, msg));
+    }
+
+    let is_repeating = params.iter().any(|p| p.kind == ParamKind::Repeating);
+    if let Some(extra_arg) = args.get(params.len()).filter(|_| !is_repeating) {
+        let msg = RunErrorMsg::TooManyArguments {
+            func_name: func_name.into(),
+            max_arg_count: params.len(),
+        };
+        diagnostics.push(Diagnostic::error(extra_arg.span, msg));
+    }
+}
+
+/// Checks for literals that evaluation would reject: text that is not a
+/// number passed where a number is expected, and values that are not cell
+/// references passed where a cell reference is expected.
+fn diagnose_arg_types(
+    func_name: &'static str,
+    params: &[Param],
+    args: &[AstNode],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if args.iter().any(is_union) {
+        return;
+    }
+
+    for (i, arg) in args.iter().enumerate() {
+        let Some(param) = param_at(params, i) else {
+            continue;
+        };
+        let arg = unparenthesize(arg);
+
+        if param.numeric {
+            if let AstNodeContents::String(s) = &arg.inner {
+                if let Err(msg) = f64::try_from(&CellValue::Text(s.clone())) {
+                    diagnostics.push(Diagnostic::error(arg.span, msg));
+                }
+            }
+        }
+
+        let is_literal = matches!(
+            arg.inner,
+            AstNodeContents::String(_)
+                | AstNodeContents::Number(_)
+                | AstNodeContents::Bool(_)
+                | AstNodeContents::Array(_)
+        );
+        if expects_reference(func_name, i) && is_literal {
+            let msg = RunErrorMsg::Expected {
+                expected: "cell reference".into(),
+                got: Some(arg.inner.type_string().into()),
+            };
+            diagnostics.push(Diagnostic::error(arg.span, msg));
+        }
+    }
+}
+
+fn unparenthesize(node: &AstNode) -> &AstNode {
+    match &node.inner {
+        AstNodeContents::Paren(contents) => unparenthesize(contents),
+        _ => node,
+    }
+}
+
+fn is_union(node: &AstNode) -> bool {
+    matches!(
+        &unparenthesize(node).inner,
+        AstNodeContents::FunctionCall { func, .. } if func.inner == ","
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::NamedRange;
+    use crate::SheetRect;
+
+    fn diagnose(grid: &Grid, source: &str) -> Vec<(MarkerSeverity, String, String)> {
+        diagnose_formula(grid, source, pos![B2])
+            .into_iter()
+            .map(|d| (d.severity, d.message, d.span.of_str(source).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diagnose_formula() {
+        use MarkerSeverity::{Error, Warning};
+
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheets()[0].id;
+        grid.set_named_range(NamedRange {
+            name: "TaxRate".to_string(),
+            sheet_rect: Some(SheetRect::single_pos(pos![A1], sheet_id)),
+        });
+
+        assert_eq!(diagnose(&grid, "SUM(A1:A10, TaxRate) * 2"), vec![]);
+        assert_eq!(
+            diagnose(&grid, "LET(x, 1, f, LAMBDA(y, y + x), f(2))"),
+            vec![],
+        );
+
+        // Syntax error
+        let diagnostics = diagnose(&grid, "SUM(1,");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].0, Error);
+
+        // Unknown function and unknown name
+        assert_eq!(
+            diagnose(&grid, "SUMM(1) + Rate"),
+            vec![
+                (
+                    Error,
+                    "There is no function with this name".into(),
+                    "SUMM(".into(),
+                ),
+                (
+                    Warning,
+                    "There is no named range called \"Rate\"".into(),
+                    "Rate".into(),
+                ),
+            ],
+        );
+
+        // Argument counts
+        assert_eq!(
+            diagnose(&grid, "IF(TRUE, 1) + PI(3) + SUM()"),
+            vec![
+                (
+                    Error,
+                    "Function `IF` is missing required argument `f`".into(),
+                    "IF(TRUE, 1)".into(),
+                ),
+                (
+                    Error,
+                    RunErrorMsg::TooManyArguments {
+                        func_name: "PI".into(),
+                        max_arg_count: 0,
+                    }
+                    .to_string(),
+                    "3".into(),
+                ),
+            ],
+        );
+        assert_eq!(diagnose(&grid, "ROUND((A1, B1))"), vec![]);
+
+        // Sheet names
+        assert_eq!(diagnose(&grid, "'Sheet 1'!A1 + 'Sheet 1'!A:A"), vec![]);
+        assert_eq!(
+            diagnose(&grid, "SUM('Missing'!A1:B2)"),
+            vec![(
+                Error,
+                "There is no sheet called \"Missing\"".into(),
+                "'Missing'!A1".into(),
+            )],
+        );
+
+        // Invalid cell references, such as those whose cells were deleted
+        assert_eq!(
+            diagnose(&grid, "SUM(#REF!, A1) + 1"),
+            vec![(
+                Error,
+                RunErrorMsg::BadCellReference.to_string(),
+                "#REF!".into(),
+            )],
+        );
+
+        // Literal types
+        assert_eq!(
+            diagnose(&grid, "SQRT(\"abc\") + SUM(\"3\", (\"x\")) & ROW(5)"),
+            vec![
+                (Error, "Expected number, got text".into(), "\"abc\"".into()),
+                (Error, "Expected number, got text".into(), "\"x\"".into()),
+                (
+                    Error,
+                    "Expected cell reference, got numeric literal".into(),
+                    "5".into(),
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_marker_data() {
+        let source = "LEN(\"é\") +\n  SUMM(1)";
+        let diagnostics = diagnose_formula(&Grid::new(), source, Pos::ORIGIN);
+        assert_eq!(
+            diagnostics[0].to_marker_data(source),
+            MarkerData {
+                severity: MarkerSeverity::Error,
+                message: "There is no function with this name".to_string(),
+                start_line_number: 2,
+                start_column: 3,
+                end_line_number: 2,
+                end_column: 8,
+            },
+        );
+    }
+}
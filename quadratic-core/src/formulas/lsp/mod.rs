@@ -1,18 +1,25 @@
 //! Language server implementation for Monaco editor
 
+use std::borrow::Cow;
+
 use lazy_static::lazy_static;
 use serde::Serialize;
 
+mod cursor;
+pub mod diagnostics;
 pub mod types;
 
+pub use diagnostics::{diagnose_formula, Diagnostic};
 pub use types::*;
 
-use super::functions;
+use super::{functions, Param, ParamKind};
+use crate::grid::Grid;
+use crate::{Pos, Span};
 
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionList<'a> {
-    suggestions: &'a [CompletionItem],
+    suggestions: Cow<'a, [CompletionItem]>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -35,16 +42,207 @@ lazy_static! {
             insert_text_rules: Some(CompletionItemInsertTextRule::INSERT_AS_SNIPPET),
             kind: CompletionItemKind::Function,
             label: f.name.to_string(),
+            range: None,
         })
         .collect();
 }
 
+/// Functions that can return a cell reference, which are the only functions
+/// suggested where a cell reference is expected.
+const REFERENCE_FUNCTIONS: &[&str] = &["INDEX", "OFFSET", "INDIRECT"];
+
+/// Functions that don't return a value, which are not suggested for ordinary
+/// arguments. They are still suggested outside of any function call and in
+/// `LET`, where they can be called or given a name.
+const NON_VALUE_FUNCTIONS: &[&str] = &["LAMBDA"];
+
 pub fn provide_completion_items() -> CompletionList<'static> {
     CompletionList {
-        suggestions: &FUNCTION_COMPLETION_ITEMS,
+        suggestions: Cow::Borrowed(&FUNCTION_COMPLETION_ITEMS),
+    }
+}
+
+/// Returns completion items for the byte index `cursor` in a formula.
+///
+/// Inside a string, the names of sheets are suggested. Otherwise, named ranges
+/// and functions are suggested; where a cell reference is expected, only
+/// functions that can return a cell reference are suggested, where a lambda is
+/// expected, only `LAMBDA` is suggested, and functions that don't return a
+/// value are not suggested for other arguments.
+pub fn provide_completion_items_at(
+    grid: &Grid,
+    source: &str,
+    cursor: u32,
+) -> CompletionList<'static> {
+    if let Some((quote, quote_index)) = cursor::open_quote_before_cursor(source, cursor) {
+        let range = range_of_span(
+            source,
+            Span {
+                start: quote_index + 1,
+                end: cursor,
+            },
+        );
+        let suggestions = grid
+            .sheets()
+            .iter()
+            .map(|sheet| CompletionItem {
+                detail: Some("Sheet".to_string()),
+                insert_text: Some(format!(
+                    "{}{quote}!",
+                    sheet
+                        .name
+                        .replace('\\', "\\\\")
+                        .replace(quote, &format!("\\{quote}")),
+                )),
+                kind: CompletionItemKind::Reference,
+                label: sheet.name.clone(),
+                range: Some(range),
+                ..Default::default()
+            })
+            .collect();
+        return CompletionList {
+            suggestions: Cow::Owned(suggestions),
+        };
+    }
+
+    let call = cursor::call_at_cursor(source, cursor);
+    let expected_arg = call.as_ref().and_then(|call| {
+        let f = functions::lookup_function(&call.func_name)?;
+        Some((f.name, call.arg_index, param_at(&f.params, call.arg_index)?))
+    });
+    let expects_lambda = expected_arg.is_some_and(|(_, _, param)| param.name == "lambda");
+    let expects_reference =
+        expected_arg.is_some_and(|(func_name, i, _)| expects_reference(func_name, i));
+    let expects_value = call
+        .as_ref()
+        .is_some_and(|call| !call.func_name.eq_ignore_ascii_case("LET"));
+
+    let named_ranges = grid
+        .named_ranges()
+        .iter()
+        .filter(|_| !expects_lambda)
+        .map(|named_range| CompletionItem {
+            detail: Some("Named range".to_string()),
+            insert_text: Some(named_range.name.clone()),
+            kind: CompletionItemKind::Variable,
+            label: named_range.name.clone(),
+            ..Default::default()
+        });
+    let functions = FUNCTION_COMPLETION_ITEMS
+        .iter()
+        .filter(|item| {
+            if expects_lambda {
+                item.label == "LAMBDA"
+            } else if expects_reference {
+                REFERENCE_FUNCTIONS.contains(&item.label.as_str())
+            } else {
+                !expects_value || !NON_VALUE_FUNCTIONS.contains(&item.label.as_str())
+            }
+        })
+        .cloned();
+    CompletionList {
+        suggestions: Cow::Owned(named_ranges.chain(functions).collect()),
+    }
+}
+
+/// Returns signature help for the function call containing the byte index
+/// `cursor` in a formula, with the parameter at the cursor highlighted.
+pub fn provide_signature_help(source: &str, cursor: u32) -> Option<SignatureHelp> {
+    let call = cursor::call_at_cursor(source, cursor)?;
+    let f = functions::lookup_function(&call.func_name)?;
+
+    let label = f.usages_string();
+    let parameters = param_label_offsets(&label, &f.params)?;
+    let active_parameter = param_at(&f.params, call.arg_index)
+        .and_then(|param| f.params.iter().position(|p| p == param))
+        .unwrap_or(call.arg_index);
+
+    Some(SignatureHelp {
+        signatures: vec![SignatureInformation {
+            label,
+            documentation: Some(Documentation::Markdown(MarkdownString {
+                value: f.lsp_full_docs(),
+            })),
+            parameters,
+        }],
+        active_signature: 0,
+        active_parameter: active_parameter as u32,
+    })
+}
+
+/// Returns the location of each parameter name in a signature label such as
+/// `IF(condition, t, f)`, or `None` if any parameter cannot be found.
+fn param_label_offsets(label: &str, params: &[Param]) -> Option<Vec<ParameterInformation>> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut search_start = label.find('(')?;
+    params
+        .iter()
+        .map(|param| {
+            let start = label[search_start..]
+                .match_indices(param.name)
+                .map(|(i, _)| search_start + i)
+                .find(|&i| {
+                    let end = i + param.name.len();
+                    !label[..i].ends_with(is_word_char) && !label[end..].starts_with(is_word_char)
+                })?;
+            search_start = start + param.name.len();
+            Some(ParameterInformation {
+                label: [start as u32, search_start as u32],
+            })
+        })
+        .collect()
+}
+
+/// Returns the parameter that receives the argument at `index`. Arguments
+/// after a repeating parameter are all received by that parameter.
+fn param_at(params: &[Param], index: usize) -> Option<&Param> {
+    match params.iter().position(|p| p.kind == ParamKind::Repeating) {
+        Some(repeating) if index >= repeating => params.get(repeating),
+        _ => params.get(index),
+    }
+}
+
+/// Returns whether the argument at `index` to a function must be a cell
+/// reference.
+fn expects_reference(func_name: &str, index: usize) -> bool {
+    matches!(func_name, "OFFSET" | "ROW" | "COLUMN") && index == 0
+}
+
+/// Returns the 1-based line number and column of a byte index in `source`.
+/// Columns count UTF-16 code units, like JavaScript strings.
+fn line_and_column(source: &str, byte_index: u32) -> (u32, u32) {
+    let before = &source[..(byte_index as usize).min(source.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line_number = before.matches('\n').count() + 1;
+    let column = before[line_start..].encode_utf16().count() + 1;
+    (line_number as u32, column as u32)
+}
+
+/// Returns the Monaco range of a span in `source`.
+fn range_of_span(source: &str, span: Span) -> Range {
+    let (start_line_number, start_column) = line_and_column(source, span.start);
+    let (end_line_number, end_column) = line_and_column(source, span.end);
+    Range {
+        start_line_number,
+        start_column,
+        end_line_number,
+        end_column,
     }
 }
 
+/// Converts an offset in UTF-16 code units, as used by JavaScript and
+/// Monaco, to a byte index in `source`.
+pub fn byte_index_from_utf16_offset(source: &str, offset: u32) -> u32 {
+    let mut utf16_count = 0;
+    for (i, c) in source.char_indices() {
+        if utf16_count >= offset as usize {
+            return i as u32;
+        }
+        utf16_count += c.len_utf16();
+    }
+    source.len() as u32
+}
+
 pub fn provide_hover(partial_function_name: &str) -> Option<Hover> {
     let function = functions::lookup_function(partial_function_name)?;
     Some(Hover {
@@ -53,3 +251,140 @@ pub fn provide_hover(partial_function_name: &str) -> Option<Hover> {
         }],
     })
 }
+
+/// Returns markers for the problems in a formula at `pos`, such as unknown
+/// functions and wrong argument counts, so that the editor can underline them
+/// before the formula is evaluated.
+pub fn provide_diagnostics(grid: &Grid, source: &str, pos: Pos) -> Vec<MarkerData> {
+    diagnose_formula(grid, source, pos)
+        .iter()
+        .map(|diagnostic| diagnostic.to_marker_data(source))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::NamedRange;
+    use crate::SheetRect;
+
+    /// Splits a formula at the cursor, which is marked with `|`.
+    fn split_cursor(source_with_cursor: &str) -> (String, u32) {
+        let cursor = source_with_cursor.find('|').unwrap() as u32;
+        (source_with_cursor.replacen('|', "", 1), cursor)
+    }
+
+    fn labels(list: CompletionList<'_>) -> Vec<String> {
+        list.suggestions
+            .iter()
+            .map(|item| item.label.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_signature_help() {
+        let help = |s| {
+            let (source, cursor) = split_cursor(s);
+            let help = provide_signature_help(&source, cursor)?;
+            let signature = &help.signatures[0];
+            let [start, end] = signature.parameters[help.active_parameter as usize].label;
+            Some(signature.label[start as usize..end as usize].to_string())
+        };
+
+        assert_eq!(help("IF(A1, |"), Some("t".to_string()));
+        assert_eq!(help("IF(A1 > 0, \"yes\", |)"), Some("f".to_string()));
+        assert_eq!(help("SUM(1, 2, 3|"), Some("numbers".to_string()));
+        assert_eq!(
+            help("SUMIF(A1:A5, \">0\", |"),
+            Some("numbers_range".to_string())
+        );
+        assert_eq!(help("OFFSET(A1, 1, ROUND(|"), Some("number".to_string()));
+        assert_eq!(help("SUM(1)|"), None);
+        assert_eq!(help("NOTAFUNCTION(|"), None);
+
+        let (source, cursor) = split_cursor("IF(A1, |");
+        let signature = &provide_signature_help(&source, cursor).unwrap().signatures[0];
+        assert_eq!(signature.label, "IF(condition, t, f)");
+        assert_eq!(
+            signature.parameters,
+            [[3, 12], [14, 15], [17, 18]].map(|label| ParameterInformation { label }),
+        );
+    }
+
+    #[test]
+    fn test_every_function_has_signature_help() {
+        let categories = functions::CATEGORIES.iter();
+        let completed = categories.filter(|category| category.include_in_completions);
+        for f in completed.flat_map(|category| (category.get_functions)()) {
+            assert!(
+                param_label_offsets(&f.usages_string(), &f.params).is_some(),
+                "parameters of {} are missing from its usage string",
+                f.name,
+            );
+        }
+    }
+
+    #[test]
+    fn test_context_aware_completion() {
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheets()[0].id;
+        let sheet_id_2 = grid.add_sheet(None);
+        grid.try_sheet_mut(sheet_id_2).unwrap().name = "Bob's Sheet".to_string();
+        grid.set_named_range(NamedRange {
+            name: "TaxRate".to_string(),
+            sheet_rect: Some(SheetRect::single_pos(pos![A1], sheet_id)),
+        });
+
+        let complete = |s| {
+            let (source, cursor) = split_cursor(s);
+            provide_completion_items_at(&grid, &source, cursor)
+        };
+
+        // Sheet names after a single or double quote
+        let list = complete("SUM('Sh|");
+        assert_eq!(labels(list.clone()), ["Sheet 1", "Bob's Sheet"]);
+        let item = &list.suggestions[1];
+        assert_eq!(item.insert_text.as_deref(), Some("Bob\\'s Sheet'!"));
+        let range = item.range.unwrap();
+        assert_eq!((range.start_column, range.end_column), (6, 8));
+        let list = complete("SUM(\"|");
+        assert_eq!(labels(list.clone()), ["Sheet 1", "Bob's Sheet"]);
+        let item = &list.suggestions[1];
+        assert_eq!(item.insert_text.as_deref(), Some("Bob's Sheet\"!"));
+
+        // Named ranges and all functions
+        let all = labels(complete("1 + |"));
+        assert_eq!(all[0], "TaxRate");
+        assert!(all.contains(&"SUM".to_string()));
+        assert!(all.contains(&"LAMBDA".to_string()));
+        assert_eq!(labels(complete("LET(f, |")), all);
+
+        // No `LAMBDA` where a value is expected
+        let values = labels(complete("SUM(|"));
+        assert!(values.contains(&"SUM".to_string()));
+        assert!(!values.contains(&"LAMBDA".to_string()));
+        assert_eq!(values.len(), all.len() - 1);
+        assert_eq!(labels(complete("IF(A1, 1, |")), values);
+
+        // Only functions that return a reference
+        assert_eq!(
+            labels(complete("ROW(|")),
+            ["TaxRate", "INDIRECT", "INDEX", "OFFSET"],
+        );
+
+        // Only `LAMBDA`
+        assert_eq!(labels(complete("BYROW(A1:C3, |")), ["LAMBDA"]);
+    }
+
+    #[test]
+    fn test_byte_index_from_utf16_offset() {
+        let source = "\"é😀\" & A1";
+        assert_eq!(byte_index_from_utf16_offset(source, 0), 0);
+        assert_eq!(byte_index_from_utf16_offset(source, 2), 3);
+        assert_eq!(byte_index_from_utf16_offset(source, 4), 7);
+        assert_eq!(
+            byte_index_from_utf16_offset(source, 100),
+            source.len() as u32
+        );
+    }
+}
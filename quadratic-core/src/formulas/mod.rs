@@ -4,14 +4,17 @@ mod tests;
 
 mod ast;
 mod cell_ref;
+mod conditional;
 mod criteria;
 mod ctx;
 #[allow(clippy::vec_init_then_push)]
 pub mod functions;
+mod lambda;
 mod lexer;
 pub mod lsp;
 mod params;
 mod parser;
+mod reference;
 mod wildcards;
 
 use ast::AstNode;
@@ -21,8 +24,11 @@ pub use criteria::Criterion;
 pub use ctx::Ctx;
 use functions::FormulaFnArgs;
 use params::{Param, ParamKind};
-pub use parser::{find_cell_references, parse_formula};
-use wildcards::wildcard_pattern_to_regex;
+pub use parser::{
+    a1_to_r1c1, find_cell_references, find_named_ranges, is_valid_named_range_name, parse_formula,
+    r1c1_to_a1, replace_cell_references, replace_sheet_references,
+};
+use wildcards::{wildcard_pattern_to_regex, wildcard_search_regex};
 
 /// Escapes a formula string.
 pub fn escape_string(s: &str) -> String {
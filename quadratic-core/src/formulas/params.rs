@@ -6,8 +6,20 @@ pub(super) struct Param {
     pub name: &'static str,
     pub kind: ParamKind,
     pub zip_mapped: bool,
+    /// Whether the argument is coerced to a number.
+    pub numeric: bool,
 }
 impl Param {
+    /// Constructs a parameter description for a function that is not defined
+    /// using `formula_fn!`.
+    pub(super) fn new(name: &'static str, kind: ParamKind) -> Self {
+        Param {
+            name,
+            kind,
+            zip_mapped: false,
+            numeric: false,
+        }
+    }
     /// Returns whether the parameter is required.
     pub(super) fn is_required(&self) -> bool {
         match self.kind {
@@ -40,6 +52,14 @@ pub(super) enum ParamKind {
     Repeating,
 }
 
+/// Returns whether a parameter type, as written in `formula_fn!`, is coerced
+/// to a number.
+pub(super) fn is_numeric_type(type_str: &str) -> bool {
+    type_str
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| matches!(word, "f64" | "i64" | "u32" | "BigDecimal"))
+}
+
 /// Generates an argument completion snippet for a function, not including the
 /// function name.
 ///
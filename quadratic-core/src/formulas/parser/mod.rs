@@ -1,6 +1,8 @@
 //! Parser that turns a flat list of tokens directly into an AST.
 
 use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::{Regex, RegexBuilder};
 
 #[macro_use]
 mod macros;
@@ -43,6 +45,271 @@ pub fn find_cell_references(source: &str, pos: Pos) -> Vec<Spanned<RangeRef>> {
     ret
 }
 
+/// Returns the names of all named ranges referred to in a formula, as
+/// written.
+pub fn find_named_ranges(source: &str) -> Vec<Spanned<String>> {
+    lexer::tokenize(source)
+        .filter(|t| t.inner == Token::Identifier)
+        .map(|t| Spanned {
+            span: t.span,
+            inner: t.span.of_str(source).to_string(),
+        })
+        .collect()
+}
+
+/// Returns whether `name` can be used as the name of a named range. A name
+/// consists of a letter or underscore followed by any letters, digits, and/or
+/// underscores, and must not be a boolean or a cell reference in any case,
+/// including an R1C1-style cell reference such as `RC` or `R2C3`.
+pub fn is_valid_named_range_name(name: &str) -> bool {
+    let tokens = lexer::tokenize(&name.to_ascii_uppercase()).collect_vec();
+    matches!(
+        tokens.as_slice(),
+        [Spanned {
+            inner: Token::Identifier,
+            ..
+        }]
+    ) && CellRef::parse_r1c1(name).is_none()
+}
+
+/// Rewrites every cell reference in a formula.
+///
+/// `f` is called with each reference (relative to `pos`) and returns its
+/// replacement (relative to `new_pos`), or `None` if the reference is no longer
+/// valid, in which case it is replaced with `#REF!`. Sheet names and
+/// everything else in the formula are kept as written.
+pub fn replace_cell_references(
+    source: &str,
+    pos: Pos,
+    new_pos: Pos,
+    f: impl FnMut(&RangeRef) -> Option<RangeRef>,
+) -> String {
+    rewrite_cell_references(source, pos, new_pos, ReferenceStyle::A1, f)
+}
+
+/// Rewrites every A1-style cell reference in a formula at `pos` in R1C1-style
+/// notation. Sheet names and everything else in the formula are kept as
+/// written.
+pub fn a1_to_r1c1(source: &str, pos: Pos) -> String {
+    rewrite_cell_references(source, pos, pos, ReferenceStyle::R1C1, |r| Some(r.clone()))
+}
+
+/// Rewrites every R1C1-style cell reference in a formula at `pos` in A1-style
+/// notation, so that it can be parsed by [`parse_formula()`]. Sheet names,
+/// string literals, comments, and everything else in the formula are kept as
+/// written.
+pub fn r1c1_to_a1(source: &str, pos: Pos) -> String {
+    lazy_static! {
+        /// Regex that matches an R1C1-style cell reference, or a whole-row or
+        /// whole-column range reference.
+        ///
+        /// \bR(C)?C(C)?|\bR(C)?:R(C)?|\bC(C)?:C(C)?
+        ///    R(C)?C(C)?                            cell reference
+        ///                R(C)?:R(C)?               row range
+        ///                            C(C)?:C(C)?   column range
+        /// (where each `(C)` is a coordinate, such as `[-1]` or `n5`)
+        static ref R1C1_REFERENCE_REGEX: Regex = {
+            let c = r"(\[\s*[+-]?\d+\s*\]|n?\d+)?";
+            let pattern = format!(r"\bR{c}C{c}|\bR{c}:R{c}|\bC{c}:C{c}");
+            RegexBuilder::new(&pattern).case_insensitive(true).build().unwrap()
+        };
+    }
+
+    // References are not replaced inside strings, comments, or sheet names.
+    let protected_spans = lexer::tokenize(source)
+        .filter(|t| {
+            matches!(
+                t.inner,
+                Token::StringLiteral
+                    | Token::UnterminatedStringLiteral
+                    | Token::Comment
+                    | Token::UnterminatedBlockComment
+                    | Token::UnquotedSheetReference
+            )
+        })
+        .map(|t| t.span.start as usize..t.span.end as usize)
+        .collect_vec();
+
+    let mut ret = String::new();
+    let mut last_end = 0;
+    for m in R1C1_REFERENCE_REGEX.find_iter(source) {
+        if protected_spans.iter().any(|span| span.contains(&m.start())) {
+            continue;
+        }
+        // Skip anything that is only the start of a longer name, such as
+        // `RC_total` or a function call such as `RC(`.
+        let next_char = source[m.end()..].chars().next();
+        if next_char.is_some_and(|c| c.is_alphanumeric() || "_.([".contains(c)) {
+            continue;
+        }
+        let Some(range_ref) = RangeRef::parse_r1c1(m.as_str()) else {
+            continue;
+        };
+        ret.push_str(&source[last_end..m.start()]);
+        ret.push_str(&range_ref.a1_string(pos));
+        last_end = m.end();
+    }
+    ret.push_str(&source[last_end..]);
+    ret
+}
+
+/// Notation used for cell references when rewriting a formula.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ReferenceStyle {
+    A1,
+    R1C1,
+}
+
+fn rewrite_cell_references(
+    source: &str,
+    pos: Pos,
+    new_pos: Pos,
+    style: ReferenceStyle,
+    mut f: impl FnMut(&RangeRef) -> Option<RangeRef>,
+) -> String {
+    let tokens = lexer::tokenize(source)
+        .filter(|t| !t.inner.is_skip())
+        .collect_vec();
+    let mut p = Parser::new(source, &tokens, pos);
+
+    // Each replacement is a span of the source and the text to put there.
+    let mut replacements: Vec<(Span, String)> = vec![];
+    while !p.is_done() {
+        let before = p;
+        let Some(Ok(range_ref)) = p.try_parse(rules::CellRangeReference) else {
+            p.next();
+            continue;
+        };
+        let new_ref = f(&range_ref.inner);
+        let is_unchanged = new_ref.as_ref() == Some(&range_ref.inner) && pos == new_pos;
+        if is_unchanged && style == ReferenceStyle::A1 {
+            continue;
+        }
+        // Only the tokens for the cell coordinates are replaced, so that sheet
+        // names are preserved.
+        let end_span = p.span();
+        match (&range_ref.inner, new_ref) {
+            (_, None) => replacements.push((range_ref.span, "#REF!".to_string())),
+            (RangeRef::Cell { .. }, Some(RangeRef::Cell { pos })) => {
+                replacements.push((end_span, cell_string(pos, new_pos, style)));
+            }
+            (RangeRef::CellRange { .. }, Some(RangeRef::CellRange { start, end })) => {
+                let mut q = before;
+                let start_span = match q.parse(rules::CellReference) {
+                    Ok(_) => q.span(),
+                    Err(_) => continue,
+                };
+                replacements.push((start_span, cell_string(start, new_pos, style)));
+                replacements.push((end_span, cell_string(end, new_pos, style)));
+            }
+            (RangeRef::RowRange { .. }, Some(new_ref @ RangeRef::RowRange { .. }))
+            | (RangeRef::ColRange { .. }, Some(new_ref @ RangeRef::ColRange { .. })) => {
+                replacements.push((end_span, range_string(new_ref, new_pos, style)));
+            }
+            (_, Some(other)) => {
+                replacements.push((range_ref.span, range_string(other, new_pos, style)));
+            }
+        }
+    }
+
+    let mut ret = source.to_string();
+    for (span, replacement) in replacements.into_iter().rev() {
+        ret.replace_range(span.start as usize..span.end as usize, &replacement);
+    }
+    ret
+}
+
+/// Rewrites the sheet name of every cell reference in a formula.
+///
+/// `f` is called with each sheet name as written and returns its replacement,
+/// or `None` if the sheet no longer exists, in which case the whole reference
+/// is replaced with `#REF!`. References without a sheet name are kept as
+/// written, and replaced sheet names keep the quotes they were written with.
+pub fn replace_sheet_references(source: &str, mut f: impl FnMut(&str) -> Option<String>) -> String {
+    let tokens = lexer::tokenize(source)
+        .filter(|t| !t.inner.is_skip())
+        .collect_vec();
+    let mut p = Parser::new(source, &tokens, Pos::ORIGIN);
+
+    // Each replacement is a span of the source and the text to put there.
+    let mut replacements: Vec<(Span, String)> = vec![];
+    while !p.is_done() {
+        let before = p;
+        let Some(Ok(range_ref)) = p.try_parse(rules::CellRangeReference) else {
+            p.next();
+            continue;
+        };
+
+        // Walk the reference again to find the span of each sheet name.
+        let mut q = before;
+        let mut sheet_replacements = vec![];
+        let mut is_valid = true;
+        loop {
+            let start_span = q.peek_next_span();
+            if let Some(Ok(sheet_name)) = q.try_parse(rules::SheetRefPrefix) {
+                match f(&sheet_name) {
+                    Some(new_name) if new_name == sheet_name => (),
+                    Some(new_name) => sheet_replacements.push((
+                        Span::merge(start_span, q.span()),
+                        sheet_prefix_string(&new_name, start_span.of_str(source)),
+                    )),
+                    None => is_valid = false,
+                }
+            }
+            q.next();
+            if q.try_parse(Token::CellRangeOp).is_none() {
+                break;
+            }
+        }
+
+        if is_valid {
+            replacements.extend(sheet_replacements);
+        } else {
+            replacements.push((range_ref.span, "#REF!".to_string()));
+        }
+    }
+
+    let mut ret = source.to_string();
+    for (span, replacement) in replacements.into_iter().rev() {
+        ret.replace_range(span.start as usize..span.end as usize, &replacement);
+    }
+    ret
+}
+
+/// Returns the sheet prefix for `sheet_name`, such as `'Sheet 1'!`, quoted
+/// the same way as the sheet prefix `original`. Sheet names that were not
+/// quoted are only quoted if they need to be.
+fn sheet_prefix_string(sheet_name: &str, original: &str) -> String {
+    let quote = match original.chars().next() {
+        Some(quote @ ('\'' | '"')) => quote,
+        _ if lexer::UNQUOTED_SHEET_REFERENCE.is_match(&format!("{sheet_name}!")) => {
+            return format!("{sheet_name}!");
+        }
+        _ => '\'',
+    };
+    let escaped = sheet_name
+        .replace('\\', "\\\\")
+        .replace(quote, &format!("\\{quote}"));
+    format!("{quote}{escaped}{quote}!")
+}
+
+/// Returns the string for a cell reference without its sheet name.
+fn cell_string(cell_ref: CellRef, base: Pos, style: ReferenceStyle) -> String {
+    let pos = CellRef {
+        sheet: None,
+        ..cell_ref
+    };
+    range_string(RangeRef::Cell { pos }, base, style)
+}
+
+/// Returns the string for a range reference.
+fn range_string(range_ref: RangeRef, base: Pos, style: ReferenceStyle) -> String {
+    match style {
+        ReferenceStyle::A1 => range_ref.a1_string(base),
+        ReferenceStyle::R1C1 => range_ref.r1c1_string(),
+    }
+}
+
 /// Token parser used to assemble an AST.
 #[derive(Debug, Copy, Clone)]
 pub struct Parser<'a> {
@@ -158,6 +425,13 @@ impl<'a> Parser<'a> {
         tmp.next()
     }
 
+    /// Returns the token immediately after the one at the cursor, without
+    /// skipping whitespace/comments and without mutably moving the cursor.
+    pub fn peek_next_noskip(self) -> Option<Token> {
+        let mut tmp = self;
+        tmp.next_noskip()
+    }
+
     /// Returns the span of the token after the one at the cursor, without
     /// mutably moving the cursor.
     pub fn peek_next_span(self) -> Span {
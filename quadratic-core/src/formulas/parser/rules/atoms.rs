@@ -1,3 +1,5 @@
+use bigdecimal::{BigDecimal, ToPrimitive, Zero};
+
 use super::*;
 
 /// Matches a string literal.
@@ -32,9 +34,23 @@ impl SyntaxRule for NumericLiteral {
     fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
         match p.next() {
             Some(Token::NumericLiteral) => {
-                let Ok(n) = p.token_str().parse::<f64>() else {
+                let Ok(n) = p.token_str().parse::<BigDecimal>() else {
                     return Err(RunErrorMsg::BadNumber.with_span(p.span()));
                 };
+                // Numbers that don't fit in an `f64` would become infinite or
+                // zero in most functions, and huge exponents such as
+                // `1e999999999` are very slow to compute with.
+                if !n.is_zero() {
+                    let magnitude = crate::formulas::functions::util::magnitude(&n);
+                    if magnitude < f64::MIN_10_EXP as i64 {
+                        return Err(RunErrorMsg::BadNumber.with_span(p.span()));
+                    }
+                    if magnitude > f64::MAX_10_EXP as i64
+                        || !matches!(n.to_f64(), Some(f) if f.is_finite())
+                    {
+                        return Err(RunErrorMsg::Overflow.with_span(p.span()));
+                    }
+                }
                 Ok(AstNode {
                     span: p.span(),
                     inner: ast::AstNodeContents::Number(n),
@@ -111,6 +127,35 @@ impl SyntaxRule for CellReference {
     }
 }
 
+/// Matches a whole-row or whole-column range reference, such as `A:A` or
+/// `'Sheet 2'!3:5`.
+#[derive(Debug, Copy, Clone)]
+pub struct RowColRangeReference;
+impl_display!(for RowColRangeReference, "row or column range reference, such as 'A:A' or '3:5'");
+impl SyntaxRule for RowColRangeReference {
+    type Output = Spanned<RangeRef>;
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        let _ = p.try_parse(SheetRefPrefix);
+        matches!(p.next(), Some(Token::ColumnRangeRef | Token::RowRangeRef))
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        let start_span = p.peek_next_span();
+
+        let sheet_name = p.try_parse(SheetRefPrefix).transpose()?;
+
+        p.next();
+        let Some(range_ref) = RangeRef::parse_a1_rows_or_columns(p.token_str(), p.pos, sheet_name)
+        else {
+            return Err(RunErrorMsg::BadCellReference.with_span(p.span()));
+        };
+        Ok(Spanned {
+            span: Span::merge(start_span, p.span()),
+            inner: range_ref,
+        })
+    }
+}
+
 /// Matches a single cell reference or a cell range reference on its own, not as
 /// part of an expression.
 #[derive(Debug, Copy, Clone)]
@@ -120,9 +165,13 @@ impl SyntaxRule for CellRangeReference {
     type Output = Spanned<RangeRef>;
 
     fn prefix_matches(&self, p: Parser<'_>) -> bool {
-        CellReference.prefix_matches(p)
+        RowColRangeReference.prefix_matches(p) || CellReference.prefix_matches(p)
     }
     fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        if RowColRangeReference.prefix_matches(*p) {
+            return p.parse(RowColRangeReference);
+        }
+
         let pos1 = p.parse(CellReference)?;
 
         // Check for a range reference.
@@ -137,6 +186,25 @@ impl SyntaxRule for CellRangeReference {
     }
 }
 
+/// Matches a reference to a cell that has been deleted, written `#REF!`.
+#[derive(Debug, Copy, Clone)]
+pub struct BadCellReferenceExpression;
+impl_display!(for BadCellReferenceExpression, "invalid cell reference '#REF!'");
+impl SyntaxRule for BadCellReferenceExpression {
+    type Output = AstNode;
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        p.next() == Some(Token::BadCellRef)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        p.next();
+        Ok(AstNode {
+            span: p.span(),
+            inner: ast::AstNodeContents::BadCellRef,
+        })
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct BoolExpression;
 impl_display!(for BoolExpression, "boolean, either 'TRUE' or 'FALSE'");
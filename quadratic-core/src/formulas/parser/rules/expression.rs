@@ -9,6 +9,8 @@ pub enum OpPrecedence {
     MulDiv,
     Pow,
     Range,
+    Intersection,
+    ImplicitIntersection,
     CellRange,
     Prefix,
     Suffix,
@@ -33,7 +35,9 @@ impl OpPrecedence {
             Self::AddSub => Self::MulDiv,
             Self::MulDiv => Self::Pow,
             Self::Pow => Self::Range,
-            Self::Range => Self::CellRange,
+            Self::Range => Self::Intersection,
+            Self::Intersection => Self::ImplicitIntersection,
+            Self::ImplicitIntersection => Self::CellRange,
             Self::CellRange => Self::Prefix,
             Self::Prefix => Self::Suffix,
             Self::Suffix => Self::Atom,
@@ -51,6 +55,10 @@ impl OpPrecedence {
             Self::MulDiv => &[Mult, Div],
             Self::Pow => &[Power],
             Self::Range => &[RangeOp],
+            // The intersection operator is whitespace, which is handled by
+            // `parse_intersection_ops()`.
+            Self::Intersection => &[],
+            Self::ImplicitIntersection => &[],
             Self::CellRange => &[CellRangeOp],
             Self::Prefix => &[],
             Self::Suffix => &[],
@@ -62,6 +70,7 @@ impl OpPrecedence {
     pub fn prefix_ops(self) -> &'static [Token] {
         use Token::*;
         match self {
+            Self::ImplicitIntersection => &[At],
             Self::Prefix => &[Plus, Minus],
             _ => &[],
         }
@@ -124,7 +133,7 @@ impl SyntaxRule for ExpressionWithPrecedence {
 
                 Token::Eql | Token::Neq | Token::Lt | Token::Gt | Token::Lte | Token::Gte => false,
 
-                Token::Plus | Token::Minus => true,
+                Token::Plus | Token::Minus | Token::At => true,
 
                 Token::Mult
                 | Token::Div
@@ -145,7 +154,11 @@ impl SyntaxRule for ExpressionWithPrecedence {
                 | Token::StringLiteral
                 | Token::UnterminatedStringLiteral
                 | Token::NumericLiteral
-                | Token::CellRef => true,
+                | Token::CellRef
+                | Token::BadCellRef
+                | Token::ColumnRangeRef
+                | Token::RowRangeRef
+                | Token::Identifier => true,
 
                 Token::Whitespace => false,
                 Token::Unknown => false,
@@ -162,7 +175,10 @@ impl SyntaxRule for ExpressionWithPrecedence {
                 p,
                 [
                     FunctionCall.map(Some),
+                    RowColRangeExpression.map(Some),
                     CellReferenceExpression.map(Some),
+                    BadCellReferenceExpression.map(Some),
+                    IdentifierExpression.map(Some),
                     StringLiteralExpression.map(Some),
                     NumericLiteral.map(Some),
                     ArrayLiteral.map(Some),
@@ -174,6 +190,7 @@ impl SyntaxRule for ExpressionWithPrecedence {
             .transpose()
             .unwrap_or_else(|| p.expected(self)),
 
+            OpPrecedence::Intersection => parse_intersection_ops(p, self.0),
             prec if !prec.binary_ops().is_empty() => parse_binary_ops_expr(p, prec),
             prec if !prec.prefix_ops().is_empty() => parse_prefix_ops(p, prec),
             prec if !prec.suffix_ops().is_empty() => parse_suffix_ops(p, prec),
@@ -243,6 +260,55 @@ fn parse_binary_ops_expr(p: &mut Parser<'_>, precedence: OpPrecedence) -> CodeRe
     Ok(ret)
 }
 
+/// Parses an expression with any number of intersection operators, such as
+/// `A1:C3 B2:D4`. Whitespace is only an intersection operator when it is
+/// followed by something that might be a reference, so that whitespace around
+/// other operators is still ignored.
+fn parse_intersection_ops(
+    p: &mut Parser<'_>,
+    precedence: OpPrecedence,
+) -> CodeResult<ast::AstNode> {
+    let recursive_expression = ExpressionWithPrecedence(precedence.next());
+
+    let mut ret = p.parse(recursive_expression)?;
+    while p.peek_next_noskip() == Some(Token::Whitespace) && is_reference_start(*p) {
+        p.next_noskip();
+        let op = Spanned {
+            span: p.span(),
+            inner: " ".to_string(),
+        };
+        let rhs = p.parse(recursive_expression)?;
+        ret = AstNode {
+            span: Span::merge(ret.span, rhs.span),
+            inner: ast::AstNodeContents::FunctionCall {
+                func: op,
+                args: vec![ret, rhs],
+            },
+        };
+    }
+    Ok(ret)
+}
+
+/// Returns whether the next token might begin a reference.
+fn is_reference_start(mut p: Parser<'_>) -> bool {
+    match p.next() {
+        Some(Token::StringLiteral) => p.next() == Some(Token::SheetRefOp),
+        Some(t) => matches!(
+            t,
+            Token::LParen
+                | Token::At
+                | Token::FunctionCall
+                | Token::UnquotedSheetReference
+                | Token::CellRef
+                | Token::BadCellRef
+                | Token::ColumnRangeRef
+                | Token::RowRangeRef
+                | Token::Identifier,
+        ),
+        None => false,
+    }
+}
+
 /// Parses an expression with any number of prefix operators.
 fn parse_prefix_ops(p: &mut Parser<'_>, precedence: OpPrecedence) -> CodeResult<ast::AstNode> {
     let allowed_ops = precedence.prefix_ops();
@@ -336,10 +402,32 @@ impl SyntaxRule for FunctionCall {
         })?;
         let args = spanned_args.inner;
 
-        Ok(AstNode {
+        let mut ret = AstNode {
             span: Span::merge(func.span, spanned_args.span),
             inner: ast::AstNodeContents::FunctionCall { func, args },
-        })
+        };
+
+        // Call the result, such as `LAMBDA(x, x + 1)(5)`. The argument list
+        // must immediately follow the right paren.
+        while p.peek_next_noskip() == Some(Token::LParen) {
+            let spanned_args = p.parse(List {
+                inner: Expression,
+                sep: Token::ArgSep,
+                start: Token::LParen,
+                end: Token::RParen,
+                sep_name: "comma",
+                allow_trailing_sep: false,
+            })?;
+            ret = AstNode {
+                span: Span::merge(ret.span, spanned_args.span),
+                inner: ast::AstNodeContents::Call {
+                    func: Box::new(ret),
+                    args: spanned_args.inner,
+                },
+            };
+        }
+
+        Ok(ret)
     }
 }
 
@@ -358,6 +446,41 @@ impl SyntaxRule for CellReferenceExpression {
     }
 }
 
+/// Matches a whole-row or whole-column range reference.
+#[derive(Debug, Copy, Clone)]
+pub struct RowColRangeExpression;
+impl_display!(for RowColRangeExpression, "row or column range reference, such as 'A:A' or '3:5'");
+impl SyntaxRule for RowColRangeExpression {
+    type Output = AstNode;
+
+    fn prefix_matches(&self, p: Parser<'_>) -> bool {
+        RowColRangeReference.prefix_matches(p)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        Ok(p.parse(RowColRangeReference)?
+            .map(ast::AstNodeContents::RangeRef))
+    }
+}
+
+/// Matches a name, which refers to a variable or a named range.
+#[derive(Debug, Copy, Clone)]
+pub struct IdentifierExpression;
+impl_display!(for IdentifierExpression, "name, such as 'TaxRate'");
+impl SyntaxRule for IdentifierExpression {
+    type Output = AstNode;
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        p.next() == Some(Token::Identifier)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        p.parse(Token::Identifier)?;
+        Ok(AstNode {
+            span: p.span(),
+            inner: ast::AstNodeContents::Identifier(p.token_str().to_string()),
+        })
+    }
+}
+
 /// Matches a pair of parentheses containing an expression.
 #[derive(Debug, Copy, Clone)]
 pub struct ParenExpression;
@@ -369,9 +492,35 @@ impl SyntaxRule for ParenExpression {
         Surround::paren(Expression).prefix_matches(p)
     }
     fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
-        p.parse(Surround::paren(
-            Expression.map(|expr| ast::AstNodeContents::Paren(Box::new(expr))),
-        ))
+        let spanned_exprs = p.parse(List {
+            inner: Expression,
+            sep: Token::ArgSep,
+            start: Token::LParen,
+            end: Token::RParen,
+            sep_name: "comma",
+            allow_trailing_sep: false,
+        })?;
+        let span = spanned_exprs.span;
+        let mut exprs = spanned_exprs.inner;
+
+        // Several expressions separated by commas are a union of ranges, such
+        // as `(A1:A10, C1:C10)`.
+        let inner = if exprs.len() <= 1 {
+            let expr = exprs.pop().unwrap_or(AstNode {
+                span: Span::empty(span.start + 1),
+                inner: ast::AstNodeContents::Empty,
+            });
+            ast::AstNodeContents::Paren(Box::new(expr))
+        } else {
+            ast::AstNodeContents::FunctionCall {
+                func: Spanned {
+                    span,
+                    inner: ",".to_string(),
+                },
+                args: exprs,
+            }
+        };
+        Ok(AstNode { span, inner })
     }
 }
 
@@ -0,0 +1,473 @@
+//! Evaluation of cell references, of reference operators such as `:` and
+//! `@`, and of functions such as `INDEX` and `OFFSET` that take or return a
+//! reference rather than a value.
+//!
+//! These are evaluated directly from the syntax tree instead of through
+//! [`super::functions`], because once a reference has been evaluated to an
+//! array of values, its location in the grid is lost.
+
+use std::num::NonZeroU32;
+use std::ops::Range;
+
+use smallvec::SmallVec;
+
+use super::ast::AstNodeContents;
+use super::lambda::wrong_arg_count;
+use super::*;
+use crate::{
+    grid::GridBounds, limits::CELL_RANGE_LIMIT, Array, ArraySize, CellValue, CodeResult,
+    CoerceInto, Pos, Rect, RunError, RunErrorMsg, SheetRect, Spanned, Value,
+};
+
+/// Functions that are evaluated by [`AstNode::eval_reference_function()`].
+const REFERENCE_FUNCTIONS: &[&str] = &["INDEX", "OFFSET", "ROW", "COLUMN"];
+
+/// Returns whether a call to `func_name` must be evaluated by
+/// [`AstNode::eval_reference_function()`].
+pub(super) fn is_reference_function(func_name: &str) -> bool {
+    REFERENCE_FUNCTIONS
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(func_name))
+}
+
+/// Operators that are evaluated by [`AstNode::eval_reference_operator()`]: cell
+/// range (`:`), intersection (` `), union (`,`), and implicit intersection
+/// (`@`).
+const REFERENCE_OPERATORS: &[&str] = &[":", " ", ",", "@"];
+
+/// Returns whether `op` must be evaluated by
+/// [`AstNode::eval_reference_operator()`].
+pub(super) fn is_reference_operator(op: &str) -> bool {
+    REFERENCE_OPERATORS.contains(&op)
+}
+
+/// Evaluates the arguments to a function call, returning errors as values.
+///
+/// If `allow_unions` is true, then each range in a union is passed as a
+/// separate argument, so that `SUM((A1:A10, C1:C10))` is the same as
+/// `SUM(A1:A10, C1:C10)`.
+pub(super) fn eval_function_args(
+    ctx: &mut Ctx<'_>,
+    args: &[AstNode],
+    allow_unions: bool,
+) -> Vec<Spanned<Value>> {
+    let mut ret = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg.union_areas() {
+            Some(areas) if allow_unions => {
+                for area in areas {
+                    let value = area
+                        .eval_reference(ctx)
+                        .and_then(|sheet_rect| ctx.get_sheet_rect(sheet_rect.inner, area.span));
+                    ret.push(match value {
+                        Ok(array) => array.map(Value::from),
+                        Err(e) => Spanned {
+                            span: area.span,
+                            inner: CellValue::Error(Box::new(e)).into(),
+                        },
+                    });
+                }
+            }
+            _ => ret.push(arg.eval_or_error_value(ctx)),
+        }
+    }
+    ret
+}
+
+impl AstNode {
+    /// Evaluates an operator for which [`is_reference_operator()`] returns
+    /// `true`.
+    pub(super) fn eval_reference_operator(&self, ctx: &mut Ctx<'_>) -> CodeResult<Value> {
+        if let AstNodeContents::FunctionCall { func, args } = &self.inner {
+            // Implicit intersection of a value that is not a reference takes
+            // its top-left element.
+            if func.inner == "@" && !self.is_reference(ctx) {
+                let [arg] = args.as_slice() else {
+                    internal_error!("invalid arguments to implicit intersection operator");
+                };
+                return Ok(match arg.eval(ctx)?.inner {
+                    Value::Single(value) => value.into(),
+                    Value::Array(array) => array.get(0, 0)?.clone().into(),
+                });
+            }
+        }
+        let sheet_rect = self.eval_reference(ctx)?.inner;
+        Ok(ctx.get_sheet_rect(sheet_rect, self.span)?.inner.into())
+    }
+
+    /// Evaluates a call to a function for which [`is_reference_function()`]
+    /// returns `true`.
+    pub(super) fn eval_reference_function(
+        &self,
+        ctx: &mut Ctx<'_>,
+        func: &Spanned<String>,
+        args: &[AstNode],
+    ) -> CodeResult<Value> {
+        let span = self.span;
+        match func.inner.to_ascii_uppercase().as_str() {
+            "ROW" | "COLUMN" => {
+                let is_row = func.inner.eq_ignore_ascii_case("ROW");
+                let func_name = if is_row { "ROW" } else { "COLUMN" };
+                let sheet_rect = match args {
+                    [] => SheetRect::single_sheet_pos(ctx.sheet_pos),
+                    [reference] => reference.eval_reference(ctx)?.inner,
+                    [_, extra, ..] => {
+                        return Err(RunErrorMsg::TooManyArguments {
+                            func_name: func_name.into(),
+                            max_arg_count: 1,
+                        }
+                        .with_span(extra.span));
+                    }
+                };
+                let (min, max) = if is_row {
+                    (sheet_rect.min.y, sheet_rect.max.y)
+                } else {
+                    (sheet_rect.min.x, sheet_rect.max.x)
+                };
+                let len = u32::try_from(max.saturating_sub(min).saturating_add(1))
+                    .ok()
+                    .filter(|&len| len <= CELL_RANGE_LIMIT)
+                    .ok_or(RunErrorMsg::ArrayTooBig.with_span(span))?;
+                // Rows are numbered as displayed, and columns are numbered
+                // starting from 1 at column A.
+                let (size, values): (ArraySize, SmallVec<_>) = if is_row {
+                    let size = ArraySize::new_or_err(1, len)?;
+                    (size, sheet_rect.y_range().map(CellValue::from).collect())
+                } else {
+                    let size = ArraySize::new_or_err(len, 1)?;
+                    (size, sheet_rect.x_range().map(|x| (x + 1).into()).collect())
+                };
+                Ok(Array::new_row_major(size, values)?.into())
+            }
+
+            // `INDEX` also works on arrays that are not references.
+            "INDEX" if !args.first().is_some_and(|arg| arg.is_reference(ctx)) => {
+                let (array, row, col) = match args {
+                    [array, row] => (array, row, None),
+                    [array, row, col] => (array, row, Some(col)),
+                    _ => {
+                        return Err(wrong_arg_count(
+                            "INDEX",
+                            args,
+                            &["array", "row", "column"],
+                            span,
+                        ));
+                    }
+                };
+                let array = Array::from(array.eval(ctx)?.inner);
+                let row = row.eval_index(ctx)?;
+                let col = col.map(|col| col.eval_index(ctx)).transpose()?;
+                let rect = index_rect(array.size(), row, col)?;
+                let values = rect
+                    .iter()
+                    .map(|pos| array.get(pos.x as u32, pos.y as u32).cloned())
+                    .collect::<Result<SmallVec<_>, _>>()?;
+                Ok(Array::new_row_major(rect.size(), values)?.into())
+            }
+
+            _ => {
+                let sheet_rect = self.eval_reference(ctx)?.inner;
+                Ok(ctx.get_sheet_rect(sheet_rect, span)?.inner.into())
+            }
+        }
+    }
+
+    /// Evaluates an expression that must be a reference, such as `A1:B5` or
+    /// `OFFSET(A1, 2, 3)`, without reading the cells it refers to.
+    pub(super) fn eval_reference(&self, ctx: &mut Ctx<'_>) -> CodeResult<Spanned<SheetRect>> {
+        let span = self.span;
+        let base: Pos = ctx.sheet_pos.into();
+
+        let sheet_rect = match &self.inner {
+            AstNodeContents::Paren(contents) => contents.eval_reference(ctx)?.inner,
+
+            AstNodeContents::CellRef(cell_ref) => {
+                let sheet = ctx.resolve_sheet(&cell_ref.sheet, span)?;
+                SheetRect::single_pos(cell_ref.resolve_from(base), sheet.id)
+            }
+
+            // Only the part of a whole-row or whole-column range that contains
+            // data is used, as when reading its values.
+            AstNodeContents::RangeRef(range_ref) => {
+                let (sheet, mut rect) = ctx.resolve_row_col_range(range_ref, span)?;
+                let bounds = match sheet.bounds(true) {
+                    GridBounds::NonEmpty(bounds) => bounds,
+                    GridBounds::Empty => Rect::single_pos(Pos::ORIGIN),
+                };
+                if matches!(range_ref, RangeRef::RowRange { .. }) {
+                    (rect.min.x, rect.max.x) = (bounds.min.x, bounds.max.x);
+                } else {
+                    (rect.min.y, rect.max.y) = (bounds.min.y, bounds.max.y);
+                }
+                rect.to_sheet_rect(sheet.id)
+            }
+
+            AstNodeContents::FunctionCall { func, args } if func.inner == ":" => {
+                let [start, end] = args.as_slice() else {
+                    internal_error!("invalid arguments to cell range operator");
+                };
+                let start = start.eval_reference(ctx)?.inner;
+                // The end of a range is on the same sheet as the start unless
+                // it says otherwise.
+                let end = match &end.inner {
+                    AstNodeContents::CellRef(cell_ref) if cell_ref.sheet.is_none() => {
+                        SheetRect::single_pos(cell_ref.resolve_from(base), start.sheet_id)
+                    }
+                    _ => end.eval_reference(ctx)?.inner,
+                };
+                if start.sheet_id != end.sheet_id {
+                    return Err(RunErrorMsg::BadCellReference.with_span(span));
+                }
+                start.union(&end)
+            }
+
+            AstNodeContents::FunctionCall { func, args } if func.inner == " " => {
+                let [a, b] = args.as_slice() else {
+                    internal_error!("invalid arguments to intersection operator");
+                };
+                let a = a.eval_reference(ctx)?.inner;
+                let b = b.eval_reference(ctx)?.inner;
+                if a.sheet_id != b.sheet_id {
+                    return Err(RunErrorMsg::BadCellReference.with_span(span));
+                }
+                a.intersection(&b)
+                    .ok_or(RunErrorMsg::EmptyIntersection.with_span(span))?
+            }
+
+            // Implicit intersection selects the cell in the same row or column
+            // as the current cell.
+            AstNodeContents::FunctionCall { func, args } if func.inner == "@" => {
+                let [reference] = args.as_slice() else {
+                    internal_error!("invalid arguments to implicit intersection operator");
+                };
+                // Whole rows and columns are not limited to the cells that
+                // contain data.
+                let reference = match &reference.inner {
+                    AstNodeContents::RangeRef(range_ref) => {
+                        let (sheet, rect) = ctx.resolve_row_col_range(range_ref, reference.span)?;
+                        rect.to_sheet_rect(sheet.id)
+                    }
+                    _ => reference.eval_reference(ctx)?.inner,
+                };
+                let x = implicit_intersection(reference.min.x, reference.max.x, base.x);
+                let y = implicit_intersection(reference.min.y, reference.max.y, base.y);
+                let (Some(x), Some(y)) = (x, y) else {
+                    return Err(RunErrorMsg::EmptyIntersection.with_span(span));
+                };
+                SheetRect::single_pos(Pos { x, y }, reference.sheet_id)
+            }
+
+            AstNodeContents::FunctionCall { func, .. } if func.inner == "," => {
+                return Err(RunErrorMsg::Unexpected("union of ranges".into()).with_span(span));
+            }
+
+            AstNodeContents::Identifier(name) if ctx.variable(name).is_none() => ctx
+                .grid
+                .named_range(name)
+                .and_then(|named_range| named_range.sheet_rect)
+                .ok_or(RunErrorMsg::BadCellReference.with_span(span))?,
+
+            AstNodeContents::FunctionCall { func, args } if !ctx.is_special_form(&func.inner) => {
+                match func.inner.to_ascii_uppercase().as_str() {
+                    "INDEX" => {
+                        let (reference, row, col) = match args.as_slice() {
+                            [reference, row] => (reference, row, None),
+                            [reference, row, col] => (reference, row, Some(col)),
+                            _ => {
+                                return Err(wrong_arg_count(
+                                    "INDEX",
+                                    args,
+                                    &["array", "row", "column"],
+                                    span,
+                                ));
+                            }
+                        };
+                        let reference = reference.eval_reference(ctx)?.inner;
+                        let row = row.eval_index(ctx)?;
+                        let col = col.map(|col| col.eval_index(ctx)).transpose()?;
+                        let mut rect = index_rect(reference.size(), row, col)?;
+                        rect.translate(reference.min.x, reference.min.y);
+                        rect.to_sheet_rect(reference.sheet_id)
+                    }
+
+                    "OFFSET" => {
+                        let (reference, rows, cols, height, width) = match args.as_slice() {
+                            [reference, rows, cols, size @ ..] if size.len() <= 2 => {
+                                (reference, rows, cols, size.first(), size.get(1))
+                            }
+                            _ => {
+                                return Err(wrong_arg_count(
+                                    "OFFSET",
+                                    args,
+                                    &["reference", "rows", "columns", "height", "width"],
+                                    span,
+                                ));
+                            }
+                        };
+                        let reference = reference.eval_reference(ctx)?.inner;
+                        let rows = rows.eval_index(ctx)?.inner;
+                        let cols = cols.eval_index(ctx)?.inner;
+                        let height = eval_offset_len(ctx, height, reference.height())?;
+                        let width = eval_offset_len(ctx, width, reference.width())?;
+                        SheetRect::from_numbers(
+                            reference.min.x.saturating_add(cols),
+                            reference.min.y.saturating_add(rows),
+                            width,
+                            height,
+                            reference.sheet_id,
+                        )
+                    }
+
+                    "INDIRECT" => {
+                        let [cellref_string] = args.as_slice() else {
+                            return Err(wrong_arg_count(
+                                "INDIRECT",
+                                args,
+                                &["cellref_string"],
+                                span,
+                            ));
+                        };
+                        let s = cellref_string
+                            .eval(ctx)?
+                            .into_cell_value()?
+                            .try_coerce::<String>()?;
+                        let (sheet_ref, rect) = parse_a1_range(&s.inner, base)
+                            .ok_or(RunErrorMsg::BadCellReference.with_span(s.span))?;
+                        let sheet = ctx.resolve_sheet(&sheet_ref, s.span)?;
+                        rect.to_sheet_rect(sheet.id)
+                    }
+
+                    _ => return Err(self.expected_reference_error()),
+                }
+            }
+
+            _ => return Err(self.expected_reference_error()),
+        };
+
+        Ok(Spanned {
+            span,
+            inner: sheet_rect,
+        })
+    }
+
+    /// Returns whether this expression evaluates to a reference, so that it
+    /// can be passed to [`AstNode::eval_reference()`].
+    fn is_reference(&self, ctx: &Ctx<'_>) -> bool {
+        match &self.inner {
+            AstNodeContents::Paren(contents) => contents.is_reference(ctx),
+            AstNodeContents::CellRef(_) | AstNodeContents::RangeRef(_) => true,
+            AstNodeContents::Identifier(name) => ctx.variable(name).is_none(),
+            AstNodeContents::FunctionCall { func, .. }
+                if matches!(func.inner.as_str(), ":" | " ") =>
+            {
+                true
+            }
+            AstNodeContents::FunctionCall { func, args } if func.inner == "@" => {
+                args.first().is_some_and(|arg| arg.is_reference(ctx))
+            }
+            AstNodeContents::FunctionCall { func, .. } if func.inner == "," => false,
+            AstNodeContents::FunctionCall { func, args } if !ctx.is_special_form(&func.inner) => {
+                match func.inner.to_ascii_uppercase().as_str() {
+                    "OFFSET" | "INDIRECT" => true,
+                    "INDEX" => args.first().is_some_and(|arg| arg.is_reference(ctx)),
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the ranges in a union such as `(A1:A10, C1:C10)`, or `None` if
+    /// this is not a union.
+    fn union_areas(&self) -> Option<Vec<&AstNode>> {
+        match &self.inner {
+            AstNodeContents::Paren(contents) => contents.union_areas(),
+            AstNodeContents::FunctionCall { func, args } if func.inner == "," => Some(
+                args.iter()
+                    .flat_map(|arg| arg.union_areas().unwrap_or_else(|| vec![arg]))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Evaluates a row or column number.
+    fn eval_index(&self, ctx: &mut Ctx<'_>) -> CodeResult<Spanned<i64>> {
+        self.eval(ctx)?.into_cell_value()?.try_coerce()
+    }
+
+    fn expected_reference_error(&self) -> RunError {
+        RunErrorMsg::Expected {
+            expected: "cell reference".into(),
+            got: Some(self.inner.type_string().into()),
+        }
+        .with_span(self.span)
+    }
+}
+
+/// Returns the coordinate along one axis of the cell selected by implicit
+/// intersection of the range from `min` to `max`: the only coordinate in the
+/// range, or else `current` if it is in the range.
+fn implicit_intersection(min: i64, max: i64, current: i64) -> Option<i64> {
+    if min == max {
+        Some(min)
+    } else {
+        (min..=max).contains(&current).then_some(current)
+    }
+}
+
+/// Evaluates the optional height or width argument to `OFFSET`, which
+/// defaults to the size of the original reference.
+fn eval_offset_len(ctx: &mut Ctx<'_>, arg: Option<&AstNode>, default: usize) -> CodeResult<i64> {
+    match arg {
+        Some(arg) if !matches!(arg.inner, AstNodeContents::Empty) => {
+            let len = arg.eval_index(ctx)?;
+            if len.inner < 1 {
+                return Err(RunErrorMsg::InvalidArgument.with_span(len.span));
+            }
+            Ok(len.inner)
+        }
+        _ => Ok(default as i64),
+    }
+}
+
+/// Returns the part of a range of size `size` that is selected by `INDEX`,
+/// relative to the top-left corner of the range. A row or column number of 0
+/// selects the whole column or row.
+fn index_rect(size: ArraySize, row: Spanned<i64>, col: Option<Spanned<i64>>) -> CodeResult<Rect> {
+    // If there is only one index, then a single row is indexed by column.
+    let (row, col) = match col {
+        None if size.h.get() == 1 => (None, Some(row)),
+        col => (Some(row), col),
+    };
+    Ok(Rect::from_ranges(
+        index_range(col, size.w)?,
+        index_range(row, size.h)?,
+    ))
+}
+
+/// Returns the range of 0-based indices selected by a 1-based index.
+fn index_range(index: Option<Spanned<i64>>, len: NonZeroU32) -> CodeResult<Range<i64>> {
+    let len = i64::from(len.get());
+    match index {
+        None | Some(Spanned { inner: 0, .. }) => Ok(0..len),
+        Some(Spanned { inner: i, .. }) if (1..=len).contains(&i) => Ok(i - 1..i),
+        Some(index) => Err(RunErrorMsg::IndexOutOfBounds.with_span(index.span)),
+    }
+}
+
+/// Parses an A1-style cell or cell range reference, such as `B3` or
+/// `Sheet2!A1:C5`, relative to a given location.
+fn parse_a1_range(s: &str, base: Pos) -> Option<(Option<String>, Rect)> {
+    let (start, end) = match s.rsplit_once(':') {
+        Some((start, end)) => (start, Some(end)),
+        None => (s, None),
+    };
+    let start = CellRef::parse_a1(start, base)?;
+    let start_pos = start.resolve_from(base);
+    let end_pos = match end {
+        Some(end) => CellRef::parse_a1(end, base)?.resolve_from(base),
+        None => start_pos,
+    };
+    Some((start.sheet, Rect::new_span(start_pos, end_pos)))
+}
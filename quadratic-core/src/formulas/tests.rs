@@ -4,7 +4,7 @@ pub(crate) use super::*;
 pub(crate) use crate::grid::Grid;
 pub(crate) use crate::values::*;
 pub(crate) use crate::{array, CodeResult, RunError, RunErrorMsg, Spanned};
-use crate::{Pos, SheetPos};
+use crate::{grid::sheet::rows_columns::GridShift, Pos, SheetPos, SheetRect, Span};
 
 pub(crate) fn try_eval_at(grid: &Grid, pos: SheetPos, s: &str) -> CodeResult<Value> {
     println!("Evaluating formula {s:?} at {pos:?}");
@@ -86,6 +86,103 @@ fn test_formula_circular_array_ref() {
     );
 }
 
+#[test]
+fn test_formula_row_col_range_ref() {
+    let mut g = Grid::new();
+    let sheet = &mut g.sheets_mut()[0];
+    let _ = sheet.set_cell_value(pos![B1], 1);
+    let _ = sheet.set_cell_value(pos![B2], 10);
+    let _ = sheet.set_cell_value(pos![C2], 100);
+    let _ = sheet.set_cell_value(pos![D5], 1000);
+    sheet.recalculate_bounds();
+    let sheet_id = sheet.id;
+
+    assert_eq!("11", eval_to_string(&g, "SUM(B:B)"));
+    assert_eq!("111", eval_to_string(&g, "SUM($B:C)"));
+    assert_eq!("110", eval_to_string(&g, "SUM(2:2)"));
+    assert_eq!("1111", eval_to_string(&g, "SUM(1:5)"));
+    assert_eq!("0", eval_to_string(&g, "SUM(Z:Z)"));
+    assert_eq!("0", eval_to_string(&g, "SUM(100:100)"));
+
+    // Only the cells within the data bounds are read and recorded as
+    // accessed, but the whole column is returned as a reference.
+    let mut ctx = Ctx::new(&g, pos![A0].to_sheet_pos(sheet_id));
+    let value = parse_formula("B:B", Pos::ORIGIN)
+        .unwrap()
+        .eval(&mut ctx)
+        .unwrap();
+    assert_eq!(value.to_string(), "{1; 10; ; ; }");
+    assert_eq!(
+        ctx.cells_accessed,
+        std::collections::HashSet::from([crate::SheetRect::new_pos_span(
+            pos![B1],
+            pos![B5],
+            sheet_id
+        )]),
+    );
+    assert_eq!(
+        ctx.row_col_references("SUM(B:B) + A1"),
+        vec![crate::SheetRect::new_pos_span(
+            Pos { x: 1, y: i64::MIN },
+            Pos { x: 1, y: i64::MAX },
+            sheet_id,
+        )],
+    );
+
+    // A formula inside the range it references is circular.
+    let _ = g.sheets_mut()[0].set_cell_value(pos![B3], 5);
+    g.sheets_mut()[0].recalculate_bounds();
+    let mut ctx = Ctx::new(&g, pos![B3].to_sheet_pos(sheet_id));
+    assert_eq!(
+        RunErrorMsg::CircularReference,
+        parse_formula("SUM(B:B)", pos![B3])
+            .unwrap()
+            .eval(&mut ctx)
+            .unwrap_err()
+            .msg,
+    );
+}
+
+#[test]
+fn test_formula_named_range() {
+    let mut g = Grid::new();
+    let sheet = &mut g.sheets_mut()[0];
+    let _ = sheet.set_cell_value(pos![A1], 100);
+    let _ = sheet.set_cell_value(pos![A2], 200);
+    let _ = sheet.set_cell_value(pos![B1], 0.25);
+    let sheet_id = sheet.id;
+    let revenue = crate::SheetRect::new_pos_span(pos![A1], pos![A2], sheet_id);
+    g.set_named_range(crate::grid::NamedRange {
+        name: "Revenue".to_string(),
+        sheet_rect: Some(revenue),
+    });
+    g.set_named_range(crate::grid::NamedRange {
+        name: "Tax_Rate".to_string(),
+        sheet_rect: Some(crate::SheetRect::single_pos(pos![B1], sheet_id)),
+    });
+
+    assert_eq!("300", eval_to_string(&g, "SUM(Revenue)"));
+    assert_eq!("{25; 50}", eval_to_string(&g, "revenue * TAX_RATE"));
+    assert_eq!("75", eval_to_string(&g, "SUM(Revenue) * Tax_Rate"));
+    expect_err(&RunErrorMsg::BadCellReference, &g, "Missing + 1");
+
+    // The whole range is recorded as accessed.
+    let mut ctx = Ctx::new(&g, pos![C1].to_sheet_pos(sheet_id));
+    parse_formula("SUM(Revenue)", Pos::ORIGIN)
+        .unwrap()
+        .eval(&mut ctx)
+        .unwrap();
+    assert!(ctx.cells_accessed.contains(&revenue));
+
+    // A formula inside the range it references is circular.
+    assert_eq!(
+        RunErrorMsg::CircularReference,
+        try_eval_at(&g, pos![A2].to_sheet_pos(sheet_id), "SUM(Revenue)")
+            .unwrap_err()
+            .msg,
+    );
+}
+
 #[test]
 fn test_formula_range_operator() {
     let expected = "{1; 2; 3; 4; 5}";
@@ -103,6 +200,136 @@ fn test_formula_range_operator() {
     );
 }
 
+#[test]
+fn test_formula_percent_operator() {
+    let mut g = Grid::new();
+    let _ = g.sheets_mut()[0].set_cell_value(pos![B1], 20);
+
+    assert_eq!("2", eval_to_string(&g, "B1*10%"));
+    assert_eq!("0.5", eval_to_string(&g, "50%"));
+    assert_eq!("0.125", eval_to_string(&g, "12.5 %"));
+    assert_eq!("0.02", eval_to_string(&g, "200%%"));
+    assert_eq!("-0.05", eval_to_string(&g, "-5%"));
+}
+
+/// Returns a grid where each cell in `B1:E4` contains its column number times
+/// 10 plus its row number.
+fn grid_with_numbers() -> Grid {
+    let mut g = Grid::new();
+    let sheet = &mut g.sheets_mut()[0];
+    for x in 1..=4 {
+        for y in 1..=4 {
+            let _ = sheet.set_cell_value(Pos { x, y }, x * 10 + y);
+        }
+    }
+    sheet.recalculate_bounds();
+    g
+}
+
+#[test]
+fn test_formula_intersection_operator() {
+    let g = grid_with_numbers();
+
+    assert_eq!("{22}", eval_to_string(&g, "C1:C4 B2:E2"));
+    assert_eq!("{22, 32; 23, 33}", eval_to_string(&g, "B1:E4 C2:D3"));
+    assert_eq!("45", eval_to_string(&g, "SUM(B1:C4 C2:E3)"));
+    assert_eq!("{22}", eval_to_string(&g, "(B1:C4 C2:E3) B2:E2"));
+    assert_eq!("{22}", eval_to_string(&g, "C:C 2:2"));
+
+    // Whitespace around other operators is not an intersection.
+    assert_eq!("32", eval_to_string(&g, "B1 + C1"));
+    assert_eq!("140", eval_to_string(&g, "SUM(B1:B4 , C1:C4 )"));
+
+    let e = eval_to_err(&g, "B1:B4 C1:C4");
+    assert_eq!(RunErrorMsg::EmptyIntersection, e.msg);
+    assert_eq!(Some(Span { start: 0, end: 11 }), e.span);
+    let e = eval_to_err(&g, "(1 C1:C4)");
+    assert_eq!(
+        RunErrorMsg::Expected {
+            expected: "cell reference".into(),
+            got: Some("numeric literal".into()),
+        },
+        e.msg,
+    );
+    assert_eq!(Some(Span { start: 1, end: 2 }), e.span);
+
+    // Only the intersection is accessed.
+    let mut ctx = Ctx::new(&g, Pos::ORIGIN.to_sheet_pos(g.sheets()[0].id));
+    parse_formula("B1:E4 C2:C3", Pos::ORIGIN)
+        .unwrap()
+        .eval(&mut ctx)
+        .unwrap();
+    let sheet_id = g.sheets()[0].id;
+    assert_eq!(
+        ctx.cells_accessed,
+        [SheetRect::from_numbers(2, 2, 1, 2, sheet_id)].into(),
+    );
+}
+
+#[test]
+fn test_formula_union_operator() {
+    let g = grid_with_numbers();
+
+    assert_eq!("180", eval_to_string(&g, "SUM((B1:B4, D1:D4))"));
+    assert_eq!("181", eval_to_string(&g, "SUM((B1:B4,D1:D4), 1)"));
+    assert_eq!("5", eval_to_string(&g, "COUNT((B1, C1:C2, (D1, E1)))"));
+    assert_eq!("1", eval_to_string(&g, "(1)"));
+
+    let e = eval_to_err(&g, "(B1, C1)");
+    assert_eq!(RunErrorMsg::Unexpected("union of ranges".into()), e.msg);
+    assert_eq!(Some(Span { start: 0, end: 8 }), e.span);
+    expect_err(
+        &RunErrorMsg::Unexpected("union of ranges".into()),
+        &g,
+        "(B1, C1) + 1",
+    );
+    let e = eval_to_err(&g, "SUM((B1, 5))");
+    assert_eq!(
+        RunErrorMsg::Expected {
+            expected: "cell reference".into(),
+            got: Some("numeric literal".into()),
+        },
+        e.msg,
+    );
+    assert_eq!(Some(Span { start: 9, end: 10 }), e.span);
+}
+
+#[test]
+fn test_formula_implicit_intersection_operator() {
+    let g = grid_with_numbers();
+    let sheet_id = g.sheets()[0].id;
+
+    // References are absolute because formulas are parsed at A0 and
+    // evaluated elsewhere.
+
+    // Evaluate in column C, below the numbers.
+    let pos = pos![C6].to_sheet_pos(sheet_id);
+    assert_eq!("{21}", eval_to_string_at(&g, pos, "@$B$1:$E$1"));
+    assert_eq!("21", eval_to_string_at(&g, pos, "SUM(@($B$1:$E$4 $1:$1))"));
+    assert_eq!("{24}", eval_to_string_at(&g, pos, "@$C$4"));
+    assert_eq!("1", eval_to_string_at(&g, pos, "@{1, 2; 3, 4}"));
+    assert_eq!("5", eval_to_string_at(&g, pos, "@5"));
+    let e = try_eval_at(&g, pos, "@$B$1:$B$4").unwrap_err();
+    assert_eq!(RunErrorMsg::EmptyIntersection, e.msg);
+    assert_eq!(Some(Span { start: 0, end: 10 }), e.span);
+
+    // Evaluate in row 3, to the right of the numbers.
+    let pos = pos![F3].to_sheet_pos(sheet_id);
+    assert_eq!("{13}", eval_to_string_at(&g, pos, "@$B$1:$B$4"));
+    assert_eq!("{13}", eval_to_string_at(&g, pos, "@$B:$B"));
+
+    // Only the selected cell is accessed.
+    let mut ctx = Ctx::new(&g, pos);
+    parse_formula("@$B$1:$B$4", Pos::ORIGIN)
+        .unwrap()
+        .eval(&mut ctx)
+        .unwrap();
+    assert_eq!(
+        ctx.cells_accessed,
+        [SheetRect::single_pos(pos![B3], sheet_id)].into(),
+    );
+}
+
 #[test]
 fn test_formula_blank_array_parsing() {
     let g = Grid::new();
@@ -324,6 +551,91 @@ fn test_find_cell_references() {
     assert_eq!(cell_references_found.len(), test_cases.len());
 }
 
+#[test]
+fn test_replace_cell_references() {
+    let source = "SUM(A1:$B$3) + 'banana'!C4 + D5";
+    // Move everything down one row, and invalidate `D5`.
+    let replaced = replace_cell_references(source, Pos::ORIGIN, Pos::ORIGIN, |range_ref| {
+        let shift = |cell_ref: &CellRef| {
+            let mut cell_ref = cell_ref.clone();
+            cell_ref.y = match cell_ref.y {
+                CellRefCoord::Relative(y) => CellRefCoord::Relative(y + 1),
+                CellRefCoord::Absolute(y) => CellRefCoord::Absolute(y + 1),
+            };
+            cell_ref
+        };
+        match range_ref {
+            RangeRef::Cell { pos } if pos.x == CellRefCoord::Relative(3) => None,
+            RangeRef::Cell { pos } => Some(RangeRef::Cell { pos: shift(pos) }),
+            RangeRef::CellRange { start, end } => Some(RangeRef::CellRange {
+                start: shift(start),
+                end: shift(end),
+            }),
+            other => Some(other.clone()),
+        }
+    });
+    assert_eq!(replaced, "SUM(A2:$B$4) + 'banana'!C5 + #REF!");
+
+    // Replacements are written relative to the new position of the formula.
+    let replaced = replace_cell_references("A1 + $A$1", Pos::ORIGIN, Pos { x: 1, y: 1 }, |r| {
+        Some(r.clone())
+    });
+    assert_eq!(replaced, "B2 + $A$1");
+
+    // Whole-row and whole-column ranges keep their sheet names.
+    let replaced =
+        replace_cell_references("SUM('banana'!B:C, 3:$4)", Pos::ORIGIN, Pos::ORIGIN, |r| {
+            GridShift::InsertColumn(0).shift_range_ref(r, Pos::ORIGIN, Pos::ORIGIN, |_| true)
+        });
+    assert_eq!(replaced, "SUM('banana'!C:D, 3:$4)");
+
+    let g = Grid::new();
+    assert_eq!(
+        RunErrorMsg::BadCellReference,
+        eval_to_err(&g, "#REF! + 1").msg,
+    );
+}
+
+#[test]
+fn test_r1c1_conversion() {
+    let pos = pos![C5];
+
+    let source = "SUM(B4:$B$5) + 'banana'!C6 * $D5 - A:$C + SUM(3:$4) + \"A1\"";
+    let r1c1 = a1_to_r1c1(source, pos);
+    assert_eq!(
+        r1c1,
+        "SUM(R[-1]C[-1]:R5C1) + 'banana'!R[1]C * RC3 - C[-2]:C2 + SUM(R[-2]:R4) + \"A1\"",
+    );
+    assert_eq!(r1c1_to_a1(&r1c1, pos), source);
+
+    // The same R1C1 formula in every row refers to the cell to its left.
+    let same_formula = "RC[-1] * 2";
+    assert_eq!(a1_to_r1c1("B5 * 2", pos![C5]), same_formula);
+    assert_eq!(a1_to_r1c1("B6 * 2", pos![C6]), same_formula);
+    assert_eq!(r1c1_to_a1(same_formula, pos![C7]), "B7 * 2");
+
+    // Lowercase and negative coordinates are accepted.
+    assert_eq!(r1c1_to_a1("rc[1] + Rn2Cn1", pos), "D5 + $nA$n2");
+
+    // Strings, comments, function names, and other names are not references.
+    let source = "ROUND(RC_total, 2) & \"RC\" /* R1C1 */ + RC(1)";
+    assert_eq!(r1c1_to_a1(source, pos), source);
+
+    // Sheet names are kept as written.
+    assert_eq!(
+        r1c1_to_a1("'Sheet 2'!R[-1]C:R[1]C + RC!RC", pos),
+        "'Sheet 2'!C4:C6 + RC!C5",
+    );
+
+    // Converted formulas evaluate the same way.
+    let mut g = Grid::new();
+    let sheet = &mut g.sheets_mut()[0];
+    sheet.set_cell_value(pos![B5], 10);
+    let sheet_id = sheet.id;
+    let a1 = r1c1_to_a1("R5C1 * 2", pos);
+    assert_eq!("20", eval_to_string_at(&g, pos.to_sheet_pos(sheet_id), &a1));
+}
+
 #[test]
 fn test_sheet_references() {
     let mut g = Grid::new();
@@ -2,10 +2,22 @@ use regex::{Regex, RegexBuilder};
 
 use crate::{RunError, RunErrorMsg};
 
+/// Builds a case-insensitive regex that matches a whole string against a
+/// wildcard pattern.
 pub fn wildcard_pattern_to_regex(s: &str) -> Result<Regex, RunError> {
+    // Match whole string using `^...$`.
+    build_regex(s, &format!("^{}$", wildcard_pattern_to_regex_string(s)))
+}
+
+/// Builds a case-insensitive regex that matches a wildcard pattern anywhere
+/// within a string.
+pub fn wildcard_search_regex(s: &str) -> Result<Regex, RunError> {
+    build_regex(s, &wildcard_pattern_to_regex_string(s))
+}
+
+fn wildcard_pattern_to_regex_string(s: &str) -> String {
     let mut chars = s.chars();
     let mut regex_string = String::new();
-    regex_string.push('^'); // Match whole string using `^...$`.
     while let Some(c) = chars.next() {
         match c {
             // Escape the next character, if there is one. Otherwise ignore.
@@ -20,8 +32,11 @@ pub fn wildcard_pattern_to_regex(s: &str) -> Result<Regex, RunError> {
             _ => regex_string.push_str(&regex::escape(&c.to_string())),
         }
     }
-    regex_string.push('$'); // Match whole string using `^...$`.
-    RegexBuilder::new(&regex_string)
+    regex_string
+}
+
+fn build_regex(s: &str, regex_string: &str) -> Result<Regex, RunError> {
+    RegexBuilder::new(regex_string)
         .case_insensitive(true)
         .build()
         .map_err(|e| {
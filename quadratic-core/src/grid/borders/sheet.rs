@@ -7,8 +7,9 @@ use crate::grid::block::SameValue;
 use crate::grid::borders::cell::{CellBorders, CellSide};
 use crate::grid::borders::compute_indices;
 use crate::grid::borders::style::{BorderSelection, BorderStyle};
+use crate::grid::sheet::rows_columns::GridShift;
 use crate::grid::{ColumnData, Sheet};
-use crate::{Pos, Rect};
+use crate::{Axis, Pos, Rect};
 
 pub fn generate_borders(
     sheet: &Sheet,
@@ -132,6 +133,97 @@ impl SheetBorders {
         sheet_borders.per_cell.replace_rect(&cloned_id_space, rect);
         sheet_borders
     }
+
+    /// Clones the borders in a region, including the lines used for
+    /// rendering, so that they can be restored with `set_rect()`.
+    pub(crate) fn clone_rect(&self, rect: &Rect) -> SheetBorders {
+        SheetBorders {
+            per_cell: self.per_cell.clone_rect(rect),
+            render_lookup: self.render_lookup.clone_rect(rect),
+        }
+    }
+
+    /// Returns the range of rows (for a column) or columns (for a row) that
+    /// have borders in the row or column removed by `shift`.
+    pub(crate) fn range_in_shift(&self, shift: GridShift) -> Option<Range<i64>> {
+        let index = shift.index();
+        match shift.axis() {
+            Axis::X => crate::util::union_ranges(
+                [
+                    self.per_cell.borders.get(&index).and_then(|c| c.range()),
+                    self.render_lookup
+                        .vertical
+                        .get(&index)
+                        .and_then(|c| c.range()),
+                    self.render_lookup
+                        .vertical
+                        .get(&(index + 1))
+                        .and_then(|c| c.range()),
+                ]
+                .into_iter()
+                .chain(
+                    self.render_lookup
+                        .horizontal
+                        .iter()
+                        .map(|(&y, line)| line.get(index).is_some().then_some(y..y + 1)),
+                ),
+            ),
+            Axis::Y => crate::util::union_ranges(
+                [
+                    self.render_lookup
+                        .horizontal
+                        .get(&index)
+                        .and_then(|c| c.range()),
+                    self.render_lookup
+                        .horizontal
+                        .get(&(index + 1))
+                        .and_then(|c| c.range()),
+                ]
+                .into_iter()
+                .chain(
+                    self.per_cell
+                        .borders
+                        .iter()
+                        .map(|(&x, column)| column.get(index).is_some().then_some(x..x + 1)),
+                )
+                .chain(
+                    self.render_lookup
+                        .vertical
+                        .iter()
+                        .map(|(&x, line)| line.get(index).is_some().then_some(x..x + 1)),
+                ),
+            ),
+        }
+    }
+
+    /// Inserts or deletes a row or column of borders.
+    pub(crate) fn shift(&mut self, shift: GridShift) {
+        match shift.axis() {
+            Axis::X => {
+                shift_keys(&mut self.per_cell.borders, shift);
+                shift_keys(&mut self.render_lookup.vertical, shift);
+                for row in self.render_lookup.horizontal.values_mut() {
+                    row.shift(shift);
+                }
+            }
+            Axis::Y => {
+                shift_keys(&mut self.render_lookup.horizontal, shift);
+                for column in self.per_cell.borders.values_mut() {
+                    column.shift(shift);
+                }
+                for column in self.render_lookup.vertical.values_mut() {
+                    column.shift(shift);
+                }
+            }
+        }
+    }
+}
+
+fn shift_keys<T>(map: &mut HashMap<i64, T>, shift: GridShift) {
+    *map = std::mem::take(map)
+        .into_iter()
+        .filter_map(|(i, value)| Some((shift.shift_index(i)?, value)))
+        .collect();
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
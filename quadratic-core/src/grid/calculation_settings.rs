@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use super::Grid;
+
+/// Workbook-level settings for how formulas are recalculated.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CalculationSettings {
+    /// Whether formulas in a circular reference are evaluated repeatedly
+    /// until their values converge, instead of returning an error.
+    pub iterative: bool,
+    /// Maximum number of times to evaluate the formulas in a circular
+    /// reference.
+    pub max_iterations: u32,
+    /// Largest change in any value between iterations for the values to be
+    /// considered converged.
+    pub max_change: f64,
+}
+
+impl Default for CalculationSettings {
+    fn default() -> Self {
+        // same defaults as Excel
+        CalculationSettings {
+            iterative: false,
+            max_iterations: 100,
+            max_change: 0.001,
+        }
+    }
+}
+
+impl CalculationSettings {
+    /// Largest allowed number of iterations, the same as Excel's, so that a
+    /// circular reference that does not converge can't run for too long.
+    pub const MAX_ITERATIONS: u32 = 32767;
+
+    /// Returns whether the number of iterations is between one and
+    /// [`Self::MAX_ITERATIONS`] and the maximum change is a nonnegative
+    /// number.
+    pub fn is_valid(&self) -> bool {
+        (1..=Self::MAX_ITERATIONS).contains(&self.max_iterations)
+            && !self.max_change.is_nan()
+            && self.max_change >= 0.0
+    }
+}
+
+impl Grid {
+    pub fn calculation_settings(&self) -> &CalculationSettings {
+        &self.calculation_settings
+    }
+
+    /// Replaces the calculation settings, returning the old settings.
+    pub fn set_calculation_settings(
+        &mut self,
+        settings: CalculationSettings,
+    ) -> CalculationSettings {
+        std::mem::replace(&mut self.calculation_settings, settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid() {
+        let settings = |max_iterations, max_change| CalculationSettings {
+            iterative: true,
+            max_iterations,
+            max_change,
+        };
+        assert!(CalculationSettings::default().is_valid());
+        assert!(settings(1, 0.0).is_valid());
+        assert!(settings(32767, 0.001).is_valid());
+        assert!(!settings(0, 0.001).is_valid());
+        assert!(!settings(32768, 0.001).is_valid());
+        assert!(!settings(u32::MAX, 0.001).is_valid());
+        assert!(!settings(100, -0.001).is_valid());
+        assert!(!settings(100, f64::NAN).is_valid());
+    }
+}
@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
 
 use super::formatting::*;
+use super::sheet::rows_columns::GridShift;
 use super::{Block, BlockContent, SameValue};
 use crate::{CellValue, IsBlank};
 
@@ -77,6 +78,25 @@ impl Column {
             || self.text_color.get(y).is_some()
             || self.fill_color.get(y).is_some()
     }
+
+    /// Inserts or deletes a row in this column, shifting the values and
+    /// formatting below it.
+    pub fn shift(&mut self, shift: GridShift) {
+        self.values = std::mem::take(&mut self.values)
+            .into_iter()
+            .filter_map(|(y, value)| Some((shift.shift_index(y)?, value)))
+            .collect();
+        self.align.shift(shift);
+        self.wrap.shift(shift);
+        self.numeric_format.shift(shift);
+        self.numeric_decimals.shift(shift);
+        self.numeric_commas.shift(shift);
+        self.bold.shift(shift);
+        self.italic.shift(shift);
+        self.text_color.shift(shift);
+        self.fill_color.shift(shift);
+        self.render_size.shift(shift);
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -266,6 +286,32 @@ impl<B: BlockContent> ColumnData<B> {
         to_return
     }
 
+    /// Inserts an empty entry or deletes an entry at the index of `shift`,
+    /// moving all later blocks to make room or fill the gap.
+    pub fn shift(&mut self, shift: GridShift) {
+        let index = shift.index();
+        if shift.is_insert() {
+            if let Some(block) = self.remove_block_containing(index) {
+                let [above, below] = block.split(index);
+                self.add_blocks(above);
+                self.add_blocks(below);
+            }
+        } else {
+            self.remove_range(index..index + 1);
+        }
+        self.0 = std::mem::take(&mut self.0)
+            .into_iter()
+            .map(|(y, mut block)| {
+                // no block starts at a deleted index after `remove_range()`
+                block.y = shift.shift_index(y).unwrap_or(y);
+                (block.y, block)
+            })
+            .collect();
+        if !shift.is_insert() {
+            self.try_merge_at(index);
+        }
+    }
+
     pub fn range(&self) -> Option<Range<i64>> {
         let min = *self.0.first_key_value()?.0;
         let max = self.0.last_key_value()?.1.end();
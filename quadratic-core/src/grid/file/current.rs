@@ -1,13 +1,13 @@
 use crate::color::Rgba;
-use crate::grid::file::v1_5::schema::{self as current};
+use crate::grid::file::v1_6::schema::{self as current};
 use crate::grid::{
     block::SameValue, formatting::RenderSize, generate_borders, set_rect_borders,
-    sheet::sheet_offsets::SheetOffsets, BorderSelection, BorderStyle, CellAlign, CellBorderLine,
-    CellWrap, CodeRun, Column, ColumnData, Grid, GridBounds, NumericFormat, NumericFormatKind,
-    Sheet, SheetBorders, SheetId,
+    sheet::sheet_offsets::SheetOffsets, BorderSelection, BorderStyle, CalculationSettings,
+    CellAlign, CellBorderLine, CellWrap, CodeRun, Column, ColumnData, Grid, GridBounds, NamedRange,
+    NumericFormat, NumericFormatKind, Sheet, SheetBorders, SheetId,
 };
 use crate::grid::{CodeCellLanguage, CodeRunResult};
-use crate::{CellValue, CodeCellValue, Pos, Rect, Value};
+use crate::{CellValue, CodeCellValue, Instant, Pos, Rect, Value};
 use anyhow::Result;
 use bigdecimal::BigDecimal;
 use chrono::Utc;
@@ -75,6 +75,10 @@ fn set_column_format_numeric_format(
                         current::NumericFormatKind::Currency => NumericFormatKind::Currency,
                         current::NumericFormatKind::Percentage => NumericFormatKind::Percentage,
                         current::NumericFormatKind::Exponential => NumericFormatKind::Exponential,
+                        current::NumericFormatKind::Date => NumericFormatKind::Date,
+                        current::NumericFormatKind::Time => NumericFormatKind::Time,
+                        current::NumericFormatKind::DateTime => NumericFormatKind::DateTime,
+                        current::NumericFormatKind::Duration => NumericFormatKind::Duration,
                     },
                     symbol: format.value.symbol.to_owned(),
                 }),
@@ -229,10 +233,27 @@ fn import_code_cell_output(type_field: &str, value: &str) -> CellValue {
         "text" => CellValue::Text(value.to_owned()),
         "number" => CellValue::Number(BigDecimal::from_str(value).unwrap_or_default()),
         "html" => CellValue::Html(value.to_owned()),
+        "time instant" => Instant::parse(value).map_or(CellValue::Blank, CellValue::Instant),
+        "time duration" => {
+            serde_json::from_str(value).map_or(CellValue::Blank, CellValue::Duration)
+        }
         _ => CellValue::Blank,
     }
 }
 
+fn export_code_cell_output(cell_value: &CellValue) -> current::OutputValueValue {
+    let value = match cell_value {
+        // the display string of a duration cannot be parsed, so it is saved
+        // using `serde_json` like duration cell values
+        CellValue::Duration(duration) => serde_json::to_string(duration).unwrap_or_default(),
+        _ => cell_value.to_string(),
+    };
+    current::OutputValueValue {
+        type_field: cell_value.type_name().into(),
+        value,
+    }
+}
+
 fn import_code_cell_builder(sheet: &current::Sheet) -> Result<IndexMap<Pos, CodeRun>> {
     // davidfig: probably the more idiomatic way is to return the code_runs below. It's above my skill level, though.
     let mut code_runs = IndexMap::new();
@@ -306,6 +327,23 @@ pub fn import(file: current::GridSchema) -> Result<Grid> {
                 Ok(new_sheet)
             })
             .collect::<Result<_>>()?,
+        named_ranges: file
+            .named_ranges
+            .into_iter()
+            .map(|named_range| NamedRange {
+                name: named_range.name,
+                sheet_rect: named_range.sheet_rect.map(Into::into),
+            })
+            .collect(),
+        calculation_settings: file
+            .calculation_settings
+            .map(|settings| CalculationSettings {
+                iterative: settings.iterative,
+                max_iterations: settings.max_iterations,
+                max_change: settings.max_change,
+            })
+            .filter(CalculationSettings::is_valid)
+            .unwrap_or_default(),
     })
 }
 
@@ -377,6 +415,10 @@ fn export_column_data_numeric_format(
                             NumericFormatKind::Exponential => {
                                 current::NumericFormatKind::Exponential
                             }
+                            NumericFormatKind::Date => current::NumericFormatKind::Date,
+                            NumericFormatKind::Time => current::NumericFormatKind::Time,
+                            NumericFormatKind::DateTime => current::NumericFormatKind::DateTime,
+                            NumericFormatKind::Duration => current::NumericFormatKind::Duration,
                         },
                         symbol: block.content.value.symbol.clone(),
                     },
@@ -497,12 +539,13 @@ fn export_column_builder(sheet: &Sheet) -> Vec<(i64, current::Column)> {
                                     CellValue::Logical(logical) => {
                                         current::CellValue::Logical(*logical)
                                     }
-                                    CellValue::Instant(instant) => {
-                                        current::CellValue::Instant(instant.to_string())
-                                    }
-                                    CellValue::Duration(duration) => {
-                                        current::CellValue::Duration(duration.to_string())
-                                    }
+                                    // these are parsed using `serde_json` on import
+                                    CellValue::Instant(instant) => current::CellValue::Instant(
+                                        serde_json::to_string(instant).unwrap_or_default(),
+                                    ),
+                                    CellValue::Duration(duration) => current::CellValue::Duration(
+                                        serde_json::to_string(duration).unwrap_or_default(),
+                                    ),
                                     CellValue::Error(error) => current::CellValue::Error(
                                         current::RunError::from_grid_run_error(error),
                                     ),
@@ -573,12 +616,9 @@ pub fn export(grid: &mut Grid) -> Result<current::GridSchema> {
                     .map(|(pos, code_run)| {
                         let result = match &code_run.result {
                             CodeRunResult::Ok(output) => current::CodeRunResult::Ok(match output {
-                                Value::Single(cell_value) => {
-                                    current::OutputValue::Single(current::OutputValueValue {
-                                        type_field: cell_value.type_name().into(),
-                                        value: cell_value.to_string(),
-                                    })
-                                }
+                                Value::Single(cell_value) => current::OutputValue::Single(
+                                    export_code_cell_output(cell_value),
+                                ),
                                 Value::Array(array) => {
                                     current::OutputValue::Array(current::OutputArray {
                                         size: current::OutputSize {
@@ -587,12 +627,7 @@ pub fn export(grid: &mut Grid) -> Result<current::GridSchema> {
                                         },
                                         values: array
                                             .rows()
-                                            .flat_map(|row| {
-                                                row.iter().map(|cell| current::OutputValueValue {
-                                                    type_field: cell.type_name().into(),
-                                                    value: cell.to_string(),
-                                                })
-                                            })
+                                            .flat_map(|row| row.iter().map(export_code_cell_output))
                                             .collect(),
                                     })
                                 }
@@ -622,5 +657,21 @@ pub fn export(grid: &mut Grid) -> Result<current::GridSchema> {
                     .collect(),
             })
             .collect(),
+        named_ranges: grid
+            .named_ranges()
+            .iter()
+            .map(|named_range| current::NamedRange {
+                name: named_range.name.clone(),
+                sheet_rect: named_range.sheet_rect.map(Into::into),
+            })
+            .collect(),
+        // only saved if changed, so that files without them are unchanged
+        calculation_settings: Some(grid.calculation_settings())
+            .filter(|settings| **settings != CalculationSettings::default())
+            .map(|settings| current::CalculationSettings {
+                iterative: settings.iterative,
+                max_iterations: settings.max_iterations,
+                max_change: settings.max_change,
+            }),
     })
 }
@@ -8,12 +8,18 @@ pub mod current;
 mod v1_3;
 mod v1_4;
 mod v1_5;
+mod v1_6;
 
-pub static CURRENT_VERSION: &str = "1.5";
+pub static CURRENT_VERSION: &str = "1.6";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "version")]
 enum GridFile {
+    #[serde(rename = "1.6")]
+    V1_6 {
+        #[serde(flatten)]
+        grid: v1_6::schema::GridSchema,
+    },
     #[serde(rename = "1.5")]
     V1_5 {
         #[serde(flatten)]
@@ -32,16 +38,17 @@ enum GridFile {
 }
 
 impl GridFile {
-    fn into_latest(self) -> Result<v1_5::schema::GridSchema> {
+    fn into_latest(self) -> Result<v1_6::schema::GridSchema> {
         match self {
-            GridFile::V1_5 { grid } => Ok(grid),
-            GridFile::V1_4 { grid } => v1_4::file::upgrade(grid),
+            GridFile::V1_6 { grid } => Ok(grid),
+            GridFile::V1_5 { grid } => v1_5::file::upgrade(grid),
+            GridFile::V1_4 { grid } => v1_5::file::upgrade(v1_4::file::upgrade(grid)?),
             GridFile::V1_3 { grid } => {
                 if let Ok(v1_4) = v1_3::file::upgrade(grid) {
-                    v1_4::file::upgrade(v1_4)
+                    v1_5::file::upgrade(v1_4::file::upgrade(v1_4)?)
                 } else {
                     Err(anyhow!(
-                        "Failed to upgrade from v1.3 to v1.4 (on the way to v1.5"
+                        "Failed to upgrade from v1.3 to v1.4 (on the way to v1.6"
                     ))
                 }
             }
@@ -78,8 +85,11 @@ mod tests {
     use super::*;
     use crate::{
         color::Rgba,
-        grid::{generate_borders, set_rect_borders, BorderSelection, BorderStyle, CellBorderLine},
-        Pos, Rect,
+        grid::{
+            generate_borders, set_rect_borders, BorderSelection, BorderStyle, CalculationSettings,
+            CellBorderLine, CodeCellLanguage, CodeRun, CodeRunResult,
+        },
+        CellValue, CodeCellValue, Duration, Instant, Pos, Rect, Value,
     };
 
     const V1_3_FILE: &str = include_str!("../../../../quadratic-rust-shared/data/grid/v1_3.grid");
@@ -206,12 +216,109 @@ mod tests {
 
     const V1_5_FILE: &str =
         include_str!("../../../../quadratic-rust-shared/data/grid/v1_5_simple.grid");
+    const V1_6_FILE: &str =
+        include_str!("../../../../quadratic-rust-shared/data/grid/v1_6_simple.grid");
 
     #[test]
-    fn imports_and_exports_a_current_grid() {
+    fn process_a_v1_5_file() {
         let mut imported = import(V1_5_FILE).unwrap();
+        assert!(imported.named_ranges().is_empty());
+        let exported = export(&mut imported).unwrap();
+        assert_eq!(import(&exported).unwrap(), imported);
+    }
+
+    #[test]
+    fn imports_and_exports_a_current_grid() {
+        let mut imported = import(V1_6_FILE).unwrap();
+        assert_eq!(imported.named_ranges()[0].name, "TaxRate");
         let exported = export(&mut imported).unwrap();
-        assert_eq!(V1_5_FILE, exported);
+        assert_eq!(V1_6_FILE, exported);
+    }
+
+    #[test]
+    fn imports_and_exports_calculation_settings() {
+        let mut grid = import(V1_6_FILE).unwrap();
+        assert_eq!(grid.calculation_settings(), &CalculationSettings::default());
+        grid.set_calculation_settings(CalculationSettings {
+            iterative: true,
+            max_iterations: 50,
+            max_change: 0.01,
+        });
+        let exported = export(&mut grid).unwrap();
+        assert_eq!(import(&exported).unwrap(), grid);
+
+        // invalid settings are replaced with the defaults
+        for settings in [
+            CalculationSettings {
+                iterative: true,
+                max_iterations: 0,
+                max_change: 0.01,
+            },
+            CalculationSettings {
+                iterative: true,
+                max_iterations: CalculationSettings::MAX_ITERATIONS + 1,
+                max_change: 0.01,
+            },
+            CalculationSettings {
+                iterative: true,
+                max_iterations: 50,
+                max_change: -1.0,
+            },
+        ] {
+            grid.set_calculation_settings(settings);
+            let exported = export(&mut grid).unwrap();
+            assert_eq!(
+                import(&exported).unwrap().calculation_settings(),
+                &CalculationSettings::default()
+            );
+        }
+    }
+
+    #[test]
+    fn imports_and_exports_date_time_code_outputs() {
+        let mut grid = Grid::new();
+        let sheet = &mut grid.sheets_mut()[0];
+        let instant = Instant::parse("2024-02-29 13:45:30").unwrap();
+        let duration = Duration::from_seconds(3725.5);
+        let outputs = [
+            CellValue::Instant(instant),
+            CellValue::Duration(duration),
+            CellValue::Duration(Duration {
+                years: 1,
+                months: -2,
+                seconds: 0.25,
+            }),
+        ];
+        for (x, output) in outputs.iter().enumerate() {
+            let pos = Pos { x: x as i64, y: 0 };
+            sheet.set_cell_value(
+                pos,
+                CellValue::Code(CodeCellValue {
+                    language: CodeCellLanguage::Formula,
+                    code: "NOW()".into(),
+                }),
+            );
+            sheet.set_code_run(
+                pos,
+                Some(CodeRun {
+                    std_out: None,
+                    std_err: None,
+                    formatted_code_string: None,
+                    spill_error: false,
+                    last_modified: chrono::Utc::now(),
+                    cells_accessed: Default::default(),
+                    result: CodeRunResult::Ok(Value::Single(output.clone())),
+                }),
+            );
+        }
+
+        let exported = export(&mut grid).unwrap();
+        let imported = import(&exported).unwrap();
+        let sheet = &imported.sheets()[0];
+        for (x, output) in outputs.iter().enumerate() {
+            let pos = Pos { x: x as i64, y: 0 };
+            assert_eq!(sheet.display_value(pos).as_ref(), Some(output));
+        }
     }
 
     #[test]
@@ -1,6 +1,215 @@
+use std::collections::HashMap;
+
+use crate::formulas::replace_sheet_references;
+use crate::grid::file::v1_5::schema as v1_5;
+use crate::grid::file::v1_6::schema as v1_6;
+use anyhow::Result;
+
+/// Converts the sheet names in formulas to sheet IDs. Formulas store sheet IDs
+/// starting in v1.6, so that references are kept when a sheet is renamed.
+fn upgrade_formula_sheet_references(sheets: &mut [v1_5::Sheet]) {
+    let sheet_ids = sheets
+        .iter()
+        .map(|sheet| (sheet.name.clone(), sheet.id.id.clone()))
+        .collect::<HashMap<_, _>>();
+    for sheet in sheets.iter_mut() {
+        for (_, column) in sheet.columns.iter_mut() {
+            for value in column.values.values_mut() {
+                if let v1_5::CellValue::Code(code_cell) = value {
+                    if code_cell.language == v1_5::CodeCellLanguage::Formula {
+                        code_cell.code = replace_sheet_references(&code_cell.code, |name| {
+                            Some(sheet_ids.get(name).cloned().unwrap_or(name.to_string()))
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn upgrade_run_error(error: v1_5::RunError) -> v1_6::RunError {
+    use v1_5::RunErrorMsg as Old;
+    use v1_6::RunErrorMsg as New;
+
+    v1_6::RunError {
+        span: error.span,
+        msg: match error.msg {
+            Old::PythonError(str) => New::PythonError(str),
+            Old::Spill => New::Spill,
+            Old::Unimplemented => New::Unimplemented,
+            Old::UnknownError => New::UnknownError,
+            Old::InternalError(str) => New::InternalError(str),
+
+            // Compile errors
+            Old::Unterminated(str) => New::Unterminated(str),
+            Old::Expected { expected, got } => New::Expected { expected, got },
+            Old::Unexpected(str) => New::Unexpected(str),
+            Old::TooManyArguments {
+                func_name,
+                max_arg_count,
+            } => New::TooManyArguments {
+                func_name,
+                max_arg_count,
+            },
+            Old::MissingRequiredArgument {
+                func_name,
+                arg_name,
+            } => New::MissingRequiredArgument {
+                func_name,
+                arg_name,
+            },
+            Old::BadFunctionName => New::BadFunctionName,
+            Old::BadCellReference => New::BadCellReference,
+            Old::BadNumber => New::BadNumber,
+
+            // Array size errors
+            Old::ExactArraySizeMismatch { expected, got } => {
+                New::ExactArraySizeMismatch { expected, got }
+            }
+            Old::ExactArrayAxisMismatch {
+                axis,
+                expected,
+                got,
+            } => New::ExactArrayAxisMismatch {
+                axis,
+                expected,
+                got,
+            },
+            Old::ArrayAxisMismatch {
+                axis,
+                expected,
+                got,
+            } => New::ArrayAxisMismatch {
+                axis,
+                expected,
+                got,
+            },
+            Old::EmptyArray => New::EmptyArray,
+            Old::NonRectangularArray => New::NonRectangularArray,
+            Old::NonLinearArray => New::NonLinearArray,
+            Old::ArrayTooBig => New::ArrayTooBig,
+
+            // Runtime errors
+            Old::CircularReference => New::CircularReference,
+            Old::Overflow => New::Overflow,
+            Old::DivideByZero => New::DivideByZero,
+            Old::NegativeExponent => New::NegativeExponent,
+            Old::NotANumber => New::NotANumber,
+            Old::Infinity => New::Infinity,
+            Old::IndexOutOfBounds => New::IndexOutOfBounds,
+            Old::NoMatch => New::NoMatch,
+            Old::InvalidArgument => New::InvalidArgument,
+        },
+    }
+}
+
+fn upgrade_cell_value(value: v1_5::CellValue) -> v1_6::CellValue {
+    match value {
+        v1_5::CellValue::Blank => v1_6::CellValue::Blank,
+        v1_5::CellValue::Text(text) => v1_6::CellValue::Text(text),
+        v1_5::CellValue::Number(number) => v1_6::CellValue::Number(number),
+        v1_5::CellValue::Html(html) => v1_6::CellValue::Html(html),
+        v1_5::CellValue::Code(code_cell) => v1_6::CellValue::Code(code_cell),
+        v1_5::CellValue::Logical(logical) => v1_6::CellValue::Logical(logical),
+        v1_5::CellValue::Instant(instant) => v1_6::CellValue::Instant(instant),
+        v1_5::CellValue::Duration(duration) => v1_6::CellValue::Duration(duration),
+        v1_5::CellValue::Error(error) => v1_6::CellValue::Error(upgrade_run_error(error)),
+    }
+}
+
+fn upgrade_numeric_format(
+    numeric_format: v1_5::ColumnRepeat<v1_5::NumericFormat>,
+) -> v1_6::ColumnRepeat<v1_6::NumericFormat> {
+    let kind = match numeric_format.value.kind {
+        v1_5::NumericFormatKind::Number => v1_6::NumericFormatKind::Number,
+        v1_5::NumericFormatKind::Currency => v1_6::NumericFormatKind::Currency,
+        v1_5::NumericFormatKind::Percentage => v1_6::NumericFormatKind::Percentage,
+        v1_5::NumericFormatKind::Exponential => v1_6::NumericFormatKind::Exponential,
+    };
+    v1_6::ColumnRepeat {
+        value: v1_6::NumericFormat {
+            kind,
+            symbol: numeric_format.value.symbol,
+        },
+        len: numeric_format.len,
+    }
+}
+
+fn upgrade_column(column: v1_5::Column) -> v1_6::Column {
+    v1_6::Column {
+        values: column
+            .values
+            .into_iter()
+            .map(|(y, value)| (y, upgrade_cell_value(value)))
+            .collect(),
+        align: column.align,
+        wrap: column.wrap,
+        numeric_format: column
+            .numeric_format
+            .into_iter()
+            .map(|(y, numeric_format)| (y, upgrade_numeric_format(numeric_format)))
+            .collect(),
+        numeric_decimals: column.numeric_decimals,
+        numeric_commas: column.numeric_commas,
+        bold: column.bold,
+        italic: column.italic,
+        text_color: column.text_color,
+        fill_color: column.fill_color,
+        render_size: column.render_size,
+    }
+}
+
+fn upgrade_code_run(code_run: v1_5::CodeRun) -> v1_6::CodeRun {
+    v1_6::CodeRun {
+        formatted_code_string: code_run.formatted_code_string,
+        std_out: code_run.std_out,
+        std_err: code_run.std_err,
+        cells_accessed: code_run.cells_accessed,
+        result: match code_run.result {
+            v1_5::CodeRunResult::Ok(output) => v1_6::CodeRunResult::Ok(output),
+            v1_5::CodeRunResult::Err(error) => v1_6::CodeRunResult::Err(upgrade_run_error(error)),
+        },
+        spill_error: code_run.spill_error,
+        last_modified: code_run.last_modified,
+    }
+}
+
+fn upgrade_sheet(sheet: v1_5::Sheet) -> v1_6::Sheet {
+    v1_6::Sheet {
+        id: sheet.id,
+        name: sheet.name,
+        color: sheet.color,
+        order: sheet.order,
+        offsets: sheet.offsets,
+        columns: sheet
+            .columns
+            .into_iter()
+            .map(|(x, column)| (x, upgrade_column(column)))
+            .collect(),
+        borders: sheet.borders,
+        code_runs: sheet
+            .code_runs
+            .into_iter()
+            .map(|(pos, code_run)| (pos, upgrade_code_run(code_run)))
+            .collect(),
+    }
+}
+
+pub(crate) fn upgrade(mut schema: v1_5::GridSchema) -> Result<v1_6::GridSchema> {
+    upgrade_formula_sheet_references(&mut schema.sheets);
+    let schema = v1_6::GridSchema {
+        version: Some("1.6".into()),
+        sheets: schema.sheets.into_iter().map(upgrade_sheet).collect(),
+        named_ranges: vec![],
+        calculation_settings: None,
+    };
+    Ok(schema)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::grid::file::v1_5::schema::GridSchema;
+    use crate::grid::file::v1_5::schema::{CellValue, CodeCell, CodeCellLanguage, GridSchema};
+    use crate::grid::file::v1_6::schema as v1_6;
     use anyhow::{anyhow, Result};
 
     const V1_5_FILE: &str =
@@ -15,6 +224,42 @@ mod tests {
         serde_json::to_string(grid_schema).map_err(|e| anyhow!("Could not export file: {:?}", e))
     }
 
+    #[test]
+    fn upgrades_a_v1_5_file() {
+        let imported = import(V1_5_FILE).unwrap();
+        let upgraded = super::upgrade(imported.clone()).unwrap();
+        assert_eq!(upgraded.version, Some("1.6".into()));
+        assert_eq!(
+            serde_json::to_value(&upgraded.sheets).unwrap(),
+            serde_json::to_value(&imported.sheets).unwrap(),
+        );
+        assert!(upgraded.named_ranges.is_empty());
+    }
+
+    #[test]
+    fn upgrades_formula_sheet_references() {
+        let mut imported = import(V1_5_FILE).unwrap();
+        let sheet = &mut imported.sheets[0];
+        let sheet_id = sheet.id.id.clone();
+        let code_cell = |code: &str| CodeCell {
+            language: CodeCellLanguage::Formula,
+            code: code.to_string(),
+        };
+        let values = &mut sheet.columns[0].1.values;
+        values.insert(
+            "10".into(),
+            CellValue::Code(code_cell("'Sheet 1'!A0 & Other!A0")),
+        );
+
+        let upgraded = super::upgrade(imported).unwrap();
+        assert_eq!(
+            upgraded.sheets[0].columns[0].1.values.get("10"),
+            Some(&v1_6::CellValue::Code(code_cell(&format!(
+                "'{sheet_id}'!A0 & Other!A0"
+            )))),
+        );
+    }
+
     #[test]
     fn import_and_export_a_v1_5_file() {
         let imported = import(V1_5_FILE).unwrap();
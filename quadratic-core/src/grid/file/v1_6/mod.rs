@@ -0,0 +1,2 @@
+pub mod run_error;
+pub mod schema;
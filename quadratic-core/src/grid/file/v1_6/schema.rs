@@ -0,0 +1,113 @@
+use chrono::{serde::ts_seconds_option, DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub use crate::grid::file::v1_5::schema::*;
+
+pub use super::run_error::RunError;
+pub use super::run_error::RunErrorMsg;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GridSchema {
+    pub sheets: Vec<Sheet>,
+    pub named_ranges: Vec<NamedRange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calculation_settings: Option<CalculationSettings>,
+    pub version: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sheet {
+    pub id: Id,
+    pub name: String,
+    pub color: Option<String>,
+    pub order: String,
+    pub offsets: Offsets,
+    pub columns: Vec<(i64, Column)>,
+    pub borders: Borders,
+    pub code_runs: Vec<(Pos, CodeRun)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeRun {
+    pub formatted_code_string: Option<String>,
+    pub std_out: Option<String>,
+    pub std_err: Option<String>,
+    pub cells_accessed: Vec<SheetRect>,
+    pub result: CodeRunResult,
+    pub spill_error: bool,
+
+    // the Option is necessary to use serde
+    #[serde(with = "ts_seconds_option")]
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CodeRunResult {
+    Ok(OutputValue),
+    Err(RunError),
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    pub values: HashMap<String, CellValue>,
+    pub align: HashMap<String, ColumnRepeat<CellAlign>>,
+    pub wrap: HashMap<String, ColumnRepeat<CellWrap>>,
+    pub numeric_format: HashMap<String, ColumnRepeat<NumericFormat>>,
+    pub numeric_decimals: HashMap<String, ColumnRepeat<i16>>,
+    pub numeric_commas: HashMap<String, ColumnRepeat<bool>>,
+    pub bold: HashMap<String, ColumnRepeat<bool>>,
+    pub italic: HashMap<String, ColumnRepeat<bool>>,
+    pub text_color: HashMap<String, ColumnRepeat<String>>,
+    pub fill_color: HashMap<String, ColumnRepeat<String>>,
+    pub render_size: HashMap<String, ColumnRepeat<RenderSize>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CellValue {
+    Blank,
+    Text(String),
+    Number(String),
+    Html(String),
+    Code(CodeCell),
+    Logical(bool),
+    Instant(String),
+    Duration(String),
+    Error(RunError),
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum NumericFormatKind {
+    #[default]
+    Number,
+    Currency,
+    Percentage,
+    Exponential,
+    Date,
+    Time,
+    DateTime,
+    Duration,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NumericFormat {
+    #[serde(rename = "type")]
+    pub kind: NumericFormatKind,
+    pub symbol: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedRange {
+    pub name: String,
+    pub sheet_rect: Option<SheetRect>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalculationSettings {
+    pub iterative: bool,
+    pub max_iterations: u32,
+    pub max_change: f64,
+}
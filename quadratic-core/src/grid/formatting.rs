@@ -156,6 +156,8 @@ pub enum CellWrap {
 pub struct NumericFormat {
     #[serde(rename = "type")]
     pub kind: NumericFormatKind,
+    /// Currency symbol for [`NumericFormatKind::Currency`], or the `strftime`
+    /// or Excel-style pattern for date and time kinds.
     pub symbol: Option<String>,
 }
 
@@ -179,6 +181,30 @@ pub enum NumericFormatKind {
     Currency, // { symbol: String }, // TODO: would be nice if this were just a single char (and it could be)
     Percentage,
     Exponential,
+    Date,
+    Time,
+    DateTime,
+    Duration,
+}
+impl NumericFormatKind {
+    /// Returns whether this kind displays dates, times, or durations.
+    pub fn is_date_time(self) -> bool {
+        self.default_pattern().is_some()
+    }
+    /// Returns the pattern used for date and time kinds when no pattern is
+    /// given.
+    pub fn default_pattern(self) -> Option<&'static str> {
+        match self {
+            NumericFormatKind::Number
+            | NumericFormatKind::Currency
+            | NumericFormatKind::Percentage
+            | NumericFormatKind::Exponential => None,
+            NumericFormatKind::Date => Some("%Y-%m-%d"),
+            NumericFormatKind::Time => Some("%H:%M:%S"),
+            NumericFormatKind::DateTime => Some("%Y-%m-%d %H:%M:%S"),
+            NumericFormatKind::Duration => Some("[h]:mm:ss"),
+        }
+    }
 }
 
 /// Whether a set of booleans has any `true` values and/or any `false` values.
@@ -10,6 +10,7 @@ pub use borders::{
     IdSpaceBorders, LegacyCellBorder, LegacyCellBorders, SheetBorders,
 };
 pub use bounds::GridBounds;
+pub use calculation_settings::CalculationSettings;
 pub use code_run::*;
 pub use column::{Column, ColumnData};
 pub use formatting::{
@@ -17,6 +18,7 @@ pub use formatting::{
     NumericDecimals, NumericFormat, NumericFormatKind, RenderSize, TextColor,
 };
 pub use ids::*;
+pub use named_range::NamedRange;
 use serde::{Deserialize, Serialize};
 pub use sheet::Sheet;
 #[cfg(feature = "js")]
@@ -25,12 +27,14 @@ use wasm_bindgen::prelude::*;
 mod block;
 mod borders;
 mod bounds;
+mod calculation_settings;
 mod code_run;
 mod column;
 pub mod file;
 pub mod formatting;
 mod ids;
 pub mod js_types;
+mod named_range;
 mod offsets;
 pub mod search;
 pub mod series;
@@ -41,6 +45,8 @@ pub mod sheets;
 #[cfg_attr(feature = "js", wasm_bindgen)]
 pub struct Grid {
     sheets: Vec<Sheet>,
+    named_ranges: Vec<NamedRange>,
+    calculation_settings: CalculationSettings,
 }
 impl Default for Grid {
     fn default() -> Self {
@@ -49,7 +55,11 @@ impl Default for Grid {
 }
 impl Grid {
     pub fn new() -> Self {
-        let mut ret = Grid { sheets: vec![] };
+        let mut ret = Grid {
+            sheets: vec![],
+            named_ranges: vec![],
+            calculation_settings: CalculationSettings::default(),
+        };
         ret.add_sheet(None);
         ret
     }
@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+
+use super::{sheet::rows_columns::GridShift, Grid, SheetId};
+use crate::{formulas::is_valid_named_range_name, SheetRect};
+
+/// Workbook-level name for a range of cells, such as `TaxRate`, which can be
+/// used in formulas in place of a cell reference.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NamedRange {
+    pub name: String,
+    /// `None` if all of the cells were deleted, in which case the name is a
+    /// bad cell reference.
+    pub sheet_rect: Option<SheetRect>,
+}
+
+impl Grid {
+    pub fn named_ranges(&self) -> &[NamedRange] {
+        &self.named_ranges
+    }
+
+    /// Returns the named range with the given name, ignoring case.
+    pub fn named_range(&self, name: &str) -> Option<&NamedRange> {
+        self.named_ranges
+            .iter()
+            .find(|named_range| named_range.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Adds a named range, replacing any existing named range with the same
+    /// name (ignoring case). Returns the replaced named range, if any.
+    pub fn set_named_range(&mut self, named_range: NamedRange) -> Option<NamedRange> {
+        let old = self.remove_named_range(&named_range.name);
+        self.named_ranges.push(named_range);
+        old
+    }
+
+    /// Removes the named range with the given name, ignoring case. Returns the
+    /// removed named range, if any.
+    pub fn remove_named_range(&mut self, name: &str) -> Option<NamedRange> {
+        let i = self
+            .named_ranges
+            .iter()
+            .position(|named_range| named_range.name.eq_ignore_ascii_case(name))?;
+        Some(self.named_ranges.remove(i))
+    }
+
+    /// Renames a named range. Returns `false` if there is no named range
+    /// called `old_name` or if `new_name` is invalid or already in use by
+    /// another named range.
+    pub fn rename_named_range(&mut self, old_name: &str, new_name: &str) -> bool {
+        if !is_valid_named_range_name(new_name) {
+            return false;
+        }
+        if !old_name.eq_ignore_ascii_case(new_name) && self.named_range(new_name).is_some() {
+            return false;
+        }
+        match self
+            .named_ranges
+            .iter_mut()
+            .find(|named_range| named_range.name.eq_ignore_ascii_case(old_name))
+        {
+            Some(named_range) => {
+                named_range.name = new_name.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Updates the named ranges in a sheet for a row or column being inserted
+    /// or deleted. Ranges are moved or clipped, and a range whose cells are
+    /// all deleted becomes a bad cell reference. Returns the named ranges that
+    /// changed, as they were before the shift.
+    pub fn shift_named_ranges(&mut self, sheet_id: SheetId, shift: GridShift) -> Vec<NamedRange> {
+        let mut changed = vec![];
+        for named_range in &mut self.named_ranges {
+            let Some(sheet_rect) = named_range.sheet_rect else {
+                continue;
+            };
+            if sheet_rect.sheet_id != sheet_id {
+                continue;
+            }
+            let new_sheet_rect = shift
+                .shift_rect(sheet_rect.into())
+                .map(|rect| rect.to_sheet_rect(sheet_id));
+            if new_sheet_rect != named_range.sheet_rect {
+                changed.push(named_range.clone());
+                named_range.sheet_rect = new_sheet_rect;
+            }
+        }
+        changed
+    }
+
+    /// Makes every named range in a deleted sheet a bad cell reference.
+    /// Returns the named ranges that changed, as they were before.
+    pub fn remove_sheet_from_named_ranges(&mut self, sheet_id: SheetId) -> Vec<NamedRange> {
+        let mut changed = vec![];
+        for named_range in &mut self.named_ranges {
+            if named_range
+                .sheet_rect
+                .is_some_and(|sheet_rect| sheet_rect.sheet_id == sheet_id)
+            {
+                changed.push(named_range.clone());
+                named_range.sheet_rect = None;
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pos;
+
+    fn named_range(grid: &Grid, name: &str) -> NamedRange {
+        NamedRange {
+            name: name.to_string(),
+            sheet_rect: Some(SheetRect::single_pos(
+                Pos { x: 1, y: 2 },
+                grid.first_sheet_id(),
+            )),
+        }
+    }
+
+    #[test]
+    fn test_set_and_remove_named_range() {
+        let mut grid = Grid::new();
+        let tax_rate = named_range(&grid, "TaxRate");
+        assert_eq!(grid.set_named_range(tax_rate.clone()), None);
+        assert_eq!(grid.named_range("taxrate"), Some(&tax_rate));
+        assert_eq!(grid.named_range("Revenue"), None);
+
+        let replacement = named_range(&grid, "TAXRATE");
+        assert_eq!(grid.set_named_range(replacement.clone()), Some(tax_rate));
+        assert_eq!(grid.named_ranges(), std::slice::from_ref(&replacement));
+
+        assert_eq!(grid.remove_named_range("TaxRate"), Some(replacement));
+        assert_eq!(grid.remove_named_range("TaxRate"), None);
+        assert!(grid.named_ranges().is_empty());
+    }
+
+    #[test]
+    fn test_rename_named_range() {
+        let mut grid = Grid::new();
+        grid.set_named_range(named_range(&grid, "TaxRate"));
+        grid.set_named_range(named_range(&grid, "Revenue"));
+
+        assert!(grid.rename_named_range("taxrate", "Tax_Rate"));
+        assert!(grid.named_range("TaxRate").is_none());
+        assert_eq!(grid.named_range("tax_rate").unwrap().name, "Tax_Rate");
+
+        assert!(grid.rename_named_range("Tax_Rate", "TAX_RATE"));
+        assert!(!grid.rename_named_range("TAX_RATE", "revenue"));
+        assert!(!grid.rename_named_range("TAX_RATE", "A1"));
+        assert!(!grid.rename_named_range("Missing", "Other"));
+    }
+
+    #[test]
+    fn test_shift_named_ranges() {
+        let mut grid = Grid::new();
+        let sheet_id = grid.first_sheet_id();
+        let other_id = grid.add_sheet(None);
+        let range = |min_y, max_y, sheet_id| {
+            Some(SheetRect::new_pos_span(
+                Pos { x: 0, y: min_y },
+                Pos { x: 1, y: max_y },
+                sheet_id,
+            ))
+        };
+        for (name, sheet_rect) in [
+            ("Span", range(2, 4, sheet_id)),
+            ("Single", range(3, 3, sheet_id)),
+            ("Other", range(3, 3, other_id)),
+        ] {
+            grid.set_named_range(NamedRange {
+                name: name.to_string(),
+                sheet_rect,
+            });
+        }
+        let sheet_rect = |grid: &Grid, name| grid.named_range(name).unwrap().sheet_rect;
+
+        let changed = grid.shift_named_ranges(sheet_id, GridShift::InsertRow(0));
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed[0].sheet_rect, range(2, 4, sheet_id));
+        assert_eq!(sheet_rect(&grid, "Span"), range(3, 5, sheet_id));
+        assert_eq!(sheet_rect(&grid, "Single"), range(4, 4, sheet_id));
+        assert_eq!(sheet_rect(&grid, "Other"), range(3, 3, other_id));
+
+        let changed = grid.shift_named_ranges(sheet_id, GridShift::DeleteRow(4));
+        assert_eq!(changed.len(), 2);
+        assert_eq!(sheet_rect(&grid, "Span"), range(3, 4, sheet_id));
+        assert_eq!(sheet_rect(&grid, "Single"), None);
+
+        assert!(grid
+            .shift_named_ranges(sheet_id, GridShift::InsertColumn(5))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_remove_sheet_from_named_ranges() {
+        let mut grid = Grid::new();
+        let sheet_id = grid.first_sheet_id();
+        let other_id = grid.add_sheet(None);
+        grid.set_named_range(named_range(&grid, "TaxRate"));
+        grid.set_named_range(NamedRange {
+            name: "Other".to_string(),
+            sheet_rect: Some(SheetRect::single_pos(Pos { x: 1, y: 2 }, other_id)),
+        });
+
+        let changed = grid.remove_sheet_from_named_ranges(sheet_id);
+        assert_eq!(changed, [named_range(&grid, "TaxRate")]);
+        assert_eq!(grid.named_range("TaxRate").unwrap().sheet_rect, None);
+        assert!(grid.named_range("Other").unwrap().sheet_rect.is_some());
+        assert!(grid.remove_sheet_from_named_ranges(sheet_id).is_empty());
+    }
+
+    #[test]
+    fn test_is_valid_named_range_name() {
+        assert!(is_valid_named_range_name("TaxRate"));
+        assert!(is_valid_named_range_name("Q1_Sales"));
+        assert!(is_valid_named_range_name("_total"));
+        assert!(!is_valid_named_range_name(""));
+        assert!(!is_valid_named_range_name("A1"));
+        assert!(!is_valid_named_range_name("ab12"));
+        assert!(!is_valid_named_range_name("True"));
+        assert!(!is_valid_named_range_name("1st"));
+        assert!(!is_valid_named_range_name("Tax Rate"));
+        assert!(!is_valid_named_range_name("Tax.Rate"));
+        assert!(!is_valid_named_range_name("RC"));
+        assert!(!is_valid_named_range_name("r2c3"));
+        assert!(!is_valid_named_range_name("Rn1C"));
+        assert!(is_valid_named_range_name("R"));
+        assert!(is_valid_named_range_name("Rate"));
+        assert!(is_valid_named_range_name("RC_total"));
+    }
+}
@@ -52,6 +52,25 @@ impl Offsets {
         }
     }
 
+    /// Inserts a column/row with the default size at `index`, shifting the
+    /// ones after it.
+    pub fn insert_elem(&mut self, index: i64) {
+        self.sizes = std::mem::take(&mut self.sizes)
+            .into_iter()
+            .map(|(k, v)| if k >= index { (k + 1, v) } else { (k, v) })
+            .collect();
+    }
+    /// Deletes the column/row at `index`, shifting the ones after it, and
+    /// returns its old size.
+    pub fn delete_elem(&mut self, index: i64) -> f64 {
+        let old = self.sizes.remove(&index).unwrap_or(self.default);
+        self.sizes = std::mem::take(&mut self.sizes)
+            .into_iter()
+            .map(|(k, v)| if k > index { (k - 1, v) } else { (k, v) })
+            .collect();
+        old
+    }
+
     /// Returns the width/height of a column/row.
     pub fn get_size(&self, index: i64) -> f64 {
         *self.sizes.get(&index).unwrap_or(&self.default)
@@ -156,6 +175,20 @@ impl Offsets {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_insert_delete_elem() {
+        let mut offsets = Offsets::new(10.0);
+        offsets.set_size(1, 20.0);
+        offsets.set_size(3, 30.0);
+        offsets.insert_elem(2);
+        assert_eq!(offsets.get_size(1), 20.0);
+        assert_eq!(offsets.get_size(3), 10.0);
+        assert_eq!(offsets.get_size(4), 30.0);
+        assert_eq!(offsets.delete_elem(1), 20.0);
+        assert_eq!(offsets.get_size(1), 10.0);
+        assert_eq!(offsets.get_size(3), 30.0);
+    }
+
     #[test]
     fn test_offsets_structure() {
         let mut offsets = Offsets::new(10.0);
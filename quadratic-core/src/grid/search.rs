@@ -1,11 +1,13 @@
 use std::str::FromStr;
 
-use crate::SheetPos;
+use crate::{CodeCellValue, SheetPos};
 
 use super::{sheet::search::SearchOptions, Grid, SheetId};
 
 impl Grid {
     pub fn search(&self, query: &String, options: SearchOptions) -> Vec<SheetPos> {
+        // formulas are matched against the sheet names that the user sees
+        let display_code = |code: &CodeCellValue| self.display_code(code);
         let mut result = Vec::new();
         if let Some(sheet_id) = options
             .sheet_id
@@ -13,12 +15,12 @@ impl Grid {
             .map(|id| SheetId::from_str(id).unwrap_or_default())
         {
             if let Some(sheet) = self.try_sheet(sheet_id) {
-                let sheet_result = sheet.search(query, &options);
+                let sheet_result = sheet.search_with_code(query, &options, &display_code);
                 result.extend(sheet_result);
             }
         } else {
             for sheet in self.sheets() {
-                let sheet_result = sheet.search(query, &options);
+                let sheet_result = sheet.search_with_code(query, &options, &display_code);
                 result.extend(sheet_result);
             }
         }
@@ -28,7 +30,7 @@ impl Grid {
 
 #[cfg(test)]
 mod test {
-    use crate::{CellValue, Pos};
+    use crate::{grid::CodeCellLanguage, CellValue, Pos};
 
     use super::*;
 
@@ -132,4 +134,33 @@ mod test {
         );
         assert_eq!(result.len(), 0);
     }
+
+    #[test]
+    fn search_formula_sheet_names() {
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheet_ids()[0];
+        let data_id = grid.add_sheet(None);
+        grid.try_sheet_mut(data_id).unwrap().name = "Data".to_string();
+
+        let code = grid.formula_sheet_names_to_ids("'Data'!A1 + 1");
+        grid.try_sheet_mut(sheet_id).unwrap().set_cell_value(
+            Pos { x: 0, y: 0 },
+            CellValue::Code(CodeCellValue {
+                language: CodeCellLanguage::Formula,
+                code,
+            }),
+        );
+
+        let search_code = |query: &str| {
+            grid.search(
+                &query.to_string(),
+                SearchOptions {
+                    search_code: Some(true),
+                    ..SearchOptions::default()
+                },
+            )
+        };
+        assert_eq!(search_code("data").len(), 1);
+        assert_eq!(search_code(&data_id.to_string()).len(), 0);
+    }
 }
@@ -22,6 +22,7 @@ pub mod cell_values;
 pub mod code;
 pub mod formatting;
 pub mod rendering;
+pub mod rows_columns;
 pub mod search;
 pub mod sheet_offsets;
 
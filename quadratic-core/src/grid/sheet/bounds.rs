@@ -1,5 +1,5 @@
 use crate::{
-    grid::{Column, GridBounds},
+    grid::{CodeRun, Column, GridBounds},
     CellValue, Pos,
 };
 
@@ -36,6 +36,18 @@ impl Sheet {
         });
     }
 
+    /// Adds the output of a code run to the bounds without recalculating them.
+    /// This keeps the bounds large enough for whole-row and whole-column
+    /// references while code cells are computed; bounds only shrink when they
+    /// are recalculated.
+    pub fn add_code_run_to_bounds(&mut self, pos: Pos, code_run: &CodeRun) {
+        let output_rect = code_run.output_rect(pos, false);
+        self.data_bounds.add(output_rect.min);
+        self.data_bounds.add(output_rect.max);
+        self.format_bounds.add(output_rect.min);
+        self.format_bounds.add(output_rect.max);
+    }
+
     /// Returns whether the sheet is completely empty.
     pub fn is_empty(&self) -> bool {
         self.data_bounds.is_empty() && self.format_bounds.is_empty()
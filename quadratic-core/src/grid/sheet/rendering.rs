@@ -70,7 +70,10 @@ impl Sheet {
 
         match column {
             None => {
-                let align = if matches!(value, CellValue::Number(_)) {
+                let align = if matches!(
+                    value,
+                    CellValue::Number(_) | CellValue::Instant(_) | CellValue::Duration(_)
+                ) {
                     Some(CellAlign::Right)
                 } else {
                     None
@@ -110,6 +113,11 @@ impl Sheet {
 
                         value.to_display(numeric_format, numeric_decimals, numeric_commas)
                     }
+                    CellValue::Instant(_) | CellValue::Duration(_) => {
+                        // dates and times are aligned like numbers
+                        align = align.or(Some(CellAlign::Right));
+                        value.to_display(column.numeric_format.get(y), None, None)
+                    }
                     CellValue::Logical(bool) => {
                         special = match bool {
                             true => Some(JsRenderCellSpecial::True),
@@ -0,0 +1,397 @@
+//! Inserting and deleting whole rows and columns.
+//!
+//! Every structure in the sheet that is keyed by a row or column index is
+//! shifted so that the contents after the insertion/deletion point move along
+//! with it.
+
+use super::Sheet;
+use crate::formulas::{CellRef, CellRefCoord, RangeRef};
+use crate::{Axis, Pos, Rect};
+
+/// A structural change to a sheet that shifts every row or column at or after
+/// an index.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GridShift {
+    InsertColumn(i64),
+    DeleteColumn(i64),
+    InsertRow(i64),
+    DeleteRow(i64),
+}
+impl GridShift {
+    /// Returns the axis along which coordinates are shifted.
+    pub fn axis(self) -> Axis {
+        match self {
+            GridShift::InsertColumn(_) | GridShift::DeleteColumn(_) => Axis::X,
+            GridShift::InsertRow(_) | GridShift::DeleteRow(_) => Axis::Y,
+        }
+    }
+    /// Returns the index of the row or column that is inserted or deleted.
+    pub fn index(self) -> i64 {
+        match self {
+            GridShift::InsertColumn(i)
+            | GridShift::DeleteColumn(i)
+            | GridShift::InsertRow(i)
+            | GridShift::DeleteRow(i) => i,
+        }
+    }
+    /// Returns whether this shift inserts a row or column.
+    pub fn is_insert(self) -> bool {
+        matches!(self, GridShift::InsertColumn(_) | GridShift::InsertRow(_))
+    }
+    /// Returns the shift that undoes this one.
+    pub fn inverse(self) -> Self {
+        match self {
+            GridShift::InsertColumn(i) => GridShift::DeleteColumn(i),
+            GridShift::DeleteColumn(i) => GridShift::InsertColumn(i),
+            GridShift::InsertRow(i) => GridShift::DeleteRow(i),
+            GridShift::DeleteRow(i) => GridShift::InsertRow(i),
+        }
+    }
+
+    /// Returns the new value of an index along the shifted axis, or `None` if
+    /// it is deleted.
+    pub fn shift_index(self, index: i64) -> Option<i64> {
+        let i = self.index();
+        if self.is_insert() {
+            Some(if index >= i { index + 1 } else { index })
+        } else if index == i {
+            None
+        } else {
+            Some(if index > i { index - 1 } else { index })
+        }
+    }
+    /// Returns the new inclusive bounds of a range along the shifted axis. A
+    /// range grows when a row/column is inserted inside it and shrinks when one
+    /// is deleted inside it. Returns `None` if the whole range is deleted.
+    /// An upper bound of `i64::MAX` is unbounded and is left as is.
+    pub fn shift_index_range(self, lo: i64, hi: i64) -> Option<(i64, i64)> {
+        let i = self.index();
+        let is_unbounded = hi == i64::MAX;
+        if self.is_insert() {
+            let lo = if lo >= i { lo + 1 } else { lo };
+            let hi = if hi >= i && !is_unbounded { hi + 1 } else { hi };
+            Some((lo, hi))
+        } else if lo == i && hi == i {
+            None
+        } else {
+            let lo = if lo > i { lo - 1 } else { lo };
+            let hi = if hi >= i && !is_unbounded { hi - 1 } else { hi };
+            Some((lo, hi))
+        }
+    }
+
+    /// Returns the new position of a cell, or `None` if it is deleted.
+    pub fn shift_pos(self, pos: Pos) -> Option<Pos> {
+        Some(match self.axis() {
+            Axis::X => Pos {
+                x: self.shift_index(pos.x)?,
+                y: pos.y,
+            },
+            Axis::Y => Pos {
+                x: pos.x,
+                y: self.shift_index(pos.y)?,
+            },
+        })
+    }
+    /// Returns the new bounds of a rectangle, or `None` if it is entirely
+    /// deleted.
+    pub fn shift_rect(self, rect: Rect) -> Option<Rect> {
+        Some(match self.axis() {
+            Axis::X => {
+                let (x1, x2) = self.shift_index_range(rect.min.x, rect.max.x)?;
+                Rect::new_span(
+                    Pos {
+                        x: x1,
+                        y: rect.min.y,
+                    },
+                    Pos {
+                        x: x2,
+                        y: rect.max.y,
+                    },
+                )
+            }
+            Axis::Y => {
+                let (y1, y2) = self.shift_index_range(rect.min.y, rect.max.y)?;
+                Rect::new_span(
+                    Pos {
+                        x: rect.min.x,
+                        y: y1,
+                    },
+                    Pos {
+                        x: rect.max.x,
+                        y: y2,
+                    },
+                )
+            }
+        })
+    }
+
+    /// Rewrites a formula's range reference for this shift.
+    ///
+    /// `old_base` is the position of the formula before the shift and
+    /// `new_base` is its position after. `is_shifted` returns whether a
+    /// reference's sheet is the one being shifted. References into other
+    /// sheets keep pointing at the same cells, but relative coordinates are
+    /// updated if the formula itself moved. Returns `None` if the reference
+    /// points only to deleted cells.
+    pub fn shift_range_ref(
+        self,
+        range_ref: &RangeRef,
+        old_base: Pos,
+        new_base: Pos,
+        is_shifted: impl Fn(&Option<String>) -> bool,
+    ) -> Option<RangeRef> {
+        let rebase = |coord: CellRefCoord, target: i64, base: i64| match coord {
+            CellRefCoord::Relative(_) => CellRefCoord::Relative(target - base),
+            CellRefCoord::Absolute(_) => CellRefCoord::Absolute(target),
+        };
+        let shift_pair = |c1: CellRefCoord, c2: CellRefCoord, axis: Axis, shifted: bool| {
+            let (old, new) = match axis {
+                Axis::X => (old_base.x, new_base.x),
+                Axis::Y => (old_base.y, new_base.y),
+            };
+            let (t1, t2) = (c1.resolve_from(old), c2.resolve_from(old));
+            let (n1, n2) = if shifted && axis == self.axis() {
+                let (lo, hi) = self.shift_index_range(t1.min(t2), t1.max(t2))?;
+                if t1 <= t2 {
+                    (lo, hi)
+                } else {
+                    (hi, lo)
+                }
+            } else {
+                (t1, t2)
+            };
+            Some((rebase(c1, n1, new), rebase(c2, n2, new)))
+        };
+
+        Some(match range_ref {
+            RangeRef::RowRange { start, end, sheet } => {
+                let (start, end) = shift_pair(*start, *end, Axis::Y, is_shifted(sheet))?;
+                RangeRef::RowRange {
+                    start,
+                    end,
+                    sheet: sheet.clone(),
+                }
+            }
+            RangeRef::ColRange { start, end, sheet } => {
+                let (start, end) = shift_pair(*start, *end, Axis::X, is_shifted(sheet))?;
+                RangeRef::ColRange {
+                    start,
+                    end,
+                    sheet: sheet.clone(),
+                }
+            }
+            RangeRef::CellRange { start, end } => {
+                let shifted = is_shifted(&start.sheet);
+                let (x1, x2) = shift_pair(start.x, end.x, Axis::X, shifted)?;
+                let (y1, y2) = shift_pair(start.y, end.y, Axis::Y, shifted)?;
+                RangeRef::CellRange {
+                    start: CellRef {
+                        sheet: start.sheet.clone(),
+                        x: x1,
+                        y: y1,
+                    },
+                    end: CellRef {
+                        sheet: end.sheet.clone(),
+                        x: x2,
+                        y: y2,
+                    },
+                }
+            }
+            RangeRef::Cell { pos } => {
+                let shifted = is_shifted(&pos.sheet);
+                let (x, _) = shift_pair(pos.x, pos.x, Axis::X, shifted)?;
+                let (y, _) = shift_pair(pos.y, pos.y, Axis::Y, shifted)?;
+                RangeRef::Cell {
+                    pos: CellRef {
+                        sheet: pos.sheet.clone(),
+                        x,
+                        y,
+                    },
+                }
+            }
+        })
+    }
+}
+
+impl Sheet {
+    /// Inserts or deletes a row or column, shifting values, formatting,
+    /// borders, code runs, and offsets after it.
+    ///
+    /// This does not rewrite formulas that refer to the shifted cells; see
+    /// [`GridShift::shift_range_ref()`].
+    pub fn shift(&mut self, shift: GridShift) {
+        match shift.axis() {
+            Axis::X => {
+                self.columns = std::mem::take(&mut self.columns)
+                    .into_iter()
+                    .filter_map(|(x, mut column)| {
+                        let x = shift.shift_index(x)?;
+                        column.x = x;
+                        Some((x, column))
+                    })
+                    .collect();
+            }
+            Axis::Y => {
+                for column in self.columns.values_mut() {
+                    column.shift(shift);
+                }
+            }
+        }
+        self.code_runs = std::mem::take(&mut self.code_runs)
+            .into_iter()
+            .filter_map(|(pos, code_run)| Some((shift.shift_pos(pos)?, code_run)))
+            .collect();
+        self.borders.shift(shift);
+        self.offsets.shift(shift);
+        self.recalculate_bounds();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Rgba;
+    use crate::grid::{
+        generate_borders, Bold, BorderSelection, BorderStyle, CellBorderLine, CodeRun,
+        CodeRunResult,
+    };
+    use crate::{CellValue, Value};
+    use chrono::Utc;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_shift_index() {
+        assert_eq!(GridShift::InsertRow(3).shift_index(2), Some(2));
+        assert_eq!(GridShift::InsertRow(3).shift_index(3), Some(4));
+        assert_eq!(GridShift::DeleteRow(3).shift_index(3), None);
+        assert_eq!(GridShift::DeleteRow(3).shift_index(5), Some(4));
+
+        assert_eq!(
+            GridShift::InsertColumn(3).shift_index_range(1, 5),
+            Some((1, 6))
+        );
+        assert_eq!(
+            GridShift::InsertColumn(3).shift_index_range(3, 5),
+            Some((4, 6))
+        );
+        assert_eq!(
+            GridShift::DeleteColumn(3).shift_index_range(1, 5),
+            Some((1, 4))
+        );
+        assert_eq!(
+            GridShift::DeleteColumn(3).shift_index_range(3, 5),
+            Some((3, 4))
+        );
+        assert_eq!(GridShift::DeleteColumn(3).shift_index_range(3, 3), None);
+        assert_eq!(
+            GridShift::InsertRow(3).shift_index_range(i64::MIN, i64::MAX),
+            Some((i64::MIN, i64::MAX))
+        );
+        assert_eq!(
+            GridShift::DeleteRow(3).shift_index_range(i64::MIN, i64::MAX),
+            Some((i64::MIN, i64::MAX))
+        );
+    }
+
+    #[test]
+    fn test_shift_range_ref() {
+        let base = Pos { x: 0, y: 0 };
+        let parse = |s: &str| RangeRef::Cell {
+            pos: CellRef::parse_a1(s, base).unwrap(),
+        };
+        let shifted = |shift: GridShift, r: &RangeRef, new_base: Pos| -> Option<String> {
+            Some(
+                shift
+                    .shift_range_ref(r, base, new_base, |_| true)?
+                    .a1_string(new_base),
+            )
+        };
+
+        let shift = GridShift::InsertRow(2);
+        assert_eq!(shifted(shift, &parse("B1"), base), Some("B1".to_string()));
+        assert_eq!(shifted(shift, &parse("B2"), base), Some("B3".to_string()));
+        assert_eq!(
+            shifted(shift, &parse("$B$5"), base),
+            Some("$B$6".to_string())
+        );
+
+        let shift = GridShift::DeleteColumn(1);
+        assert_eq!(shifted(shift, &parse("B1"), base), None);
+        assert_eq!(shifted(shift, &parse("C1"), base), Some("B1".to_string()));
+
+        let range = RangeRef::CellRange {
+            start: CellRef::parse_a1("A1", base).unwrap(),
+            end: CellRef::parse_a1("C3", base).unwrap(),
+        };
+        assert_eq!(shifted(shift, &range, base), Some("A1:B3".to_string()));
+    }
+
+    #[test]
+    fn test_sheet_shift() {
+        let mut sheet = Sheet::test();
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, CellValue::Text("a".into()));
+        sheet.set_cell_value(Pos { x: 1, y: 1 }, CellValue::Text("b".into()));
+        sheet.set_formatting_value::<Bold>(Pos { x: 1, y: 1 }, Some(true));
+        sheet.offsets.set_column_width(1, 200.0);
+        sheet.code_runs.insert(
+            Pos { x: 2, y: 2 },
+            CodeRun {
+                formatted_code_string: None,
+                std_out: None,
+                std_err: None,
+                cells_accessed: HashSet::new(),
+                result: CodeRunResult::Ok(Value::Single(CellValue::Number(1.into()))),
+                spill_error: false,
+                last_modified: Utc::now(),
+            },
+        );
+        let rect = Rect::new_span(Pos { x: 1, y: 1 }, Pos { x: 1, y: 1 });
+        let style = BorderStyle::new(Rgba::default(), CellBorderLine::Line1);
+        let borders = generate_borders(&sheet, &rect, vec![BorderSelection::All], Some(style));
+        sheet.set_region_borders(&rect, borders);
+
+        sheet.shift(GridShift::InsertColumn(1));
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Text("a".into()))
+        );
+        assert_eq!(sheet.cell_value(Pos { x: 1, y: 1 }), None);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 2, y: 1 }),
+            Some(CellValue::Text("b".into()))
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 2, y: 1 }),
+            Some(true)
+        );
+        assert_eq!(sheet.offsets.column_width(2), 200.0);
+        assert_eq!(sheet.offsets.column_width(1), crate::DEFAULT_COLUMN_WIDTH);
+        assert!(sheet.code_runs.contains_key(&Pos { x: 3, y: 2 }));
+        assert!(sheet.borders().per_cell.borders.contains_key(&2));
+
+        sheet.shift(GridShift::InsertRow(0));
+        assert_eq!(
+            sheet.cell_value(Pos { x: 2, y: 2 }),
+            Some(CellValue::Text("b".into()))
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 2, y: 2 }),
+            Some(true)
+        );
+
+        sheet.shift(GridShift::DeleteRow(0));
+        sheet.shift(GridShift::DeleteColumn(2));
+        assert_eq!(sheet.cell_value(Pos { x: 2, y: 1 }), None);
+        assert_eq!(sheet.get_formatting_value::<Bold>(Pos { x: 2, y: 1 }), None);
+        assert!(sheet.code_runs.contains_key(&Pos { x: 2, y: 2 }));
+        assert_eq!(sheet.offsets.column_width(2), crate::DEFAULT_COLUMN_WIDTH);
+        assert_eq!(
+            sheet.bounds(false),
+            crate::grid::GridBounds::NonEmpty(Rect::new_span(
+                Pos { x: 0, y: 0 },
+                Pos { x: 2, y: 2 }
+            ))
+        );
+    }
+}
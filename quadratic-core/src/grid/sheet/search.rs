@@ -1,6 +1,6 @@
 use crate::{
     grid::{CodeRunResult, Column},
-    CellValue, Pos, SheetPos, Value,
+    CellValue, CodeCellValue, Pos, SheetPos, Value,
 };
 
 use super::Sheet;
@@ -34,6 +34,7 @@ impl Sheet {
         case_sensitive: bool,
         whole_cell: bool,
         search_code: bool,
+        display_code: &dyn Fn(&CodeCellValue) -> String,
     ) -> bool {
         match cell_value {
             CellValue::Text(text) => {
@@ -73,7 +74,7 @@ impl Sheet {
             }
             CellValue::Code(code) => {
                 if search_code {
-                    let code = &code.code;
+                    let code = display_code(code);
                     (case_sensitive && code.contains(query))
                         || (!case_sensitive && code.to_lowercase().contains(query))
                 } else {
@@ -91,6 +92,7 @@ impl Sheet {
         case_sensitive: bool,
         whole_cell: bool,
         search_code: bool,
+        display_code: &dyn Fn(&CodeCellValue) -> String,
     ) -> Vec<SheetPos> {
         self.columns
             .iter()
@@ -104,6 +106,7 @@ impl Sheet {
                         case_sensitive,
                         whole_cell,
                         search_code,
+                        display_code,
                     ) {
                         Some(SheetPos {
                             x: *x,
@@ -123,6 +126,7 @@ impl Sheet {
         query: &String,
         case_sensitive: bool,
         whole_cell: bool,
+        display_code: &dyn Fn(&CodeCellValue) -> String,
     ) -> Vec<SheetPos> {
         let mut results = vec![];
         self.code_runs
@@ -138,6 +142,7 @@ impl Sheet {
                             case_sensitive,
                             whole_cell,
                             false, // code_runs can never have code within them (although that would be cool if they did ;)
+                            display_code,
                         ) {
                             results.push(pos.to_sheet_pos(self.id));
                         }
@@ -157,6 +162,7 @@ impl Sheet {
                                     case_sensitive,
                                     whole_cell,
                                     false, // code_runs can never have code within them (although that would be cool if they did ;)
+                                    display_code,
                                 ) {
                                     results.push(SheetPos {
                                         x: pos.x + x as i64,
@@ -178,6 +184,17 @@ impl Sheet {
     ///
     /// Returns `Vec<SheetPos>` for all cells that match.
     pub fn search(&self, query: &String, options: &SearchOptions) -> Vec<SheetPos> {
+        self.search_with_code(query, options, &|code| code.code.clone())
+    }
+
+    /// Searches the Sheet for a match to the query string, matching code cells
+    /// against the code returned by `display_code`.
+    pub fn search_with_code(
+        &self,
+        query: &String,
+        options: &SearchOptions,
+        display_code: &dyn Fn(&CodeCellValue) -> String,
+    ) -> Vec<SheetPos> {
         let case_sensitive = options.case_sensitive.unwrap_or(false);
         let query = if case_sensitive {
             query.to_owned()
@@ -186,8 +203,14 @@ impl Sheet {
         };
         let whole_cell = options.whole_cell.unwrap_or(false);
         let search_code = options.search_code.unwrap_or(false);
-        let mut results = self.search_cell_values(&query, case_sensitive, whole_cell, search_code);
-        results.extend(self.search_code_runs(&query, case_sensitive, whole_cell));
+        let mut results = self.search_cell_values(
+            &query,
+            case_sensitive,
+            whole_cell,
+            search_code,
+            display_code,
+        );
+        results.extend(self.search_code_runs(&query, case_sensitive, whole_cell, display_code));
         results.sort_by(|a, b| {
             let order = a.x.cmp(&b.x);
             if order == std::cmp::Ordering::Equal {
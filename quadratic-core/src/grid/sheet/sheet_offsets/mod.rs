@@ -1,10 +1,11 @@
 use crate::{grid::offsets::Offsets, THUMBNAIL_WIDTH};
-use crate::{Pos, Rect, ScreenRect, THUMBNAIL_HEIGHT};
+use crate::{Axis, Pos, Rect, ScreenRect, THUMBNAIL_HEIGHT};
 use serde::{Deserialize, Serialize};
 use std::ops::Range;
 use wasm_bindgen::prelude::wasm_bindgen;
 
 use self::{resize_transient::TransientResize, sheet_offsets_wasm::OffsetsSizeChanges};
+use super::rows_columns::GridShift;
 
 pub mod resize_transient;
 pub mod sheet_offsets_wasm;
@@ -107,6 +108,20 @@ impl SheetOffsets {
         old
     }
 
+    /// Inserts or deletes a column or row, shifting the sizes after it.
+    pub fn shift(&mut self, shift: GridShift) {
+        let offsets = match shift.axis() {
+            Axis::X => &mut self.column_widths,
+            Axis::Y => &mut self.row_heights,
+        };
+        if shift.is_insert() {
+            offsets.insert_elem(shift.index());
+        } else {
+            offsets.delete_elem(shift.index());
+        }
+        self.calculate_thumbnail();
+    }
+
     pub fn column_width(&self, x: i64) -> f64 {
         self.column_widths.get_size(x)
     }
@@ -1,4 +1,5 @@
-use super::{Grid, Sheet, SheetId};
+use super::{CodeCellLanguage, Grid, Sheet, SheetId};
+use crate::{formulas::replace_sheet_references, CodeCellValue};
 use lexicon_fractional_index::key_between;
 use std::str::FromStr;
 
@@ -156,10 +157,48 @@ impl Grid {
         self.sheets.iter_mut().find(|s| s.id == sheet_id)
     }
 
-    #[cfg(test)]
     pub fn sheets_mut(&mut self) -> &mut [Sheet] {
         &mut self.sheets
     }
+
+    /// Returns the sheet referred to by a sheet name in a formula. Formulas
+    /// store sheet IDs, but may also contain sheet names if they were written
+    /// before sheets were stored by ID.
+    pub fn try_sheet_from_formula_ref(&self, sheet_ref: &str) -> Option<&Sheet> {
+        match SheetId::from_str(sheet_ref) {
+            Ok(sheet_id) => self.try_sheet(sheet_id),
+            Err(_) => self.try_sheet_from_name(sheet_ref.to_string()),
+        }
+    }
+
+    /// Converts the sheet names in a formula to sheet IDs so that references
+    /// are kept when a sheet is renamed. Unknown sheet names are left as is.
+    pub fn formula_sheet_names_to_ids(&self, code: &str) -> String {
+        replace_sheet_references(code, |sheet_ref| {
+            Some(match self.try_sheet_from_formula_ref(sheet_ref) {
+                Some(sheet) => sheet.id.to_string(),
+                None => sheet_ref.to_string(),
+            })
+        })
+    }
+
+    /// Returns the code of a code cell as the user sees it, with the sheet IDs
+    /// in formulas converted to sheet names.
+    pub fn display_code(&self, code_cell: &CodeCellValue) -> String {
+        match code_cell.language {
+            CodeCellLanguage::Formula => self.formula_sheet_ids_to_names(&code_cell.code),
+            _ => code_cell.code.clone(),
+        }
+    }
+
+    /// Converts the sheet IDs in a formula to the current sheet names for
+    /// display. References to deleted sheets are replaced with `#REF!`.
+    pub fn formula_sheet_ids_to_names(&self, code: &str) -> String {
+        replace_sheet_references(code, |sheet_ref| match SheetId::from_str(sheet_ref) {
+            Ok(sheet_id) => Some(self.try_sheet(sheet_id)?.name.clone()),
+            Err(_) => Some(sheet_ref.to_string()),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -175,6 +214,50 @@ mod test {
         assert!(grid.try_sheet(SheetId::new()).is_none());
     }
 
+    #[test]
+    fn test_formula_sheet_refs() {
+        let mut grid = Grid::new();
+        let sheet_id = grid.add_sheet(None);
+        grid.try_sheet_mut(sheet_id).unwrap().name = "Sheet 2".to_string();
+
+        let code = grid.formula_sheet_names_to_ids("SUM('Sheet 2'!A1:B2) + 'Other'!A1 + C3");
+        assert_eq!(code, format!("SUM('{sheet_id}'!A1:B2) + 'Other'!A1 + C3"));
+        assert_eq!(
+            grid.formula_sheet_ids_to_names(&code),
+            "SUM('Sheet 2'!A1:B2) + 'Other'!A1 + C3"
+        );
+
+        grid.try_sheet_mut(sheet_id).unwrap().name = "Renamed".to_string();
+        assert_eq!(
+            grid.formula_sheet_ids_to_names(&code),
+            "SUM('Renamed'!A1:B2) + 'Other'!A1 + C3"
+        );
+
+        // the quotes that the sheet name was written with are kept
+        let quoted = grid.formula_sheet_names_to_ids("\"Renamed\"!A1 + Renamed!A2");
+        assert_eq!(quoted, format!("\"{sheet_id}\"!A1 + '{sheet_id}'!A2"));
+        assert_eq!(
+            grid.formula_sheet_ids_to_names(&quoted),
+            "\"Renamed\"!A1 + 'Renamed'!A2"
+        );
+        grid.try_sheet_mut(sheet_id).unwrap().name = "Bob's \"data\"".to_string();
+        assert_eq!(
+            grid.formula_sheet_ids_to_names(&quoted),
+            "\"Bob's \\\"data\\\"\"!A1 + 'Bob\\'s \"data\"'!A2"
+        );
+        let quoted = grid.formula_sheet_ids_to_names(&quoted);
+        assert_eq!(
+            grid.formula_sheet_names_to_ids(&quoted),
+            format!("\"{sheet_id}\"!A1 + '{sheet_id}'!A2")
+        );
+
+        grid.remove_sheet(sheet_id);
+        assert_eq!(
+            grid.formula_sheet_ids_to_names(&code),
+            "SUM(#REF!) + 'Other'!A1 + C3"
+        );
+    }
+
     #[test]
     fn test_try_sheet_mut_from_id() {
         let mut grid = Grid::new();
@@ -387,6 +387,22 @@ impl SheetRect {
         }
     }
 
+    /// Returns the region contained in both rectangles, or `None` if they
+    /// don't intersect or are on different sheets.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.intersects(*other).then(|| SheetRect {
+            min: Pos {
+                x: std::cmp::max(self.min.x, other.min.x),
+                y: std::cmp::max(self.min.y, other.min.y),
+            },
+            max: Pos {
+                x: std::cmp::min(self.max.x, other.max.x),
+                y: std::cmp::min(self.max.y, other.max.y),
+            },
+            sheet_id: self.sheet_id,
+        })
+    }
+
     pub fn top_left(&self) -> SheetPos {
         SheetPos {
             x: self.min.x,
@@ -722,6 +738,21 @@ mod test {
         let _ = rect1.union(&rect2);
     }
 
+    #[test]
+    fn test_sheet_rect_intersection() {
+        let sheet_id = SheetId::new();
+        let rect1 = SheetRect::from_numbers(1, 2, 3, 4, sheet_id);
+        let rect2 = SheetRect::from_numbers(2, 3, 4, 5, sheet_id);
+        let rect = rect1.intersection(&rect2).unwrap();
+        assert_eq!(rect.min, Pos { x: 2, y: 3 });
+        assert_eq!(rect.max, Pos { x: 3, y: 5 });
+
+        let rect3 = SheetRect::from_numbers(4, 2, 1, 1, sheet_id);
+        assert_eq!(rect1.intersection(&rect3), None);
+        let rect4 = SheetRect::from_numbers(1, 2, 3, 4, SheetId::new());
+        assert_eq!(rect1.intersection(&rect4), None);
+    }
+
     #[test]
     fn test_top_left() {
         let sheet_id = SheetId::new();
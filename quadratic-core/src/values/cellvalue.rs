@@ -98,8 +98,8 @@ impl CellValue {
             CellValue::Number(n) => n.to_string(),
             CellValue::Logical(true) => "TRUE".to_string(),
             CellValue::Logical(false) => "FALSE".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
+            CellValue::Instant(i) => i.repr(),
+            CellValue::Duration(d) => d.repr(),
             CellValue::Error(_) => "[error]".to_string(),
             CellValue::Html(s) => s.clone(),
             CellValue::Code(_) => todo!("repr of python"),
@@ -136,12 +136,42 @@ impl CellValue {
         }
     }
 
+    /// Formats an instant, duration, or serial number using a date or time
+    /// numeric format. Returns `None` if the format is not a date or time
+    /// format or the value cannot be formatted with it.
+    fn to_date_time_display(&self, numeric_format: &NumericFormat) -> Option<String> {
+        let kind = numeric_format.kind;
+        let default_pattern = kind.default_pattern()?;
+        let pattern = numeric_format.symbol.as_deref().unwrap_or(default_pattern);
+        match (self, kind) {
+            (CellValue::Instant(_), NumericFormatKind::Duration) => None,
+            (CellValue::Instant(i), _) => i.format(pattern),
+            (CellValue::Duration(d), NumericFormatKind::Duration) => d.format(pattern),
+            // A duration shown as a date or time is relative to serial number 0.
+            (CellValue::Duration(d), _) => Instant::from_serial_number(0.0)
+                .add_duration(*d)?
+                .format(pattern),
+            (CellValue::Number(n), NumericFormatKind::Duration) => {
+                let days = n.to_f64()?;
+                Duration::from_seconds(days * 86400.0).format(pattern)
+            }
+            (CellValue::Number(n), _) => Instant::from_serial_number(n.to_f64()?).format(pattern),
+            _ => None,
+        }
+    }
+
     pub fn to_display(
         &self,
         numeric_format: Option<NumericFormat>,
         numeric_decimals: Option<i16>,
         numeric_commas: Option<bool>,
     ) -> String {
+        if let Some(s) = numeric_format
+            .as_ref()
+            .and_then(|numeric_format| self.to_date_time_display(numeric_format))
+        {
+            return s;
+        }
         match self {
             CellValue::Blank => String::new(),
             CellValue::Text(s) => s.to_string(),
@@ -201,14 +231,18 @@ impl CellValue {
                         number.push('%');
                         number
                     }
-                    NumericFormatKind::Number => number,
-                    NumericFormatKind::Exponential => number,
+                    NumericFormatKind::Number
+                    | NumericFormatKind::Exponential
+                    | NumericFormatKind::Date
+                    | NumericFormatKind::Time
+                    | NumericFormatKind::DateTime
+                    | NumericFormatKind::Duration => number,
                 }
             }
             CellValue::Logical(true) => "true".to_string(),
             CellValue::Logical(false) => "false".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
+            CellValue::Instant(i) => i.to_string(),
+            CellValue::Duration(d) => d.to_string(),
             CellValue::Error(_) => "[error]".to_string(),
 
             // this should not render
@@ -224,8 +258,8 @@ impl CellValue {
             CellValue::Number(n) => n.to_string(),
             CellValue::Logical(true) => "true".to_string(),
             CellValue::Logical(false) => "false".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
+            CellValue::Instant(i) => i.to_string(),
+            CellValue::Duration(d) => d.to_string(),
             CellValue::Error(_) => "[error]".to_string(),
 
             // this should not be editable
@@ -373,6 +407,35 @@ impl CellValue {
             .unwrap_or_else(|| type_id(lhs).cmp(&type_id(rhs))))
     }
 
+    /// Compares two values for sorting a range. Unlike `cmp()`, this never
+    /// fails: values of different types are grouped by type, with numbers
+    /// first, then dates, durations, text, logicals, errors, and then blanks.
+    /// Dates and durations are not mixed in with numbers.
+    pub fn sort_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        fn type_id(v: &CellValue) -> u8 {
+            match v {
+                CellValue::Number(_) => 0,
+                CellValue::Instant(_) => 1,
+                CellValue::Duration(_) => 2,
+                CellValue::Text(_) => 3,
+                CellValue::Logical(_) => 4,
+                CellValue::Error(_) => 5,
+                CellValue::Html(_) => 6,
+                CellValue::Code(_) => 7,
+                CellValue::Blank => 8,
+            }
+        }
+
+        match type_id(self).cmp(&type_id(other)) {
+            std::cmp::Ordering::Equal => self
+                .partial_cmp(other)
+                .ok()
+                .flatten()
+                .unwrap_or(std::cmp::Ordering::Equal),
+            ordering => ordering,
+        }
+    }
+
     /// Returns whether `self == other` using `CellValue::cmp()`.
     pub fn eq(&self, other: &Self) -> CodeResult<bool> {
         Ok(self.cmp(other)? == std::cmp::Ordering::Equal)
@@ -507,7 +570,7 @@ mod test {
 
     use crate::{
         grid::{NumericFormat, NumericFormatKind},
-        CellValue,
+        CellValue, Duration, Instant,
     };
 
     #[test]
@@ -700,6 +763,66 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_date_time_display() {
+        let format = |kind, symbol: Option<&str>| {
+            Some(NumericFormat {
+                kind,
+                symbol: symbol.map(String::from),
+            })
+        };
+        let instant = CellValue::Instant(crate::Instant::parse("2024-03-01 13:45").unwrap());
+        assert_eq!(
+            instant.to_display(format(NumericFormatKind::Date, None), None, None),
+            "2024-03-01"
+        );
+        assert_eq!(
+            instant.to_display(
+                format(NumericFormatKind::DateTime, Some("m/d/yy h:mm AM/PM")),
+                None,
+                None
+            ),
+            "3/1/24 1:45 PM"
+        );
+        assert_eq!(
+            instant.to_display(format(NumericFormatKind::Time, Some("%H:%M")), None, None),
+            "13:45"
+        );
+        // currency symbols are not patterns
+        assert_eq!(
+            instant.to_display(format(NumericFormatKind::Currency, Some("$")), None, None),
+            "2024-03-01 13:45:00"
+        );
+
+        let serial_number = CellValue::Number(BigDecimal::from_str("45352.5").unwrap());
+        assert_eq!(
+            serial_number.to_display(format(NumericFormatKind::DateTime, None), None, None),
+            "2024-03-01 12:00:00"
+        );
+        assert_eq!(
+            serial_number.to_display(format(NumericFormatKind::Duration, None), None, None),
+            "1088460:00:00"
+        );
+
+        let duration = CellValue::Duration(crate::Duration::from_seconds(5400.0));
+        assert_eq!(
+            duration.to_display(
+                format(NumericFormatKind::Duration, Some("[m] \"min\"")),
+                None,
+                None
+            ),
+            "90 min"
+        );
+        assert_eq!(
+            duration.to_display(
+                format(NumericFormatKind::Time, Some("h:mm AM/PM")),
+                None,
+                None
+            ),
+            "1:30 AM"
+        );
+    }
+
     #[test]
     fn test_with_commas() {
         let value = BigDecimal::from_str("123123123");
@@ -714,4 +837,55 @@ mod test {
             "-123,123,123.123456"
         );
     }
+
+    #[test]
+    fn test_sort_cmp() {
+        use std::cmp::Ordering;
+
+        let number = CellValue::Number(BigDecimal::from(10));
+        let text = CellValue::Text(String::from("abc"));
+        let logical = CellValue::Logical(false);
+        let error = CellValue::Error(Box::new(crate::RunErrorMsg::DivideByZero.without_span()));
+
+        assert_eq!(number.sort_cmp(&text), Ordering::Less);
+        assert_eq!(text.sort_cmp(&logical), Ordering::Less);
+        assert_eq!(logical.sort_cmp(&error), Ordering::Less);
+        assert_eq!(error.sort_cmp(&CellValue::Blank), Ordering::Less);
+        assert_eq!(error.sort_cmp(&error), Ordering::Equal);
+        assert_eq!(
+            text.sort_cmp(&CellValue::Text(String::from("ABD"))),
+            Ordering::Less
+        );
+
+        // each type is sorted in its own group
+        let date = CellValue::Instant(Instant::parse("2024-03-01").unwrap());
+        let later_date = CellValue::Instant(Instant::parse("2024-03-02").unwrap());
+        let duration = CellValue::Duration(Duration::from_seconds(60.0));
+        let mut values = vec![
+            CellValue::Blank,
+            text.clone(),
+            later_date.clone(),
+            duration.clone(),
+            CellValue::Number(BigDecimal::from(1_000_000)),
+            error.clone(),
+            date.clone(),
+            logical.clone(),
+            number.clone(),
+        ];
+        values.sort_by(CellValue::sort_cmp);
+        assert_eq!(
+            values,
+            vec![
+                number,
+                CellValue::Number(BigDecimal::from(1_000_000)),
+                date,
+                later_date,
+                duration,
+                text,
+                logical,
+                error,
+                CellValue::Blank,
+            ]
+        );
+    }
 }
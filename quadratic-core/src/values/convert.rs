@@ -1,6 +1,6 @@
 use bigdecimal::{BigDecimal, ToPrimitive, Zero};
 
-use super::{CellValue, IsBlank, Value};
+use super::{CellValue, Duration, Instant, IsBlank, Value};
 use crate::{CodeResult, CodeResultExt, RunErrorMsg, Span, Spanned, Unspan};
 
 const CURRENCY_PREFIXES: &[char] = &['$', '¥', '£', '€'];
@@ -42,6 +42,11 @@ impl From<f64> for CellValue {
             .map_or_else(|_| CellValue::Text(value.to_string()), CellValue::Number)
     }
 }
+impl From<BigDecimal> for CellValue {
+    fn from(value: BigDecimal) -> Self {
+        CellValue::Number(value)
+    }
+}
 impl From<i64> for CellValue {
     fn from(value: i64) -> Self {
         CellValue::Number(BigDecimal::from(value))
@@ -62,6 +67,16 @@ impl From<bool> for CellValue {
         CellValue::Logical(value)
     }
 }
+impl From<Instant> for CellValue {
+    fn from(value: Instant) -> Self {
+        CellValue::Instant(value)
+    }
+}
+impl From<Duration> for CellValue {
+    fn from(value: Duration) -> Self {
+        CellValue::Duration(value)
+    }
+}
 impl<T> From<CodeResult<T>> for CellValue
 where
     CellValue: From<T>,
@@ -131,6 +146,21 @@ impl<'a> TryFrom<&'a CellValue> for f64 {
         }
     }
 }
+impl<'a> TryFrom<&'a CellValue> for BigDecimal {
+    type Error = RunErrorMsg;
+
+    fn try_from(value: &'a CellValue) -> Result<Self, Self::Error> {
+        match value {
+            CellValue::Number(n) => Ok(n.clone()),
+            // Go through the shortest decimal representation of the float so
+            // that values such as `0.1` are not extended with binary noise.
+            other => f64::try_from(other)?
+                .to_string()
+                .parse()
+                .map_err(|_| RunErrorMsg::NotANumber),
+        }
+    }
+}
 impl<'a> TryFrom<&'a CellValue> for i64 {
     type Error = RunErrorMsg;
 
@@ -163,6 +193,26 @@ impl<'a> TryFrom<&'a CellValue> for bool {
         }
     }
 }
+impl<'a> TryFrom<&'a CellValue> for Instant {
+    type Error = RunErrorMsg;
+
+    fn try_from(value: &'a CellValue) -> Result<Self, Self::Error> {
+        // Numbers are spreadsheet serial numbers, and durations are relative
+        // to the spreadsheet epoch, so that `HOUR(TIME(13, 0, 0))` works.
+        match value {
+            CellValue::Instant(i) => Ok(*i),
+            CellValue::Duration(d) => Instant::from_serial_number(0.0)
+                .add_duration(*d)
+                .ok_or(RunErrorMsg::Overflow),
+            CellValue::Text(s) => Instant::parse(s).ok_or_else(|| RunErrorMsg::Expected {
+                expected: "date".into(),
+                got: Some(value.type_name().into()),
+            }),
+            CellValue::Error(e) => Err(e.msg.clone()),
+            _ => Ok(Instant::from_serial_number(f64::try_from(value)?)),
+        }
+    }
+}
 
 impl TryFrom<CellValue> for String {
     type Error = RunErrorMsg;
@@ -187,8 +237,11 @@ macro_rules! impl_try_from_cell_value_for {
     };
 }
 impl_try_from_cell_value_for!(f64);
+impl_try_from_cell_value_for!(BigDecimal);
 impl_try_from_cell_value_for!(i64);
+impl_try_from_cell_value_for!(u32);
 impl_try_from_cell_value_for!(bool);
+impl_try_from_cell_value_for!(Instant);
 
 impl<'a> TryFrom<&'a Value> for &'a CellValue {
     type Error = RunErrorMsg;
@@ -225,8 +278,11 @@ macro_rules! impl_try_from_value_for {
 }
 impl_try_from_value_for!(String);
 impl_try_from_value_for!(f64);
+impl_try_from_value_for!(BigDecimal);
 impl_try_from_value_for!(i64);
+impl_try_from_value_for!(u32);
 impl_try_from_value_for!(bool);
+impl_try_from_value_for!(Instant);
 
 /// Coercion from `Value` or `CellValue` into a particular Rust type.
 pub trait CoerceInto: Sized + Unspan
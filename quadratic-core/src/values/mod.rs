@@ -10,6 +10,7 @@ mod cellvalue;
 mod convert;
 mod isblank;
 mod time;
+mod time_format;
 
 pub use array::Array;
 pub use array_size::{ArraySize, Axis};
@@ -18,6 +19,7 @@ pub use cellvalue::CodeCellValue;
 pub use convert::CoerceInto;
 pub use isblank::IsBlank;
 pub use time::{Duration, Instant};
+pub use time_format::is_time_pattern;
 
 use crate::{CodeResult, CodeResultExt, RunErrorMsg, SpannableIterExt, Spanned};
 
@@ -77,6 +79,12 @@ impl Value {
             Value::Array(array) => array.cell_values_slice(),
         }
     }
+    pub fn cell_values_slice_mut(&mut self) -> &mut [CellValue] {
+        match self {
+            Value::Single(value) => std::slice::from_mut(value),
+            Value::Array(array) => array.cell_values_slice_mut(),
+        }
+    }
 
     /// Returns a formula-source-code representation of the value.
     pub fn repr(&self) -> String {
@@ -1,7 +1,38 @@
 use std::fmt;
 
+use chrono::{Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Number of days between the spreadsheet epoch (1899-12-30) and the Unix
+/// epoch (1970-01-01).
+const SERIAL_NUMBER_UNIX_EPOCH: f64 = 25569.0;
+
+/// Date formats accepted by [`Instant::parse()`], in order of precedence.
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%-m/%-d/%Y",
+    "%-m-%-d-%Y",
+    "%B %-d, %Y",
+    "%b %-d, %Y",
+    "%B %-d %Y",
+    "%b %-d %Y",
+    "%-d %B %Y",
+    "%-d %b %Y",
+];
+
+/// Time formats accepted by [`Instant::parse()`] after a date and by
+/// [`Duration::parse_time()`], in order of precedence.
+const TIME_FORMATS: &[&str] = &["%H:%M:%S%.f", "%H:%M", "%-I:%M:%S %p", "%-I:%M %p"];
+
+fn unix_epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(1970, 1, 1)
+        .expect("valid date")
+        .and_time(NaiveTime::MIN)
+}
+
 #[cfg_attr(test, derive(proptest_derive::Arbitrary))]
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
 #[cfg_attr(feature = "js", derive(ts_rs::TS))]
@@ -12,7 +43,121 @@ pub struct Instant {
 
 impl fmt::Display for Instant {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{s} seconds", s = self.seconds)
+        match self.datetime() {
+            Some(dt) if dt.time() == NaiveTime::MIN => write!(f, "{}", dt.format("%Y-%m-%d")),
+            Some(dt) => write!(f, "{}", dt.format("%Y-%m-%d %H:%M:%S%.f")),
+            None => write!(f, "{s} seconds", s = self.seconds),
+        }
+    }
+}
+
+impl Instant {
+    /// Constructs an instant from a date and time, which is assumed to be in
+    /// UTC.
+    pub fn from_datetime(dt: NaiveDateTime) -> Self {
+        Self {
+            seconds: (dt - unix_epoch()).num_milliseconds() as f64 / 1000.0,
+        }
+    }
+    /// Constructs an instant at midnight on a date.
+    pub fn from_date(date: NaiveDate) -> Self {
+        Self::from_datetime(date.and_time(NaiveTime::MIN))
+    }
+    /// Returns the current instant.
+    pub fn now() -> Self {
+        Self::from_datetime(Utc::now().naive_utc())
+    }
+
+    /// Constructs an instant from a spreadsheet serial number, which is the
+    /// number of days since 1899-12-30.
+    pub fn from_serial_number(serial_number: f64) -> Self {
+        Self {
+            seconds: (serial_number - SERIAL_NUMBER_UNIX_EPOCH) * SECONDS_PER_DAY,
+        }
+    }
+    /// Returns the spreadsheet serial number of the instant, which is the
+    /// number of days since 1899-12-30.
+    pub fn serial_number(self) -> f64 {
+        self.seconds / SECONDS_PER_DAY + SERIAL_NUMBER_UNIX_EPOCH
+    }
+
+    /// Returns the date and time of the instant, rounded to the nearest
+    /// millisecond, or `None` if it is out of range.
+    pub fn datetime(self) -> Option<NaiveDateTime> {
+        let millis = (self.seconds * 1000.0).round();
+        if !millis.is_finite() || millis.abs() > i64::MAX as f64 {
+            return None;
+        }
+        unix_epoch().checked_add_signed(chrono::Duration::milliseconds(millis as i64))
+    }
+    /// Returns the date of the instant, or `None` if it is out of range.
+    pub fn date(self) -> Option<NaiveDate> {
+        self.datetime().map(|dt| dt.date())
+    }
+
+    /// Returns a formula-source-code representation of the instant.
+    pub fn repr(self) -> String {
+        let Some(dt) = self.datetime() else {
+            return self.to_string();
+        };
+        let date = format!("DATEVALUE(\"{}\")", dt.format("%Y-%m-%d"));
+        if dt.time() == NaiveTime::MIN {
+            return date;
+        }
+        let seconds = dt.second() as f64 + dt.nanosecond() as f64 / 1e9;
+        format!("{date} + TIME({}, {}, {seconds})", dt.hour(), dt.minute())
+    }
+
+    /// Parses a date, optionally followed by a time, in one of several common
+    /// formats such as `2024-03-01`, `3/1/2024`, `March 1, 2024`, or
+    /// `2024-03-01 13:45:00`.
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::parse_with_format(s).map(|(instant, _)| instant)
+    }
+    /// Same as [`Instant::parse()`], but also returns the `strftime` pattern
+    /// that matched, which can be used to display the value the same way it
+    /// was entered.
+    pub fn parse_with_format(s: &str) -> Option<(Self, String)> {
+        let s = s.trim();
+        DATE_FORMATS.iter().find_map(|date_format| {
+            if let Ok(date) = NaiveDate::parse_from_str(s, date_format) {
+                return Some((Self::from_date(date), date_format.to_string()));
+            }
+            let separators: &[&str] = if *date_format == "%Y-%m-%d" {
+                &[" ", "T"]
+            } else {
+                &[" "]
+            };
+            separators.iter().find_map(|separator| {
+                TIME_FORMATS.iter().find_map(|time_format| {
+                    let format = format!("{date_format}{separator}{time_format}");
+                    let dt = NaiveDateTime::parse_from_str(s, &format).ok()?;
+                    Some((Self::from_datetime(dt), format))
+                })
+            })
+        })
+    }
+
+    /// Adds a (possibly fractional or negative) number of days.
+    pub fn add_days(self, days: f64) -> Self {
+        Self {
+            seconds: self.seconds + days * SECONDS_PER_DAY,
+        }
+    }
+    /// Adds a duration, applying calendar months and years first so that the
+    /// day of the month is preserved where possible. Returns `None` if the
+    /// result is out of range.
+    pub fn add_duration(self, duration: Duration) -> Option<Self> {
+        let months = duration.years as i64 * 12 + duration.months as i64;
+        let dt = self.datetime()?;
+        let dt = match u32::try_from(months.unsigned_abs()).ok()? {
+            0 => dt,
+            n if months > 0 => dt.checked_add_months(Months::new(n))?,
+            n => dt.checked_sub_months(Months::new(n))?,
+        };
+        Some(Self {
+            seconds: Self::from_datetime(dt).seconds + duration.seconds,
+        })
     }
 }
 
@@ -45,13 +190,98 @@ pub struct Duration {
 
 impl fmt::Display for Duration {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{y} years, {m} months, {s} seconds",
-            y = self.years,
-            m = self.months,
-            s = self.seconds,
-        )
+        let plural = |n: i64, unit: &str| match n {
+            1 | -1 => format!("{n} {unit}"),
+            _ => format!("{n} {unit}s"),
+        };
+
+        let mut parts = vec![];
+        if self.years != 0 {
+            parts.push(plural(self.years as i64, "year"));
+        }
+        if self.months != 0 {
+            parts.push(plural(self.months as i64, "month"));
+        }
+
+        let millis = (self.seconds.abs() * 1000.0).round() as i64;
+        let sign = if self.seconds < 0.0 && millis != 0 {
+            "-"
+        } else {
+            ""
+        };
+        let days = millis / 86_400_000;
+        if days != 0 {
+            parts.push(format!("{sign}{}", plural(days, "day")));
+        }
+        let time_millis = millis % 86_400_000;
+        if time_millis != 0 || parts.is_empty() {
+            let time = NaiveTime::MIN + chrono::Duration::milliseconds(time_millis);
+            parts.push(format!("{sign}{}", time.format("%H:%M:%S%.f")));
+        }
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+impl Duration {
+    /// Constructs a duration from a number of seconds.
+    pub fn from_seconds(seconds: f64) -> Self {
+        Self {
+            years: 0,
+            months: 0,
+            seconds,
+        }
+    }
+
+    /// Returns a formula-source-code representation of the duration. Durations
+    /// that include months or years can't be written using `TIME()`, so they
+    /// are represented by their display format instead.
+    pub fn repr(self) -> String {
+        if self.years != 0 || self.months != 0 {
+            return self.to_string();
+        }
+        let hours = (self.seconds / 3600.0).trunc();
+        let minutes = ((self.seconds - hours * 3600.0) / 60.0).trunc();
+        let seconds = self.seconds - hours * 3600.0 - minutes * 60.0;
+        format!("TIME({hours}, {minutes}, {seconds})")
+    }
+    /// Parses a time of day such as `13:45`, `13:45:30.5`, or `1:45 PM` as
+    /// the duration since midnight.
+    pub fn parse_time(s: &str) -> Option<Self> {
+        Self::parse_time_with_format(s).map(|(duration, _)| duration)
+    }
+    /// Same as [`Duration::parse_time()`], but also returns the `strftime`
+    /// pattern that matched.
+    pub fn parse_time_with_format(s: &str) -> Option<(Self, String)> {
+        let s = s.trim();
+        TIME_FORMATS.iter().find_map(|time_format| {
+            let time = NaiveTime::parse_from_str(s, time_format).ok()?;
+            let seconds = time.num_seconds_from_midnight() as f64 + time.nanosecond() as f64 / 1e9;
+            Some((Self::from_seconds(seconds), time_format.to_string()))
+        })
+    }
+}
+
+impl std::ops::Add for Duration {
+    type Output = Duration;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Duration {
+            years: self.years.saturating_add(rhs.years),
+            months: self.months.saturating_add(rhs.months),
+            seconds: self.seconds + rhs.seconds,
+        }
+    }
+}
+impl std::ops::Neg for Duration {
+    type Output = Duration;
+
+    fn neg(self) -> Self::Output {
+        Duration {
+            years: self.years.saturating_neg(),
+            months: self.months.saturating_neg(),
+            seconds: -self.seconds,
+        }
     }
 }
 
@@ -82,3 +312,161 @@ impl Ord for Duration {
         ret
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_instant_display() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        assert_eq!("2024-03-01", Instant::from_date(date).to_string());
+        let dt = date.and_hms_opt(13, 45, 30).unwrap();
+        assert_eq!(
+            "2024-03-01 13:45:30",
+            Instant::from_datetime(dt).to_string()
+        );
+        let dt = date.and_hms_milli_opt(13, 45, 30, 250).unwrap();
+        assert_eq!(
+            "2024-03-01 13:45:30.250",
+            Instant::from_datetime(dt).to_string(),
+        );
+        assert_eq!("1970-01-01", Instant { seconds: 0.0 }.to_string());
+    }
+
+    #[test]
+    fn test_instant_serial_number() {
+        let instant = Instant::from_serial_number(45352.5);
+        assert_eq!("2024-03-01 12:00:00", instant.to_string());
+        assert_eq!(45352.5, instant.serial_number());
+    }
+
+    #[test]
+    fn test_instant_parse() {
+        for s in [
+            "2024-03-01",
+            "2024/03/01",
+            "3/1/2024",
+            "03-01-2024",
+            "March 1, 2024",
+            "Mar 1 2024",
+            "1 March 2024",
+            " 2024-03-01 ",
+        ] {
+            assert_eq!(
+                Some("2024-03-01".to_string()),
+                Instant::parse(s).map(|i| i.to_string()),
+                "{s}"
+            );
+        }
+        for s in [
+            "2024-03-01 13:45",
+            "2024-03-01T13:45:00",
+            "3/1/2024 1:45 PM",
+            "March 1, 2024 01:45:00 pm",
+        ] {
+            assert_eq!(
+                Some("2024-03-01 13:45:00".to_string()),
+                Instant::parse(s).map(|i| i.to_string()),
+                "{s}",
+            );
+        }
+        assert_eq!(None, Instant::parse("hello"));
+        assert_eq!(None, Instant::parse("2024-02-30"));
+    }
+
+    #[test]
+    fn test_instant_parse_with_format() {
+        let (instant, format) = Instant::parse_with_format("3/1/2024 1:45 PM").unwrap();
+        assert_eq!("%-m/%-d/%Y %-I:%M %p", format);
+        assert_eq!(
+            Some("3/1/2024 1:45 PM".to_string()),
+            instant.format(&format)
+        );
+    }
+
+    #[test]
+    fn test_duration_parse_time() {
+        assert_eq!(
+            Some(Duration::from_seconds(49500.0)),
+            Duration::parse_time("13:45")
+        );
+        assert_eq!(
+            Some(Duration::from_seconds(49530.5)),
+            Duration::parse_time("13:45:30.5"),
+        );
+        assert_eq!(
+            Some(Duration::from_seconds(6300.0)),
+            Duration::parse_time("1:45 AM")
+        );
+        assert_eq!(None, Duration::parse_time("2024-03-01"));
+    }
+
+    #[test]
+    fn test_instant_add_duration() {
+        let jan31 = Instant::from_date(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap());
+        let one_month = Duration {
+            years: 0,
+            months: 1,
+            seconds: 0.0,
+        };
+        assert_eq!(
+            "2024-02-29",
+            jan31.add_duration(one_month).unwrap().to_string()
+        );
+        assert_eq!(
+            "2023-12-31",
+            jan31.add_duration(-one_month).unwrap().to_string()
+        );
+        assert_eq!(
+            "2024-01-31 06:00:00",
+            jan31
+                .add_duration(Duration::from_seconds(21600.0))
+                .unwrap()
+                .to_string(),
+        );
+        assert_eq!("2024-02-01", jan31.add_days(1.0).to_string());
+    }
+
+    #[test]
+    fn test_duration_display() {
+        assert_eq!("00:00:00", Duration::from_seconds(0.0).to_string());
+        assert_eq!("13:45:00", Duration::from_seconds(49500.0).to_string());
+        assert_eq!("-01:30:00", Duration::from_seconds(-5400.0).to_string());
+        assert_eq!(
+            "2 days, 00:00:01.500",
+            Duration::from_seconds(172801.5).to_string(),
+        );
+        assert_eq!(
+            "1 year, 2 months",
+            Duration {
+                years: 1,
+                months: 2,
+                seconds: 0.0,
+            }
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn test_duration_repr() {
+        for (seconds, repr) in [
+            (0.0, "TIME(0, 0, 0)"),
+            (49500.0, "TIME(13, 45, 0)"),
+            (-5400.0, "TIME(-1, -30, 0)"),
+            (172801.5, "TIME(48, 0, 1.5)"),
+        ] {
+            assert_eq!(repr, Duration::from_seconds(seconds).repr());
+        }
+        let calendar = Duration {
+            years: 1,
+            months: 2,
+            seconds: 0.0,
+        };
+        assert_eq!("1 year, 2 months", calendar.repr());
+        assert_eq!(
+            "1 year, 2 months",
+            crate::CellValue::Duration(calendar).repr()
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Formatting of instants and durations using user-supplied patterns.
+//!
+//! Patterns may use either `strftime` syntax (such as `%Y-%m-%d`), which is
+//! detected by the presence of a `%` specifier, or Excel-style syntax (such as
+//! `yyyy-mm-dd` or `[h]:mm:ss`).
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::Timelike;
+
+use super::{Duration, Instant};
+
+impl Instant {
+    /// Formats the instant using a `strftime` or Excel-style pattern. Returns
+    /// `None` if the pattern is invalid or the instant is out of range.
+    pub fn format(self, pattern: &str) -> Option<String> {
+        let parts = if is_strftime(pattern) {
+            vec![StrftimePart::Strftime(pattern.to_string())]
+        } else {
+            excel_to_strftime(pattern)
+        };
+        let dt = self.datetime()?;
+        let mut ret = String::new();
+        for part in parts {
+            match part {
+                StrftimePart::Strftime(strftime) => {
+                    let items = StrftimeItems::new(&strftime).collect::<Vec<_>>();
+                    if items.iter().any(|item| matches!(item, Item::Error)) {
+                        return None;
+                    }
+                    ret.push_str(&dt.format_with_items(items.into_iter()).to_string());
+                }
+                StrftimePart::Fraction(digits) => {
+                    let nanos = format!("{:09}", dt.nanosecond() % 1_000_000_000);
+                    ret.push('.');
+                    ret.push_str(&format!("{nanos:0<digits$}")[..digits]);
+                }
+            }
+        }
+        Some(ret)
+    }
+}
+
+/// Returns whether a pattern formats dates, times, or durations rather than
+/// numbers, because it is a `strftime` pattern or contains an Excel-style
+/// date or time field such as `yyyy`, `hh`, or `[m]`.
+pub fn is_time_pattern(pattern: &str) -> bool {
+    is_strftime(pattern)
+        || tokenize_excel_pattern(pattern).iter().any(|token| {
+            matches!(
+                token,
+                ExcelToken::Field(..) | ExcelToken::Elapsed(..) | ExcelToken::AmPm(_)
+            )
+        })
+}
+
+impl Duration {
+    /// Formats the duration using an Excel-style elapsed time pattern such as
+    /// `[h]:mm:ss` or `d "days" hh:mm`. Bracketed units (`[h]`, `[m]`, `[s]`)
+    /// are totals; other units wrap around at the next larger unit. Returns
+    /// `None` if the duration includes months or years, which have no fixed
+    /// length.
+    pub fn format(self, pattern: &str) -> Option<String> {
+        if self.years != 0 || self.months != 0 {
+            return None;
+        }
+
+        let total_millis = (self.seconds.abs() * 1000.0).round() as u64;
+        let mut ret = String::new();
+        if self.seconds < 0.0 && total_millis != 0 {
+            ret.push('-');
+        }
+
+        let tokens = tokenize_excel_pattern(pattern);
+        // Largest unit that is shown, which does not wrap around.
+        let largest = tokens.iter().find_map(|token| match token {
+            ExcelToken::Field(c, _) | ExcelToken::Elapsed(c, _) => Some(*c),
+            _ => None,
+        });
+        for (i, token) in tokens.iter().enumerate() {
+            match token {
+                ExcelToken::Literal(s) => ret.push_str(s),
+                ExcelToken::AmPm(_) => (),
+                ExcelToken::Fraction(digits) => {
+                    let millis = format!("{:03}", total_millis % 1000);
+                    ret.push('.');
+                    ret.push_str(&format!("{millis:0<digits$}", digits = *digits)[..*digits]);
+                }
+                ExcelToken::Field(c, width) | ExcelToken::Elapsed(c, width) => {
+                    let unit_millis = match c {
+                        'd' => 86_400_000,
+                        'h' => 3_600_000,
+                        'm' if matches!(token, ExcelToken::Elapsed(..))
+                            || is_minutes(&tokens, i) =>
+                        {
+                            60_000
+                        }
+                        's' => 1000,
+                        _ => continue,
+                    };
+                    let modulus = match c {
+                        _ if matches!(token, ExcelToken::Elapsed(..)) || largest == Some(*c) => {
+                            u64::MAX
+                        }
+                        'h' => 24,
+                        'm' | 's' => 60,
+                        _ => u64::MAX,
+                    };
+                    let n = (total_millis / unit_millis) % modulus;
+                    ret.push_str(&format!("{n:0width$}", width = (*width).min(2)));
+                }
+            }
+        }
+        Some(ret)
+    }
+}
+
+/// Returns whether a pattern contains a `strftime` specifier such as `%Y`.
+fn is_strftime(pattern: &str) -> bool {
+    pattern.split('%').skip(1).any(|rest| {
+        rest.starts_with(|c: char| c.is_ascii_alphabetic() || matches!(c, '-' | '.' | '%'))
+    })
+}
+
+/// Token in an Excel-style date, time, or duration pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ExcelToken {
+    Literal(String),
+    /// Run of a single field character (`y`, `m`, `d`, `h`, or `s`) and its
+    /// length.
+    Field(char, usize),
+    /// Bracketed elapsed time field such as `[h]`.
+    Elapsed(char, usize),
+    /// Fractional seconds with the given number of digits.
+    Fraction(usize),
+    /// `AM/PM` or `am/pm`; the flag is whether it is uppercase.
+    AmPm(bool),
+}
+
+fn tokenize_excel_pattern(pattern: &str) -> Vec<ExcelToken> {
+    let mut tokens = vec![];
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c.to_ascii_lowercase() {
+            '"' => ExcelToken::Literal(chars.by_ref().take_while(|&c| c != '"').collect()),
+            '\\' => ExcelToken::Literal(chars.next().map(String::from).unwrap_or_default()),
+            field @ ('y' | 'm' | 'd' | 'h' | 's') => {
+                let mut len = 1;
+                while chars.next_if(|c| c.to_ascii_lowercase() == field).is_some() {
+                    len += 1;
+                }
+                ExcelToken::Field(field, len)
+            }
+            '[' => {
+                let inner = chars.by_ref().take_while(|&c| c != ']').collect::<String>();
+                let lower = inner.to_ascii_lowercase();
+                match lower.chars().next() {
+                    Some(field @ ('h' | 'm' | 's')) if lower.chars().all(|c| c == field) => {
+                        ExcelToken::Elapsed(field, lower.len())
+                    }
+                    // Colors and conditions don't apply to text output.
+                    _ => continue,
+                }
+            }
+            '.' if chars.peek() == Some(&'0') => {
+                let mut digits = 0;
+                while chars.next_if_eq(&'0').is_some() {
+                    digits += 1;
+                }
+                ExcelToken::Fraction(digits)
+            }
+            'a' => {
+                let rest = chars.clone().take(4).collect::<String>();
+                if rest.eq_ignore_ascii_case("m/pm") {
+                    chars.nth(3);
+                    ExcelToken::AmPm(c.is_ascii_uppercase())
+                } else {
+                    ExcelToken::Literal(c.to_string())
+                }
+            }
+            _ => ExcelToken::Literal(c.to_string()),
+        };
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Returns whether the `m` field at index `i` means minutes rather than
+/// months, which is the case when it follows an hour field or precedes a
+/// seconds field.
+fn is_minutes(tokens: &[ExcelToken], i: usize) -> bool {
+    let is_field = |token: &ExcelToken| {
+        matches!(
+            token,
+            ExcelToken::Field(..) | ExcelToken::Elapsed(..) | ExcelToken::Fraction(_)
+        )
+    };
+    let prev = tokens[..i].iter().rev().find(|t| is_field(t));
+    let next = tokens[i + 1..].iter().find(|t| is_field(t));
+    matches!(
+        prev,
+        Some(ExcelToken::Field('h', _) | ExcelToken::Elapsed('h', _))
+    ) || matches!(
+        next,
+        Some(ExcelToken::Field('s', _) | ExcelToken::Elapsed('s', _))
+    )
+}
+
+/// Part of a pattern for formatting an instant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum StrftimePart {
+    Strftime(String),
+    /// Fractional seconds with the given number of digits, which `strftime`
+    /// only supports in multiples of three.
+    Fraction(usize),
+}
+
+/// Converts an Excel-style date and time pattern to `strftime` patterns and
+/// fractional seconds.
+fn excel_to_strftime(pattern: &str) -> Vec<StrftimePart> {
+    let tokens = tokenize_excel_pattern(pattern);
+    let twelve_hour = tokens.iter().any(|t| matches!(t, ExcelToken::AmPm(_)));
+    let mut ret = vec![];
+    let mut strftime = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let s = match token {
+            ExcelToken::Literal(s) => s.replace('%', "%%"),
+            ExcelToken::Field('y', 1 | 2) => "%y".to_string(),
+            ExcelToken::Field('y', _) => "%Y".to_string(),
+            ExcelToken::Field('m', len) if is_minutes(&tokens, i) => match len {
+                1 => "%-M".to_string(),
+                _ => "%M".to_string(),
+            },
+            ExcelToken::Field('m', 1) => "%-m".to_string(),
+            ExcelToken::Field('m', 2) => "%m".to_string(),
+            ExcelToken::Field('m', 3) => "%b".to_string(),
+            ExcelToken::Field('m', _) => "%B".to_string(),
+            ExcelToken::Field('d', 1) => "%-d".to_string(),
+            ExcelToken::Field('d', 2) => "%d".to_string(),
+            ExcelToken::Field('d', 3) => "%a".to_string(),
+            ExcelToken::Field('d', _) => "%A".to_string(),
+            ExcelToken::Field('h', 1) if twelve_hour => "%-I".to_string(),
+            ExcelToken::Field('h', _) if twelve_hour => "%I".to_string(),
+            ExcelToken::Field('h', 1) => "%-H".to_string(),
+            ExcelToken::Field('h', _) => "%H".to_string(),
+            ExcelToken::Field('s', 1) => "%-S".to_string(),
+            ExcelToken::Field('s', _) => "%S".to_string(),
+            ExcelToken::Fraction(digits) => {
+                ret.push(StrftimePart::Strftime(std::mem::take(&mut strftime)));
+                ret.push(StrftimePart::Fraction(*digits));
+                continue;
+            }
+            ExcelToken::AmPm(true) => "%p".to_string(),
+            ExcelToken::AmPm(false) => "%P".to_string(),
+            ExcelToken::Field(..) | ExcelToken::Elapsed(..) => String::new(),
+        };
+        strftime.push_str(&s);
+    }
+    ret.push(StrftimePart::Strftime(strftime));
+    ret
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn instant() -> Instant {
+        let dt = NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_hms_milli_opt(13, 5, 9, 250)
+            .unwrap();
+        Instant::from_datetime(dt)
+    }
+
+    #[test]
+    fn test_format_instant_strftime() {
+        assert_eq!(Some("2024-03-01".into()), instant().format("%Y-%m-%d"));
+        assert_eq!(
+            Some("Friday, March 1 at 01:05 PM".into()),
+            instant().format("%A, %B %-d at %I:%M %p"),
+        );
+        assert_eq!(None, instant().format("%Q"));
+    }
+
+    #[test]
+    fn test_format_instant_excel() {
+        let i = instant();
+        assert_eq!(Some("2024-03-01".into()), i.format("yyyy-mm-dd"));
+        assert_eq!(Some("3/1/24".into()), i.format("m/d/yy"));
+        assert_eq!(Some("Mar 1, 2024".into()), i.format("mmm d, yyyy"));
+        assert_eq!(Some("Friday, March 01".into()), i.format("dddd, mmmm dd"));
+        assert_eq!(Some("13:05:09".into()), i.format("hh:mm:ss"));
+        assert_eq!(Some("1:05 PM".into()), i.format("h:mm AM/PM"));
+        assert_eq!(Some("05:09.250".into()), i.format("mm:ss.000"));
+        assert_eq!(Some("05:09.2".into()), i.format("mm:ss.0"));
+        assert_eq!(Some("05:09.25".into()), i.format("mm:ss.00"));
+        assert_eq!(Some("09.2500".into()), i.format("ss.0000"));
+        assert_eq!(Some("100% on 3/1".into()), i.format("\"100%\" \"on\" m/d"));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        let d = Duration::from_seconds(2.0 * 86400.0 + 3.0 * 3600.0 + 4.0 * 60.0 + 5.5);
+        assert_eq!(Some("51:04:05".into()), d.format("[h]:mm:ss"));
+        assert_eq!(Some("3064:05.5".into()), d.format("[m]:ss.0"));
+        assert_eq!(Some("3064:05.50".into()), d.format("[m]:ss.00"));
+        assert_eq!(Some("3064:05.500".into()), d.format("[m]:ss.000"));
+        assert_eq!(Some("2 days 03:04".into()), d.format("d \"days\" hh:mm"));
+        assert_eq!(Some("2 03:04:05".into()), d.format("d hh:mm:ss"));
+        assert_eq!(
+            Some("-1:30".into()),
+            Duration::from_seconds(-5400.0).format("[h]:mm"),
+        );
+        let calendar = Duration {
+            years: 1,
+            months: 0,
+            seconds: 0.0,
+        };
+        assert_eq!(None, calendar.format("[h]:mm"));
+    }
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Returns a stringified JSON object of the workbook's calculation
+    /// settings.
+    #[wasm_bindgen(js_name = "getCalculationSettings")]
+    pub fn js_get_calculation_settings(&self) -> Result<String, JsValue> {
+        Ok(serde_json::to_string(self.grid().calculation_settings()).map_err(|e| e.to_string())?)
+    }
+
+    /// Changes the workbook's calculation settings. Returns a
+    /// [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setCalculationSettings")]
+    pub fn js_set_calculation_settings(
+        &mut self,
+        iterative: bool,
+        max_iterations: u32,
+        max_change: f64,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let settings = CalculationSettings {
+            iterative,
+            max_iterations,
+            max_change,
+        };
+        Ok(serde_wasm_bindgen::to_value(
+            &self.set_calculation_settings(settings, cursor),
+        )?)
+    }
+}
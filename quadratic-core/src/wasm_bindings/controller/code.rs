@@ -31,7 +31,12 @@ impl GridController {
         let Some(sheet) = self.try_sheet_from_string_id(sheet_id) else {
             return Ok(JsValue::null());
         };
-        if let Some(edit_code) = sheet.edit_code_value(*pos) {
+        if let Some(mut edit_code) = sheet.edit_code_value(*pos) {
+            if edit_code.language == CodeCellLanguage::Formula {
+                edit_code.code_string = self
+                    .grid()
+                    .formula_sheet_ids_to_names(&edit_code.code_string);
+            }
             Ok(serde_wasm_bindgen::to_value(&edit_code)?)
         } else {
             Ok(JsValue::null())
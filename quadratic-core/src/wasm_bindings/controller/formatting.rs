@@ -145,6 +145,35 @@ impl GridController {
         )?)
     }
 
+    /// Sets cells numeric_format to a date, time, date and time, or duration
+    /// format. `kind` is one of `DATE`, `TIME`, `DATETIME`, or `DURATION`, and
+    /// `pattern` is a `strftime` or Excel-style pattern (or `None` to use the
+    /// default for the kind).
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "setCellDateTimeFormat")]
+    pub fn js_set_cell_date_time_format(
+        &mut self,
+        sheet_id: String,
+        rect: &Rect,
+        kind: String,
+        pattern: Option<String>,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        let kind = NumericFormatKind::from_str(&kind)
+            .ok()
+            .filter(|kind| kind.is_date_time())
+            .ok_or("Invalid date or time format")?;
+        let date_time = NumericFormat {
+            kind,
+            symbol: pattern,
+        };
+        Ok(serde_wasm_bindgen::to_value(
+            &self.set_cell_numeric_format(rect.to_sheet_rect(sheet_id), Some(date_time), cursor),
+        )?)
+    }
+
     /// Sets cells numeric_commas
     ///
     /// Returns a [`TransactionSummary`].
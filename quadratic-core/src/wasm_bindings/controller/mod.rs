@@ -6,16 +6,20 @@ use std::str::FromStr;
 pub mod auto_complete;
 pub mod borders;
 pub mod bounds;
+pub mod calculation_settings;
 pub mod cells;
 pub mod clipboard;
 pub mod code;
 pub mod export;
 pub mod formatting;
 pub mod import;
+pub mod named_ranges;
 pub mod render;
+pub mod rows_columns;
 pub mod search;
 pub mod sheet_offsets;
 pub mod sheets;
+pub mod sort;
 pub mod summarize;
 pub mod transactions;
 
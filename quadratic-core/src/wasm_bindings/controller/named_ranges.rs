@@ -0,0 +1,53 @@
+use super::*;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Returns a stringified JSON list of the grid's named ranges.
+    #[wasm_bindgen(js_name = "getNamedRanges")]
+    pub fn js_get_named_ranges(&self) -> Result<String, JsValue> {
+        Ok(serde_json::to_string(self.grid().named_ranges()).map_err(|e| e.to_string())?)
+    }
+
+    /// Adds a named range, or changes the cells that an existing named range
+    /// refers to. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "addNamedRange")]
+    pub fn js_add_named_range(
+        &mut self,
+        name: String,
+        sheet_id: String,
+        rect: &Rect,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        Ok(serde_wasm_bindgen::to_value(&self.add_named_range(
+            name,
+            rect.to_sheet_rect(sheet_id),
+            cursor,
+        ))?)
+    }
+
+    /// Renames a named range. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "renameNamedRange")]
+    pub fn js_rename_named_range(
+        &mut self,
+        old_name: String,
+        new_name: String,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(
+            &self.rename_named_range(old_name, new_name, cursor),
+        )?)
+    }
+
+    /// Deletes a named range. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "deleteNamedRange")]
+    pub fn js_delete_named_range(
+        &mut self,
+        name: String,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(
+            &self.delete_named_range(name, cursor),
+        )?)
+    }
+}
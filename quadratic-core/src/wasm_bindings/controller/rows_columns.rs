@@ -0,0 +1,64 @@
+use super::*;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Inserts an empty column. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "insertColumn")]
+    pub fn js_insert_column(
+        &mut self,
+        sheet_id: String,
+        column: i32,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        Ok(serde_wasm_bindgen::to_value(&self.insert_column(
+            sheet_id,
+            column as i64,
+            cursor,
+        ))?)
+    }
+
+    /// Deletes a column. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "deleteColumn")]
+    pub fn js_delete_column(
+        &mut self,
+        sheet_id: String,
+        column: i32,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        Ok(serde_wasm_bindgen::to_value(&self.delete_column(
+            sheet_id,
+            column as i64,
+            cursor,
+        ))?)
+    }
+
+    /// Inserts an empty row. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "insertRow")]
+    pub fn js_insert_row(
+        &mut self,
+        sheet_id: String,
+        row: i32,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        Ok(serde_wasm_bindgen::to_value(
+            &self.insert_row(sheet_id, row as i64, cursor),
+        )?)
+    }
+
+    /// Deletes a row. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "deleteRow")]
+    pub fn js_delete_row(
+        &mut self,
+        sheet_id: String,
+        row: i32,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        Ok(serde_wasm_bindgen::to_value(
+            &self.delete_row(sheet_id, row as i64, cursor),
+        )?)
+    }
+}
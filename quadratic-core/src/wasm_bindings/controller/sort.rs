@@ -0,0 +1,32 @@
+use super::*;
+use crate::controller::operations::sort::SortKey;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Sorts the rows of a range given a JSON array of [`SortKey`]s.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "sortRange")]
+    pub fn js_sort_range(
+        &mut self,
+        sheet_id: String,
+        rect: &Rect,
+        keys: String,
+        has_header: bool,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let Ok(sheet_id) = SheetId::from_str(&sheet_id) else {
+            return Result::Err("Invalid sheet id".into());
+        };
+        let keys: Vec<SortKey> = match serde_json::from_str(&keys) {
+            Ok(keys) => keys,
+            Err(e) => return Err(JsValue::from_str(&format!("Invalid sort keys: {}", e))),
+        };
+        Ok(serde_wasm_bindgen::to_value(&self.sort_range(
+            rect.to_sheet_rect(sheet_id),
+            &keys,
+            has_header,
+            cursor,
+        ))?)
+    }
+}
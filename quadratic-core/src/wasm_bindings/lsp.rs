@@ -12,6 +12,34 @@ pub fn provide_completion_items(
     )?)
 }
 
+/// Returns a Monaco `SignatureHelp` for the function call at the cursor, or
+/// `null` if the cursor is not inside a function call. The caller must wrap it
+/// in a `SignatureHelpResult` with a `dispose()` method.
+#[wasm_bindgen(js_name = "provideSignatureHelp")]
+pub fn provide_signature_help(
+    text_model: JsValue,
+    position: JsValue,
+    _token: JsValue,
+    _context: JsValue,
+) -> Result<JsValue, JsValue> {
+    let (source, cursor) = text_and_cursor(&text_model, &position)?;
+    let result = crate::formulas::lsp::provide_signature_help(&source, cursor);
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+/// Returns the text of a Monaco text model and the byte index of a position in
+/// it.
+fn text_and_cursor(text_model: &JsValue, position: &JsValue) -> Result<(String, u32), JsValue> {
+    let source = jsexpr!(text_model.getValue())
+        .as_string()
+        .unwrap_or_default();
+    let offset = jsexpr!(text_model.getOffsetAt(position))
+        .as_f64()
+        .unwrap_or_default();
+    let cursor = crate::formulas::lsp::byte_index_from_utf16_offset(&source, offset as u32);
+    Ok((source, cursor))
+}
+
 #[wasm_bindgen(js_name = "provideHover")]
 pub async fn provide_hover(
     text_model: JsValue,
@@ -24,3 +52,37 @@ pub async fn provide_hover(
     let result = crate::formulas::lsp::provide_hover(&partial_function_name);
     Ok(serde_wasm_bindgen::to_value(&result)?)
 }
+
+#[wasm_bindgen]
+impl GridController {
+    /// Returns Monaco completion items for the cursor position in a formula,
+    /// including sheet names and named ranges in this file.
+    #[wasm_bindgen(js_name = "provideCompletionItems")]
+    pub fn js_provide_completion_items(
+        &self,
+        text_model: JsValue,
+        position: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let (source, cursor) = text_and_cursor(&text_model, &position)?;
+        let result =
+            crate::formulas::lsp::provide_completion_items_at(self.grid(), &source, cursor);
+        Ok(serde_wasm_bindgen::to_value(&result)?)
+    }
+
+    /// Returns Monaco markers for the problems in a formula at (x, y), so that
+    /// they can be underlined before the formula is evaluated.
+    #[wasm_bindgen(js_name = "provideDiagnostics")]
+    pub fn js_provide_diagnostics(
+        &self,
+        formula_string: &str,
+        x: f64,
+        y: f64,
+    ) -> Result<JsValue, JsValue> {
+        let pos = Pos {
+            x: x as i64,
+            y: y as i64,
+        };
+        let result = crate::formulas::lsp::provide_diagnostics(self.grid(), formula_string, pos);
+        Ok(serde_wasm_bindgen::to_value(&result)?)
+    }
+}
@@ -114,6 +114,28 @@ pub async fn parse_formula(formula_string: &str, x: f64, y: f64) -> JsValue {
     serde_wasm_bindgen::to_value(&result).unwrap()
 }
 
+/// Rewrites the cell references in a formula at (x, y) from A1-style notation
+/// to R1C1-style notation, such as `R[-1]C` for the cell above.
+#[wasm_bindgen]
+pub fn formula_a1_to_r1c1(formula_string: &str, x: f64, y: f64) -> String {
+    let pos = Pos {
+        x: x as i64,
+        y: y as i64,
+    };
+    formulas::a1_to_r1c1(formula_string, pos)
+}
+
+/// Rewrites the cell references in a formula at (x, y) from R1C1-style
+/// notation to A1-style notation.
+#[wasm_bindgen]
+pub fn formula_r1c1_to_a1(formula_string: &str, x: f64, y: f64) -> String {
+    let pos = Pos {
+        x: x as i64,
+        y: y as i64,
+    };
+    formulas::r1c1_to_a1(formula_string, pos)
+}
+
 #[cfg(test)]
 mod tests {
     /// Run this test with `--nocapture` to generate the example for the